@@ -0,0 +1,128 @@
+//! Structure-aware: directives assembled from the DSL's own properties with
+//! arbitrary values, so most inputs get past the tokenizer and exercise value
+//! parsing, `validate` and `apply` instead of failing on the first keyword.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use parquet::file::properties::WriterProperties;
+use parquet_linter::prescription::Prescription;
+
+const FILE_PROPERTIES: &[&str] = &[
+    "compression",
+    "max_row_group_size",
+    "data_page_size_limit",
+    "data_page_row_count_limit",
+    "write_batch_size",
+    "read_batch_size",
+    "statistics_truncate_length",
+    "arrow_schema",
+];
+
+const COLUMN_PROPERTIES: &[&str] = &[
+    "compression",
+    "encoding",
+    "dictionary",
+    "dictionary_page_size_limit",
+    "statistics",
+    "statistics_truncate_length",
+    "bloom_filter",
+    "bloom_filter_ndv",
+    "bloom_filter_fpp",
+    "nullable",
+    "cast",
+];
+
+const WORDS: &[&str] = &[
+    "none",
+    "true",
+    "false",
+    "chunk",
+    "page",
+    "plain",
+    "delta_binary_packed",
+    "delta_length_byte_array",
+    "delta_byte_array",
+    "byte_stream_split",
+    "uncompressed",
+    "snappy",
+    "lz4_raw",
+    "preserve",
+    "normalize",
+    "strip",
+];
+
+const WRAPPERS: &[&str] = &["zstd", "gzip", "brotli", "fixed_len_byte_array"];
+
+#[derive(Arbitrary, Debug)]
+enum Value {
+    Word(u8),
+    Integer(i64),
+    Float(f64),
+    Wrapped(u8, i64),
+    Raw(String),
+}
+
+impl Value {
+    fn render(&self) -> String {
+        match self {
+            Value::Word(i) => WORDS[*i as usize % WORDS.len()].to_string(),
+            Value::Integer(n) => n.to_string(),
+            Value::Float(x) => x.to_string(),
+            Value::Wrapped(i, n) => format!("{}({n})", WRAPPERS[*i as usize % WRAPPERS.len()]),
+            Value::Raw(text) => text.clone(),
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+enum Directive {
+    File {
+        property: u8,
+        value: Value,
+    },
+    Column {
+        path: Vec<String>,
+        property: u8,
+        value: Value,
+    },
+}
+
+impl Directive {
+    fn render(&self) -> String {
+        match self {
+            Directive::File { property, value } => format!(
+                "set file {} {}",
+                FILE_PROPERTIES[*property as usize % FILE_PROPERTIES.len()],
+                value.render()
+            ),
+            Directive::Column {
+                path,
+                property,
+                value,
+            } => format!(
+                "set column {} {} {}",
+                path.join("."),
+                COLUMN_PROPERTIES[*property as usize % COLUMN_PROPERTIES.len()],
+                value.render()
+            ),
+        }
+    }
+}
+
+fuzz_target!(|directives: Vec<Directive>| {
+    let text: Vec<String> = directives.iter().map(Directive::render).collect();
+    let Ok(prescription) = Prescription::parse(&text.join("\n")) else {
+        return;
+    };
+    let _ = prescription.validate();
+    prescription.apply(WriterProperties::builder()).build();
+    for directive in prescription.directives() {
+        let _ = (directive.setting(), directive.value(), directive.column());
+    }
+
+    let printed = prescription.to_string();
+    let reparsed = Prescription::parse(&printed).expect("printed prescription parses");
+    assert_eq!(reparsed.to_string(), printed);
+});
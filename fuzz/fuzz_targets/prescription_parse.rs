@@ -0,0 +1,26 @@
+//! Arbitrary bytes through both prescription parsers. Parsing must not
+//! panic, and whatever parses must apply to a writer without panicking and
+//! print back to text that parses to the same prescription.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use parquet::file::properties::WriterProperties;
+use parquet_linter::prescription::{DatasetPrescription, Prescription};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = DatasetPrescription::parse(text);
+    }
+    let Ok(prescription) = Prescription::parse_bytes(data) else {
+        return;
+    };
+    let _ = prescription.validate();
+    prescription.apply(WriterProperties::builder()).build();
+
+    let printed = prescription.to_string();
+    let reparsed = Prescription::parse(&printed).expect("printed prescription parses");
+    assert_eq!(reparsed.to_string(), printed);
+    let inline = Prescription::parse(&prescription.to_inline()).expect("inline form parses");
+    assert_eq!(inline.to_string(), printed);
+});
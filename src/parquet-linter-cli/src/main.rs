@@ -1,12 +1,36 @@
+mod serve;
+
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use futures::StreamExt;
+use std::collections::BTreeMap;
 use std::fs;
+use std::io;
 use std::path::PathBuf;
 use std::process;
+use std::sync::Arc;
 
-use parquet_linter::diagnostic::Severity;
-use parquet_linter::prescription::Prescription;
+use parquet_linter::Linter;
+use parquet_linter::cache::ResultCache;
+use parquet_linter::compaction::{self, CompactionPlan};
+use parquet_linter::compare;
+use parquet_linter::config::Config;
+use parquet_linter::cost::{CpuCostModel, GpuCostModel};
+use parquet_linter::delta::{self, DeltaTable};
+use parquet_linter::diagnostic::{self, Category, Severity};
+use parquet_linter::dump;
+use parquet_linter::fix;
+use parquet_linter::iceberg::{self, IcebergTable};
+use parquet_linter::loader::{self, LoadedFile};
+use parquet_linter::options::{CardinalityMode, LintOptions, Workload, parse_row_groups};
+use parquet_linter::plan::FixPlan;
+use parquet_linter::prescription::{DatasetPrescription, Prescription};
+use parquet_linter::sarif;
+use parquet_linter::summary::FileSummary;
+use parquet_linter::target::TargetReader;
+use parquet_linter::thresholds::{Preset, Thresholds};
+use parquet_linter::tune::{self, TuneReport};
 
 #[derive(Parser)]
 #[command(
@@ -16,22 +40,216 @@ use parquet_linter::prescription::Prescription;
     arg_required_else_help = true
 )]
 struct Cli {
-    /// File path or URL (local, s3://, https://)
+    /// File path or URL (local, s3://, https://); a directory or a URL
+    /// ending in `/` lints every `*.parquet` file below it
     #[arg(value_name = "FILE")]
     file: Option<String>,
+    /// Files linted at once when FILE is a directory or prefix
+    #[arg(long, default_value_t = 8)]
+    jobs: usize,
     /// Only run specific rules (comma-separated)
     #[arg(long, value_delimiter = ',')]
     rules: Option<Vec<String>>,
     /// Minimum severity to display
     #[arg(long)]
     severity: Option<Severity>,
+    /// Only report these categories (comma-separated: correctness,
+    /// performance, compatibility, gpu); the exit code follows the filter
+    #[arg(long, value_delimiter = ',')]
+    category: Option<Vec<Category>>,
     /// Write merged prescription DSL from lint results to a text file
     #[arg(long, value_name = "FILE")]
     export_prescription: Option<PathBuf>,
+    /// Print the merged prescription instead of diagnostics, one directive
+    /// per line (or on one line with --format inline)
+    #[arg(long)]
+    print_prescription: bool,
+    /// Exit with 1 only if a warning or error still carries a fix, e.g. to
+    /// check that a rewritten file needs no further rewrite. Like `compare`,
+    /// which exits with 1 if the files differ, this replaces the
+    /// severity-based exit codes; 10 still means the check could not run
+    #[arg(long)]
+    assert_clean: bool,
+    /// Report time, bytes fetched and requests per phase and rule (to stderr)
+    #[arg(long)]
+    timings: bool,
+    /// Reuse results for unchanged files from the cache directory
+    #[arg(long)]
+    cache: bool,
+    /// Cache directory (implies --cache; default: ~/.cache/parquet-linter)
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Output format for diagnostics
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Print one entry per column instead of grouping columns that share the
+    /// same rule and fix
+    #[arg(long)]
+    no_group: bool,
+    #[command(flatten)]
+    lint: LintArgs,
     #[command(subcommand)]
     command: Option<Command>,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Colored, human-readable report
+    Text,
+    /// GitHub Actions `::warning` annotations, one per diagnostic
+    Github,
+    /// Versioned JSON document (see doc/lint-output.schema.json)
+    Json,
+    /// One JSON report per line, printed per file as soon as it is linted
+    /// when FILE is a directory or prefix (files in completion order)
+    Jsonl,
+    /// SARIF 2.1.0 log, e.g. for GitHub code scanning
+    Sarif,
+    /// With --print-prescription: one `;`-separated line, as accepted by
+    /// `rewrite --prescription-text`
+    Inline,
+}
+
+#[derive(Args)]
+struct LintArgs {
+    #[command(flatten)]
+    workload: WorkloadArgs,
+    /// Sample every column to build value distributions (slower)
+    #[arg(long)]
+    profile_values: bool,
+    /// Re-compress samples of large columns with candidate codecs (slower)
+    #[arg(long)]
+    probe_compression: bool,
+    /// Re-encode samples of large columns with candidate encodings (slower)
+    #[arg(long)]
+    probe_encodings: bool,
+    /// Distinct-count accuracy: fast (samples rows of a few row groups),
+    /// balanced (every row of those row groups) or exact (every row group)
+    #[arg(long, value_name = "MODE", default_value_t = CardinalityMode::Fast)]
+    cardinality: CardinalityMode,
+    /// Cap on decoded sample data held in memory at once
+    #[arg(long, value_name = "MB")]
+    sample_memory_mb: Option<usize>,
+    /// Only lint these columns (comma-separated dotted paths; a name also
+    /// selects the leaves nested below it)
+    #[arg(long, value_delimiter = ',')]
+    columns: Option<Vec<String>>,
+    /// Skip columns matching these patterns (comma-separated; `*` and `?`
+    /// wildcards)
+    #[arg(long, value_delimiter = ',')]
+    ignore_columns: Vec<String>,
+    /// Only lint these row groups (e.g. `0,5,17-20`)
+    #[arg(long, value_name = "LIST")]
+    row_groups: Option<String>,
+    /// Hardware the fetch/decode cost estimates assume
+    #[arg(long, value_enum, default_value_t = CostModelArg::Cpu)]
+    cost_model: CostModelArg,
+    /// Reader the file must stay readable by: legacy, spark-3.3, spark-3.5
+    /// or spark-4.0. Flags unreadable codecs and encodings and drops fixes
+    /// that would introduce them.
+    #[arg(long, value_name = "READER")]
+    target: Option<String>,
+    /// How eager rules are to flag columns: conservative (sizes doubled),
+    /// default or aggressive (sizes halved); overrides the config file's
+    #[arg(long)]
+    preset: Option<Preset>,
+    /// Rule config (TOML) to disable rules, override severities and tune
+    /// thresholds [default: ./.parquet-linter.toml if present]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Pin rule thresholds with the `configure rule` lines of a prescription
+    /// file (its `set` directives are ignored here)
+    #[arg(long, value_name = "FILE")]
+    policy: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CostModelArg {
+    /// CPU decoding from object storage
+    Cpu,
+    /// GPU decoding (nvCOMP-style codecs) behind PCIe
+    Gpu,
+}
+
+impl LintArgs {
+    fn into_lint_options(self) -> Result<LintOptions> {
+        let defaults = LintOptions::default();
+        let config = match self
+            .config
+            .or_else(|| Config::discover(&std::env::current_dir().ok()?))
+        {
+            Some(path) => Config::load(&path)?,
+            None => Config::default(),
+        };
+        let policy = self.policy.as_ref().map(read_prescription).transpose()?;
+        let mut options = LintOptions {
+            workload: self.workload.into_workload()?,
+            profile_values: self.profile_values,
+            probe_compression: self.probe_compression,
+            probe_encodings: self.probe_encodings,
+            cardinality: self.cardinality,
+            sample_memory_limit: self
+                .sample_memory_mb
+                .map_or(defaults.sample_memory_limit, |mb| mb * 1024 * 1024),
+            cost_model: match self.cost_model {
+                CostModelArg::Cpu => Arc::new(CpuCostModel::default()),
+                CostModelArg::Gpu => Arc::new(GpuCostModel::default()),
+            },
+            columns: self.columns,
+            ignore_columns: self.ignore_columns,
+            row_groups: self
+                .row_groups
+                .as_deref()
+                .map(parse_row_groups)
+                .transpose()?,
+            target: self
+                .target
+                .as_deref()
+                .map(TargetReader::parse)
+                .transpose()?,
+            thresholds: Thresholds::new(self.preset.or(config.preset).unwrap_or_default()),
+            ..defaults
+        };
+        config.apply(&mut options);
+        if let Some(policy) = policy {
+            options.thresholds.configure(&policy.common)?;
+        }
+        Ok(options)
+    }
+}
+
+/// Hints about which columns queries touch, so rules can prioritize them.
+#[derive(Args)]
+struct WorkloadArgs {
+    /// Columns used in query predicates (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    filter_columns: Vec<String>,
+    /// Columns read by queries (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    projection_columns: Vec<String>,
+    /// Columns used as join or lookup keys (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    join_keys: Vec<String>,
+    /// Query log with `filter|project|join <columns>` lines
+    #[arg(long, value_name = "FILE")]
+    query_log: Option<PathBuf>,
+}
+
+impl WorkloadArgs {
+    fn into_workload(self) -> Result<Workload> {
+        let mut workload = match &self.query_log {
+            Some(path) => Workload::parse(&fs::read_to_string(path)?)?,
+            None => Workload::default(),
+        };
+        workload.extend(Workload {
+            filter_columns: self.filter_columns,
+            projection_columns: self.projection_columns,
+            join_keys: self.join_keys,
+        });
+        Ok(workload)
+    }
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Rewrite a parquet file using lint results or a prescription
@@ -39,20 +257,191 @@ enum Command {
         /// File path or URL (local, s3://, https://)
         file: Option<String>,
         /// Output file path
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "in_place")]
         output: Option<PathBuf>,
+        /// Replace FILE with its rewrite: atomically for local files, with a
+        /// conditional put on object stores that support it
+        #[arg(long)]
+        in_place: bool,
+        /// With --in-place, keep the original at FILE plus this suffix
+        #[arg(long, value_name = "SUFFIX", requires = "in_place")]
+        backup_suffix: Option<String>,
         /// Only apply fixes from specific rules (comma-separated)
         #[arg(long, value_delimiter = ',')]
         rules: Option<Vec<String>>,
-        /// Apply a prescription DSL file directly (without running lint)
+        /// Apply a prescription DSL file (`-` for stdin) directly, without
+        /// running lint. File sections of a dataset prescription are matched
+        /// against FILE.
         #[arg(long, value_name = "FILE")]
         from_prescription: Option<PathBuf>,
+        /// Like --from-prescription, with the DSL given inline; directives
+        /// are separated by newlines or `;`
+        #[arg(long, value_name = "TEXT", conflicts_with = "from_prescription")]
+        prescription_text: Option<String>,
         /// Show what would be fixed without writing
         #[arg(long)]
         dry_run: bool,
         /// Write merged prescription DSL to a text file
         #[arg(long, value_name = "FILE")]
         export_prescription: Option<PathBuf>,
+        /// Write the fix plan (each directive with its rule, message and
+        /// affected bytes) as JSON
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["from_prescription", "prescription_text"])]
+        export_plan: Option<PathBuf>,
+        #[command(flatten)]
+        lint: LintArgs,
+    },
+    /// Rewrite several files with a dataset prescription, each with the
+    /// common directives plus those of its first matching file section
+    Apply {
+        /// Files to rewrite, as paths or URLs
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Prescription DSL file, optionally with `file "<glob>":` sections
+        #[arg(short, long, value_name = "FILE")]
+        prescription: PathBuf,
+        /// Directory for rewritten files; relative local paths keep their
+        /// directories, other inputs are written by file name
+        #[arg(long)]
+        output_dir: PathBuf,
+        /// Show the prescription each file would get without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rewrite every parquet file below a directory with one (dataset)
+    /// prescription, keeping relative paths. Exits with 10 if any file
+    /// failed
+    Fix {
+        /// Directory to read parquet files from, recursively
+        #[arg(long)]
+        input_dir: PathBuf,
+        /// Directory to write rewritten files to
+        #[arg(long)]
+        output_dir: PathBuf,
+        /// Prescription DSL file (`-` for stdin); file sections match paths
+        /// relative to --input-dir
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            required_unless_present = "prescription_text"
+        )]
+        prescription: Option<PathBuf>,
+        /// The prescription DSL inline; directives are separated by newlines
+        /// or `;`
+        #[arg(long, value_name = "TEXT", conflicts_with = "prescription")]
+        prescription_text: Option<String>,
+        /// Files rewritten at the same time
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+    },
+    /// Merge small parquet files below a directory into files of about
+    /// --target-size, within each directory and schema. Exits with 10 if any
+    /// merged file failed
+    Compact {
+        /// Directory to read parquet files from, recursively
+        #[arg(long, required_unless_present = "from_plan")]
+        input_dir: Option<PathBuf>,
+        /// Directory to write merged files to, at the inputs' relative paths
+        #[arg(long, required_unless_present = "from_plan")]
+        output_dir: Option<PathBuf>,
+        /// Target size of merged files in bytes
+        #[arg(long, default_value_t = compaction::DEFAULT_TARGET_BYTES)]
+        target_size: u64,
+        /// Prescription DSL file for merged files; file sections match each
+        /// batch's first input relative to --input-dir
+        #[arg(short, long, value_name = "FILE")]
+        prescription: Option<PathBuf>,
+        /// Write the compaction plan (outputs, their inputs and
+        /// prescriptions) as JSON
+        #[arg(long, value_name = "FILE")]
+        export_plan: Option<PathBuf>,
+        /// Run a plan written by --export-plan instead of planning
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["input_dir", "output_dir", "prescription"])]
+        from_plan: Option<PathBuf>,
+        /// Show the plan without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Search for the prescription with the lowest measured scan cost on a
+    /// sampled re-encode of the file
+    Tune {
+        /// File path or URL (local, s3://, https://)
+        file: String,
+        /// Write the best prescription DSL to a text file
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        lint: LintArgs,
+    },
+    /// Lint every data file of an Iceberg table and summarize per partition
+    Iceberg {
+        /// Table location (root directory or a *.metadata.json file)
+        location: String,
+        /// Only run specific rules (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        rules: Option<Vec<String>>,
+        /// Minimum severity to display
+        #[arg(long)]
+        severity: Option<Severity>,
+        #[command(flatten)]
+        lint: LintArgs,
+    },
+    /// Lint every active data file of a Delta Lake table
+    Delta {
+        /// Table root (the directory containing `_delta_log`)
+        location: String,
+        /// Only run specific rules (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        rules: Option<Vec<String>>,
+        /// Minimum severity to display
+        #[arg(long)]
+        severity: Option<Severity>,
+        #[command(flatten)]
+        lint: LintArgs,
+    },
+    /// Show how the writer configurations of two files differ: codecs,
+    /// encodings, dictionary, statistics levels and sizes per column. Exits
+    /// with 1 if they differ.
+    Compare {
+        /// First file path or URL
+        a: String,
+        /// Second file path or URL
+        b: String,
+    },
+    /// Serve lint, prescription and rewrite requests over HTTP
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: std::net::SocketAddr,
+        /// Directory `/rewrite` writes to; rewrites are refused without it
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+        /// URL or directory requests may read from (repeatable; defaults to
+        /// the current directory)
+        #[arg(long = "allow", value_name = "ROOT")]
+        allow: Vec<String>,
+        /// Only run specific rules (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        rules: Option<Vec<String>>,
+        #[command(flatten)]
+        lint: LintArgs,
+    },
+    /// Print every rule's name, code, category, default severity, tunable
+    /// parameters and prescribed settings as JSON
+    RulesManifest,
+    /// Print the footer structure (schema, row groups, chunk sizes,
+    /// encodings, statistics) as JSON, e.g. to attach to a bug report
+    DumpMetadata {
+        /// File path or URL (local, s3://, https://)
+        file: String,
+        /// Hash column names and reduce statistics and key-value metadata
+        /// values to their lengths
+        #[arg(long)]
+        anonymize: bool,
+        /// Write the JSON to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
     },
 }
 
@@ -67,9 +456,60 @@ fn write_prescription(path: &PathBuf, prescription: &Prescription) -> Result<()>
     Ok(())
 }
 
-fn read_prescription(path: &PathBuf) -> Result<Prescription> {
-    let text = fs::read_to_string(path)?;
-    Prescription::parse(&text).map_err(Into::into)
+/// Read a prescription DSL file, or standard input for `-`.
+fn read_prescription(path: &PathBuf) -> Result<DatasetPrescription> {
+    let text = if path.as_os_str() == "-" {
+        io::read_to_string(io::stdin())?
+    } else {
+        fs::read_to_string(path)?
+    };
+    DatasetPrescription::parse(&text).map_err(Into::into)
+}
+
+/// Where `rewrite` writes its result.
+enum RewriteTarget<'a> {
+    Output(PathBuf),
+    InPlace(fix::InPlaceRewrite<'a>),
+}
+
+impl RewriteTarget<'_> {
+    async fn write(&self, file: &LoadedFile, prescription: &Prescription) -> Result<()> {
+        match self {
+            RewriteTarget::Output(path) => fix::rewrite_file(file, path, prescription).await,
+            RewriteTarget::InPlace(in_place) => in_place.run(file, prescription).await,
+        }
+    }
+}
+
+impl std::fmt::Display for RewriteTarget<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RewriteTarget::Output(path) => write!(f, "{}", path.display()),
+            RewriteTarget::InPlace(in_place) => {
+                write!(f, "{} in place", in_place.location)?;
+                match in_place.backup_suffix {
+                    Some(suffix) => write!(f, " (original kept at {}{suffix})", in_place.location),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Where `apply` writes `file`: under `output_dir` at the same relative path
+/// for relative local inputs, otherwise by file name alone.
+fn apply_output_path(output_dir: &std::path::Path, file: &str) -> PathBuf {
+    let path = std::path::Path::new(file);
+    let relative = !file.contains("://")
+        && path.is_relative()
+        && path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if relative {
+        output_dir.join(path)
+    } else {
+        output_dir.join(file.rsplit('/').next().unwrap_or(file))
+    }
 }
 
 fn warn_if_conflicting_for_apply(prescription: &Prescription) {
@@ -81,78 +521,513 @@ fn warn_if_conflicting_for_apply(prescription: &Prescription) {
     }
 }
 
+fn print_diagnostics(filtered: &[&diagnostic::Diagnostic], no_group: bool) {
+    if filtered.is_empty() {
+        println!("{}", "No issues found. ✓".green().bold());
+        return;
+    }
+    if no_group {
+        for d in filtered {
+            d.print_colored();
+            println!();
+        }
+    } else {
+        for group in diagnostic::group(filtered.iter().copied()) {
+            group.print_colored();
+            println!();
+        }
+    }
+    let summary = format!("{} issue(s) found.", filtered.len());
+    println!("{}", summary.yellow().bold());
+}
+
+fn print_tune_report(report: &TuneReport) {
+    let header = format!("Measured on a {}-row sample:", report.sample_rows);
+    println!("{}", header.bold());
+    for c in &report.candidates {
+        let m = &c.measurement;
+        println!(
+            "  {:<9} {:>12} bytes  decode {:>8.2} ms  scan cost {:>8.2} ms  ({} directive(s))",
+            c.name,
+            m.compressed_size,
+            m.decode_seconds * 1e3,
+            c.scan_cost * 1e3,
+            c.prescription.directives().len()
+        );
+    }
+    for level in &report.levels {
+        println!();
+        let header = format!("Compression levels for {}:", level.column);
+        println!("{}", header.bold());
+        for (codec, m) in &level.trials {
+            let marker = if *codec == level.chosen { "*" } else { " " };
+            println!(
+                "{marker} {:<10} {:>12} bytes  encode {:>8.2} ms  decode {:>8.2} ms",
+                codec.to_string(),
+                m.compressed_size,
+                m.encode_seconds * 1e3,
+                m.decode_seconds * 1e3
+            );
+        }
+    }
+    let best = report.best();
+    println!();
+    let msg = format!("Best: {}", best.name);
+    println!("{}", msg.green().bold());
+    if best.prescription.is_empty() {
+        println!(
+            "{}",
+            "The current settings are already the cheapest. ✓".green()
+        );
+    } else {
+        println!("{}", best.prescription);
+    }
+}
+
+async fn lint_iceberg(
+    location: &str,
+    rules: Option<&[String]>,
+    severity: Severity,
+    options: &LintOptions,
+) -> Result<i32> {
+    let table = IcebergTable::load(location).await?;
+    let report = iceberg::lint_table(&table, rules, options).await?;
+    let msg = format!(
+        "{} data file(s) in {}",
+        table.data_files.len(),
+        table.metadata_location
+    );
+    println!("{}", msg.cyan().bold());
+    for partition in report.partitions() {
+        let name = if partition.partition.is_empty() {
+            "unpartitioned"
+        } else {
+            &partition.partition
+        };
+        let top: Vec<String> = partition
+            .rules
+            .iter()
+            .map(|(rule, n)| format!("{rule} ×{n}"))
+            .collect();
+        println!(
+            "spec {} {}: {} file(s), {} error(s), {} warning(s), {} suggestion(s){}",
+            partition.spec_id,
+            name.bold(),
+            partition.files,
+            partition.errors,
+            partition.warnings,
+            partition.suggestions,
+            if top.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", top.join(", "))
+            },
+        );
+    }
+    println!();
+    for d in report
+        .table_diagnostics
+        .iter()
+        .filter(|d| d.severity >= severity)
+    {
+        d.print_colored();
+        println!();
+    }
+    Ok(parquet_linter::exit_code(
+        report.all_diagnostics().filter(|d| d.severity >= severity),
+    ))
+}
+
+async fn lint_delta(
+    location: &str,
+    rules: Option<&[String]>,
+    severity: Severity,
+    options: &LintOptions,
+) -> Result<i32> {
+    let table = DeltaTable::load(location).await?;
+    let report = delta::lint_table(&table, rules, options).await?;
+    let msg = format!(
+        "{} data file(s) at version {}",
+        table.data_files.len(),
+        table.version
+    );
+    println!("{}", msg.cyan().bold());
+    for file in &report.files {
+        let shown = file
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity >= severity)
+            .count();
+        if shown > 0 {
+            println!("{}: {shown} issue(s)", file.file.uri.bold());
+        }
+    }
+    println!();
+    for d in report
+        .table_diagnostics
+        .iter()
+        .filter(|d| d.severity >= severity)
+    {
+        d.print_colored();
+        println!();
+    }
+    Ok(parquet_linter::exit_code(
+        report.all_diagnostics().filter(|d| d.severity >= severity),
+    ))
+}
+
+/// Lint every parquet file below `location` and print a line per file plus
+/// totals (or one JSON document, a JSON line per file, or annotations).
+/// Returns the exit code: `EXIT_INTERNAL_ERROR` if any file failed, else
+/// that of the diagnostics passing `filter`.
+async fn lint_prefix(
+    linter: &Linter,
+    location: &str,
+    jobs: usize,
+    format: OutputFormat,
+    filter: impl Fn(&diagnostic::Diagnostic) -> bool,
+) -> Result<i32> {
+    let locations = loader::list_parquet(location).await?;
+    if matches!(format, OutputFormat::Text) {
+        let msg = format!("{} parquet file(s) below {location}", locations.len());
+        println!("{}\n", msg.cyan().bold());
+    }
+    let mut results = match format {
+        OutputFormat::Jsonl => linter.lint_all_unordered(&locations, jobs).boxed_local(),
+        _ => linter.lint_all(&locations, jobs).boxed_local(),
+    };
+    let mut counts = [0usize; 3];
+    let mut failed = 0;
+    let mut files_by_rule: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut reports = Vec::new();
+    let mut sarif_files = Vec::new();
+    let mut sarif_failures = Vec::new();
+    while let Some((file, result)) = results.next().await {
+        let diagnostics = match result {
+            Ok(diagnostics) => diagnostics,
+            Err(e) => {
+                failed += 1;
+                match format {
+                    OutputFormat::Json => {
+                        reports.push(serde_json::json!({"file": file, "error": format!("{e:#}")}))
+                    }
+                    OutputFormat::Jsonl => {
+                        println!(
+                            "{}",
+                            serde_json::json!({
+                                "schema_version": diagnostic::JSON_SCHEMA_VERSION,
+                                "file": file,
+                                "error": format!("{e:#}"),
+                            })
+                        )
+                    }
+                    OutputFormat::Sarif => sarif_failures.push((file, format!("{e:#}"))),
+                    _ => eprintln!("{}: {}", file.bold(), format!("{e:#}").red()),
+                }
+                continue;
+            }
+        };
+        let shown: Vec<_> = diagnostics.iter().filter(|d| filter(d)).collect();
+        let mut file_counts = [0usize; 3];
+        for d in &shown {
+            file_counts[match d.severity {
+                Severity::Suggestion => 0,
+                Severity::Warning => 1,
+                Severity::Error => 2,
+            }] += 1;
+        }
+        let mut rules: Vec<&str> = shown.iter().map(|d| d.rule_name).collect();
+        rules.sort();
+        rules.dedup();
+        for rule in rules {
+            *files_by_rule.entry(rule).or_default() += 1;
+        }
+        for (total, n) in counts.iter_mut().zip(file_counts) {
+            *total += n;
+        }
+        if matches!(format, OutputFormat::Sarif) {
+            // One log for the whole run, written once every file is done.
+            drop(shown);
+            let shown: Vec<_> = diagnostics.into_iter().filter(|d| filter(d)).collect();
+            sarif_files.push((file, shown));
+            continue;
+        }
+        match format {
+            OutputFormat::Json => reports.push(diagnostic::json_report(file, shown)),
+            OutputFormat::Jsonl => println!("{}", diagnostic::json_report(file, shown)),
+            OutputFormat::Github => {
+                for d in shown {
+                    println!("{}", d.to_github_annotation(file));
+                }
+            }
+            OutputFormat::Sarif => {}
+            OutputFormat::Text | OutputFormat::Inline => {
+                let [suggestions, warnings, errors] = file_counts;
+                if shown.is_empty() {
+                    println!("{}: {}", file.bold(), "clean".green());
+                } else {
+                    println!(
+                        "{}: {errors} error(s), {warnings} warning(s), {suggestions} suggestion(s)",
+                        file.bold()
+                    );
+                }
+            }
+        }
+    }
+
+    let [suggestions, warnings, errors] = counts;
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "schema_version": diagnostic::JSON_SCHEMA_VERSION,
+                "location": location,
+                "files": reports,
+                "totals": {
+                    "files": locations.len(),
+                    "failed": failed,
+                    "errors": errors,
+                    "warnings": warnings,
+                    "suggestions": suggestions,
+                },
+            });
+            println!("{report}");
+        }
+        OutputFormat::Sarif => {
+            let files: Vec<_> = sarif_files
+                .iter()
+                .map(|(file, diagnostics)| (*file, diagnostics.iter().collect()))
+                .collect();
+            println!("{}", sarif::report(&files, &sarif_failures));
+        }
+        OutputFormat::Github | OutputFormat::Jsonl => {}
+        OutputFormat::Text | OutputFormat::Inline => {
+            let msg = format!(
+                "{} file(s), {failed} failed: {errors} error(s), {warnings} warning(s), \
+                 {suggestions} suggestion(s)",
+                locations.len()
+            );
+            println!("\n{}", msg.bold());
+            let mut by_files: Vec<_> = files_by_rule.into_iter().collect();
+            by_files.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+            for (rule, n) in by_files {
+                println!("  {rule}: {n} file(s)");
+            }
+        }
+    }
+    Ok(if failed > 0 {
+        parquet_linter::EXIT_INTERNAL_ERROR
+    } else if errors > 0 {
+        3
+    } else if warnings > 0 {
+        2
+    } else {
+        i32::from(suggestions > 0)
+    })
+}
+
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() {
+    let cli = Cli::try_parse().unwrap_or_else(|e| {
+        let _ = e.print();
+        // Keep clap's usage-error code 2 from reading as "warnings found".
+        process::exit(if e.use_stderr() {
+            parquet_linter::EXIT_INTERNAL_ERROR
+        } else {
+            0
+        })
+    });
+    if let Err(e) = run(cli).await {
+        eprintln!("Error: {e:?}");
+        process::exit(parquet_linter::EXIT_INTERNAL_ERROR);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
         None => {
             let file = cli
                 .file
                 .ok_or_else(|| anyhow::anyhow!("missing FILE argument for check mode"))?;
+            match (cli.print_prescription, cli.format) {
+                (false, OutputFormat::Inline) => {
+                    anyhow::bail!("--format inline requires --print-prescription")
+                }
+                (
+                    true,
+                    OutputFormat::Github
+                    | OutputFormat::Json
+                    | OutputFormat::Jsonl
+                    | OutputFormat::Sarif,
+                ) => {
+                    anyhow::bail!("--print-prescription supports --format text or inline")
+                }
+                _ => {}
+            }
             let severity = cli.severity.unwrap_or(Severity::Suggestion);
             let rules = cli.rules;
             let export_prescription = cli.export_prescription;
+            let options = cli.lint.into_lint_options()?;
 
-            let (store, path) = parquet_linter::loader::parse(&file)?;
-            let diagnostics = parquet_linter::lint(store, path, rules.as_deref()).await?;
+            let mut builder = Linter::builder().options(options);
+            if let Some(rules) = rules {
+                builder = builder.rules(rules);
+            }
+            let cache_dir = cli
+                .cache_dir
+                .or_else(|| cli.cache.then(ResultCache::default_dir).flatten());
+            if let Some(dir) = &cache_dir {
+                builder = builder.cache(ResultCache::new(dir.clone()));
+            }
+            let linter = builder.build();
+            if loader::is_prefix(&file) {
+                if export_prescription.is_some()
+                    || cli.print_prescription
+                    || cli.assert_clean
+                    || cli.timings
+                {
+                    anyhow::bail!(
+                        "--export-prescription, --print-prescription, --assert-clean and \
+                         --timings take a single file, not a directory or prefix"
+                    );
+                }
+                let filter = |d: &diagnostic::Diagnostic| {
+                    d.severity >= severity
+                        && cli
+                            .category
+                            .as_ref()
+                            .is_none_or(|c| c.contains(&d.category))
+                };
+                let code = lint_prefix(&linter, &file, cli.jobs, cli.format, filter).await?;
+                process::exit(code);
+            }
+            let loaded = linter.open(&file).await?;
+            let summary = FileSummary::from_metadata(&loaded.metadata);
+            let diagnostics = if cli.timings {
+                // Timings describe a real run, so bypass the cache.
+                let (diagnostics, metrics) = linter.lint_file_with_metrics(&loaded).await?;
+                eprint!("{metrics}");
+                diagnostics
+            } else {
+                linter.lint_file_cached(&file, &loaded).await?
+            };
+            let diagnostics: Vec<_> = diagnostics
+                .into_iter()
+                .filter(|d| {
+                    cli.category
+                        .as_ref()
+                        .is_none_or(|c| c.contains(&d.category))
+                })
+                .collect();
             let filtered: Vec<_> = diagnostics
                 .iter()
                 .filter(|d| d.severity >= severity)
                 .collect();
 
-            if export_prescription.is_some() {
-                let mut prescription = Prescription::new();
-                for diagnostic in &filtered {
-                    prescription.extend(diagnostic.prescription.clone());
-                }
+            if export_prescription.is_some() || cli.print_prescription {
+                let prescription =
+                    FixPlan::new(filtered.iter().copied(), &loaded.metadata).prescription();
                 if let Err(conflict) = prescription.validate() {
                     let msg = format!(
                         "Prescription contains conflicting directives (exporting for review anyway): {conflict}"
                     );
-                    println!("{}", msg.yellow().bold());
+                    eprintln!("{}", msg.yellow().bold());
                 }
 
                 if let Some(path) = &export_prescription {
                     write_prescription(path, &prescription)?;
                 }
+                if cli.print_prescription {
+                    match cli.format {
+                        OutputFormat::Inline => println!("{}", prescription.to_inline()),
+                        _ => println!("{prescription}"),
+                    }
+                }
             }
 
-            if filtered.is_empty() {
-                println!("{}", "No issues found. ✓".green().bold());
-            } else {
-                for d in &filtered {
-                    d.print_colored();
-                    println!();
+            match cli.format {
+                _ if cli.print_prescription => {}
+                OutputFormat::Github => {
+                    for d in &filtered {
+                        println!("{}", d.to_github_annotation(&file));
+                    }
+                }
+                OutputFormat::Sarif => {
+                    println!("{}", sarif::report(&[(&file, filtered.clone())], &[]));
+                }
+                OutputFormat::Json | OutputFormat::Jsonl => {
+                    let mut report = diagnostic::json_report(&file, filtered.iter().copied());
+                    report["summary"] = summary.to_json();
+                    println!("{report}");
+                }
+                OutputFormat::Text | OutputFormat::Inline => {
+                    println!("{}\n", summary.to_string().dimmed());
+                    print_diagnostics(&filtered, cli.no_group);
                 }
-                let summary = format!("{} issue(s) found.", filtered.len());
-                println!("{}", summary.yellow().bold());
             }
 
-            if parquet_linter::has_warnings_or_errors(&diagnostics) {
-                process::exit(1);
+            if cli.assert_clean {
+                let mut unfixed: Vec<&str> = diagnostics
+                    .iter()
+                    .filter(|d| d.severity >= Severity::Warning && !d.prescription.is_empty())
+                    .map(|d| d.rule_name)
+                    .collect();
+                unfixed.sort();
+                unfixed.dedup();
+                if !unfixed.is_empty() {
+                    let msg = format!(
+                        "Not clean, fixes still prescribed by: {}",
+                        unfixed.join(", ")
+                    );
+                    eprintln!("{}", msg.red().bold());
+                    process::exit(1);
+                }
+            } else {
+                process::exit(parquet_linter::exit_code(filtered.iter().copied()));
             }
         }
         Some(Command::Rewrite {
             file,
             output,
+            in_place,
+            backup_suffix,
             rules,
             from_prescription,
+            prescription_text,
             dry_run,
             export_prescription,
+            export_plan,
+            lint,
         }) => {
             let file =
                 file.ok_or_else(|| anyhow::anyhow!("missing FILE argument for rewrite mode"))?;
-            let output =
-                output.ok_or_else(|| anyhow::anyhow!("missing --output for rewrite mode"))?;
+            let output = match output {
+                Some(output) => RewriteTarget::Output(output),
+                None if in_place => RewriteTarget::InPlace(fix::InPlaceRewrite {
+                    location: &file,
+                    backup_suffix: backup_suffix.as_deref(),
+                }),
+                None => anyhow::bail!("missing --output (or --in-place) for rewrite mode"),
+            };
 
-            if let Some(prescription_path) = from_prescription {
+            let given = match (from_prescription, prescription_text) {
+                (Some(path), _) if path.as_os_str() == "-" => {
+                    Some((read_prescription(&path)?, "stdin".to_string()))
+                }
+                (Some(path), _) => Some((read_prescription(&path)?, path.display().to_string())),
+                (None, Some(text)) => Some((
+                    DatasetPrescription::parse(&text)?,
+                    "--prescription-text".to_string(),
+                )),
+                (None, None) => None,
+            };
+            if let Some((dataset, source)) = given {
                 if rules.is_some() {
                     return Err(anyhow::anyhow!(
-                        "--rules cannot be used with --from-prescription"
+                        "--rules cannot be used with a given prescription"
                     ));
                 }
 
-                let prescription = read_prescription(&prescription_path)?;
+                let prescription = dataset.for_file(&file);
                 if prescription.is_empty() {
                     println!("{}", "No directives to apply. ✓".green().bold());
                     return Ok(());
@@ -165,69 +1040,300 @@ async fn main() -> Result<()> {
 
                 if dry_run {
                     let msg = format!(
-                        "Dry run: {} directive(s) loaded from {}:",
+                        "Dry run: {} directive(s) loaded from {source}:",
                         prescription.directives().len(),
-                        prescription_path.display()
                     );
                     println!("{}", msg.cyan().bold());
                     println!("{prescription}");
                 } else {
                     let (store, path) = parquet_linter::loader::parse(&file)?;
-                    parquet_linter::fix::rewrite(store, path, &output, &prescription).await?;
+                    let loaded = LoadedFile::open(store, path).await?;
+                    output.write(&loaded, &prescription).await?;
                     let msg = format!(
-                        "Applied {} directive(s) from {}, wrote {}",
+                        "Applied {} directive(s) from {source}, wrote {output}",
                         prescription.directives().len(),
-                        prescription_path.display(),
-                        output.display()
                     );
                     println!("{}", msg.green().bold());
                 }
             } else {
+                let options = lint.into_lint_options()?;
                 let (store, path) = parquet_linter::loader::parse(&file)?;
+                let loaded = LoadedFile::open(store, path).await?;
                 let diagnostics =
-                    parquet_linter::lint(store.clone(), path.clone(), rules.as_deref()).await?;
-                let mut prescription = Prescription::new();
-                for diagnostic in &diagnostics {
-                    prescription.extend(diagnostic.prescription.clone());
-                }
+                    parquet_linter::lint_file(&loaded, rules.as_deref(), &options).await?;
+                let plan = FixPlan::new(&diagnostics, &loaded.metadata);
 
-                if prescription.is_empty() {
+                if plan.is_empty() {
                     println!("{}", "No fixes to apply. ✓".green().bold());
                     return Ok(());
                 }
 
+                let prescription = plan.prescription();
                 warn_if_conflicting_for_apply(&prescription);
 
-                for diagnostic in &diagnostics {
-                    if diagnostic.prescription.is_empty() {
-                        continue;
-                    }
-                    diagnostic.print_colored();
-                    println!();
-                }
-
                 if let Some(path) = &export_prescription {
                     write_prescription(path, &prescription)?;
                 }
+                if let Some(path) = &export_plan {
+                    fs::write(path, format!("{:#}\n", plan.to_json()))?;
+                    let msg = format!("Wrote fix plan to {}", path.display());
+                    println!("{}", msg.cyan().bold());
+                }
 
                 if dry_run {
                     let msg = format!(
                         "Dry run: {} directive(s) would be applied:",
-                        prescription.directives().len()
+                        plan.steps.len()
                     );
                     println!("{}", msg.cyan().bold());
-                    println!("{prescription}");
+                    println!("{plan}");
                 } else {
-                    parquet_linter::fix::rewrite(store, path, &output, &prescription).await?;
-                    let msg = format!(
-                        "Applied {} directive(s), wrote {}",
-                        prescription.directives().len(),
-                        output.display()
-                    );
+                    println!("{plan}\n");
+                    output.write(&loaded, &prescription).await?;
+                    let msg = format!("Applied {} directive(s), wrote {output}", plan.steps.len());
                     println!("{}", msg.green().bold());
                 }
             }
         }
+        Some(Command::Apply {
+            files,
+            prescription,
+            output_dir,
+            dry_run,
+        }) => {
+            let dataset = read_prescription(&prescription)?;
+            for file in &files {
+                let prescription = dataset.for_file(file);
+                let section = dataset
+                    .section(file)
+                    .map_or("no file section".to_string(), |s| {
+                        format!("file \"{}\"", s.pattern)
+                    });
+                if prescription.is_empty() {
+                    println!("{file}: {}", format!("skipped ({section})").dimmed());
+                    continue;
+                }
+                warn_if_conflicting_for_apply(&prescription);
+                if dry_run {
+                    println!("{}", format!("{file}: {section}").cyan().bold());
+                    println!("{prescription}");
+                    continue;
+                }
+                let output = apply_output_path(&output_dir, file);
+                if let Some(parent) = output.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let (store, path) = parquet_linter::loader::parse(file)?;
+                parquet_linter::fix::rewrite(store, path, &output, &prescription).await?;
+                let msg = format!(
+                    "{file}: applied {} directive(s) ({section}), wrote {}",
+                    prescription.directives().len(),
+                    output.display()
+                );
+                println!("{}", msg.green().bold());
+            }
+        }
+        Some(Command::Fix {
+            input_dir,
+            output_dir,
+            prescription,
+            prescription_text,
+            jobs,
+        }) => {
+            let prescription = match (prescription, prescription_text) {
+                (Some(path), _) => read_prescription(&path)?,
+                (None, Some(text)) => DatasetPrescription::parse(&text)?,
+                (None, None) => unreachable!("clap requires a prescription"),
+            };
+            let reports = fix::DirRewrite {
+                input_dir: &input_dir,
+                output_dir: &output_dir,
+                prescription: &prescription,
+                jobs,
+            }
+            .run()
+            .await?;
+            let mut failed = 0;
+            for report in &reports {
+                let file = report.relative.display();
+                match &report.result {
+                    Ok(0) => println!("{file}: {}", "copied (no directives)".dimmed()),
+                    Ok(n) => println!("{file}: {}", format!("applied {n} directive(s)").green()),
+                    Err(e) => {
+                        failed += 1;
+                        println!("{file}: {}", format!("failed: {e:#}").red().bold());
+                    }
+                }
+            }
+            let msg = format!(
+                "{} file(s) written to {}, {failed} failed",
+                reports.len() - failed,
+                output_dir.display()
+            );
+            if failed > 0 {
+                println!("{}", msg.red().bold());
+                process::exit(parquet_linter::EXIT_INTERNAL_ERROR);
+            }
+            println!("{}", msg.green().bold());
+        }
+        Some(Command::Compact {
+            input_dir,
+            output_dir,
+            target_size,
+            prescription,
+            export_plan,
+            from_plan,
+            dry_run,
+        }) => {
+            let plan = match (from_plan, input_dir, output_dir) {
+                (Some(path), _, _) => {
+                    let value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+                    CompactionPlan::from_json(&value)?
+                }
+                (None, Some(input_dir), Some(output_dir)) => {
+                    let prescription = match &prescription {
+                        Some(path) => read_prescription(path)?,
+                        None => DatasetPrescription::default(),
+                    };
+                    compaction::CompactionPlanner {
+                        input_dir: &input_dir,
+                        output_dir: &output_dir,
+                        target_bytes: target_size,
+                        prescription: &prescription,
+                    }
+                    .plan()
+                    .await?
+                }
+                _ => anyhow::bail!("missing --input-dir/--output-dir or --from-plan"),
+            };
+            if let Some(path) = &export_plan {
+                fs::write(path, format!("{:#}\n", plan.to_json()))?;
+                let msg = format!("Wrote compaction plan to {}", path.display());
+                println!("{}", msg.cyan().bold());
+            }
+            if plan.batches.is_empty() {
+                println!("{}", "No small files to merge. ✓".green().bold());
+                return Ok(());
+            }
+            if dry_run {
+                let msg = format!(
+                    "Dry run: {} merged file(s) would be written:",
+                    plan.batches.len()
+                );
+                println!("{}", msg.cyan().bold());
+                println!("{plan}");
+                return Ok(());
+            }
+            let reports = plan.run().await;
+            let mut failed = 0;
+            for report in &reports {
+                let file = report.output.display();
+                match &report.result {
+                    Ok(rows) => println!("{file}: {}", format!("wrote {rows} rows").green()),
+                    Err(e) => {
+                        failed += 1;
+                        println!("{file}: {}", format!("failed: {e:#}").red().bold());
+                    }
+                }
+            }
+            let msg = format!(
+                "{} file(s) written to {}, {failed} failed",
+                reports.len() - failed,
+                plan.output_dir.display()
+            );
+            if failed > 0 {
+                println!("{}", msg.red().bold());
+                process::exit(parquet_linter::EXIT_INTERNAL_ERROR);
+            }
+            println!("{}", msg.green().bold());
+        }
+        Some(Command::Tune { file, output, lint }) => {
+            let options = lint.into_lint_options()?;
+            let (store, path) = parquet_linter::loader::parse(&file)?;
+            let loaded = LoadedFile::open(store, path).await?;
+            let report = tune::tune(&loaded, &options).await?;
+            print_tune_report(&report);
+            if let Some(path) = &output {
+                write_prescription(path, &report.best().prescription)?;
+            }
+        }
+        Some(Command::Iceberg {
+            location,
+            rules,
+            severity,
+            lint,
+        }) => {
+            let severity = severity.unwrap_or(Severity::Suggestion);
+            let options = lint.into_lint_options()?;
+            process::exit(lint_iceberg(&location, rules.as_deref(), severity, &options).await?);
+        }
+        Some(Command::Delta {
+            location,
+            rules,
+            severity,
+            lint,
+        }) => {
+            let severity = severity.unwrap_or(Severity::Suggestion);
+            let options = lint.into_lint_options()?;
+            process::exit(lint_delta(&location, rules.as_deref(), severity, &options).await?);
+        }
+        Some(Command::Compare { a, b }) => {
+            let mut metadata = Vec::new();
+            for location in [&a, &b] {
+                let (store, path) = parquet_linter::loader::parse(location)?;
+                metadata.push(LoadedFile::open(store, path).await?.metadata);
+            }
+            let diff = compare::writer_config_diff(&metadata[0], &metadata[1]);
+            if diff.is_empty() {
+                println!("{}", "Same writer configuration. ✓".green().bold());
+                return Ok(());
+            }
+            println!("{diff}");
+            process::exit(1);
+        }
+        Some(Command::Serve {
+            listen,
+            output_dir,
+            allow,
+            rules,
+            lint,
+        }) => {
+            let mut builder = Linter::builder().options(lint.into_lint_options()?);
+            if let Some(rules) = rules {
+                builder = builder.rules(rules);
+            }
+            let server = serve::Server {
+                linter: builder.build(),
+                output_dir,
+                allowed_roots: if allow.is_empty() {
+                    vec![serve::root_url(".")?]
+                } else {
+                    allow
+                        .iter()
+                        .map(|root| serve::root_url(root))
+                        .collect::<Result<_>>()?
+                },
+            };
+            // Lints are CPU-bound: serve them from worker threads rather
+            // than this single-threaded runtime.
+            tokio::task::spawn_blocking(move || server.run_blocking(listen)).await??;
+        }
+        Some(Command::RulesManifest) => {
+            println!("{:#}", parquet_linter::rules::rules_manifest());
+        }
+        Some(Command::DumpMetadata {
+            file,
+            anonymize,
+            output,
+        }) => {
+            let (store, path) = parquet_linter::loader::parse(&file)?;
+            let loaded = LoadedFile::open(store, path).await?;
+            let json = format!("{:#}\n", dump::metadata_json(&loaded.metadata, anonymize));
+            match output {
+                Some(output) => fs::write(output, json)?,
+                None => print!("{json}"),
+            }
+        }
     }
     Ok(())
 }
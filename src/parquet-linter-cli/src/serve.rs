@@ -0,0 +1,428 @@
+//! `parquet-linter serve`: the linter behind a small HTTP/1.1 API.
+//!
+//! - `GET /lint?url=...`: the `--format json` report
+//! - `GET /prescription?url=...`: the merged prescription DSL
+//! - `POST /rewrite?url=...&output=NAME`: rewrite into `--output-dir`, with
+//!   the request body as prescription or, when empty, the lint fixes
+//!
+//! Each connection serves one request and is closed. Requests must arrive
+//! within [`READ_TIMEOUT`] and be answered within [`REQUEST_TIMEOUT`], at
+//! most [`MAX_CONNECTIONS`] are served at once, and `url` must fall under
+//! one of the server's allowed roots. The server runs on its own
+//! multi-threaded runtime, so a long lint does not hold up the others.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use colored::Colorize;
+use parquet_linter::Linter;
+use parquet_linter::diagnostic;
+use parquet_linter::fix;
+use parquet_linter::plan::FixPlan;
+use parquet_linter::prescription::Prescription;
+use parquet_linter::summary::FileSummary;
+use serde_json::json;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use url::Url;
+
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// How long a client may take to send its whole request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long linting or rewriting one file may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Connections served concurrently; further ones wait to be accepted.
+const MAX_CONNECTIONS: usize = 64;
+
+pub struct Server {
+    pub linter: Linter,
+    /// Where `/rewrite` writes; rewrites are refused without it.
+    pub output_dir: Option<PathBuf>,
+    /// URL prefixes requests may read from, built with [`root_url`].
+    pub allowed_roots: Vec<Url>,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: format!("{value}\n"),
+        }
+    }
+
+    fn text(body: String) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/plain; charset=utf-8",
+            body,
+        }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self::json(status, json!({ "error": message.to_string() }))
+    }
+}
+
+/// A request the client got wrong, answered with 400 instead of 500.
+#[derive(Debug)]
+struct BadRequest(String);
+
+impl std::fmt::Display for BadRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for BadRequest {}
+
+fn bad_request(message: impl Into<String>) -> anyhow::Error {
+    BadRequest(message.into()).into()
+}
+
+impl Server {
+    /// Serve on a new multi-threaded runtime until accepting fails. Blocks
+    /// the calling thread.
+    pub fn run_blocking(self, listen: SocketAddr) -> Result<()> {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?
+            .block_on(self.run(listen))
+    }
+
+    async fn run(self, listen: SocketAddr) -> Result<()> {
+        let listener = TcpListener::bind(listen)
+            .await
+            .with_context(|| format!("failed to listen on {listen}"))?;
+        let msg = format!("Listening on http://{}", listener.local_addr()?);
+        eprintln!("{}", msg.cyan().bold());
+        let server = Arc::new(self);
+        let permits = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        loop {
+            let permit = permits.clone().acquire_owned().await?;
+            let (stream, peer) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.connection(stream).await {
+                    eprintln!("{peer}: {e:#}");
+                }
+                drop(permit);
+            });
+        }
+    }
+
+    async fn connection(&self, mut stream: TcpStream) -> Result<()> {
+        let response = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+            Ok(Ok(request)) => {
+                eprintln!("{} {}", request.method, request.path);
+                tokio::time::timeout(REQUEST_TIMEOUT, self.respond(&request))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Response::error(503, "request took too long and was abandoned")
+                    })
+            }
+            Ok(Err(e)) => Response::error(400, format!("{e:#}")),
+            Err(_) => Response::error(408, "request not received in time"),
+        };
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            response.status,
+            reason(response.status),
+            response.content_type,
+            response.body.len(),
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(response.body.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    async fn respond(&self, request: &Request) -> Response {
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/lint") => self.lint(request).await,
+            ("GET", "/prescription") => self.prescription(request).await,
+            ("POST", "/rewrite") => self.rewrite(request).await,
+            (_, "/lint" | "/prescription" | "/rewrite") => {
+                return Response::error(405, "method not allowed");
+            }
+            _ => return Response::error(404, format!("no endpoint {}", request.path)),
+        };
+        result.unwrap_or_else(|e| {
+            let status = if e.is::<BadRequest>() { 400 } else { 500 };
+            Response::error(status, format!("{e:#}"))
+        })
+    }
+
+    /// The `url` parameter, resolved and checked against the allowed roots.
+    fn source(&self, request: &Request) -> Result<Url> {
+        let location = request.param("url")?;
+        let url = match Url::parse(location) {
+            Ok(url) => url,
+            Err(url::ParseError::RelativeUrlWithoutBase) => {
+                let path = Path::new(location)
+                    .canonicalize()
+                    .map_err(|_| bad_request(format!("file not found: {location}")))?;
+                Url::from_file_path(&path)
+                    .map_err(|_| bad_request(format!("invalid file path: {location}")))?
+            }
+            Err(e) => return Err(bad_request(format!("invalid url {location:?}: {e}"))),
+        };
+        if !self
+            .allowed_roots
+            .iter()
+            .any(|root| url.as_str().starts_with(root.as_str()))
+        {
+            return Err(bad_request(format!(
+                "url {location:?} is outside the allowed roots"
+            )));
+        }
+        Ok(url)
+    }
+
+    async fn lint(&self, request: &Request) -> Result<Response> {
+        let url = request.param("url")?;
+        let file = self.linter.open(self.source(request)?.as_str()).await?;
+        let diagnostics = self.linter.lint_file(&file).await?;
+        let mut report = diagnostic::json_report(url, &diagnostics);
+        report["summary"] = FileSummary::from_metadata(&file.metadata).to_json();
+        Ok(Response::json(200, report))
+    }
+
+    async fn prescription(&self, request: &Request) -> Result<Response> {
+        let file = self.linter.open(self.source(request)?.as_str()).await?;
+        let diagnostics = self.linter.lint_file(&file).await?;
+        let prescription = FixPlan::new(&diagnostics, &file.metadata).prescription();
+        Ok(Response::text(format!("{prescription}\n")))
+    }
+
+    async fn rewrite(&self, request: &Request) -> Result<Response> {
+        let Some(output_dir) = &self.output_dir else {
+            return Ok(Response::error(
+                403,
+                "rewrites are disabled; start the server with --output-dir",
+            ));
+        };
+        let source = self.source(request)?;
+        let output = output_dir.join(relative_output(request.param("output")?)?);
+        let file = self.linter.open(source.as_str()).await?;
+        let body = std::str::from_utf8(&request.body)
+            .map_err(|_| bad_request("prescription body is not UTF-8"))?;
+        let prescription = if body.trim().is_empty() {
+            let diagnostics = self.linter.lint_file(&file).await?;
+            FixPlan::new(&diagnostics, &file.metadata).prescription()
+        } else {
+            Prescription::parse(body).map_err(|e| bad_request(e.to_string()))?
+        };
+        prescription
+            .validate()
+            .map_err(|e| bad_request(e.to_string()))?;
+        if let Some(parent) = output.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        fix::rewrite_file(&file, &output, &prescription).await?;
+        Ok(Response::json(
+            200,
+            json!({
+                "output": output.display().to_string(),
+                "prescription": prescription.to_string(),
+            }),
+        ))
+    }
+}
+
+impl Request {
+    fn param(&self, name: &str) -> Result<&str> {
+        self.query
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| bad_request(format!("missing query parameter `{name}`")))
+    }
+}
+
+/// A root for [`Server::allowed_roots`]: a URL or local directory, ending in
+/// `/` so that `s3://bucket/data` does not also admit `s3://bucket/database`.
+pub fn root_url(root: &str) -> Result<Url> {
+    let mut url = match Url::parse(root) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            let path = Path::new(root)
+                .canonicalize()
+                .with_context(|| format!("directory not found: {root}"))?;
+            Url::from_directory_path(&path)
+                .map_err(|_| anyhow!("invalid directory path: {root}"))?
+        }
+        Err(e) => return Err(e).with_context(|| format!("invalid url {root:?}")),
+    };
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
+/// `output` as a path that stays inside the output directory.
+fn relative_output(output: &str) -> Result<&Path> {
+    let path = Path::new(output);
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(bad_request(format!(
+            "output {output:?} must be a relative path without `..`"
+        )));
+    }
+    Ok(path)
+}
+
+async fn read_request(stream: &mut (impl AsyncRead + Unpin)) -> Result<Request> {
+    let mut buf = Vec::new();
+    let header_len = loop {
+        let mut chunk = [0u8; 8192];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("connection closed before the request was complete");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            bail!("request headers exceed {MAX_HEADER_BYTES} bytes");
+        }
+    };
+
+    let mut headers = [httparse::EMPTY_HEADER; 64];
+    let mut parsed = httparse::Request::new(&mut headers);
+    parsed.parse(&buf[..header_len])?;
+    let method = parsed.method.ok_or_else(|| anyhow!("missing method"))?;
+    let target = parsed.path.ok_or_else(|| anyhow!("missing path"))?;
+    let content_length = match parsed
+        .headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case("content-length"))
+    {
+        Some(h) => std::str::from_utf8(h.value)?.trim().parse::<usize>()?,
+        None => 0,
+    };
+    if content_length > MAX_BODY_BYTES {
+        bail!("request body exceeds {MAX_BODY_BYTES} bytes");
+    }
+
+    let mut body = buf[header_len..].to_vec();
+    while body.len() < content_length {
+        let mut chunk = vec![0u8; content_length - body.len()];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("connection closed before the body was complete");
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        body,
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn parse(raw: &[u8]) -> Result<Request> {
+        read_request(&mut &raw[..]).await
+    }
+
+    #[tokio::test]
+    async fn parses_method_path_query_and_body() -> Result<()> {
+        let request = parse(
+            b"POST /rewrite?url=s3%3A%2F%2Fb%2Fa.parquet&output=x.parquet HTTP/1.1\r\n\
+              Content-Length: 11\r\n\r\nset file xy",
+        )
+        .await?;
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/rewrite");
+        assert_eq!(request.param("url")?, "s3://b/a.parquet");
+        assert_eq!(request.param("output")?, "x.parquet");
+        assert_eq!(request.body, b"set file xy");
+        assert!(request.param("missing").unwrap_err().is::<BadRequest>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_and_truncated_requests() {
+        let huge = format!(
+            "GET /lint?url={} HTTP/1.1\r\n",
+            "a".repeat(MAX_HEADER_BYTES)
+        );
+        assert!(parse(huge.as_bytes()).await.is_err());
+        let body = format!(
+            "POST /rewrite HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        assert!(parse(body.as_bytes()).await.is_err());
+        assert!(
+            parse(b"POST /rewrite HTTP/1.1\r\nContent-Length: 5\r\n\r\nab")
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn relative_output_rejects_escapes() {
+        assert!(relative_output("out/a.parquet").is_ok());
+        assert!(relative_output("./a.parquet").is_ok());
+        for escape in ["", "../a.parquet", "out/../../a.parquet", "/tmp/a.parquet"] {
+            assert!(relative_output(escape).is_err(), "{escape:?}");
+        }
+    }
+
+    #[test]
+    fn roots_match_whole_path_segments() -> Result<()> {
+        let root = root_url("s3://bucket/data")?;
+        assert_eq!(root.as_str(), "s3://bucket/data/");
+        assert!("s3://bucket/data/a.parquet".starts_with(root.as_str()));
+        assert!(!"s3://bucket/database/a.parquet".starts_with(root.as_str()));
+        // Dot segments are resolved before the prefix check.
+        let escaped = Url::parse("s3://bucket/data/../secret/a.parquet")?;
+        assert!(!escaped.as_str().starts_with(root.as_str()));
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use anyhow::{Result, bail};
+use parquet_linter::diagnostic::{Diagnostic, Location};
+use serde_json::{Value, json};
+
+/// Version of the baseline file layout.
+const BASELINE_VERSION: u64 = 1;
+
+/// Diagnostics of every corpus file, keyed by path relative to the corpus.
+#[derive(Default)]
+pub struct CorpusResults {
+    files: BTreeMap<String, FileResult>,
+}
+
+enum FileResult {
+    Diagnostics(Vec<Value>),
+    Error(String),
+}
+
+impl CorpusResults {
+    pub fn record(&mut self, file: String, diagnostics: &[Diagnostic]) {
+        let diagnostics = diagnostics.iter().map(Diagnostic::to_json).collect();
+        self.files
+            .insert(file, FileResult::Diagnostics(diagnostics));
+    }
+
+    pub fn record_error(&mut self, file: String, error: &anyhow::Error) {
+        self.files
+            .insert(file, FileResult::Error(format!("{error:#}")));
+    }
+
+    pub fn to_json(&self) -> Value {
+        let files: serde_json::Map<String, Value> = self
+            .files
+            .iter()
+            .map(|(file, result)| {
+                let value = match result {
+                    FileResult::Diagnostics(diagnostics) => json!({ "diagnostics": diagnostics }),
+                    FileResult::Error(error) => json!({ "error": error }),
+                };
+                (file.clone(), value)
+            })
+            .collect();
+        json!({
+            "baseline_version": BASELINE_VERSION,
+            "files": files,
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self> {
+        if value["baseline_version"].as_u64() != Some(BASELINE_VERSION) {
+            bail!("unsupported baseline version {}", value["baseline_version"]);
+        }
+        let Some(entries) = value["files"].as_object() else {
+            bail!("baseline has no `files` object");
+        };
+        let mut files = BTreeMap::new();
+        for (file, entry) in entries {
+            let result = match (entry["diagnostics"].as_array(), entry["error"].as_str()) {
+                (Some(diagnostics), _) => FileResult::Diagnostics(diagnostics.clone()),
+                (None, Some(error)) => FileResult::Error(error.to_string()),
+                (None, None) => {
+                    bail!("baseline entry for {file} has neither diagnostics nor error")
+                }
+            };
+            files.insert(file.clone(), result);
+        }
+        Ok(Self { files })
+    }
+
+    /// How `current` classifies each file differently from `self`.
+    pub fn diff(&self, current: &CorpusResults) -> CorpusDiff {
+        let names: BTreeSet<&String> = self.files.keys().chain(current.files.keys()).collect();
+        let mut files = BTreeMap::new();
+        for name in names {
+            let before = self.files.get(name).map(FileResult::findings);
+            let after = current.files.get(name).map(FileResult::findings);
+            let change = match (before, after) {
+                (None, _) => FileChange::Added,
+                (_, None) => FileChange::Removed,
+                (Some(before), Some(after)) => {
+                    let removed: Vec<String> = before.difference(&after).cloned().collect();
+                    let added: Vec<String> = after.difference(&before).cloned().collect();
+                    if removed.is_empty() && added.is_empty() {
+                        continue;
+                    }
+                    FileChange::Changed { removed, added }
+                }
+            };
+            files.insert(name.clone(), change);
+        }
+        CorpusDiff { files }
+    }
+}
+
+impl FileResult {
+    /// What the comparison looks at: rule, severity, location and fix of
+    /// each diagnostic. Messages carry measured numbers and may be reworded,
+    /// so they are left out.
+    fn findings(&self) -> BTreeSet<String> {
+        match self {
+            FileResult::Error(error) => BTreeSet::from([format!("error: {error}")]),
+            FileResult::Diagnostics(diagnostics) => diagnostics.iter().map(finding).collect(),
+        }
+    }
+}
+
+fn finding(diagnostic: &Value) -> String {
+    let location = match Location::from_json(&diagnostic["location"]) {
+        Some(location) => location.to_string(),
+        None => diagnostic["location"].to_string(),
+    };
+    let mut line = format!(
+        "[{}] {} @ {location}",
+        diagnostic["severity"].as_str().unwrap_or("?"),
+        diagnostic["rule_name"].as_str().unwrap_or("?"),
+    );
+    let fixes: Vec<&str> = diagnostic["directives"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d["text"].as_str())
+        .collect();
+    if !fixes.is_empty() {
+        line.push_str(&format!(" fix: {}", fixes.join("; ")));
+    }
+    line
+}
+
+pub struct CorpusDiff {
+    pub files: BTreeMap<String, FileChange>,
+}
+
+pub enum FileChange {
+    /// In this run, not in the baseline.
+    Added,
+    /// In the baseline, not found in this run.
+    Removed,
+    Changed {
+        removed: Vec<String>,
+        added: Vec<String>,
+    },
+}
+
+impl CorpusDiff {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl fmt::Display for CorpusDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (file, change) in &self.files {
+            match change {
+                FileChange::Added => writeln!(f, "{file}: not in the baseline")?,
+                FileChange::Removed => {
+                    writeln!(f, "{file}: in the baseline, missing from the corpus")?
+                }
+                FileChange::Changed { removed, added } => {
+                    writeln!(f, "{file}")?;
+                    for line in removed {
+                        writeln!(f, "  - {line}")?;
+                    }
+                    for line in added {
+                        writeln!(f, "  + {line}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
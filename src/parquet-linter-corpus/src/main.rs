@@ -0,0 +1,107 @@
+mod baseline;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use anyhow::{Context, Result, ensure};
+use clap::Parser;
+use colored::Colorize;
+use parquet_linter::Linter;
+use parquet_linter::fix::parquet_files_below;
+
+use crate::baseline::CorpusResults;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "parquet-linter-corpus",
+    about = "Lint every parquet file in a local corpus and diff the diagnostics against a baseline"
+)]
+struct Cli {
+    /// Directory of parquet files, searched recursively
+    corpus: PathBuf,
+
+    /// Baseline JSON to compare with
+    #[arg(long, value_name = "FILE")]
+    baseline: PathBuf,
+
+    /// Write this run's results to --baseline instead of comparing
+    #[arg(long)]
+    update: bool,
+
+    /// Also write this run's results as JSON
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Only run specific rules (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    rules: Option<Vec<String>>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let files = parquet_files_below(&cli.corpus)?;
+    ensure!(
+        !files.is_empty(),
+        "no parquet files found below {}",
+        cli.corpus.display()
+    );
+
+    let mut builder = Linter::builder();
+    if let Some(rules) = cli.rules {
+        builder = builder.rules(rules);
+    }
+    let linter = builder.build();
+
+    let mut results = CorpusResults::default();
+    for (i, relative) in files.iter().enumerate() {
+        let name = relative.to_string_lossy().replace('\\', "/");
+        eprintln!("[{}/{}] {name}", i + 1, files.len());
+        let path = cli.corpus.join(relative);
+        let location = path.to_str().context("corpus path is not valid UTF-8")?;
+        match linter.lint(location).await {
+            Ok(diagnostics) => results.record(name, &diagnostics),
+            Err(e) => results.record_error(name, &e),
+        }
+    }
+
+    let json = format!("{:#}\n", results.to_json());
+    if let Some(output) = &cli.output {
+        fs::write(output, &json)
+            .with_context(|| format!("failed to write {}", output.display()))?;
+    }
+    if cli.update {
+        fs::write(&cli.baseline, &json)
+            .with_context(|| format!("failed to write {}", cli.baseline.display()))?;
+        let msg = format!(
+            "Wrote baseline for {} files to {}",
+            files.len(),
+            cli.baseline.display()
+        );
+        println!("{}", msg.green().bold());
+        return Ok(());
+    }
+
+    let text = fs::read_to_string(&cli.baseline).with_context(|| {
+        format!(
+            "failed to read {} (create it with --update)",
+            cli.baseline.display()
+        )
+    })?;
+    let baseline = CorpusResults::from_json(&serde_json::from_str(&text)?)?;
+    let diff = baseline.diff(&results);
+    if diff.is_empty() {
+        let msg = format!("{} files match the baseline. ✓", files.len());
+        println!("{}", msg.green().bold());
+        return Ok(());
+    }
+    print!("{diff}");
+    let msg = format!(
+        "{} of {} files changed classification",
+        diff.files.len(),
+        files.len()
+    );
+    println!("{}", msg.yellow().bold());
+    process::exit(1);
+}
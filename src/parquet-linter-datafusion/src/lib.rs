@@ -0,0 +1,128 @@
+//! Derive parquet-linter [`Workload`] hints from DataFusion queries, so
+//! prescriptions (bloom filters, sorting, statistics) follow the columns that
+//! queries actually project, filter and join on.
+//!
+//! ```no_run
+//! # async fn example() -> datafusion::error::Result<()> {
+//! let workload = parquet_linter_datafusion::workload_for_file(
+//!     "events.parquet",
+//!     "events",
+//!     &["SELECT payload FROM events WHERE event_date = '2024-01-01'"],
+//! )
+//! .await?;
+//! let options = parquet_linter::options::LintOptions {
+//!     workload,
+//!     ..Default::default()
+//! };
+//! # Ok(())
+//! # }
+//! ```
+
+use datafusion::common::tree_node::TreeNodeRecursion;
+use datafusion::error::Result;
+use datafusion::logical_expr::{Expr, LogicalPlan};
+use datafusion::prelude::{ParquetReadOptions, SessionContext};
+use parquet_linter::options::Workload;
+
+/// Register the Parquet file at `path` as `table` and extract the workload of
+/// `queries` run against it.
+pub async fn workload_for_file(path: &str, table: &str, queries: &[&str]) -> Result<Workload> {
+    let ctx = SessionContext::new();
+    ctx.register_parquet(table, path, ParquetReadOptions::default())
+        .await?;
+    workload_from_sql(&ctx, queries.iter().copied()).await
+}
+
+/// Plan each query against `ctx`, whose tables must already be registered,
+/// and merge the columns they touch.
+pub async fn workload_from_sql<'a>(
+    ctx: &SessionContext,
+    queries: impl IntoIterator<Item = &'a str>,
+) -> Result<Workload> {
+    let mut workload = Workload::default();
+    for sql in queries {
+        // The optimized plan has projections and filters pushed into scans,
+        // which is what the reader will actually do.
+        let plan = ctx.sql(sql).await?.into_optimized_plan()?;
+        workload.extend(workload_from_plan(&plan)?);
+    }
+    Ok(workload)
+}
+
+/// Columns a logical plan (including subqueries) reads, filters on and joins
+/// on. Columns of every scanned table are reported by name; names that are
+/// not in the linted file simply never match.
+pub fn workload_from_plan(plan: &LogicalPlan) -> Result<Workload> {
+    let mut workload = Workload::default();
+    plan.apply_with_subqueries(|node| {
+        match node {
+            LogicalPlan::TableScan(scan) => {
+                let projected = scan.projected_schema.fields().iter();
+                push_unique(
+                    &mut workload.projection_columns,
+                    projected.map(|f| f.name().clone()),
+                );
+                for filter in &scan.filters {
+                    push_columns(&mut workload.filter_columns, filter);
+                }
+            }
+            LogicalPlan::Filter(filter) => {
+                push_columns(&mut workload.filter_columns, &filter.predicate);
+            }
+            LogicalPlan::Join(join) => {
+                for (left, right) in &join.on {
+                    push_columns(&mut workload.join_keys, left);
+                    push_columns(&mut workload.join_keys, right);
+                }
+                if let Some(filter) = &join.filter {
+                    push_columns(&mut workload.filter_columns, filter);
+                }
+            }
+            _ => {}
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(workload)
+}
+
+fn push_columns(names: &mut Vec<String>, expr: &Expr) {
+    push_unique(
+        names,
+        expr.column_refs().into_iter().map(|c| c.name.clone()),
+    );
+}
+
+fn push_unique(names: &mut Vec<String>, new: impl IntoIterator<Item = String>) {
+    for name in new {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{ArrayRef, Int64Array, RecordBatch};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn sql_columns_map_to_workload_kinds() -> Result<()> {
+        let column = || Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef;
+        let batch =
+            RecordBatch::try_from_iter([("a", column()), ("b", column()), ("c", column())])?;
+        let ctx = SessionContext::new();
+        ctx.register_batch("t", batch.clone())?;
+        ctx.register_batch("u", batch)?;
+
+        let workload = workload_from_sql(
+            &ctx,
+            ["SELECT t.c FROM t JOIN u ON t.a = u.a WHERE t.b > 1"],
+        )
+        .await?;
+        assert!(workload.filter_columns.contains(&"b".to_string()));
+        assert_eq!(workload.join_keys, ["a"]);
+        assert!(workload.projection_columns.contains(&"c".to_string()));
+        Ok(())
+    }
+}
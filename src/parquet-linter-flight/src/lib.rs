@@ -0,0 +1,239 @@
+//! Arrow Flight service for parquet-linter. A client sends a ticket naming a
+//! file and receives one row per diagnostic:
+//!
+//! - `DoGet` with ticket `s3://bucket/file.parquet` (or JSON
+//!   `{"url": "...", "rules": ["..."]}`) streams the diagnostics batch.
+//! - `DoAction` of type `prescription` with the same ticket as body returns
+//!   the merged prescription DSL of all diagnostics.
+
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, StringArray};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use parquet_linter::diagnostic::Diagnostic;
+use parquet_linter::loader::LoadedFile;
+use parquet_linter::options::LintOptions;
+use parquet_linter::prescription::Prescription;
+use tonic::{Request, Response, Status, Streaming};
+
+pub use arrow_flight::flight_service_server::FlightServiceServer;
+
+const PRESCRIPTION_ACTION: &str = "prescription";
+
+/// What to lint, decoded from a ticket or action body.
+struct LintRequest {
+    url: String,
+    rules: Option<Vec<String>>,
+}
+
+impl LintRequest {
+    fn decode(bytes: &[u8]) -> Result<Self, Status> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| Status::invalid_argument("ticket must be UTF-8"))?
+            .trim();
+        if !text.starts_with('{') {
+            return Ok(Self {
+                url: text.to_string(),
+                rules: None,
+            });
+        }
+        let value: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| Status::invalid_argument(format!("invalid ticket: {e}")))?;
+        let url = value["url"]
+            .as_str()
+            .ok_or_else(|| Status::invalid_argument("ticket is missing url"))?;
+        let rules = value["rules"].as_array().map(|rules| {
+            rules
+                .iter()
+                .filter_map(|r| r.as_str().map(str::to_string))
+                .collect()
+        });
+        Ok(Self {
+            url: url.to_string(),
+            rules,
+        })
+    }
+
+    #[tracing::instrument(name = "lint_request", skip_all, fields(url = %self.url))]
+    async fn run(&self, options: &LintOptions) -> Result<Vec<Diagnostic>, Status> {
+        let lint = async {
+            let (store, path) = parquet_linter::loader::parse(&self.url)?;
+            let file = LoadedFile::open(store, path).await?;
+            parquet_linter::lint_file(&file, self.rules.as_deref(), options).await
+        };
+        lint.await
+            .map_err(|e| Status::failed_precondition(format!("{e:#}")))
+    }
+}
+
+/// Schema of the batches returned by `DoGet`.
+pub fn diagnostics_schema() -> SchemaRef {
+    let field = |name| Field::new(name, DataType::Utf8, false);
+    Arc::new(Schema::new(vec![
+        field("rule_name"),
+        field("severity"),
+        field("category"),
+        field("location"),
+        field("message"),
+        // Prescription DSL, one directive per line.
+        field("prescription"),
+    ]))
+}
+
+/// One row per diagnostic, in [`diagnostics_schema`].
+pub fn diagnostics_batch(diagnostics: &[Diagnostic]) -> RecordBatch {
+    let column = |f: &dyn Fn(&Diagnostic) -> String| {
+        Arc::new(StringArray::from_iter_values(diagnostics.iter().map(f))) as _
+    };
+    RecordBatch::try_new(
+        diagnostics_schema(),
+        vec![
+            column(&|d| d.rule_name.to_string()),
+            column(&|d| d.severity.to_string()),
+            column(&|d| d.category.to_string()),
+            column(&|d| d.location.to_string()),
+            column(&|d| d.message.clone()),
+            column(&|d| d.prescription.to_string()),
+        ],
+    )
+    .expect("columns match the diagnostics schema")
+}
+
+#[derive(Default)]
+pub struct LintService {
+    pub options: LintOptions,
+}
+
+#[tonic::async_trait]
+impl FlightService for LintService {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let lint = LintRequest::decode(&request.get_ref().ticket)?;
+        let batch = diagnostics_batch(&lint.run(&self.options).await?);
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(diagnostics_schema())
+            .build(stream::once(async { Ok(batch) }))
+            .map_err(Status::from);
+        Ok(Response::new(stream.boxed()))
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        let action = request.get_ref();
+        if action.r#type != PRESCRIPTION_ACTION {
+            return Err(Status::unimplemented(format!(
+                "unknown action '{}'",
+                action.r#type
+            )));
+        }
+        let lint = LintRequest::decode(&action.body)?;
+        let mut prescription = Prescription::new();
+        for d in lint.run(&self.options).await? {
+            prescription.extend(d.prescription);
+        }
+        let result = arrow_flight::Result {
+            body: prescription.to_string().into(),
+        };
+        Ok(Response::new(stream::once(async { Ok(result) }).boxed()))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        let action = ActionType {
+            r#type: PRESCRIPTION_ACTION.to_string(),
+            description: "Merged prescription DSL for the file named in the body".to_string(),
+        };
+        Ok(Response::new(stream::once(async { Ok(action) }).boxed()))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        let options = arrow_flight::IpcWriteOptions::default();
+        let result = SchemaAsIpc::new(&diagnostics_schema(), &options)
+            .try_into()
+            .map_err(|e: arrow_schema::ArrowError| Status::internal(e.to_string()))?;
+        Ok(Response::new(result))
+    }
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info"))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented("poll_flight_info"))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tickets_accept_plain_urls_and_json() {
+        let plain = LintRequest::decode(b"s3://bucket/a.parquet").unwrap();
+        assert_eq!(plain.url, "s3://bucket/a.parquet");
+        assert!(plain.rules.is_none());
+
+        let json = LintRequest::decode(br#"{"url": "a.parquet", "rules": ["page-size"]}"#).unwrap();
+        assert_eq!(json.url, "a.parquet");
+        assert_eq!(json.rules.unwrap(), ["page-size"]);
+    }
+}
@@ -0,0 +1,51 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+use parquet_linter_flight::{FlightServiceServer, LintService};
+
+/// Serve parquet-linter diagnostics over Arrow Flight
+#[derive(Parser)]
+#[command(name = "parquet-linter-flight")]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "0.0.0.0:50051")]
+    listen: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    #[cfg(feature = "otel")]
+    let provider = init_tracing()?;
+    println!("Serving Arrow Flight on {}", cli.listen);
+    tonic::transport::Server::builder()
+        .add_service(FlightServiceServer::new(LintService::default()))
+        .serve(cli.listen)
+        .await?;
+    #[cfg(feature = "otel")]
+    provider.shutdown()?;
+    Ok(())
+}
+
+/// Send spans to the OTLP collector named by `OTEL_EXPORTER_OTLP_ENDPOINT`.
+#[cfg(feature = "otel")]
+fn init_tracing() -> anyhow::Result<opentelemetry_sdk::trace::SdkTracerProvider> {
+    use opentelemetry::trace::TracerProvider as _;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .build()?;
+    let resource = opentelemetry_sdk::Resource::builder()
+        .with_service_name("parquet-linter")
+        .build();
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource)
+        .build();
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("parquet-linter")))
+        .init();
+    Ok(provider)
+}
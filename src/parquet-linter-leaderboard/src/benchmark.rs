@@ -3,40 +3,239 @@ use std::fs::File;
 use std::path::Path;
 use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use parquet::arrow::ProjectionMask;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::file::reader::ChunkReader;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkOptions {
+    pub batch_size: usize,
+    /// Timed reads per file.
+    pub iterations: usize,
+    /// Untimed reads before the timed ones, to warm the page cache, the
+    /// allocator and the CPU frequency.
+    pub warmup: usize,
+    /// Read the file into memory once and decode from there, so disk and
+    /// page cache noise stay out of the timings.
+    pub in_memory: bool,
+    /// Also time each top-level column on its own.
+    pub per_column: bool,
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct Measurement {
+    /// Median decode time over the timed iterations.
     pub loading_time_ms: f64,
+    pub min_loading_time_ms: f64,
+    pub stddev_loading_time_ms: f64,
     pub file_size_mb: f64,
     pub cost: f64,
+    pub num_rows: usize,
+    /// Uncompressed column chunk bytes from the footer, in MB.
+    pub decompressed_mb: f64,
 }
 
-pub fn measure(path: &Path, batch_size: usize, iterations: usize) -> Result<Measurement> {
-    let iterations = iterations.max(1);
-    let mut best_loading_time_ms = f64::INFINITY;
+impl Measurement {
+    pub fn rows_per_sec(&self) -> f64 {
+        per_sec(self.num_rows as f64, self.loading_time_ms)
+    }
 
-    for _ in 0..iterations {
-        let input = File::open(path)?;
-        let builder = ParquetRecordBatchReaderBuilder::try_new(input)?;
-        let reader = builder.with_batch_size(batch_size).build()?;
+    pub fn decompressed_mb_per_sec(&self) -> f64 {
+        per_sec(self.decompressed_mb, self.loading_time_ms)
+    }
 
-        let start = Instant::now();
-        let mut total_rows = 0usize;
-        for batch in reader {
-            total_rows += batch?.num_rows();
+    /// Standard deviation relative to the median, in percent.
+    pub fn relative_stddev_pct(&self) -> f64 {
+        if self.loading_time_ms == 0.0 {
+            0.0
+        } else {
+            self.stddev_loading_time_ms / self.loading_time_ms * 100.0
         }
-        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
-        let _ = total_rows;
+    }
+}
+
+/// Decode time and size of one top-level column, read on its own.
+#[derive(Debug, Clone)]
+pub struct ColumnMeasurement {
+    pub name: String,
+    /// Median decode time over the timed iterations.
+    pub loading_time_ms: f64,
+    /// Column chunk bytes on disk over all row groups, in MB.
+    pub compressed_mb: f64,
+    pub decompressed_mb: f64,
+}
 
-        best_loading_time_ms = best_loading_time_ms.min(elapsed_ms);
+pub fn per_sec(amount: f64, time_ms: f64) -> f64 {
+    if time_ms == 0.0 {
+        0.0
+    } else {
+        amount / (time_ms / 1000.0)
     }
+}
 
+/// Times full decodes of `path`. The reads run on a dedicated thread, away
+/// from the async runtime that downloads and rewrites files.
+pub fn measure(path: &Path, options: &BenchmarkOptions) -> Result<Measurement> {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .name("benchmark".to_string())
+            .spawn_scoped(scope, || measure_on_this_thread(path, options))?
+            .join()
+            .map_err(|_| anyhow!("benchmark thread panicked"))?
+    })
+}
+
+fn measure_on_this_thread(path: &Path, options: &BenchmarkOptions) -> Result<Measurement> {
     let file_size_mb = fs::metadata(path)?.len() as f64 / (1024.0 * 1024.0);
+    let in_memory = if options.in_memory {
+        Some(Bytes::from(fs::read(path)?))
+    } else {
+        None
+    };
+    let read = || match &in_memory {
+        Some(bytes) => decode(bytes.clone(), options.batch_size, None),
+        None => decode(File::open(path)?, options.batch_size, None),
+    };
+    let (decoded, mut times_ms) = repeat(options, read)?;
+
+    let (median, min, stddev) = summarize(&mut times_ms);
     Ok(Measurement {
-        loading_time_ms: best_loading_time_ms,
+        loading_time_ms: median,
+        min_loading_time_ms: min,
+        stddev_loading_time_ms: stddev,
         file_size_mb,
-        cost: best_loading_time_ms + file_size_mb,
+        cost: median + file_size_mb,
+        num_rows: decoded.num_rows,
+        decompressed_mb: decoded.decompressed_mb,
     })
 }
+
+/// Times decodes of each top-level column of `path` on its own, so the
+/// file's decode time can be attributed to the columns that spend it.
+pub fn measure_columns(path: &Path, options: &BenchmarkOptions) -> Result<Vec<ColumnMeasurement>> {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .name("benchmark".to_string())
+            .spawn_scoped(scope, || measure_columns_on_this_thread(path, options))?
+            .join()
+            .map_err(|_| anyhow!("benchmark thread panicked"))?
+    })
+}
+
+fn measure_columns_on_this_thread(
+    path: &Path,
+    options: &BenchmarkOptions,
+) -> Result<Vec<ColumnMeasurement>> {
+    let in_memory = if options.in_memory {
+        Some(Bytes::from(fs::read(path)?))
+    } else {
+        None
+    };
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    let schema = builder.parquet_schema();
+    let roots = schema.root_schema().get_fields();
+    let mut columns: Vec<ColumnMeasurement> = roots
+        .iter()
+        .map(|field| ColumnMeasurement {
+            name: field.name().to_string(),
+            loading_time_ms: 0.0,
+            compressed_mb: 0.0,
+            decompressed_mb: 0.0,
+        })
+        .collect();
+    for rg in builder.metadata().row_groups() {
+        for (leaf, chunk) in rg.columns().iter().enumerate() {
+            let column = &mut columns[schema.get_column_root_idx(leaf)];
+            column.compressed_mb += chunk.compressed_size() as f64 / (1024.0 * 1024.0);
+            column.decompressed_mb += chunk.uncompressed_size() as f64 / (1024.0 * 1024.0);
+        }
+    }
+
+    for (root, column) in columns.iter_mut().enumerate() {
+        let read = || match &in_memory {
+            Some(bytes) => decode(bytes.clone(), options.batch_size, Some(root)),
+            None => decode(File::open(path)?, options.batch_size, Some(root)),
+        };
+        let (_, mut times_ms) = repeat(options, read)?;
+        column.loading_time_ms = summarize(&mut times_ms).0;
+    }
+    Ok(columns)
+}
+
+/// Runs `read` for the warm-up and then the timed iterations, returning the
+/// last decode and every timed run's time.
+fn repeat(
+    options: &BenchmarkOptions,
+    read: impl Fn() -> Result<(Decoded, f64)>,
+) -> Result<(Decoded, Vec<f64>)> {
+    for _ in 0..options.warmup {
+        read()?;
+    }
+    let mut times_ms = Vec::with_capacity(options.iterations.max(1));
+    let mut decoded = Decoded::default();
+    for _ in 0..options.iterations.max(1) {
+        let (run, elapsed_ms) = read()?;
+        decoded = run;
+        times_ms.push(elapsed_ms);
+    }
+    Ok((decoded, times_ms))
+}
+
+#[derive(Debug, Default)]
+struct Decoded {
+    num_rows: usize,
+    decompressed_mb: f64,
+}
+
+/// Decodes every batch, of all columns or of top-level column `root` only,
+/// timing the decode but not the footer read.
+fn decode<R: ChunkReader + 'static>(
+    input: R,
+    batch_size: usize,
+    root: Option<usize>,
+) -> Result<(Decoded, f64)> {
+    let mut builder = ParquetRecordBatchReaderBuilder::try_new(input)?;
+    if let Some(root) = root {
+        let mask = ProjectionMask::roots(builder.parquet_schema(), [root]);
+        builder = builder.with_projection(mask);
+    }
+    let decompressed_mb = builder
+        .metadata()
+        .row_groups()
+        .iter()
+        .flat_map(|rg| rg.columns())
+        .map(|c| c.uncompressed_size() as f64)
+        .sum::<f64>()
+        / (1024.0 * 1024.0);
+    let reader = builder.with_batch_size(batch_size).build()?;
+
+    let start = Instant::now();
+    let mut num_rows = 0usize;
+    for batch in reader {
+        num_rows += batch?.num_rows();
+    }
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    Ok((
+        Decoded {
+            num_rows,
+            decompressed_mb,
+        },
+        elapsed_ms,
+    ))
+}
+
+/// Median, minimum and population standard deviation of `times`.
+fn summarize(times: &mut [f64]) -> (f64, f64, f64) {
+    times.sort_by(f64::total_cmp);
+    let n = times.len();
+    let median = if n.is_multiple_of(2) {
+        (times[n / 2 - 1] + times[n / 2]) / 2.0
+    } else {
+        times[n / 2]
+    };
+    let mean = times.iter().sum::<f64>() / n as f64;
+    let variance = times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / n as f64;
+    (median, times[0], variance.sqrt())
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::fs;
+
+/// The machine and build a benchmark ran on, so results from different
+/// machines can be told apart.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub cpu_model: Option<String>,
+    pub cores: usize,
+    pub memory_bytes: Option<u64>,
+    /// `parquet` and the `arrow-*` crates are released together from
+    /// arrow-rs, so one version covers both.
+    pub arrow_rs_version: &'static str,
+    pub linter_version: &'static str,
+    pub profile: &'static str,
+    pub target: String,
+    /// SIMD target features the build was compiled with.
+    pub target_features: Vec<&'static str>,
+}
+
+impl Environment {
+    pub fn detect() -> Self {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let meminfo = fs::read_to_string("/proc/meminfo").unwrap_or_default();
+        let target_features = [
+            ("sse4.2", cfg!(target_feature = "sse4.2")),
+            ("avx2", cfg!(target_feature = "avx2")),
+            ("avx512f", cfg!(target_feature = "avx512f")),
+            ("neon", cfg!(target_feature = "neon")),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect();
+        Self {
+            cpu_model: proc_field(&cpuinfo, "model name").map(str::to_string),
+            cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            memory_bytes: proc_field(&meminfo, "MemTotal")
+                .and_then(|v| v.trim_end_matches("kB").trim().parse::<u64>().ok())
+                .map(|kb| kb * 1024),
+            arrow_rs_version: parquet::file::properties::DEFAULT_CREATED_BY
+                .trim_start_matches("parquet-rs version "),
+            linter_version: env!("CARGO_PKG_VERSION"),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            },
+            target: format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+            target_features,
+        }
+    }
+}
+
+/// The value of the first `key: value` line of a `/proc` file.
+fn proc_field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim() == key).then(|| value.trim())
+    })
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} cores",
+            self.cpu_model.as_deref().unwrap_or("unknown CPU"),
+            self.cores
+        )?;
+        if let Some(bytes) = self.memory_bytes {
+            write!(
+                f,
+                ", {:.1} GB RAM",
+                bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+            )?;
+        }
+        write!(
+            f,
+            "; arrow-rs {}, parquet-linter {}; {} build for {}",
+            self.arrow_rs_version, self.linter_version, self.profile, self.target
+        )?;
+        if !self.target_features.is_empty() {
+            write!(f, " (+{})", self.target_features.join(" +"))?;
+        }
+        Ok(())
+    }
+}
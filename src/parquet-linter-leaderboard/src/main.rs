@@ -1,5 +1,6 @@
 mod benchmark;
 mod download;
+mod environment;
 mod report;
 
 use std::collections::BTreeMap;
@@ -9,9 +10,13 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result, bail, ensure};
 use clap::Parser;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet_linter::diagnostic::Diagnostic;
+use parquet_linter::loader::LoadedFile;
+use parquet_linter::plan::FixPlan;
 use parquet_linter::prescription::Prescription;
 
-use crate::benchmark::Measurement;
+use crate::benchmark::{BenchmarkOptions, Measurement};
+use crate::environment::Environment;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -37,9 +42,23 @@ struct Cli {
     #[arg(long, default_value = "output")]
     output_dir: PathBuf,
 
+    /// Timed reads per file; the median is reported
     #[arg(long, default_value_t = 3)]
     iterations: usize,
 
+    /// Untimed reads per file before the timed ones
+    #[arg(long, default_value_t = 1)]
+    warmup: usize,
+
+    /// Read each file into memory before timing, leaving disk I/O out
+    #[arg(long)]
+    in_memory: bool,
+
+    /// Also decode each top-level column on its own and report the columns
+    /// that take longest
+    #[arg(long)]
+    per_column: bool,
+
     #[arg(long, default_value_t = 8192)]
     batch_size: usize,
 }
@@ -56,6 +75,13 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
     ensure!(cli.batch_size > 0, "--batch-size must be > 0");
     ensure!(cli.iterations > 0, "--iterations must be > 0");
+    let options = BenchmarkOptions {
+        batch_size: cli.batch_size,
+        iterations: cli.iterations,
+        warmup: cli.warmup,
+        in_memory: cli.in_memory,
+        per_column: cli.per_column,
+    };
 
     let manifest_text = match &cli.parquet_manifest {
         Some(path) => fs::read_to_string(path)
@@ -69,24 +95,14 @@ async fn main() -> Result<()> {
     );
 
     match (cli.from_linter, cli.from_custom_prescription.as_deref()) {
-        (true, None) => {
-            run_from_linter(
-                &urls,
-                &cli.data_dir,
-                &cli.output_dir,
-                cli.batch_size,
-                cli.iterations,
-            )
-            .await
-        }
+        (true, None) => run_from_linter(&urls, &cli.data_dir, &cli.output_dir, &options).await,
         (false, Some(prescription_dir)) => {
             run_from_custom_prescription(
                 &urls,
                 &cli.data_dir,
                 &cli.output_dir,
                 prescription_dir,
-                cli.batch_size,
-                cli.iterations,
+                &options,
             )
             .await
         }
@@ -156,8 +172,7 @@ async fn run_from_linter(
     urls: &[&str],
     data_dir: &Path,
     output_dir: &Path,
-    batch_size: usize,
-    iterations: usize,
+    options: &BenchmarkOptions,
 ) -> Result<()> {
     fs::create_dir_all(data_dir)?;
     fs::create_dir_all(output_dir)?;
@@ -167,12 +182,8 @@ async fn run_from_linter(
     let mut prescriptions = Vec::with_capacity(urls.len());
     for (index, _) in urls.iter().enumerate() {
         let input_path = data_dir.join(format!("{index}.parquet"));
-        let diagnostics = lint_local_file(&input_path).await?;
-
-        let mut prescription = Prescription::new();
-        for diagnostic in &diagnostics {
-            prescription.extend(diagnostic.prescription.clone());
-        }
+        let (diagnostics, plan) = lint_local_file(&input_path).await?;
+        let prescription = plan.prescription();
         if let Err(conflict) = prescription.validate() {
             println!(
                 "Warning: conflicting directives for linter-generated prescription #{} (continuing with last directive wins): {}",
@@ -192,15 +203,7 @@ async fn run_from_linter(
         });
     }
 
-    run_benchmark(
-        urls,
-        data_dir,
-        output_dir,
-        prescriptions,
-        batch_size,
-        iterations,
-    )
-    .await
+    run_benchmark(urls, data_dir, output_dir, prescriptions, options).await
 }
 
 async fn run_from_custom_prescription(
@@ -208,8 +211,7 @@ async fn run_from_custom_prescription(
     data_dir: &Path,
     output_dir: &Path,
     prescription_dir: &Path,
-    batch_size: usize,
-    iterations: usize,
+    options: &BenchmarkOptions,
 ) -> Result<()> {
     let prescriptions = load_prescriptions(prescription_dir, urls.len())?;
     if prescriptions.is_empty() {
@@ -218,15 +220,7 @@ async fn run_from_custom_prescription(
             prescription_dir.display()
         );
     }
-    run_benchmark(
-        urls,
-        data_dir,
-        output_dir,
-        prescriptions,
-        batch_size,
-        iterations,
-    )
-    .await
+    run_benchmark(urls, data_dir, output_dir, prescriptions, options).await
 }
 
 async fn run_benchmark(
@@ -234,11 +228,12 @@ async fn run_benchmark(
     data_dir: &Path,
     output_dir: &Path,
     prescriptions: Vec<LoadedPrescription>,
-    batch_size: usize,
-    iterations: usize,
+    options: &BenchmarkOptions,
 ) -> Result<()> {
     fs::create_dir_all(data_dir)?;
     fs::create_dir_all(output_dir)?;
+    let environment = Environment::detect();
+    println!("Environment: {environment}");
 
     let missing_inputs: Vec<_> = prescriptions
         .iter()
@@ -281,18 +276,28 @@ async fn run_benchmark(
         validate_schema_match(&input_path, &output_path)
             .with_context(|| format!("schema mismatch for file #{}", item.index))?;
 
-        let original = benchmark::measure(&input_path, batch_size, iterations)?;
-        let output = benchmark::measure(&output_path, batch_size, iterations)?;
+        let original = benchmark::measure(&input_path, options)?;
+        let output = benchmark::measure(&output_path, options)?;
         print_file_summary(item.index, original, output);
+        let (original_columns, output_columns) = if options.per_column {
+            (
+                benchmark::measure_columns(&input_path, options)?,
+                benchmark::measure_columns(&output_path, options)?,
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
         results.push(report::FileResult {
             index: item.index,
             original,
             output,
+            original_columns,
+            output_columns,
         });
     }
 
     println!();
-    report::print(&results);
+    report::print(&results, &environment);
     Ok(())
 }
 
@@ -314,12 +319,20 @@ async fn ensure_inputs_for_indexes(
     Ok(())
 }
 
-async fn lint_local_file(path: &Path) -> Result<Vec<parquet_linter::diagnostic::Diagnostic>> {
+async fn lint_local_file(path: &Path) -> Result<(Vec<Diagnostic>, FixPlan)> {
     let (store, object_path) = parquet_linter::loader::parse(
         path.to_str()
             .ok_or_else(|| anyhow::anyhow!("non-utf8 path: {}", path.display()))?,
     )?;
-    parquet_linter::lint(store, object_path, None).await
+    let file = LoadedFile::open(store, object_path).await?;
+    let diagnostics = parquet_linter::lint_file(
+        &file,
+        None,
+        &parquet_linter::options::LintOptions::default(),
+    )
+    .await?;
+    let plan = FixPlan::new(&diagnostics, &file.metadata);
+    Ok((diagnostics, plan))
 }
 
 fn validate_schema_match(original_path: &Path, rewritten_path: &Path) -> Result<()> {
@@ -345,13 +358,21 @@ fn print_file_summary(index: usize, original: Measurement, output: Measurement)
         (output.cost - original.cost) / original.cost * 100.0
     };
     println!(
-        "File #{index}: cost {:.2} -> {:.2} ({:+.2}%), size {:.2}MB -> {:.2}MB, time {:.2}ms -> {:.2}ms",
+        "File #{index}: cost {:.2} -> {:.2} ({:+.2}%), size {:.2}MB -> {:.2}MB, time {:.2}ms (±{:.1}%, min {:.2}) -> {:.2}ms (±{:.1}%, min {:.2}), {:.2} -> {:.2} Mrows/s, {:.1} -> {:.1} MB/s decompressed",
         original.cost,
         output.cost,
         delta_cost_pct,
         original.file_size_mb,
         output.file_size_mb,
         original.loading_time_ms,
-        output.loading_time_ms
+        original.relative_stddev_pct(),
+        original.min_loading_time_ms,
+        output.loading_time_ms,
+        output.relative_stddev_pct(),
+        output.min_loading_time_ms,
+        original.rows_per_sec() / 1e6,
+        output.rows_per_sec() / 1e6,
+        original.decompressed_mb_per_sec(),
+        output.decompressed_mb_per_sec()
     );
 }
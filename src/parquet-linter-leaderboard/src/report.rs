@@ -1,23 +1,42 @@
 use colored::Colorize;
 
-use crate::benchmark::Measurement;
+use crate::benchmark::{ColumnMeasurement, Measurement, per_sec};
+use crate::environment::Environment;
 
-#[derive(Debug, Clone, Copy)]
+/// Columns listed in the hot columns section.
+const HOT_COLUMNS: usize = 10;
+
+#[derive(Debug, Clone)]
 pub struct FileResult {
     pub index: usize,
     pub original: Measurement,
     pub output: Measurement,
+    /// Per-column timings of the original and rewritten file, empty unless
+    /// `--per-column` was given.
+    pub original_columns: Vec<ColumnMeasurement>,
+    pub output_columns: Vec<ColumnMeasurement>,
 }
 
-pub fn print(results: &[FileResult]) {
+pub fn print(results: &[FileResult], environment: &Environment) {
     if results.is_empty() {
         println!("No results.");
         return;
     }
 
     println!(
-        "{:<6} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
-        "file", "orig_mb", "new_mb", "orig_ms", "new_ms", "orig_cost", "new_cost", "cost_%"
+        "{:<6} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "file",
+        "orig_mb",
+        "new_mb",
+        "orig_ms",
+        "new_ms",
+        "orig_Mr/s",
+        "new_Mr/s",
+        "orig_MB/s",
+        "new_MB/s",
+        "orig_cost",
+        "new_cost",
+        "cost_%"
     );
 
     let mut total_original = 0.0;
@@ -26,6 +45,9 @@ pub fn print(results: &[FileResult]) {
     let mut total_output_ms = 0.0;
     let mut total_original_mb = 0.0;
     let mut total_output_mb = 0.0;
+    let mut total_rows = 0;
+    let mut total_original_decompressed_mb = 0.0;
+    let mut total_output_decompressed_mb = 0.0;
 
     for result in results {
         total_original += result.original.cost;
@@ -34,6 +56,9 @@ pub fn print(results: &[FileResult]) {
         total_output_ms += result.output.loading_time_ms;
         total_original_mb += result.original.file_size_mb;
         total_output_mb += result.output.file_size_mb;
+        total_rows += result.original.num_rows;
+        total_original_decompressed_mb += result.original.decompressed_mb;
+        total_output_decompressed_mb += result.output.decompressed_mb;
 
         let pct = pct_change(result.original.cost, result.output.cost);
         let pct_text = format!("{pct:+.2}%");
@@ -44,12 +69,16 @@ pub fn print(results: &[FileResult]) {
         };
 
         println!(
-            "{:<6} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10}",
+            "{:<6} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.1} {:>10.1} {:>10.2} {:>10.2} {:>10}",
             result.index,
             result.original.file_size_mb,
             result.output.file_size_mb,
             result.original.loading_time_ms,
             result.output.loading_time_ms,
+            result.original.rows_per_sec() / 1e6,
+            result.output.rows_per_sec() / 1e6,
+            result.original.decompressed_mb_per_sec(),
+            result.output.decompressed_mb_per_sec(),
             result.original.cost,
             result.output.cost,
             pct_colored
@@ -65,7 +94,7 @@ pub fn print(results: &[FileResult]) {
     };
 
     println!(
-        "{:<6} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "{:<6} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
         "------",
         "----------",
         "----------",
@@ -73,15 +102,29 @@ pub fn print(results: &[FileResult]) {
         "----------",
         "----------",
         "----------",
+        "----------",
+        "----------",
+        "----------",
+        "----------",
         "----------"
     );
+    // Throughput over all files is total work over total time, so large
+    // files weigh in proportionally instead of averaging per-file rates.
+    let original_rows_per_sec = per_sec(total_rows as f64, total_original_ms);
+    let output_rows_per_sec = per_sec(total_rows as f64, total_output_ms);
+    let original_mb_per_sec = per_sec(total_original_decompressed_mb, total_original_ms);
+    let output_mb_per_sec = per_sec(total_output_decompressed_mb, total_output_ms);
     println!(
-        "{:<6} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10}",
+        "{:<6} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.1} {:>10.1} {:>10.2} {:>10.2} {:>10}",
         "total",
         total_original_mb,
         total_output_mb,
         total_original_ms,
         total_output_ms,
+        original_rows_per_sec / 1e6,
+        output_rows_per_sec / 1e6,
+        original_mb_per_sec,
+        output_mb_per_sec,
         total_original,
         total_output,
         total_pct_colored
@@ -96,17 +139,75 @@ pub fn print(results: &[FileResult]) {
 
     println!();
     println!(
-        "Baseline total: size {:.2} MB, time {:.2} ms, cost {:.2}",
-        total_original_mb, total_original_ms, total_original
+        "Baseline total: size {:.2} MB, time {:.2} ms, {:.2} Mrows/s, {:.1} MB/s decompressed, cost {:.2}",
+        total_original_mb,
+        total_original_ms,
+        original_rows_per_sec / 1e6,
+        original_mb_per_sec,
+        total_original
     );
     println!(
-        "Rewritten total: size {:.2} MB, time {:.2} ms, cost {:.2}",
-        total_output_mb, total_output_ms, total_output
+        "Rewritten total: size {:.2} MB, time {:.2} ms, {:.2} Mrows/s, {:.1} MB/s decompressed, cost {:.2}",
+        total_output_mb,
+        total_output_ms,
+        output_rows_per_sec / 1e6,
+        output_mb_per_sec,
+        total_output
     );
     println!(
         "Diff: size {:+.2} MB ({:+.2}%), time {:+.2} ms ({:+.2}%), cost {:+.2} ({:+.2}%)",
         size_delta, size_delta_pct, time_delta, time_delta_pct, cost_delta, cost_delta_pct
     );
+    print_hot_columns(results);
+    // Timings only compare across runs on the same machine and build.
+    println!("Measured on: {environment}");
+}
+
+/// The columns that take longest to decode in the original files, over all
+/// files, with their time in the rewritten file next to it.
+fn print_hot_columns(results: &[FileResult]) {
+    let mut hot: Vec<(usize, &ColumnMeasurement, Option<&ColumnMeasurement>, f64)> = results
+        .iter()
+        .flat_map(|result| {
+            let columns_ms: f64 = result
+                .original_columns
+                .iter()
+                .map(|c| c.loading_time_ms)
+                .sum();
+            result.original_columns.iter().map(move |column| {
+                let output = result.output_columns.iter().find(|c| c.name == column.name);
+                let share = if columns_ms == 0.0 {
+                    0.0
+                } else {
+                    column.loading_time_ms / columns_ms * 100.0
+                };
+                (result.index, column, output, share)
+            })
+        })
+        .collect();
+    if hot.is_empty() {
+        return;
+    }
+    hot.sort_by(|a, b| b.1.loading_time_ms.total_cmp(&a.1.loading_time_ms));
+
+    println!();
+    println!("{}", "Hot columns".bold());
+    println!(
+        "{:<6} {:<24} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "file", "column", "orig_ms", "new_ms", "orig_mb", "new_mb", "file_%"
+    );
+    for (index, column, output, share) in hot.into_iter().take(HOT_COLUMNS) {
+        println!(
+            "{:<6} {:<24} {:>10.2} {:>10} {:>10.2} {:>10} {:>9.1}%",
+            index,
+            column.name,
+            column.loading_time_ms,
+            output.map_or("-".to_string(), |c| format!("{:.2}", c.loading_time_ms)),
+            column.compressed_mb,
+            output.map_or("-".to_string(), |c| format!("{:.2}", c.compressed_mb)),
+            share
+        );
+    }
 }
 
 fn pct_change(original: f64, new: f64) -> f64 {
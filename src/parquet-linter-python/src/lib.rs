@@ -0,0 +1,145 @@
+//! Python bindings: `lint`, `rewrite` and `Prescription`.
+//!
+//! ```python
+//! import parquet_linter
+//!
+//! for d in parquet_linter.lint("data.parquet"):
+//!     print(d.severity, d.rule_name, d.message)
+//!
+//! p = parquet_linter.Prescription.parse("set file compression zstd(3)")
+//! p.apply("data.parquet", "fixed.parquet")
+//! ```
+
+use std::future::Future;
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use linter::diagnostic;
+use linter::prescription;
+
+/// One lint finding.
+#[pyclass(frozen, get_all, module = "parquet_linter")]
+struct Diagnostic {
+    rule_name: String,
+    /// `"suggestion"`, `"warning"` or `"error"`.
+    severity: String,
+    /// `"correctness"`, `"performance"`, `"compatibility"` or `"gpu"`.
+    category: String,
+    /// `"file"`, `"row_group[i]"` or `"column[i](path)"`.
+    location: String,
+    message: String,
+    prescription: Prescription,
+}
+
+#[pymethods]
+impl Diagnostic {
+    fn __repr__(&self) -> String {
+        format!(
+            "Diagnostic(severity={:?}, rule_name={:?}, location={:?})",
+            self.severity, self.rule_name, self.location
+        )
+    }
+}
+
+impl From<diagnostic::Diagnostic> for Diagnostic {
+    fn from(d: diagnostic::Diagnostic) -> Self {
+        Self {
+            rule_name: d.rule_name.to_string(),
+            severity: d.severity.to_string(),
+            category: d.category.to_string(),
+            location: d.location.to_string(),
+            message: d.message,
+            prescription: Prescription(d.prescription),
+        }
+    }
+}
+
+/// Writer settings in the prescription DSL.
+#[pyclass(frozen, module = "parquet_linter")]
+#[derive(Clone)]
+struct Prescription(prescription::Prescription);
+
+#[pymethods]
+impl Prescription {
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        prescription::Prescription::parse(text)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Rewrite `path` into the local file `output` with these settings.
+    fn apply(&self, py: Python<'_>, path: &str, output: PathBuf) -> PyResult<()> {
+        rewrite(py, path, output, self)
+    }
+
+    /// Raise `ValueError` if two directives set the same property differently.
+    fn validate(&self) -> PyResult<()> {
+        self.0
+            .validate()
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Each directive as one DSL line.
+    fn directives(&self) -> Vec<String> {
+        self.0.directives().iter().map(|d| d.to_string()).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.directives().len()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Lint a local path or URL, optionally running only the named rules.
+#[pyfunction]
+#[pyo3(signature = (path, rules=None))]
+fn lint(py: Python<'_>, path: &str, rules: Option<Vec<String>>) -> PyResult<Vec<Diagnostic>> {
+    let diagnostics = block_on(py, async {
+        let (store, path) = linter::loader::parse(path)?;
+        linter::lint(store, path, rules.as_deref()).await
+    })?;
+    Ok(diagnostics.into_iter().map(Diagnostic::from).collect())
+}
+
+/// Rewrite `path` into the local file `output` using `prescription`.
+#[pyfunction]
+fn rewrite(
+    py: Python<'_>,
+    path: &str,
+    output: PathBuf,
+    prescription: &Prescription,
+) -> PyResult<()> {
+    block_on(py, async {
+        let (store, path) = linter::loader::parse(path)?;
+        linter::fix::rewrite(store, path, &output, &prescription.0).await
+    })
+}
+
+/// Run a future to completion with the GIL released.
+fn block_on<T: Send>(
+    py: Python<'_>,
+    fut: impl Future<Output = anyhow::Result<T>> + Send,
+) -> PyResult<T> {
+    py.allow_threads(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(fut)
+    })
+    .map_err(|e| PyRuntimeError::new_err(format!("{e:#}")))
+}
+
+#[pymodule]
+fn parquet_linter(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Diagnostic>()?;
+    m.add_class::<Prescription>()?;
+    m.add_function(wrap_pyfunction!(lint, m)?)?;
+    m.add_function(wrap_pyfunction!(rewrite, m)?)?;
+    Ok(())
+}
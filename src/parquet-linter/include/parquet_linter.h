@@ -0,0 +1,30 @@
+/* C interface to parquet-linter, built with
+ * `cargo rustc -p parquet-linter --release --features ffi --crate-type cdylib`. */
+#ifndef PARQUET_LINTER_H
+#define PARQUET_LINTER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Lint `path` (local path or URL). Returns a JSON array of diagnostics (the
+ * `diagnostic` definition in doc/lint-output.schema.json) to be released with
+ * parquet_linter_string_free, or NULL on error. */
+char *parquet_linter_lint_json(const char *path);
+
+/* Rewrite `path` into the local file `output`, applying `prescription` (DSL
+ * text) or, when NULL, every fix suggested by linting. Returns 0 on success
+ * and -1 on error. */
+int parquet_linter_rewrite(const char *path, const char *output, const char *prescription);
+
+/* Message of the last failed call on this thread, or NULL. */
+const char *parquet_linter_last_error(void);
+
+/* Release a string returned by this library. */
+void parquet_linter_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PARQUET_LINTER_H */
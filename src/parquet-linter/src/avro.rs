@@ -0,0 +1,417 @@
+//! Minimal Avro object-container reader, enough for Iceberg manifest lists
+//! and manifests. Records decode to JSON objects keyed by field name; `bytes`
+//! and `fixed` values decode to lowercase hex strings.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+
+const MAGIC: &[u8; 4] = b"Obj\x01";
+const SYNC_SIZE: usize = 16;
+
+/// Decode every record of an Avro object container file.
+pub(crate) fn read_container(data: &[u8]) -> Result<Vec<Value>> {
+    let mut input = Cursor { data, pos: 0 };
+    if input.take(MAGIC.len())? != MAGIC {
+        bail!("not an Avro object container file");
+    }
+    let header = Schema::Map(Box::new(Schema::Bytes)).decode_raw(&mut input)?;
+    let field = |key: &str| -> Result<Vec<u8>> {
+        match &header {
+            Raw::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, v)| match v {
+                    Raw::Bytes(b) => Some(b.clone()),
+                    _ => None,
+                })
+                .with_context(|| format!("Avro header is missing {key}")),
+            _ => unreachable!("header decodes as a map"),
+        }
+    };
+    let schema_json: Value = serde_json::from_slice(&field("avro.schema")?)?;
+    let schema = Schema::parse(&schema_json, &mut HashMap::new())?;
+    let codec = String::from_utf8(field("avro.codec").unwrap_or_else(|_| b"null".to_vec()))?;
+    let sync = input.take(SYNC_SIZE)?.to_vec();
+
+    let mut records = Vec::new();
+    while input.pos < data.len() {
+        let count = input.long()?;
+        let size = usize::try_from(input.long()?)?;
+        let block = decompress(&codec, input.take(size)?)?;
+        let mut block_input = Cursor {
+            data: &block,
+            pos: 0,
+        };
+        for _ in 0..count {
+            records.push(schema.decode(&mut block_input)?);
+        }
+        if input.take(SYNC_SIZE)? != sync.as_slice() {
+            bail!("Avro block sync marker mismatch");
+        }
+    }
+    Ok(records)
+}
+
+fn decompress(codec: &str, block: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match codec {
+        "null" => out.extend_from_slice(block),
+        "deflate" => {
+            flate2::read::DeflateDecoder::new(block).read_to_end(&mut out)?;
+        }
+        "zstandard" => out = zstd::decode_all(block)?,
+        "snappy" => {
+            // Snappy blocks carry a trailing 4-byte CRC of the uncompressed data.
+            let body = &block[..block.len().saturating_sub(4)];
+            out = snap::raw::Decoder::new().decompress_vec(body)?;
+        }
+        other => bail!("unsupported Avro codec: {other}"),
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone)]
+enum Schema {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Fixed(usize),
+    Enum(Vec<String>),
+    Array(Box<Schema>),
+    Map(Box<Schema>),
+    Union(Vec<Schema>),
+    Record(Vec<(String, Schema)>),
+}
+
+impl Schema {
+    /// Parse a JSON schema, resolving references to previously named types.
+    fn parse(json: &Value, named: &mut HashMap<String, Schema>) -> Result<Self> {
+        match json {
+            Value::String(name) => Self::parse_name(name, named),
+            Value::Array(branches) => Ok(Schema::Union(
+                branches
+                    .iter()
+                    .map(|b| Schema::parse(b, named))
+                    .collect::<Result<_>>()?,
+            )),
+            Value::Object(obj) => {
+                let kind = obj
+                    .get("type")
+                    .context("Avro schema object is missing type")?;
+                let schema = match kind.as_str() {
+                    Some("record") | Some("error") => {
+                        let fields = obj
+                            .get("fields")
+                            .and_then(Value::as_array)
+                            .context("Avro record is missing fields")?;
+                        let mut parsed = Vec::with_capacity(fields.len());
+                        for f in fields {
+                            let name = f
+                                .get("name")
+                                .and_then(Value::as_str)
+                                .context("Avro field is missing name")?;
+                            let ty = f.get("type").context("Avro field is missing type")?;
+                            parsed.push((name.to_string(), Schema::parse(ty, named)?));
+                        }
+                        Schema::Record(parsed)
+                    }
+                    Some("enum") => Schema::Enum(
+                        obj.get("symbols")
+                            .and_then(Value::as_array)
+                            .context("Avro enum is missing symbols")?
+                            .iter()
+                            .filter_map(|s| s.as_str().map(str::to_string))
+                            .collect(),
+                    ),
+                    Some("array") => Schema::Array(Box::new(Schema::parse(
+                        obj.get("items").context("Avro array is missing items")?,
+                        named,
+                    )?)),
+                    Some("map") => Schema::Map(Box::new(Schema::parse(
+                        obj.get("values").context("Avro map is missing values")?,
+                        named,
+                    )?)),
+                    Some("fixed") => Schema::Fixed(
+                        obj.get("size")
+                            .and_then(Value::as_u64)
+                            .context("Avro fixed is missing size")?
+                            as usize,
+                    ),
+                    // A primitive wrapped in an object, e.g. with a logicalType.
+                    _ => Schema::parse(kind, named)?,
+                };
+                if let Some(name) = obj.get("name").and_then(Value::as_str) {
+                    named.insert(name.to_string(), schema.clone());
+                }
+                Ok(schema)
+            }
+            other => bail!("invalid Avro schema: {other}"),
+        }
+    }
+
+    fn parse_name(name: &str, named: &HashMap<String, Schema>) -> Result<Self> {
+        Ok(match name {
+            "null" => Schema::Null,
+            "boolean" => Schema::Boolean,
+            "int" => Schema::Int,
+            "long" => Schema::Long,
+            "float" => Schema::Float,
+            "double" => Schema::Double,
+            "bytes" => Schema::Bytes,
+            "string" => Schema::String,
+            other => named
+                .get(other)
+                .cloned()
+                .with_context(|| format!("unknown Avro type: {other}"))?,
+        })
+    }
+
+    fn decode(&self, input: &mut Cursor<'_>) -> Result<Value> {
+        Ok(self.decode_raw(input)?.into_json())
+    }
+
+    fn decode_raw(&self, input: &mut Cursor<'_>) -> Result<Raw> {
+        Ok(match self {
+            Schema::Null => Raw::Json(Value::Null),
+            Schema::Boolean => Raw::Json(Value::Bool(input.take(1)?[0] != 0)),
+            Schema::Int | Schema::Long => Raw::Json(Value::from(input.long()?)),
+            Schema::Float => {
+                let bytes: [u8; 4] = input.take(4)?.try_into()?;
+                Raw::Json(Value::from(f32::from_le_bytes(bytes) as f64))
+            }
+            Schema::Double => {
+                let bytes: [u8; 8] = input.take(8)?.try_into()?;
+                Raw::Json(Value::from(f64::from_le_bytes(bytes)))
+            }
+            Schema::Bytes => {
+                let len = usize::try_from(input.long()?)?;
+                Raw::Bytes(input.take(len)?.to_vec())
+            }
+            Schema::String => {
+                let len = usize::try_from(input.long()?)?;
+                Raw::Json(Value::String(String::from_utf8(input.take(len)?.to_vec())?))
+            }
+            Schema::Fixed(size) => Raw::Bytes(input.take(*size)?.to_vec()),
+            Schema::Enum(symbols) => {
+                let index = usize::try_from(input.long()?)?;
+                let symbol = symbols.get(index).context("Avro enum index out of range")?;
+                Raw::Json(Value::String(symbol.clone()))
+            }
+            Schema::Array(items) => {
+                let mut values = Vec::new();
+                input.blocks(|input| {
+                    values.push(items.decode(input)?);
+                    Ok(())
+                })?;
+                Raw::Json(Value::Array(values))
+            }
+            Schema::Map(values) => {
+                let mut entries = Vec::new();
+                input.blocks(|input| {
+                    let len = usize::try_from(input.long()?)?;
+                    let key = String::from_utf8(input.take(len)?.to_vec())?;
+                    entries.push((key, values.decode_raw(input)?));
+                    Ok(())
+                })?;
+                Raw::Map(entries)
+            }
+            Schema::Union(branches) => {
+                let index = usize::try_from(input.long()?)?;
+                let branch = branches
+                    .get(index)
+                    .context("Avro union index out of range")?;
+                branch.decode_raw(input)?
+            }
+            Schema::Record(fields) => {
+                let mut obj = Map::new();
+                for (name, schema) in fields {
+                    obj.insert(name.clone(), schema.decode(input)?);
+                }
+                Raw::Json(Value::Object(obj))
+            }
+        })
+    }
+}
+
+/// Decoded value before conversion to JSON; keeps raw bytes for the header.
+enum Raw {
+    Json(Value),
+    Bytes(Vec<u8>),
+    Map(Vec<(String, Raw)>),
+}
+
+impl Raw {
+    fn into_json(self) -> Value {
+        match self {
+            Raw::Json(v) => v,
+            Raw::Bytes(b) => Value::String(b.iter().map(|byte| format!("{byte:02x}")).collect()),
+            Raw::Map(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, v.into_json()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .context("truncated Avro data")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Zigzag-encoded variable-length long.
+    fn long(&mut self) -> Result<i64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+            }
+        }
+        bail!("Avro varint is too long")
+    }
+
+    /// Iterate the items of a blocked array or map.
+    fn blocks(&mut self, mut item: impl FnMut(&mut Self) -> Result<()>) -> Result<()> {
+        loop {
+            let mut count = self.long()?;
+            if count == 0 {
+                return Ok(());
+            }
+            if count < 0 {
+                // A negative count is followed by the block's byte size.
+                count = -count;
+                self.long()?;
+            }
+            for _ in 0..count {
+                item(self)?;
+            }
+        }
+    }
+}
+
+/// Test-only Avro writer mirroring the reader, for building fixtures.
+#[cfg(test)]
+pub(crate) mod writer {
+    use super::*;
+
+    /// Encode `records` as an uncompressed object container.
+    pub(crate) fn write_container(schema_json: &Value, records: &[Value]) -> Vec<u8> {
+        let schema = Schema::parse(schema_json, &mut HashMap::new()).expect("valid schema");
+        let mut out = MAGIC.to_vec();
+        let schema_text = schema_json.to_string();
+        long(&mut out, 2);
+        for (key, value) in [
+            ("avro.schema", schema_text.as_str()),
+            ("avro.codec", "null"),
+        ] {
+            bytes(&mut out, key.as_bytes());
+            bytes(&mut out, value.as_bytes());
+        }
+        long(&mut out, 0);
+        let sync = [7u8; SYNC_SIZE];
+        out.extend_from_slice(&sync);
+
+        let mut block = Vec::new();
+        for record in records {
+            encode(&schema, record, &mut block);
+        }
+        long(&mut out, records.len() as i64);
+        long(&mut out, block.len() as i64);
+        out.extend_from_slice(&block);
+        out.extend_from_slice(&sync);
+        out
+    }
+
+    fn encode(schema: &Schema, value: &Value, out: &mut Vec<u8>) {
+        match schema {
+            Schema::Null => {}
+            Schema::Boolean => out.push(u8::from(value.as_bool().unwrap())),
+            Schema::Int | Schema::Long => long(out, value.as_i64().unwrap()),
+            Schema::String => bytes(out, value.as_str().unwrap().as_bytes()),
+            Schema::Union(branches) => {
+                let index = if value.is_null() {
+                    branches.iter().position(|b| matches!(b, Schema::Null))
+                } else {
+                    branches.iter().position(|b| !matches!(b, Schema::Null))
+                }
+                .unwrap();
+                long(out, index as i64);
+                encode(&branches[index], value, out);
+            }
+            Schema::Record(fields) => {
+                for (name, field) in fields {
+                    encode(field, &value[name], out);
+                }
+            }
+            other => unimplemented!("test writer does not encode {other:?}"),
+        }
+    }
+
+    fn long(out: &mut Vec<u8>, v: i64) {
+        let mut n = ((v << 1) ^ (v >> 63)) as u64;
+        while n >= 0x80 {
+            out.push((n as u8) | 0x80);
+            n >>= 7;
+        }
+        out.push(n as u8);
+    }
+
+    fn bytes(out: &mut Vec<u8>, b: &[u8]) {
+        long(out, b.len() as i64);
+        out.extend_from_slice(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_records_with_unions() -> Result<()> {
+        let schema = json!({
+            "type": "record",
+            "name": "entry",
+            "fields": [
+                {"name": "status", "type": "int"},
+                {"name": "path", "type": "string"},
+                {"name": "sort_order_id", "type": ["null", "long"]},
+            ]
+        });
+        let records = [
+            json!({"status": 1, "path": "a.parquet", "sort_order_id": 3}),
+            json!({"status": 2, "path": "b.parquet", "sort_order_id": null}),
+        ];
+        let data = writer::write_container(&schema, &records);
+        assert_eq!(read_container(&data)?, records);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_non_avro_input() {
+        assert!(read_container(b"PAR1").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! On-disk cache of lint results keyed by object identity, so re-linting an
+//! unchanged file skips every read past a `HEAD` request.
+//!
+//! A key hashes the location, the object's etag (or last-modified time and
+//! size when the store has no etag), the linter version, the selected rules
+//! and the lint options. Entries are JSON files named by that hash, holding
+//! diagnostics in the `Diagnostic::to_json` format.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use object_store::ObjectMeta;
+use serde_json::Value;
+
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::options::LintOptions;
+use crate::prescription::Prescription;
+
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    dir: PathBuf,
+}
+
+impl ResultCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/parquet-linter`, falling back to
+    /// `$HOME/.cache/parquet-linter`.
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(base.join("parquet-linter"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cached diagnostics for `key`, or `None` on a miss or unreadable entry.
+    /// `rule_names` are the rules the linter runs, custom ones included;
+    /// entries naming any other rule are misses.
+    pub(crate) fn get(&self, key: &str, rule_names: &[&'static str]) -> Option<Vec<Diagnostic>> {
+        let text = std::fs::read_to_string(self.entry(key)).ok()?;
+        let value: Value = serde_json::from_str(&text).ok()?;
+        value
+            .as_array()?
+            .iter()
+            .map(|d| decode(d, rule_names))
+            .collect()
+    }
+
+    pub(crate) fn put(&self, key: &str, diagnostics: &[Diagnostic]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let value = Value::Array(diagnostics.iter().map(Diagnostic::to_json).collect());
+        // Write then rename so concurrent runs never read a partial entry.
+        let tmp = self.dir.join(format!("{key}.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, value.to_string())?;
+        std::fs::rename(tmp, self.entry(key))?;
+        Ok(())
+    }
+
+    fn entry(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+/// Cache key for linting `location` (whose current metadata is `meta`) with
+/// the given rule selection and options.
+pub(crate) fn key(
+    location: &str,
+    meta: &ObjectMeta,
+    rule_names: Option<&[String]>,
+    options: &LintOptions,
+) -> String {
+    let identity = match &meta.e_tag {
+        Some(etag) => format!("etag:{etag}"),
+        None => format!("modified:{}:{}", meta.last_modified.to_rfc3339(), meta.size),
+    };
+    let parts = [
+        location.to_string(),
+        identity,
+        env!("CARGO_PKG_VERSION").to_string(),
+        format!("{rule_names:?}"),
+        format!("{options:?}"),
+    ];
+    format!("{:016x}", fnv1a(parts.join("\0").as_bytes()))
+}
+
+/// FNV-1a, used because it is stable across builds unlike `DefaultHasher`.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn decode(value: &Value, rule_names: &[&'static str]) -> Option<Diagnostic> {
+    // Rule names are `&'static str`; entries from unknown rules are misses.
+    let name = value["rule_name"].as_str()?;
+    let rule_name = *rule_names.iter().find(|n| **n == name)?;
+    let severity: Severity = value["severity"].as_str()?.parse().ok()?;
+    // Entries cached before categories existed were all performance findings.
+    let category: Category = match value["category"].as_str() {
+        Some(category) => category.parse().ok()?,
+        None => Category::Performance,
+    };
+    let location = Location::from_json(&value["location"])?;
+    Some(Diagnostic {
+        rule_name,
+        severity,
+        category,
+        location,
+        message: value["message"].as_str()?.to_string(),
+        prescription: value["directives"]
+            .as_array()?
+            .iter()
+            .map(|d| d["text"].as_str())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|lines| Prescription::parse(&lines.join("\n")).ok())?,
+        related: value["related"]
+            .as_array()
+            .map(|related| related.iter().filter_map(Location::from_json).collect())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prescription::{Codec, Directive};
+    use parquet::schema::types::ColumnPath;
+
+    #[test]
+    fn entries_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = ResultCache::new(dir.path());
+        let mut prescription = Prescription::new();
+        let path = ColumnPath::new(vec!["user".to_string(), "id".to_string()]);
+        prescription.push(Directive::SetColumnCompression(
+            path.clone(),
+            Codec::Zstd(3),
+        ));
+        let diagnostic = Diagnostic {
+            rule_name: "compression-codec-upgrade",
+            severity: Severity::Warning,
+            category: Category::Performance,
+            location: Location::Column { column: 1, path },
+            message: "use zstd".to_string(),
+            prescription,
+            related: Vec::new(),
+        };
+        let names = ["compression-codec-upgrade"];
+        assert!(cache.get("k", &names).is_none());
+        cache.put("k", std::slice::from_ref(&diagnostic))?;
+        let cached = cache.get("k", &names).unwrap();
+        assert_eq!(cached[0].to_string(), diagnostic.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_custom_rule_names() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = ResultCache::new(dir.path());
+        let diagnostic = Diagnostic {
+            rule_name: "no-id-column",
+            severity: Severity::Warning,
+            category: Category::Correctness,
+            location: Location::File,
+            message: "has an id column".to_string(),
+            prescription: Prescription::new(),
+            related: Vec::new(),
+        };
+        cache.put("k", std::slice::from_ref(&diagnostic))?;
+        let cached = cache.get("k", &["no-id-column"]).unwrap();
+        assert_eq!(cached[0].rule_name, "no-id-column");
+        assert!(cache.get("k", &["sorting-order"]).is_none());
+        Ok(())
+    }
+}
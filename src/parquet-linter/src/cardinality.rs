@@ -1,18 +1,25 @@
 use anyhow::Result;
 use arrow_array::*;
+use bytes::Bytes;
 use futures::StreamExt;
 use parquet::arrow::async_reader::ParquetObjectReader;
-use parquet::column::page::Page;
+use parquet::basic::{Encoding, PageType, Type as PhysicalType};
+use parquet::column::page::{Page, PageReader};
 use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
 use std::collections::HashSet;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
 
-use crate::rule;
+use crate::loader::{self, LoadedFile};
+use crate::options::{CardinalityMode, LintOptions};
+use crate::page_cache::{ChunkSpan, PageCache};
 
 pub(crate) struct ColumnCardinality {
     pub(crate) distinct_count: u64,
     #[cfg_attr(not(test), allow(dead_code))]
     pub(crate) non_null_count: u64,
+    /// Counted over every row rather than extrapolated from a sample.
+    pub(crate) exact: bool,
 }
 
 impl ColumnCardinality {
@@ -27,71 +34,223 @@ impl ColumnCardinality {
 }
 
 const SAMPLE_ROWS: usize = 16_384;
+/// Exact mode counts columns up to this uncompressed size in a `HashSet`,
+/// and sketches larger ones.
+const MAX_EXACT_COLUMN_BYTES: i64 = 64 * 1024 * 1024; // 64 MB
+/// HyperLogLog register index bits: 2^14 registers (16 KB per column) give a
+/// standard error of about 0.8%.
+const HLL_PRECISION: u32 = 14;
+
+/// HyperLogLog sketch of 64-bit value hashes: a distinct-count estimate in
+/// fixed memory however many values are inserted.
+#[derive(Clone)]
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // Leading zeros of the remaining bits, plus one; a sentinel bit caps
+        // the count when they are all zero.
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&r| 2f64.powi(-i32::from(r)))
+            .sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        // Linear counting is far more accurate while registers are sparse.
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
 
-/// Estimate per-column cardinality using a lightweight 3-tier approach:
-/// 1. Distinct count from one row group's column statistics
-/// 2. Distinct count inferred from one row group's dictionary page
-/// 3. Sample values from one row group and estimate file-level ratio
+impl Extend<u64> for HyperLogLog {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, hashes: I) {
+        for hash in hashes {
+            self.insert(hash);
+        }
+    }
+}
+
+/// Estimate per-column cardinality using a lightweight 3-tier approach over
+/// `sample_row_groups` row groups spread across the file:
+/// 1. Distinct counts from the sampled row groups' column statistics
+/// 2. Distinct counts from dictionary pages: exact (union of every row
+///    group's dictionary entries) when every row group is fully
+///    dictionary-encoded, otherwise scaled from the sampled row groups
+/// 3. Sketch values from the sampled row groups with HyperLogLog and
+///    extrapolate to the file: the first `SAMPLE_ROWS` rows of each group
+///    in `CardinalityMode::Fast`, every row in `CardinalityMode::Balanced`
+///
+/// With `CardinalityMode::Exact`, flat columns are instead counted over
+/// every row group: exactly when small, with HyperLogLog otherwise.
+///
+/// Only the leaf columns in `cols` are estimated; the result follows their order.
 pub(crate) async fn estimate(
-    reader: &ParquetObjectReader,
-    metadata: &ParquetMetaData,
+    file: &LoadedFile,
+    cols: &[usize],
+    options: &LintOptions,
 ) -> Result<Vec<ColumnCardinality>> {
+    let (reader, metadata, pages) = (&file.reader, file.metadata.as_ref(), &file.pages);
     let num_cols = metadata.file_metadata().schema_descr().num_columns();
+    let totals = total_non_null_values_per_column(metadata, num_cols);
+    let unknown = |col_idx: usize| ColumnCardinality {
+        distinct_count: totals[col_idx],
+        non_null_count: totals[col_idx],
+        exact: false,
+    };
     if metadata.num_row_groups() == 0 {
-        return Ok(Vec::new());
+        return Ok(cols.iter().map(|&col_idx| unknown(col_idx)).collect());
     }
 
-    let sample_rg_idx = pick_sample_row_group(metadata);
-    let totals = total_non_null_values_per_column(metadata, num_cols);
+    let schema = metadata.file_metadata().schema_descr();
+    let is_flat = schema.root_schema().get_fields().len() == num_cols;
+    let sample_rgs = pick_sample_row_groups(metadata, options.cardinality_row_groups);
     let mut result: Vec<Option<ColumnCardinality>> = (0..num_cols).map(|_| None).collect();
 
-    for col_idx in 0..num_cols {
+    // Metadata-only runs stop after tier 1: every later tier reads pages.
+    let read_pages = !options.metadata_only;
+    if options.cardinality == CardinalityMode::Exact && is_flat && read_pages {
+        let (small, large): (Vec<usize>, Vec<usize>) = cols
+            .iter()
+            .partition(|&&i| column_uncompressed_size(metadata, i) <= MAX_EXACT_COLUMN_BYTES);
+        if !small.is_empty() {
+            exact_cardinalities(reader, &file.metadata, &totals, &small, &mut result).await?;
+        }
+        if !large.is_empty() {
+            let all_rgs: Vec<usize> = (0..metadata.num_row_groups()).collect();
+            sample_cardinalities(
+                reader,
+                &file.metadata,
+                &totals,
+                &all_rgs,
+                None,
+                &large,
+                &mut result,
+            )
+            .await?;
+        }
+    }
+
+    // Batch every dictionary page tier 2 may read into one coalesced fetch.
+    // A failure here resurfaces (softly) on the per-chunk reads below.
+    let dictionary_requests: Vec<(usize, usize, ChunkSpan)> = cols
+        .iter()
+        .copied()
+        .filter(|&col_idx| {
+            read_pages
+                && result[col_idx].is_none()
+                && !has_distinct_stats(metadata, &sample_rgs, col_idx)
+        })
+        .flat_map(|col_idx| {
+            fully_dictionary_row_groups(metadata, col_idx)
+                .unwrap_or_else(|| sample_rgs.clone())
+                .into_iter()
+                .map(move |rg| (rg, col_idx, ChunkSpan::Dictionary))
+        })
+        .collect();
+    let _ = pages.prefetch(&dictionary_requests).await;
+
+    for &col_idx in cols {
+        if result[col_idx].is_some() {
+            continue;
+        }
         let total_non_null = totals[col_idx];
-        let sample_col = metadata.row_group(sample_rg_idx).column(col_idx);
-        let sample_non_null = column_non_null_count(sample_col);
-        if sample_non_null == 0 {
+        let sample_non_null: Vec<u64> = sample_rgs
+            .iter()
+            .map(|&rg| column_non_null_count(metadata.row_group(rg).column(col_idx)))
+            .collect();
+        if sample_non_null.iter().all(|&n| n == 0) {
             continue;
         }
 
         // Tier 1: statistics
-        if let Some(dc) = sample_col
-            .statistics()
-            .and_then(|s| s.distinct_count_opt())
-            .map(|dc| dc.min(sample_non_null))
-        {
+        let stats_distinct: Vec<(u64, u64)> = sample_rgs
+            .iter()
+            .zip(&sample_non_null)
+            .filter_map(|(&rg, &non_null)| {
+                let dc = metadata
+                    .row_group(rg)
+                    .column(col_idx)
+                    .statistics()?
+                    .distinct_count_opt()?;
+                Some((dc.min(non_null), non_null))
+            })
+            .collect();
+        if let Some(distinct_count) = merge_distinct(&stats_distinct, total_non_null) {
             result[col_idx] = Some(ColumnCardinality {
-                distinct_count: scale_distinct(dc, sample_non_null, total_non_null),
+                distinct_count,
                 non_null_count: total_non_null,
+                exact: false,
             });
             continue;
         }
+        if !read_pages {
+            continue;
+        }
 
-        // Tier 2: dictionary page (fetches only this column chunk's bytes)
-        if let Some(dc) = dictionary_distinct_count(reader, metadata, sample_rg_idx, col_idx).await
-        {
+        // Tier 2: dictionary pages (fetches only the dictionary pages' bytes)
+        if let Some(distinct_count) = union_dictionary_entries(pages, metadata, col_idx).await {
             result[col_idx] = Some(ColumnCardinality {
-                distinct_count: scale_distinct(
-                    dc.min(sample_non_null),
-                    sample_non_null,
-                    total_non_null,
-                )
-                .max(dc.min(total_non_null)),
+                distinct_count: distinct_count.min(total_non_null),
                 non_null_count: total_non_null,
+                exact: true,
+            });
+            continue;
+        }
+        let mut dict_distinct = Vec::new();
+        for (&rg, &non_null) in sample_rgs.iter().zip(&sample_non_null) {
+            if let Some(dc) = dictionary_distinct_count(pages, rg, col_idx).await {
+                dict_distinct.push((dc.min(non_null), non_null));
+            }
+        }
+        if let Some(distinct_count) = merge_distinct(&dict_distinct, total_non_null) {
+            result[col_idx] = Some(ColumnCardinality {
+                distinct_count,
+                non_null_count: total_non_null,
+                exact: false,
             });
         }
     }
 
     // Tier 3: sample unresolved flat columns only.
-    let schema = metadata.file_metadata().schema_descr();
-    let is_flat = schema.root_schema().get_fields().len() == num_cols;
-    if is_flat {
-        let unresolved: Vec<usize> = (0..num_cols).filter(|&i| result[i].is_none()).collect();
+    if is_flat && read_pages {
+        let unresolved: Vec<usize> = cols
+            .iter()
+            .copied()
+            .filter(|&i| result[i].is_none())
+            .collect();
         if !unresolved.is_empty() {
+            let rows_per_group = match options.cardinality {
+                CardinalityMode::Fast => Some(SAMPLE_ROWS),
+                CardinalityMode::Balanced | CardinalityMode::Exact => None,
+            };
             sample_cardinalities(
                 reader,
-                metadata,
+                &file.metadata,
                 &totals,
-                sample_rg_idx,
+                &sample_rgs,
+                rows_per_group,
                 &unresolved,
                 &mut result,
             )
@@ -100,16 +259,9 @@ pub(crate) async fn estimate(
     }
 
     // Fill unknowns: assume all unique (conservative)
-    Ok(result
-        .into_iter()
-        .enumerate()
-        .map(|(col_idx, card)| {
-            let total = totals[col_idx];
-            card.unwrap_or(ColumnCardinality {
-                distinct_count: total,
-                non_null_count: total,
-            })
-        })
+    Ok(cols
+        .iter()
+        .map(|&col_idx| result[col_idx].take().unwrap_or_else(|| unknown(col_idx)))
         .collect())
 }
 
@@ -121,6 +273,59 @@ pub(crate) fn pick_sample_row_group(metadata: &ParquetMetaData) -> usize {
         .unwrap_or(0)
 }
 
+/// Pick up to `k` non-empty row groups spread evenly across the file, always
+/// including the first and (for `k > 1`) the last.
+pub(crate) fn pick_sample_row_groups(metadata: &ParquetMetaData, k: usize) -> Vec<usize> {
+    let non_empty: Vec<usize> = metadata
+        .row_groups()
+        .iter()
+        .enumerate()
+        .filter(|(_, rg)| rg.num_rows() > 0)
+        .map(|(i, _)| i)
+        .collect();
+    if non_empty.is_empty() {
+        return vec![pick_sample_row_group(metadata)];
+    }
+    spread_indices(non_empty.len(), k)
+        .into_iter()
+        .map(|i| non_empty[i])
+        .collect()
+}
+
+fn spread_indices(n: usize, k: usize) -> Vec<usize> {
+    let k = k.clamp(1, n);
+    if k == 1 {
+        return vec![0];
+    }
+    let mut picked: Vec<usize> = (0..k).map(|j| j * (n - 1) / (k - 1)).collect();
+    picked.dedup();
+    picked
+}
+
+/// Merge per-row-group `(distinct, non_null)` pairs into a file-level
+/// estimate, scaling the pooled ratio and never going below the largest
+/// single-group count.
+fn merge_distinct(samples: &[(u64, u64)], total_non_null: u64) -> Option<u64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let distinct: u64 = samples.iter().map(|(dc, _)| dc).sum();
+    let non_null: u64 = samples.iter().map(|(_, nn)| nn).sum();
+    let largest = samples.iter().map(|(dc, _)| *dc).max().unwrap_or(0);
+    Some(
+        scale_distinct(distinct.min(non_null), non_null, total_non_null)
+            .max(largest.min(total_non_null)),
+    )
+}
+
+fn column_uncompressed_size(metadata: &ParquetMetaData, col_idx: usize) -> i64 {
+    metadata
+        .row_groups()
+        .iter()
+        .map(|rg| rg.column(col_idx).uncompressed_size())
+        .sum()
+}
+
 fn total_non_null_values_per_column(metadata: &ParquetMetaData, num_cols: usize) -> Vec<u64> {
     let mut totals = vec![0u64; num_cols];
     for rg in metadata.row_groups() {
@@ -158,57 +363,178 @@ fn scale_distinct(sample_distinct: u64, sample_total: u64, full_total: u64) -> u
 
 /// Read one row group's dictionary page via a targeted byte-range fetch.
 async fn dictionary_distinct_count(
-    reader: &ParquetObjectReader,
-    metadata: &ParquetMetaData,
+    pages: &PageCache,
     rg_idx: usize,
     col_idx: usize,
 ) -> Option<u64> {
-    let mut page_reader = rule::column_page_reader(reader, metadata, rg_idx, col_idx)
+    read_dictionary_page(pages, rg_idx, col_idx)
+        .await
+        .map(|(_, num_values)| num_values)
+}
+
+/// Returns the dictionary page's decompressed PLAIN buffer and entry count.
+async fn read_dictionary_page(
+    pages: &PageCache,
+    rg_idx: usize,
+    col_idx: usize,
+) -> Option<(Bytes, u64)> {
+    let mut page_reader = pages
+        .page_reader(rg_idx, col_idx, ChunkSpan::Dictionary)
         .await
         .ok()?;
-    use parquet::column::page::PageReader;
-    if let Ok(Some(page)) = page_reader.get_next_page() {
-        match page {
-            Page::DictionaryPage { num_values, .. } => return Some(num_values as u64),
-            Page::DataPage { .. } | Page::DataPageV2 { .. } => {}
-        }
+    match page_reader.get_next_page() {
+        Ok(Some(Page::DictionaryPage {
+            buf, num_values, ..
+        })) => Some((buf, num_values as u64)),
+        _ => None,
     }
-    None
 }
 
+fn has_distinct_stats(metadata: &ParquetMetaData, row_groups: &[usize], col_idx: usize) -> bool {
+    row_groups.iter().any(|&rg| {
+        metadata
+            .row_group(rg)
+            .column(col_idx)
+            .statistics()
+            .and_then(|s| s.distinct_count_opt())
+            .is_some()
+    })
+}
+
+/// True when metadata proves every data page of the chunk is dictionary
+/// encoded (no fallback to PLAIN).
+pub(crate) fn fully_dictionary_encoded(col: &ColumnChunkMetaData) -> bool {
+    if let Some(mask) = col.page_encoding_stats_mask() {
+        return mask.is_only(Encoding::PLAIN_DICTIONARY) || mask.is_only(Encoding::RLE_DICTIONARY);
+    }
+    let Some(page_stats) = col.page_encoding_stats() else {
+        return false;
+    };
+    let mut data_pages = page_stats
+        .iter()
+        .filter(|s| matches!(s.page_type, PageType::DATA_PAGE | PageType::DATA_PAGE_V2))
+        .peekable();
+    data_pages.peek().is_some()
+        && data_pages.all(|s| {
+            matches!(
+                s.encoding,
+                Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY
+            )
+        })
+}
+
+/// Non-empty row groups of a column, or `None` unless every one of them is
+/// fully dictionary-encoded.
+fn fully_dictionary_row_groups(metadata: &ParquetMetaData, col_idx: usize) -> Option<Vec<usize>> {
+    let chunks: Vec<usize> = (0..metadata.num_row_groups())
+        .filter(|&rg| metadata.row_group(rg).column(col_idx).num_values() > 0)
+        .collect();
+    let all_dictionary = !chunks.is_empty()
+        && chunks
+            .iter()
+            .all(|&rg| fully_dictionary_encoded(metadata.row_group(rg).column(col_idx)));
+    all_dictionary.then_some(chunks)
+}
+
+/// Exact distinct count from the union of every row group's dictionary
+/// entries, or `None` unless all non-empty row groups are fully
+/// dictionary-encoded.
+async fn union_dictionary_entries(
+    pages: &PageCache,
+    metadata: &ParquetMetaData,
+    col_idx: usize,
+) -> Option<u64> {
+    let chunks = fully_dictionary_row_groups(metadata, col_idx)?;
+    let descr = metadata.file_metadata().schema_descr().column(col_idx);
+    let mut set = HashSet::new();
+    for rg in chunks {
+        let (buf, num_values) = read_dictionary_page(pages, rg, col_idx).await?;
+        hash_plain_values(
+            &buf,
+            num_values,
+            descr.physical_type(),
+            descr.type_length(),
+            &mut set,
+        )?;
+    }
+    Some(set.len() as u64)
+}
+
+/// Hash each PLAIN-encoded value in `buf`. Returns `None` if the buffer does
+/// not hold `num_values` values of the given type.
+fn hash_plain_values(
+    buf: &[u8],
+    num_values: u64,
+    physical_type: PhysicalType,
+    type_length: i32,
+    set: &mut HashSet<u64>,
+) -> Option<()> {
+    let width = match physical_type {
+        PhysicalType::INT32 | PhysicalType::FLOAT => 4,
+        PhysicalType::INT64 | PhysicalType::DOUBLE => 8,
+        PhysicalType::INT96 => 12,
+        PhysicalType::FIXED_LEN_BYTE_ARRAY => usize::try_from(type_length).ok()?,
+        PhysicalType::BYTE_ARRAY => 0,
+        PhysicalType::BOOLEAN => return None,
+    };
+    let mut rest = buf;
+    for _ in 0..num_values {
+        let value = if physical_type == PhysicalType::BYTE_ARRAY {
+            let len = u32::from_le_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+            let value = rest.get(4..4 + len)?;
+            rest = &rest[4 + len..];
+            value
+        } else {
+            let value = rest.get(..width)?;
+            rest = &rest[width..];
+            value
+        };
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        set.insert(hasher.finish());
+    }
+    Some(())
+}
+
+/// Sketch `columns` over the rows of `sample_rgs`, the first
+/// `rows_per_group` of each or all of them, and extrapolate each sketch's
+/// distinct ratio to the file.
 async fn sample_cardinalities(
     reader: &ParquetObjectReader,
-    metadata: &ParquetMetaData,
+    metadata: &Arc<ParquetMetaData>,
     non_null_totals: &[u64],
-    sample_rg_idx: usize,
+    sample_rgs: &[usize],
+    rows_per_group: Option<usize>,
     columns: &[usize],
     result: &mut [Option<ColumnCardinality>],
 ) -> Result<()> {
-    use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
-
-    let builder = ParquetRecordBatchStreamBuilder::new(reader.clone())
-        .await?
-        .with_row_groups(vec![sample_rg_idx])
-        .with_batch_size(SAMPLE_ROWS)
-        .with_limit(SAMPLE_ROWS);
-
-    // Project only the columns we need
-    let mask = parquet::arrow::ProjectionMask::leaves(
-        metadata.file_metadata().schema_descr(),
-        columns.iter().copied(),
-    );
-    let mut stream = builder.with_projection(mask).build()?;
-
-    let mut sets: Vec<HashSet<u64>> = vec![HashSet::new(); columns.len()];
+    let mut sketches = vec![HyperLogLog::new(); columns.len()];
     let mut sample_non_null_counts = vec![0u64; columns.len()];
 
-    while let Some(batch_result) = stream.next().await {
-        let batch = batch_result?;
-        // Projected batch columns are in order of `columns`
-        for (i, _col_idx) in columns.iter().enumerate() {
-            let array = batch.column(i).as_ref();
-            hash_array_values(array, &mut sets[i]);
-            sample_non_null_counts[i] += (array.len() - array.null_count()) as u64;
+    // One stream per row group so each contributes its own rows.
+    for &rg_idx in sample_rgs {
+        let mut builder = loader::stream_builder(reader, metadata)?
+            .with_row_groups(vec![rg_idx])
+            .with_batch_size(SAMPLE_ROWS);
+        if let Some(rows) = rows_per_group {
+            builder = builder.with_limit(rows);
+        }
+
+        // Project only the columns we need
+        let mask = parquet::arrow::ProjectionMask::leaves(
+            metadata.file_metadata().schema_descr(),
+            columns.iter().copied(),
+        );
+        let mut stream = builder.with_projection(mask).build()?;
+
+        while let Some(batch_result) = stream.next().await {
+            let batch = batch_result?;
+            // Projected batch columns are in order of `columns`
+            for (i, _col_idx) in columns.iter().enumerate() {
+                let array = batch.column(i).as_ref();
+                hash_array_values(array, &mut sketches[i]);
+                sample_non_null_counts[i] += (array.len() - array.null_count()) as u64;
+            }
         }
     }
 
@@ -222,7 +548,7 @@ async fn sample_cardinalities(
             continue;
         }
 
-        let sample_distinct = sets[i].len() as u64;
+        let sample_distinct = sketches[i].estimate().min(sample_non_null);
         let total_non_null = non_null_totals[col_idx];
         let estimated = scale_distinct(sample_distinct, sample_non_null, total_non_null);
 
@@ -233,6 +559,7 @@ async fn sample_cardinalities(
             result[col_idx] = Some(ColumnCardinality {
                 distinct_count: sampled,
                 non_null_count: total_non_null,
+                exact: false,
             });
         }
     }
@@ -240,15 +567,49 @@ async fn sample_cardinalities(
     Ok(())
 }
 
-fn hash_array_values(array: &dyn Array, set: &mut HashSet<u64>) {
-    for i in 0..array.len() {
-        if array.is_null(i) {
-            continue;
+/// Stream every row group of `columns` and count distinct value hashes.
+async fn exact_cardinalities(
+    reader: &ParquetObjectReader,
+    metadata: &Arc<ParquetMetaData>,
+    non_null_totals: &[u64],
+    columns: &[usize],
+    result: &mut [Option<ColumnCardinality>],
+) -> Result<()> {
+    let mask = parquet::arrow::ProjectionMask::leaves(
+        metadata.file_metadata().schema_descr(),
+        columns.iter().copied(),
+    );
+    let mut stream = loader::stream_builder(reader, metadata)?
+        .with_projection(mask)
+        .build()?;
+
+    let mut sets: Vec<HashSet<u64>> = vec![HashSet::new(); columns.len()];
+    while let Some(batch_result) = stream.next().await {
+        let batch = batch_result?;
+        for (i, set) in sets.iter_mut().enumerate() {
+            hash_array_values(batch.column(i).as_ref(), set);
         }
+    }
+
+    for (set, &col_idx) in sets.iter().zip(columns) {
+        let total_non_null = non_null_totals[col_idx];
+        result[col_idx] = Some(ColumnCardinality {
+            distinct_count: (set.len() as u64).min(total_non_null),
+            non_null_count: total_non_null,
+            exact: true,
+        });
+    }
+    Ok(())
+}
+
+/// Feed the hash of every non-null value of `array` into `sink`, a
+/// `HashSet` for exact counts or a `HyperLogLog` for estimates.
+fn hash_array_values(array: &dyn Array, sink: &mut impl Extend<u64>) {
+    sink.extend((0..array.len()).filter(|&i| !array.is_null(i)).map(|i| {
         let mut hasher = DefaultHasher::new();
         hash_value(array, i, &mut hasher);
-        set.insert(hasher.finish());
-    }
+        hasher.finish()
+    }));
 }
 
 fn hash_value(array: &dyn Array, i: usize, hasher: &mut impl Hasher) {
@@ -319,10 +680,122 @@ mod tests {
         let card = ColumnCardinality {
             distinct_count: 0,
             non_null_count: 0,
+            exact: false,
         };
         assert_eq!(card.ratio(), 0.0);
     }
 
+    #[test]
+    fn spread_indices_cover_first_middle_last() {
+        assert_eq!(spread_indices(10, 3), vec![0, 4, 9]);
+        assert_eq!(spread_indices(2, 3), vec![0, 1]);
+        assert_eq!(spread_indices(5, 1), vec![0]);
+    }
+
+    #[test]
+    fn merge_distinct_pools_row_groups() {
+        // Disjoint ranges in time-ordered groups add up.
+        assert_eq!(merge_distinct(&[(100, 100), (100, 100)], 1000), Some(1000));
+        // A low-cardinality group never drags below the largest group.
+        assert_eq!(merge_distinct(&[(5, 100), (80, 100)], 200), Some(85));
+        assert_eq!(merge_distinct(&[], 200), None);
+    }
+
+    #[tokio::test]
+    async fn exact_mode_counts_every_row_group() -> Result<()> {
+        use object_store::ObjectStore;
+        use object_store::memory::InMemory;
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+        use std::sync::Arc;
+
+        // Values repeat within each row group but differ across groups.
+        let values: Vec<i64> = (0..4000).map(|i| i / 100).collect();
+        let batch = RecordBatch::try_from_iter([("v", Arc::new(Int64Array::from(values)) as _)])?;
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(1000)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let store = Arc::new(InMemory::new());
+        let path = object_store::path::Path::from("t.parquet");
+        store.put(&path, buffer.into()).await?;
+        let file = LoadedFile::open(store, path).await?;
+
+        let options = LintOptions {
+            cardinality: CardinalityMode::Exact,
+            ..LintOptions::default()
+        };
+        let cards = estimate(&file, &[0], &options).await?;
+        assert!(cards[0].exact);
+        assert_eq!(cards[0].distinct_count, 40);
+        Ok(())
+    }
+
+    #[test]
+    fn hash_plain_values_reads_length_prefixed_byte_arrays() {
+        let mut buf = Vec::new();
+        for v in ["ab", "c", "ab"] {
+            buf.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            buf.extend_from_slice(v.as_bytes());
+        }
+        let mut set = HashSet::new();
+        assert!(hash_plain_values(&buf, 3, PhysicalType::BYTE_ARRAY, 0, &mut set).is_some());
+        assert_eq!(set.len(), 2);
+        assert!(hash_plain_values(&buf, 4, PhysicalType::BYTE_ARRAY, 0, &mut set).is_none());
+    }
+
+    #[tokio::test]
+    async fn dictionary_union_is_exact_across_row_groups() -> Result<()> {
+        use object_store::ObjectStore;
+        use object_store::memory::InMemory;
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+        use std::sync::Arc;
+
+        // Each row group has 10 distinct values; half overlap the next group.
+        let values: Vec<String> = (0..3000)
+            .map(|i| format!("k{}", (i / 1000) * 5 + i % 10))
+            .collect();
+        let batch = RecordBatch::try_from_iter([("s", Arc::new(StringArray::from(values)) as _)])?;
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(1000)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let store = Arc::new(InMemory::new());
+        let path = object_store::path::Path::from("d.parquet");
+        store.put(&path, buffer.into()).await?;
+        let file = LoadedFile::open(store, path).await?;
+
+        assert_eq!(
+            union_dictionary_entries(&file.pages, &file.metadata, 0).await,
+            Some(20)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hyperloglog_estimates_within_a_few_percent() {
+        for distinct in [10u64, 1_000, 100_000, 1_000_000] {
+            let mut sketch = HyperLogLog::new();
+            // Every value twice: duplicates must not count.
+            sketch.extend((0..distinct * 2).map(|i| {
+                let mut hasher = DefaultHasher::new();
+                (i % distinct).hash(&mut hasher);
+                hasher.finish()
+            }));
+            let error = sketch.estimate().abs_diff(distinct) as f64 / distinct as f64;
+            assert!(error < 0.03, "{distinct}: estimated {}", sketch.estimate());
+        }
+    }
+
     #[test]
     fn sampling_distinct_ignores_null_values() {
         let array = StringArray::from(vec![Some("a"), None, Some("a"), None]);
@@ -1,15 +1,26 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use arrow_schema::DataType;
-use parquet::arrow::async_reader::ParquetObjectReader;
-use parquet::basic::{LogicalType, TimeUnit, Type as PhysicalType};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::basic::{Encoding, LogicalType, TimeUnit, Type as PhysicalType};
 use parquet::file::metadata::ParquetMetaData;
 use parquet::file::statistics::Statistics;
 use parquet::schema::types::ColumnDescriptor;
+use tokio::sync::OnceCell;
 
 use crate::cardinality;
+use crate::loader::LoadedFile;
+use crate::metrics::{PHASE_CARDINALITY, PHASE_SAMPLING};
+use crate::options::LintOptions;
+use crate::probe::{self, CompressionTrial, EncodingTrial};
 
 /// Per-leaf-column context combining type information and statistics
 /// extracted from Parquet metadata.
 pub struct ColumnContext {
+    /// Leaf column index in the file schema.
+    pub column_index: usize,
     /// Parquet physical storage type.
     pub physical_type: PhysicalType,
     /// Parquet logical type annotation (if any).
@@ -23,6 +34,9 @@ pub struct ColumnContext {
     pub null_count: u64,
     /// Estimated number of distinct non-null values (file-level).
     pub distinct_count: u64,
+    /// True when `distinct_count` was counted over every row rather than
+    /// extrapolated (see `CardinalityMode::Exact`).
+    pub distinct_count_exact: bool,
 
     /// Total uncompressed byte size across all row groups.
     pub uncompressed_size: i64,
@@ -31,6 +45,32 @@ pub struct ColumnContext {
 
     /// Type-specific statistics extracted from column-chunk metadata.
     pub type_stats: TypeStats,
+
+    /// Per-row-group slices, in row-group order (empty unless
+    /// `LintOptions::row_group_contexts` is set).
+    pub row_groups: Vec<RowGroupColumnContext>,
+
+    /// Sampled value distribution (only when `LintOptions::profile_values`
+    /// is set and the column is flat).
+    pub distribution: Option<ValueDistribution>,
+
+    /// How sorted the column is, from 0 (random) to 1 (monotonic in either
+    /// direction). Averages the share of consecutive row groups whose min/max
+    /// ranges do not overlap with the share of ordered adjacent sampled values.
+    /// `None` when neither signal is available.
+    pub sortedness: Option<f64>,
+
+    /// How nulls cluster in the sampled row group (only when the column was
+    /// sampled).
+    pub null_runs: Option<NullRuns>,
+
+    /// Sampled data re-compressed with candidate codecs (only when
+    /// `LintOptions::probe_compression` is set and the column is large, or
+    /// small, UNCOMPRESSED and dictionary encoded).
+    pub compression_trials: Vec<CompressionTrial>,
+    /// Sampled data re-encoded with candidate encodings (only when
+    /// `LintOptions::probe_encodings` is set and the column is large).
+    pub encoding_trials: Vec<EncodingTrial>,
 }
 
 impl ColumnContext {
@@ -46,6 +86,11 @@ impl ColumnContext {
         }
     }
 
+    /// Measured result for `encoding`, if the encoding probe tried it.
+    pub fn encoding_trial(&self, encoding: Encoding) -> Option<&EncodingTrial> {
+        self.encoding_trials.iter().find(|t| t.encoding == encoding)
+    }
+
     pub fn cardinality_ratio(&self) -> f64 {
         let nn = self.non_null_count();
         if nn == 0 {
@@ -56,6 +101,141 @@ impl ColumnContext {
     }
 }
 
+/// One row group's view of a column, taken from its column-chunk metadata.
+pub struct RowGroupColumnContext {
+    /// Number of rows in the row group.
+    pub num_rows: u64,
+    /// Number of values (including nulls) in the column chunk.
+    pub num_values: u64,
+    /// Null count from chunk statistics (0 when absent).
+    pub null_count: u64,
+    pub uncompressed_size: i64,
+    pub compressed_size: i64,
+    /// Chunk minimum; byte values may be truncated but still bound the data.
+    pub min: Option<StatValue>,
+    /// Chunk maximum; byte values may be truncated but still bound the data.
+    pub max: Option<StatValue>,
+}
+
+impl RowGroupColumnContext {
+    pub fn null_ratio(&self) -> f64 {
+        if self.num_values == 0 {
+            0.0
+        } else {
+            self.null_count as f64 / self.num_values as f64
+        }
+    }
+}
+
+/// A statistics value widened to a comparable representation.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum StatValue {
+    Boolean(bool),
+    Int(i64),
+    Float(f64),
+    Bytes(Vec<u8>),
+}
+
+/// Value distribution observed in the sampled row group.
+pub struct ValueDistribution {
+    /// Number of non-null values sampled.
+    pub sample_size: u64,
+    /// Equi-depth histogram over numeric values.
+    pub histogram: Option<Histogram>,
+    /// Most frequent string values, most frequent first.
+    pub heavy_hitters: Vec<HeavyHitter>,
+}
+
+impl ValueDistribution {
+    /// Fraction of sampled values taken by the single most frequent string.
+    pub fn top_share(&self) -> f64 {
+        match self.heavy_hitters.first() {
+            Some(top) if self.sample_size > 0 => top.count as f64 / self.sample_size as f64,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Equi-depth histogram: each bucket holds roughly the same number of sampled
+/// values, so narrow buckets reveal where values concentrate.
+pub struct Histogram {
+    /// `buckets + 1` ascending boundaries; bucket `i` spans
+    /// `bounds[i]..=bounds[i + 1]`.
+    pub bounds: Vec<f64>,
+}
+
+impl Histogram {
+    pub fn num_buckets(&self) -> usize {
+        self.bounds.len().saturating_sub(1)
+    }
+}
+
+pub struct HeavyHitter {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Run-length histogram of consecutive nulls in the sampled rows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NullRuns {
+    /// Number of rows sampled (null or not).
+    pub sampled_rows: u64,
+    /// Number of nulls sampled.
+    pub null_count: u64,
+    /// `buckets[i]` counts null runs with length in `2^i..2^(i+1)`.
+    pub buckets: Vec<u64>,
+}
+
+impl NullRuns {
+    pub fn num_runs(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Average null run length; ~1 for salt-and-pepper nulls, large when
+    /// nulls come in blocks.
+    pub fn avg_run_length(&self) -> f64 {
+        match self.num_runs() {
+            0 => 0.0,
+            runs => self.null_count as f64 / runs as f64,
+        }
+    }
+
+    fn push_run(&mut self, len: u64) {
+        if len == 0 {
+            return;
+        }
+        let bucket = len.ilog2() as usize;
+        if self.buckets.len() <= bucket {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+        self.null_count += len;
+    }
+}
+
+/// Tracks the open null run across sampled batches.
+#[derive(Default)]
+struct NullRunAccumulator {
+    runs: NullRuns,
+    current: u64,
+}
+
+impl NullRunAccumulator {
+    fn push(&mut self, is_null: bool) {
+        self.runs.sampled_rows += 1;
+        if is_null {
+            self.current += 1;
+        } else {
+            self.runs.push_run(std::mem::take(&mut self.current));
+        }
+    }
+
+    fn finish(mut self) -> NullRuns {
+        self.runs.push_run(self.current);
+        self.runs
+    }
+}
+
 pub enum TypeStats {
     Boolean(BooleanStats),
     Int(IntStats),
@@ -109,6 +289,9 @@ pub struct StringStats {
     pub max_value: Option<String>,
     /// Length statistics from sampling one row group.
     pub lengths: Option<ByteLengthStats>,
+    /// Average common-prefix length in bytes between adjacent values of the
+    /// sorted sample (only when the column was sampled).
+    pub avg_common_prefix: Option<f64>,
 }
 
 pub struct BinaryStats {
@@ -126,17 +309,34 @@ pub struct FixedLenBinaryStats {
 }
 
 /// Build per-column contexts from metadata and cardinality estimation.
-pub async fn build(
-    reader: &ParquetObjectReader,
-    metadata: &ParquetMetaData,
+pub async fn build(file: &LoadedFile, options: &LintOptions) -> anyhow::Result<Vec<ColumnContext>> {
+    let num_cols = file.metadata.file_metadata().schema_descr().num_columns();
+    let cols: Vec<usize> = (0..num_cols).collect();
+    build_columns(file, &cols, options).await
+}
+
+/// Build contexts for the given leaf columns only, in ascending column order.
+pub async fn build_columns(
+    file: &LoadedFile,
+    cols: &[usize],
+    options: &LintOptions,
 ) -> anyhow::Result<Vec<ColumnContext>> {
-    let cardinalities = cardinality::estimate(reader, metadata).await?;
+    let mut cols = cols.to_vec();
+    cols.sort_unstable();
+    cols.dedup();
+    let (reader, metadata) = (&file.reader, file.metadata.as_ref());
+    let cardinalities = file
+        .metrics
+        .phase(
+            PHASE_CARDINALITY,
+            cardinality::estimate(file, &cols, options),
+        )
+        .await?;
     let schema = metadata.file_metadata().schema_descr();
-    let num_cols = schema.num_columns();
     let arrow_types = derive_arrow_types(metadata);
 
-    let mut columns = Vec::with_capacity(num_cols);
-    for col_idx in 0..num_cols {
+    let mut columns = Vec::with_capacity(cols.len());
+    for (&col_idx, card) in cols.iter().zip(&cardinalities) {
         let descr = schema.column(col_idx);
         let physical_type = descr.physical_type();
         let logical_type = descr.logical_type_ref().cloned();
@@ -165,26 +365,221 @@ pub async fn build(
             col_idx,
         );
 
-        let card = &cardinalities[col_idx];
+        let mut row_groups = build_row_group_contexts(metadata, col_idx);
+        let sortedness = row_group_sortedness(&row_groups);
+        if !options.row_group_contexts {
+            row_groups = Vec::new();
+        }
 
         columns.push(ColumnContext {
+            column_index: col_idx,
             physical_type,
             logical_type,
             arrow_type: arrow_types[col_idx].clone(),
             num_values,
             null_count,
             distinct_count: card.distinct_count,
+            distinct_count_exact: card.exact,
             uncompressed_size,
             compressed_size,
             type_stats,
+            row_groups,
+            distribution: None,
+            sortedness,
+            null_runs: None,
+            compression_trials: Vec::new(),
+            encoding_trials: Vec::new(),
         });
     }
 
-    fill_sampled_stats(reader, metadata, &mut columns).await?;
+    if options.metadata_only {
+        return Ok(columns);
+    }
+    file.metrics
+        .phase(PHASE_SAMPLING, async {
+            fill_sampled_stats(file, &mut columns, options).await?;
+            probe::run(reader, &file.metadata, &mut columns, options).await
+        })
+        .await?;
 
     Ok(columns)
 }
 
+/// Column contexts built on first access, so rules that never look at a
+/// column do not pay for its cardinality estimate or sampling.
+pub struct LazyColumns {
+    file: LoadedFile,
+    options: LintOptions,
+    cells: Vec<OnceCell<ColumnContext>>,
+    /// First build failure, surfaced by `lint_file` after the rules ran.
+    error: Mutex<Option<anyhow::Error>>,
+}
+
+impl LazyColumns {
+    pub fn new(file: &LoadedFile, options: &LintOptions) -> Self {
+        let num_cols = file.metadata.file_metadata().schema_descr().num_columns();
+        Self {
+            file: file.clone(),
+            options: options.clone(),
+            cells: (0..num_cols).map(|_| OnceCell::new()).collect(),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Context for one leaf column, building it if needed. Returns `None` if
+    /// the index is out of range or the build failed.
+    pub async fn get(&self, col_idx: usize) -> Option<&ColumnContext> {
+        let cell = self.cells.get(col_idx)?;
+        if !cell.initialized() {
+            self.load(&[col_idx]).await;
+        }
+        cell.get()
+    }
+
+    /// Build every listed column that is not built yet in one batch, so
+    /// their sampling and dictionary reads are shared.
+    pub async fn load(&self, cols: &[usize]) {
+        let missing: Vec<usize> = cols
+            .iter()
+            .copied()
+            .filter(|&i| self.cells.get(i).is_some_and(|c| !c.initialized()))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        match build_columns(&self.file, &missing, &self.options).await {
+            Ok(built) => {
+                for column in built {
+                    // A concurrent load may have filled the cell first.
+                    let _ = self.cells[column.column_index].set(column);
+                }
+            }
+            Err(e) => {
+                let mut error = self.error.lock().expect("column error poisoned");
+                error.get_or_insert(e);
+            }
+        }
+    }
+
+    /// The first error hit while building columns, if any.
+    pub fn take_error(&self) -> Option<anyhow::Error> {
+        self.error.lock().expect("column error poisoned").take()
+    }
+}
+
+fn build_row_group_contexts(
+    metadata: &ParquetMetaData,
+    col_idx: usize,
+) -> Vec<RowGroupColumnContext> {
+    metadata
+        .row_groups()
+        .iter()
+        .map(|rg| {
+            let col = rg.column(col_idx);
+            let stats = col.statistics();
+            let (min, max) = stats.map_or((None, None), stat_minmax);
+            RowGroupColumnContext {
+                num_rows: rg.num_rows().max(0) as u64,
+                num_values: col.num_values().max(0) as u64,
+                null_count: stats.and_then(|s| s.null_count_opt()).unwrap_or(0),
+                uncompressed_size: col.uncompressed_size(),
+                compressed_size: col.compressed_size(),
+                min,
+                max,
+            }
+        })
+        .collect()
+}
+
+/// Share of consecutive row-group pairs whose ranges are ordered in the
+/// dominant direction (ascending or descending).
+fn row_group_sortedness(row_groups: &[RowGroupColumnContext]) -> Option<f64> {
+    let ranges: Vec<(&StatValue, &StatValue)> = row_groups
+        .iter()
+        .filter_map(|rg| Some((rg.min.as_ref()?, rg.max.as_ref()?)))
+        .collect();
+    let pairs = ranges.len().checked_sub(1).filter(|&pairs| pairs > 0)?;
+    let (mut ascending, mut descending) = (0usize, 0usize);
+    for pair in ranges.windows(2) {
+        let ((prev_min, prev_max), (next_min, next_max)) = (pair[0], pair[1]);
+        if prev_max <= next_min {
+            ascending += 1;
+        }
+        if prev_min >= next_max {
+            descending += 1;
+        }
+    }
+    Some(ascending.max(descending) as f64 / pairs as f64)
+}
+
+/// Counts ordered adjacent pairs in a stream of sampled values.
+#[derive(Default)]
+struct AdjacentOrder {
+    ascending: u64,
+    descending: u64,
+    pairs: u64,
+    last: Option<StatValue>,
+}
+
+impl AdjacentOrder {
+    fn push(&mut self, value: StatValue) {
+        if let Some(last) = &self.last {
+            self.pairs += 1;
+            match last.partial_cmp(&value) {
+                Some(Ordering::Less) => self.ascending += 1,
+                Some(Ordering::Greater) => self.descending += 1,
+                Some(Ordering::Equal) => {
+                    self.ascending += 1;
+                    self.descending += 1;
+                }
+                None => {}
+            }
+        }
+        self.last = Some(value);
+    }
+
+    fn sortedness(&self) -> Option<f64> {
+        (self.pairs > 0).then(|| self.ascending.max(self.descending) as f64 / self.pairs as f64)
+    }
+}
+
+fn combine_sortedness(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some((a + b) / 2.0),
+        (a, b) => a.or(b),
+    }
+}
+
+fn stat_minmax(stats: &Statistics) -> (Option<StatValue>, Option<StatValue>) {
+    match stats {
+        Statistics::Boolean(s) => (
+            s.min_opt().map(|&v| StatValue::Boolean(v)),
+            s.max_opt().map(|&v| StatValue::Boolean(v)),
+        ),
+        Statistics::Int32(s) => (
+            s.min_opt().map(|&v| StatValue::Int(v.into())),
+            s.max_opt().map(|&v| StatValue::Int(v.into())),
+        ),
+        Statistics::Int64(s) => (
+            s.min_opt().map(|&v| StatValue::Int(v)),
+            s.max_opt().map(|&v| StatValue::Int(v)),
+        ),
+        Statistics::Float(s) => (
+            s.min_opt().map(|&v| StatValue::Float(v.into())),
+            s.max_opt().map(|&v| StatValue::Float(v.into())),
+        ),
+        Statistics::Double(s) => (
+            s.min_opt().map(|&v| StatValue::Float(v)),
+            s.max_opt().map(|&v| StatValue::Float(v)),
+        ),
+        Statistics::ByteArray(_) | Statistics::FixedLenByteArray(_) => (
+            stats.min_bytes_opt().map(|b| StatValue::Bytes(b.to_vec())),
+            stats.max_bytes_opt().map(|b| StatValue::Bytes(b.to_vec())),
+        ),
+        Statistics::Int96(_) => (None, None),
+    }
+}
+
 fn derive_arrow_types(metadata: &ParquetMetaData) -> Vec<DataType> {
     let schema_descr = metadata.file_metadata().schema_descr();
     let key_value_metadata = metadata.file_metadata().key_value_metadata();
@@ -353,6 +748,7 @@ fn extract_type_stats(
                     min_value,
                     max_value,
                     lengths: None,
+                    avg_common_prefix: None,
                 })
             } else {
                 let (min_value, max_value) = aggregate_binary_minmax(metadata, col_idx);
@@ -541,6 +937,8 @@ fn aggregate_binary_minmax(
 }
 
 const SAMPLE_ROWS: usize = 16_384;
+const HISTOGRAM_BUCKETS: usize = 16;
+const HEAVY_HITTERS: usize = 10;
 
 /// Returns true if a column has gaps that sampling can fill.
 fn needs_sampling(c: &ColumnContext) -> bool {
@@ -558,19 +956,30 @@ fn needs_sampling(c: &ColumnContext) -> bool {
     }
 }
 
-/// Sample one row group to fill in missing statistics.
+/// Returns true if a column's sampled values can feed a `ValueDistribution`.
+fn is_profilable(c: &ColumnContext) -> bool {
+    matches!(
+        c.type_stats,
+        TypeStats::Int(_) | TypeStats::Float(_) | TypeStats::String(_)
+    )
+}
+
+/// Sample one row group to fill in missing statistics and null runs and, when
+/// profiling, value distributions. Columns are read in groups whose estimated
+/// decoded size fits `LintOptions::sample_memory_limit`, so wide files do not
+/// materialize every sampled column at once.
 async fn fill_sampled_stats(
-    reader: &ParquetObjectReader,
-    metadata: &ParquetMetaData,
+    file: &LoadedFile,
     columns: &mut [ColumnContext],
+    options: &LintOptions,
 ) -> anyhow::Result<()> {
-    use futures::StreamExt;
-    use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
-
+    let metadata = file.metadata.as_ref();
     let sample_cols: Vec<usize> = columns
         .iter()
         .enumerate()
-        .filter(|(_, c)| needs_sampling(c))
+        .filter(|(_, c)| {
+            needs_sampling(c) || (options.profile_values && (is_profilable(c) || c.null_count > 0))
+        })
         .map(|(i, _)| i)
         .collect();
 
@@ -578,126 +987,415 @@ async fn fill_sampled_stats(
         return Ok(());
     }
 
-    let sample_rg_idx = cardinality::pick_sample_row_group(metadata);
-    let mask = parquet::arrow::ProjectionMask::leaves(
-        metadata.file_metadata().schema_descr(),
-        sample_cols.iter().copied(),
-    );
-    let builder = ParquetRecordBatchStreamBuilder::new(reader.clone())
-        .await?
+    let sample_rg = metadata.row_group(cardinality::pick_sample_row_group(metadata));
+    let row_share = (SAMPLE_ROWS as f64 / sample_rg.num_rows().max(1) as f64).min(1.0);
+    let sizes: Vec<(usize, u64)> = sample_cols
+        .iter()
+        .map(|&pos| {
+            let chunk = sample_rg.column(columns[pos].column_index);
+            let bytes = chunk.uncompressed_size().max(0) as f64;
+            (pos, (bytes * row_share) as u64)
+        })
+        .collect();
+    let arrow_metadata =
+        ArrowReaderMetadata::try_new(file.metadata.clone(), ArrowReaderOptions::default())?;
+    for group in group_by_budget(&sizes, options.sample_memory_limit as u64) {
+        let sampler = ColumnSampler {
+            file,
+            arrow_metadata: &arrow_metadata,
+            options,
+        };
+        sampler.sample(columns, &group).await?;
+    }
+    Ok(())
+}
+
+/// Split columns, in order, into groups whose summed size stays within
+/// `budget`. A column larger than the budget gets a group of its own.
+fn group_by_budget(sizes: &[(usize, u64)], budget: u64) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut used = 0u64;
+    for &(col_idx, size) in sizes {
+        match groups.last_mut() {
+            Some(group) if used + size <= budget => {
+                group.push(col_idx);
+                used += size;
+            }
+            _ => {
+                groups.push(vec![col_idx]);
+                used = size;
+            }
+        }
+    }
+    groups
+}
+
+/// Reads one group of sampled columns with a shared footer.
+struct ColumnSampler<'a> {
+    file: &'a LoadedFile,
+    arrow_metadata: &'a ArrowReaderMetadata,
+    options: &'a LintOptions,
+}
+
+impl ColumnSampler<'_> {
+    async fn sample(
+        &self,
+        columns: &mut [ColumnContext],
+        sample_cols: &[usize],
+    ) -> anyhow::Result<()> {
+        use futures::StreamExt;
+        use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+
+        let (metadata, options) = (self.file.metadata.as_ref(), self.options);
+        let sample_rg_idx = cardinality::pick_sample_row_group(metadata);
+        let mask = parquet::arrow::ProjectionMask::leaves(
+            metadata.file_metadata().schema_descr(),
+            sample_cols.iter().map(|&pos| columns[pos].column_index),
+        );
+        let builder = ParquetRecordBatchStreamBuilder::new_with_metadata(
+            self.file.reader.clone(),
+            self.arrow_metadata.clone(),
+        )
         .with_row_groups(vec![sample_rg_idx])
         .with_batch_size(SAMPLE_ROWS)
         .with_limit(SAMPLE_ROWS)
         .with_projection(mask);
-    let mut stream = builder.build()?;
-
-    // Per-column accumulators for byte-length stats.
-    let mut len_min = vec![usize::MAX; sample_cols.len()];
-    let mut len_max = vec![0usize; sample_cols.len()];
-    let mut len_total = vec![0u64; sample_cols.len()];
-    let mut len_count = vec![0u64; sample_cols.len()];
-
-    // Per-column accumulators for typed min/max.
-    let mut bool_min = vec![None::<bool>; sample_cols.len()];
-    let mut bool_max = vec![None::<bool>; sample_cols.len()];
-    let mut int_min = vec![None::<i64>; sample_cols.len()];
-    let mut int_max = vec![None::<i64>; sample_cols.len()];
-    let mut float_min = vec![None::<f64>; sample_cols.len()];
-    let mut float_max = vec![None::<f64>; sample_cols.len()];
-    let mut string_min = vec![None::<String>; sample_cols.len()];
-    let mut string_max = vec![None::<String>; sample_cols.len()];
-    let mut binary_min = vec![None::<Vec<u8>>; sample_cols.len()];
-    let mut binary_max = vec![None::<Vec<u8>>; sample_cols.len()];
-
-    while let Some(batch_result) = stream.next().await {
-        let batch = batch_result?;
-        // For nested schemas, projecting Parquet leaf columns can yield fewer
-        // Arrow columns (Arrow returns top-level fields). Sampling stats here
-        // assumes a 1:1 leaf-column mapping, so skip sampling instead of
-        // panicking or misapplying sampled stats.
-        if batch.num_columns() != sample_cols.len() {
-            return Ok(());
-        }
-        for (i, &col_idx) in sample_cols.iter().enumerate() {
-            let array = batch.column(i).as_ref();
-            match &columns[col_idx].type_stats {
-                TypeStats::Boolean(_) => {
-                    accumulate_bool_minmax(array, &mut bool_min[i], &mut bool_max[i]);
+        let mut stream = builder.build()?;
+
+        // Per-column accumulators for byte-length stats.
+        let mut len_min = vec![usize::MAX; sample_cols.len()];
+        let mut len_max = vec![0usize; sample_cols.len()];
+        let mut len_total = vec![0u64; sample_cols.len()];
+        let mut len_count = vec![0u64; sample_cols.len()];
+
+        // Per-column accumulators for typed min/max.
+        let mut bool_min = vec![None::<bool>; sample_cols.len()];
+        let mut bool_max = vec![None::<bool>; sample_cols.len()];
+        let mut int_min = vec![None::<i64>; sample_cols.len()];
+        let mut int_max = vec![None::<i64>; sample_cols.len()];
+        let mut float_min = vec![None::<f64>; sample_cols.len()];
+        let mut float_max = vec![None::<f64>; sample_cols.len()];
+        let mut string_min = vec![None::<String>; sample_cols.len()];
+        let mut string_max = vec![None::<String>; sample_cols.len()];
+        let mut binary_min = vec![None::<Vec<u8>>; sample_cols.len()];
+        let mut binary_max = vec![None::<Vec<u8>>; sample_cols.len()];
+
+        // Per-column accumulators for value distributions.
+        let mut numeric_values = vec![Vec::<f64>::new(); sample_cols.len()];
+        let mut string_counts = vec![HashMap::<String, u64>::new(); sample_cols.len()];
+        let mut string_values = vec![Vec::<String>::new(); sample_cols.len()];
+        let mut adjacent_order: Vec<AdjacentOrder> = sample_cols
+            .iter()
+            .map(|_| AdjacentOrder::default())
+            .collect();
+        let mut null_runs: Vec<NullRunAccumulator> = sample_cols
+            .iter()
+            .map(|_| NullRunAccumulator::default())
+            .collect();
+
+        while let Some(batch_result) = stream.next().await {
+            let batch = batch_result?;
+            // For nested schemas, projecting Parquet leaf columns can yield fewer
+            // Arrow columns (Arrow returns top-level fields). Sampling stats here
+            // assumes a 1:1 leaf-column mapping, so skip sampling instead of
+            // panicking or misapplying sampled stats.
+            if batch.num_columns() != sample_cols.len() {
+                return Ok(());
+            }
+            for (i, &pos) in sample_cols.iter().enumerate() {
+                let array = batch.column(i).as_ref();
+                for row in 0..array.len() {
+                    null_runs[i].push(array.is_null(row));
                 }
-                TypeStats::Int(_) => {
-                    accumulate_int_minmax(array, &mut int_min[i], &mut int_max[i]);
+                match &columns[pos].type_stats {
+                    TypeStats::Boolean(_) => {
+                        accumulate_bool_minmax(array, &mut bool_min[i], &mut bool_max[i]);
+                    }
+                    TypeStats::Int(_) => {
+                        accumulate_int_minmax(array, &mut int_min[i], &mut int_max[i]);
+                        accumulate_adjacent_order(array, &mut adjacent_order[i]);
+                        if options.profile_values {
+                            accumulate_numeric_values(array, &mut numeric_values[i]);
+                        }
+                    }
+                    TypeStats::Float(_) => {
+                        accumulate_float_minmax(array, &mut float_min[i], &mut float_max[i]);
+                        accumulate_adjacent_order(array, &mut adjacent_order[i]);
+                        if options.profile_values {
+                            accumulate_numeric_values(array, &mut numeric_values[i]);
+                        }
+                    }
+                    TypeStats::String(_) => {
+                        accumulate_string_minmax(array, &mut string_min[i], &mut string_max[i]);
+                        accumulate_adjacent_order(array, &mut adjacent_order[i]);
+                        accumulate_strings(array, &mut string_values[i]);
+                        if options.profile_values {
+                            accumulate_string_counts(array, &mut string_counts[i]);
+                        }
+                        accumulate_byte_lengths(
+                            array,
+                            &mut len_min[i],
+                            &mut len_max[i],
+                            &mut len_total[i],
+                            &mut len_count[i],
+                        );
+                    }
+                    TypeStats::Binary(_) => {
+                        accumulate_binary_minmax(array, &mut binary_min[i], &mut binary_max[i]);
+                        accumulate_byte_lengths(
+                            array,
+                            &mut len_min[i],
+                            &mut len_max[i],
+                            &mut len_total[i],
+                            &mut len_count[i],
+                        );
+                    }
+                    _ => {}
                 }
-                TypeStats::Float(_) => {
-                    accumulate_float_minmax(array, &mut float_min[i], &mut float_max[i]);
+            }
+        }
+
+        // Write sampled stats back, only filling in values that are still None.
+        for ((i, &pos), runs) in sample_cols.iter().enumerate().zip(null_runs) {
+            let c = &mut columns[pos];
+            c.null_runs = Some(runs.finish());
+            c.sortedness = combine_sortedness(c.sortedness, adjacent_order[i].sortedness());
+            if options.profile_values && is_profilable(c) {
+                c.distribution = Some(build_distribution(
+                    std::mem::take(&mut numeric_values[i]),
+                    std::mem::take(&mut string_counts[i]),
+                ));
+            }
+            match &mut c.type_stats {
+                TypeStats::Boolean(s) => {
+                    s.min = s.min.or(bool_min[i]);
+                    s.max = s.max.or(bool_max[i]);
                 }
-                TypeStats::String(_) => {
-                    accumulate_string_minmax(array, &mut string_min[i], &mut string_max[i]);
-                    accumulate_byte_lengths(
-                        array,
-                        &mut len_min[i],
-                        &mut len_max[i],
-                        &mut len_total[i],
-                        &mut len_count[i],
-                    );
+                TypeStats::Int(s) => {
+                    s.min = s.min.or(int_min[i]);
+                    s.max = s.max.or(int_max[i]);
                 }
-                TypeStats::Binary(_) => {
-                    accumulate_binary_minmax(array, &mut binary_min[i], &mut binary_max[i]);
-                    accumulate_byte_lengths(
-                        array,
-                        &mut len_min[i],
-                        &mut len_max[i],
-                        &mut len_total[i],
-                        &mut len_count[i],
-                    );
+                TypeStats::Float(s) => {
+                    s.min = s.min.or(float_min[i]);
+                    s.max = s.max.or(float_max[i]);
+                }
+                TypeStats::String(s) => {
+                    s.min_value = s.min_value.take().or(string_min[i].take());
+                    s.max_value = s.max_value.take().or(string_max[i].take());
+                    s.avg_common_prefix = avg_common_prefix(&mut string_values[i]);
+                    if s.lengths.is_none() && len_count[i] > 0 {
+                        s.lengths = Some(ByteLengthStats {
+                            min: len_min[i],
+                            max: len_max[i],
+                            avg: len_total[i] as f64 / len_count[i] as f64,
+                        });
+                    }
+                }
+                TypeStats::Binary(b) => {
+                    b.min_value = b.min_value.take().or(binary_min[i].take());
+                    b.max_value = b.max_value.take().or(binary_max[i].take());
+                    if b.lengths.is_none() && len_count[i] > 0 {
+                        b.lengths = Some(ByteLengthStats {
+                            min: len_min[i],
+                            max: len_max[i],
+                            avg: len_total[i] as f64 / len_count[i] as f64,
+                        });
+                    }
                 }
                 _ => {}
             }
         }
+
+        Ok(())
+    }
+}
+
+fn build_distribution(
+    mut numeric_values: Vec<f64>,
+    string_counts: HashMap<String, u64>,
+) -> ValueDistribution {
+    let sample_size = numeric_values.len() as u64 + string_counts.values().sum::<u64>();
+    ValueDistribution {
+        sample_size,
+        histogram: equi_depth_histogram(&mut numeric_values, HISTOGRAM_BUCKETS),
+        heavy_hitters: top_k(string_counts, HEAVY_HITTERS),
     }
+}
 
-    // Write sampled stats back, only filling in values that are still None.
-    for (i, col_idx) in sample_cols.into_iter().enumerate() {
-        let c = &mut columns[col_idx];
-        match &mut c.type_stats {
-            TypeStats::Boolean(s) => {
-                s.min = s.min.or(bool_min[i]);
-                s.max = s.max.or(bool_max[i]);
-            }
-            TypeStats::Int(s) => {
-                s.min = s.min.or(int_min[i]);
-                s.max = s.max.or(int_max[i]);
-            }
-            TypeStats::Float(s) => {
-                s.min = s.min.or(float_min[i]);
-                s.max = s.max.or(float_max[i]);
+fn equi_depth_histogram(values: &mut [f64], buckets: usize) -> Option<Histogram> {
+    if values.is_empty() || buckets == 0 {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let last = values.len() - 1;
+    let bounds = (0..=buckets).map(|b| values[b * last / buckets]).collect();
+    Some(Histogram { bounds })
+}
+
+fn top_k(counts: HashMap<String, u64>, k: usize) -> Vec<HeavyHitter> {
+    let mut hitters: Vec<HeavyHitter> = counts
+        .into_iter()
+        .map(|(value, count)| HeavyHitter { value, count })
+        .collect();
+    hitters.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    hitters.truncate(k);
+    hitters
+}
+
+fn accumulate_numeric_values(array: &dyn arrow_array::Array, values: &mut Vec<f64>) {
+    use arrow_array::*;
+    let any = array.as_any();
+
+    macro_rules! acc_num {
+        ($arr:expr) => {{
+            let a = $arr;
+            for i in 0..a.len() {
+                if a.is_null(i) {
+                    continue;
+                }
+                let v = a.value(i) as f64;
+                if !v.is_nan() {
+                    values.push(v);
+                }
             }
-            TypeStats::String(s) => {
-                s.min_value = s.min_value.take().or(string_min[i].take());
-                s.max_value = s.max_value.take().or(string_max[i].take());
-                if s.lengths.is_none() && len_count[i] > 0 {
-                    s.lengths = Some(ByteLengthStats {
-                        min: len_min[i],
-                        max: len_max[i],
-                        avg: len_total[i] as f64 / len_count[i] as f64,
-                    });
+        }};
+    }
+
+    if let Some(a) = any.downcast_ref::<Int32Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<Int64Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<Int16Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<Int8Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<UInt64Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<UInt32Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<UInt16Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<UInt8Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<Float32Array>() {
+        acc_num!(a);
+    } else if let Some(a) = any.downcast_ref::<Float64Array>() {
+        acc_num!(a);
+    }
+}
+
+fn accumulate_adjacent_order(array: &dyn arrow_array::Array, order: &mut AdjacentOrder) {
+    use arrow_array::*;
+    let any = array.as_any();
+
+    macro_rules! acc_order {
+        ($arr:expr, $to_value:expr) => {{
+            let a = $arr;
+            for i in 0..a.len() {
+                if a.is_null(i) {
+                    continue;
                 }
+                order.push($to_value(a.value(i)));
             }
-            TypeStats::Binary(b) => {
-                b.min_value = b.min_value.take().or(binary_min[i].take());
-                b.max_value = b.max_value.take().or(binary_max[i].take());
-                if b.lengths.is_none() && len_count[i] > 0 {
-                    b.lengths = Some(ByteLengthStats {
-                        min: len_min[i],
-                        max: len_max[i],
-                        avg: len_total[i] as f64 / len_count[i] as f64,
-                    });
+        }};
+    }
+
+    if let Some(a) = any.downcast_ref::<Int32Array>() {
+        acc_order!(a, |v| StatValue::Int(i64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<Int64Array>() {
+        acc_order!(a, StatValue::Int);
+    } else if let Some(a) = any.downcast_ref::<Int16Array>() {
+        acc_order!(a, |v| StatValue::Int(i64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<Int8Array>() {
+        acc_order!(a, |v| StatValue::Int(i64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<UInt64Array>() {
+        acc_order!(a, |v| StatValue::Float(v as f64));
+    } else if let Some(a) = any.downcast_ref::<UInt32Array>() {
+        acc_order!(a, |v| StatValue::Int(i64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<UInt16Array>() {
+        acc_order!(a, |v| StatValue::Int(i64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<UInt8Array>() {
+        acc_order!(a, |v| StatValue::Int(i64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<Float32Array>() {
+        acc_order!(a, |v| StatValue::Float(f64::from(v)));
+    } else if let Some(a) = any.downcast_ref::<Float64Array>() {
+        acc_order!(a, StatValue::Float);
+    } else if let Some(a) = any.downcast_ref::<StringArray>() {
+        acc_order!(a, |v: &str| StatValue::Bytes(v.as_bytes().to_vec()));
+    } else if let Some(a) = any.downcast_ref::<LargeStringArray>() {
+        acc_order!(a, |v: &str| StatValue::Bytes(v.as_bytes().to_vec()));
+    } else if let Some(a) = any.downcast_ref::<StringViewArray>() {
+        acc_order!(a, |v: &str| StatValue::Bytes(v.as_bytes().to_vec()));
+    }
+}
+
+/// Sorts `values` and averages the common-prefix length of adjacent pairs.
+fn avg_common_prefix(values: &mut [String]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    values.sort_unstable();
+    let total: usize = values
+        .windows(2)
+        .map(|pair| {
+            pair[0]
+                .bytes()
+                .zip(pair[1].bytes())
+                .take_while(|(a, b)| a == b)
+                .count()
+        })
+        .sum();
+    Some(total as f64 / (values.len() - 1) as f64)
+}
+
+fn accumulate_strings(array: &dyn arrow_array::Array, values: &mut Vec<String>) {
+    use arrow_array::*;
+    let any = array.as_any();
+
+    macro_rules! acc_strings {
+        ($arr:expr) => {{
+            let a = $arr;
+            values.extend(a.iter().flatten().map(str::to_owned));
+        }};
+    }
+
+    if let Some(a) = any.downcast_ref::<StringArray>() {
+        acc_strings!(a);
+    } else if let Some(a) = any.downcast_ref::<LargeStringArray>() {
+        acc_strings!(a);
+    } else if let Some(a) = any.downcast_ref::<StringViewArray>() {
+        acc_strings!(a);
+    }
+}
+
+fn accumulate_string_counts(array: &dyn arrow_array::Array, counts: &mut HashMap<String, u64>) {
+    use arrow_array::*;
+    let any = array.as_any();
+
+    macro_rules! acc_count {
+        ($arr:expr) => {{
+            let a = $arr;
+            for i in 0..a.len() {
+                if a.is_null(i) {
+                    continue;
+                }
+                let v = a.value(i);
+                if let Some(count) = counts.get_mut(v) {
+                    *count += 1;
+                } else {
+                    counts.insert(v.to_owned(), 1);
                 }
             }
-            _ => {}
-        }
+        }};
     }
 
-    Ok(())
+    if let Some(a) = any.downcast_ref::<StringArray>() {
+        acc_count!(a);
+    } else if let Some(a) = any.downcast_ref::<LargeStringArray>() {
+        acc_count!(a);
+    } else if let Some(a) = any.downcast_ref::<StringViewArray>() {
+        acc_count!(a);
+    }
 }
 
 fn accumulate_bool_minmax(
@@ -919,15 +1617,23 @@ mod tests {
     #[test]
     fn non_null_count_saturates() {
         let ctx = ColumnContext {
+            column_index: 0,
             physical_type: PhysicalType::INT32,
             logical_type: None,
             arrow_type: DataType::Int32,
             num_values: 10,
             null_count: 100,
             distinct_count: 0,
+            distinct_count_exact: false,
             uncompressed_size: 0,
             compressed_size: 0,
             type_stats: TypeStats::Unknown,
+            row_groups: Vec::new(),
+            distribution: None,
+            sortedness: None,
+            null_runs: None,
+            compression_trials: Vec::new(),
+            encoding_trials: Vec::new(),
         };
         assert_eq!(ctx.non_null_count(), 0);
     }
@@ -935,15 +1641,23 @@ mod tests {
     #[test]
     fn cardinality_ratio_zero_when_all_null() {
         let ctx = ColumnContext {
+            column_index: 0,
             physical_type: PhysicalType::INT32,
             logical_type: None,
             arrow_type: DataType::Int32,
             num_values: 100,
             null_count: 100,
             distinct_count: 0,
+            distinct_count_exact: false,
             uncompressed_size: 0,
             compressed_size: 0,
             type_stats: TypeStats::Unknown,
+            row_groups: Vec::new(),
+            distribution: None,
+            sortedness: None,
+            null_runs: None,
+            compression_trials: Vec::new(),
+            encoding_trials: Vec::new(),
         };
         assert_eq!(ctx.cardinality_ratio(), 0.0);
     }
@@ -951,19 +1665,144 @@ mod tests {
     #[test]
     fn cardinality_ratio_computes_correctly() {
         let ctx = ColumnContext {
+            column_index: 0,
             physical_type: PhysicalType::INT32,
             logical_type: None,
             arrow_type: DataType::Int32,
             num_values: 1000,
             null_count: 0,
             distinct_count: 100,
+            distinct_count_exact: false,
             uncompressed_size: 0,
             compressed_size: 0,
             type_stats: TypeStats::Unknown,
+            row_groups: Vec::new(),
+            distribution: None,
+            sortedness: None,
+            null_runs: None,
+            compression_trials: Vec::new(),
+            encoding_trials: Vec::new(),
         };
         assert!((ctx.cardinality_ratio() - 0.1).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn row_group_contexts_follow_row_group_order() -> anyhow::Result<()> {
+        use arrow_array::{Int32Array, RecordBatch};
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use std::sync::Arc;
+
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int32Array::from(vec![Some(3), None, Some(1), Some(2)])) as _,
+        )])?;
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let reader = SerializedFileReader::new(bytes::Bytes::from(buffer))?;
+
+        let row_groups = build_row_group_contexts(reader.metadata(), 0);
+        assert_eq!(row_groups.len(), 2);
+        assert_eq!(row_groups[0].null_ratio(), 0.5);
+        assert_eq!(row_groups[0].min, Some(StatValue::Int(3)));
+        assert_eq!(row_groups[1].min, Some(StatValue::Int(1)));
+        assert_eq!(row_groups[1].max, Some(StatValue::Int(2)));
+        Ok(())
+    }
+
+    #[test]
+    fn row_group_sortedness_counts_non_overlapping_ranges() {
+        let rg = |min: i64, max: i64| RowGroupColumnContext {
+            num_rows: 1,
+            num_values: 1,
+            null_count: 0,
+            uncompressed_size: 0,
+            compressed_size: 0,
+            min: Some(StatValue::Int(min)),
+            max: Some(StatValue::Int(max)),
+        };
+        assert_eq!(
+            row_group_sortedness(&[rg(0, 9), rg(10, 19), rg(20, 29)]),
+            Some(1.0)
+        );
+        assert_eq!(
+            row_group_sortedness(&[rg(20, 29), rg(10, 19), rg(0, 29)]),
+            Some(0.5)
+        );
+        assert_eq!(row_group_sortedness(&[rg(0, 9)]), None);
+    }
+
+    #[test]
+    fn adjacent_order_accepts_either_direction() {
+        let mut order = AdjacentOrder::default();
+        for v in [5, 4, 4, 3, 7] {
+            order.push(StatValue::Int(v));
+        }
+        assert_eq!(order.sortedness(), Some(0.75));
+    }
+
+    #[test]
+    fn avg_common_prefix_uses_sorted_neighbours() {
+        let mut values: Vec<String> = ["/a/b/2", "/x", "/a/b/1", "/a/c"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        // Sorted: /a/b/1, /a/b/2, /a/c, /x -> prefixes 5, 3, 1.
+        assert_eq!(avg_common_prefix(&mut values), Some(3.0));
+        assert_eq!(avg_common_prefix(&mut [String::from("only")]), None);
+    }
+
+    #[test]
+    fn null_runs_bucket_by_power_of_two() {
+        let mut acc = NullRunAccumulator::default();
+        for is_null in [true, false, true, true, true, false, false, true, true] {
+            acc.push(is_null);
+        }
+        let runs = acc.finish();
+        assert_eq!(runs.sampled_rows, 9);
+        assert_eq!(runs.null_count, 6);
+        // Runs of 1, 3, 2 -> buckets [1], [2..4) x2.
+        assert_eq!(runs.buckets, vec![1, 2]);
+        assert_eq!(runs.avg_run_length(), 2.0);
+    }
+
+    #[test]
+    fn group_by_budget_keeps_groups_within_cap() {
+        let sizes = [(0, 40), (1, 50), (2, 30), (3, 200), (4, 10)];
+        assert_eq!(
+            group_by_budget(&sizes, 100),
+            vec![vec![0, 1], vec![2], vec![3], vec![4]]
+        );
+        assert_eq!(group_by_budget(&sizes, u64::MAX), vec![vec![0, 1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn equi_depth_histogram_splits_values_evenly() {
+        let mut values: Vec<f64> = (0..=100).rev().map(f64::from).collect();
+        let histogram = equi_depth_histogram(&mut values, 4).unwrap();
+        assert_eq!(histogram.bounds, vec![0.0, 25.0, 50.0, 75.0, 100.0]);
+        assert!(equi_depth_histogram(&mut [], 4).is_none());
+    }
+
+    #[test]
+    fn distribution_keeps_most_frequent_strings_first() {
+        let counts = HashMap::from([
+            ("us".to_string(), 6),
+            ("eu".to_string(), 3),
+            ("ap".to_string(), 1),
+        ]);
+        let distribution = build_distribution(Vec::new(), counts);
+        assert_eq!(distribution.sample_size, 10);
+        assert_eq!(distribution.heavy_hitters[0].value, "us");
+        assert!((distribution.top_share() - 0.6).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn int_type_info_uses_logical_type() {
         let (is_signed, bit_width) = int_type_info(
@@ -983,4 +1822,36 @@ mod tests {
         assert!(is_signed);
         assert_eq!(bit_width, 64);
     }
+
+    #[tokio::test]
+    async fn lazy_columns_build_only_requested_columns() -> anyhow::Result<()> {
+        use arrow_array::{Int64Array, RecordBatch};
+        use object_store::ObjectStore;
+        use object_store::memory::InMemory;
+        use parquet::arrow::ArrowWriter;
+        use std::sync::Arc;
+
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int64Array::from_iter_values(0..100)) as _),
+            ("b", Arc::new(Int64Array::from_iter_values(0..100)) as _),
+        ])?;
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let store = Arc::new(InMemory::new());
+        let path = object_store::path::Path::from("lazy.parquet");
+        store.put(&path, buffer.into()).await?;
+        let file = LoadedFile::open(store, path).await?;
+
+        let columns = LazyColumns::new(&file, &LintOptions::default());
+        let b = columns.get(1).await.expect("column built");
+        assert_eq!(b.column_index, 1);
+        assert_eq!(b.num_values, 100);
+        assert!(!columns.cells[0].initialized());
+        assert!(columns.get(2).await.is_none());
+        assert!(columns.take_error().is_none());
+        Ok(())
+    }
 }
@@ -0,0 +1,330 @@
+//! Compaction plans: the small Parquet files of a directory grouped into
+//! batches of about a target size, each merged into one output written with
+//! a prescription. Plans round-trip through JSON, so they can be reviewed
+//! (or edited) before they are run.
+
+use std::fmt;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use parquet::schema::types::Type;
+use serde_json::{Value, json};
+
+use crate::fix::{RewriteWriter, SchemaRewrite};
+use crate::loader::{self, LoadedFile};
+use crate::prescription::{DatasetPrescription, Prescription};
+
+pub const DEFAULT_TARGET_BYTES: u64 = 128 * 1024 * 1024; // 128 MB
+
+/// Inputs merged into one output.
+#[derive(Debug, Clone)]
+pub struct CompactionBatch {
+    /// Relative to the plan's output directory.
+    pub output: PathBuf,
+    /// Relative to the plan's input directory, merged in this order.
+    pub inputs: Vec<PathBuf>,
+    /// Total size of the inputs.
+    pub bytes: u64,
+    pub prescription: Prescription,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompactionPlan {
+    pub input_dir: PathBuf,
+    pub output_dir: PathBuf,
+    pub target_bytes: u64,
+    pub batches: Vec<CompactionBatch>,
+}
+
+/// Plans the compaction of every Parquet file below `input_dir`. Files
+/// smaller than half the target are candidates; they merge only with files
+/// of the same directory (partition) and schema. Each batch gets the common
+/// directives of `prescription` plus the file section matching its first
+/// input. Larger files and candidates left alone in their group are not
+/// part of the plan.
+pub struct CompactionPlanner<'a> {
+    pub input_dir: &'a Path,
+    pub output_dir: &'a Path,
+    pub target_bytes: u64,
+    pub prescription: &'a DatasetPrescription,
+}
+
+/// Candidates sharing a directory and schema, in path order.
+struct Group {
+    dir: PathBuf,
+    schema: Type,
+    files: Vec<(PathBuf, u64)>,
+}
+
+impl CompactionPlanner<'_> {
+    pub async fn plan(&self) -> Result<CompactionPlan> {
+        let mut files = Vec::new();
+        crate::fix::collect_parquet_files(self.input_dir, Path::new(""), &mut files)?;
+        files.sort();
+
+        let mut groups: Vec<Group> = Vec::new();
+        for relative in files {
+            let input = self.input_dir.join(&relative);
+            let bytes = std::fs::metadata(&input)?.len();
+            if bytes * 2 >= self.target_bytes {
+                continue;
+            }
+            let (store, path) = loader::parse(&input.to_string_lossy())?;
+            let file = LoadedFile::open(store, path)
+                .await
+                .with_context(|| format!("failed to open {}", input.display()))?;
+            let schema = file.metadata.file_metadata().schema().clone();
+            let dir = relative.parent().unwrap_or(Path::new("")).to_path_buf();
+            match groups
+                .iter_mut()
+                .find(|g| g.dir == dir && g.schema == schema)
+            {
+                Some(group) => group.files.push((relative, bytes)),
+                None => groups.push(Group {
+                    dir,
+                    schema,
+                    files: vec![(relative, bytes)],
+                }),
+            }
+        }
+
+        let mut batches = Vec::new();
+        for group in groups {
+            for files in pack(&group.files, self.target_bytes) {
+                if files.len() < 2 {
+                    continue;
+                }
+                let inputs: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+                let key = inputs[0].to_string_lossy().replace('\\', "/");
+                let index = batches.len();
+                batches.push(CompactionBatch {
+                    output: group.dir.join(format!("compacted-{index:05}.parquet")),
+                    bytes: files.iter().map(|(_, bytes)| bytes).sum(),
+                    prescription: self.prescription.for_file(&key),
+                    inputs,
+                });
+            }
+        }
+        Ok(CompactionPlan {
+            input_dir: self.input_dir.to_path_buf(),
+            output_dir: self.output_dir.to_path_buf(),
+            target_bytes: self.target_bytes,
+            batches,
+        })
+    }
+}
+
+/// Consecutive runs of `files` whose sizes add up to at most `target`.
+fn pack(files: &[(PathBuf, u64)], target: u64) -> Vec<&[(PathBuf, u64)]> {
+    let mut runs = Vec::new();
+    let (mut start, mut bytes) = (0, 0);
+    for (index, (_, size)) in files.iter().enumerate() {
+        if index > start && bytes + size > target {
+            runs.push(&files[start..index]);
+            (start, bytes) = (index, 0);
+        }
+        bytes += size;
+    }
+    if start < files.len() {
+        runs.push(&files[start..]);
+    }
+    runs
+}
+
+pub struct CompactionReport {
+    /// Relative to the plan's output directory.
+    pub output: PathBuf,
+    /// Rows written.
+    pub result: Result<i64>,
+}
+
+impl CompactionPlan {
+    /// Merge every batch. One failed batch does not stop the others; its
+    /// partial output is removed.
+    pub async fn run(&self) -> Vec<CompactionReport> {
+        let mut reports = Vec::new();
+        for batch in &self.batches {
+            let output = self.output_dir.join(&batch.output);
+            let result = self.merge(batch, &output).await;
+            if result.is_err() {
+                let _ = std::fs::remove_file(&output);
+            }
+            reports.push(CompactionReport {
+                output: batch.output.clone(),
+                result,
+            });
+        }
+        reports
+    }
+
+    async fn merge(&self, batch: &CompactionBatch, output: &Path) -> Result<i64> {
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer: Option<RewriteWriter<File>> = None;
+        let mut rows = 0;
+        for relative in &batch.inputs {
+            let input = self.input_dir.join(relative);
+            let (store, path) = loader::parse(&input.to_string_lossy())?;
+            let file = LoadedFile::open(store, path).await?;
+            let builder = crate::fix::input_stream_builder(&file, &batch.prescription)?;
+            let schema = SchemaRewrite::new(builder.schema(), &batch.prescription);
+            let writer = match &mut writer {
+                Some(writer) => writer,
+                None => {
+                    // The first input's settings are the base for the output.
+                    let props = crate::fix::build_writer_properties_with_base(
+                        &file.metadata,
+                        &batch.prescription,
+                    );
+                    let out = File::create(output)?;
+                    writer.insert(crate::fix::arrow_writer(
+                        out,
+                        props,
+                        &batch.prescription,
+                        &schema,
+                    )?)
+                }
+            };
+            let mut stream = builder.build()?;
+            while let Some(record_batch) = stream.next().await {
+                let record_batch = schema.apply(record_batch?)?;
+                rows += record_batch.num_rows() as i64;
+                writer.write(&record_batch)?;
+            }
+        }
+        if let Some(writer) = writer {
+            writer.close()?;
+        }
+        Ok(rows)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let batches: Vec<Value> = self
+            .batches
+            .iter()
+            .map(|batch| {
+                json!({
+                    "output": batch.output.to_string_lossy(),
+                    "inputs": batch.inputs.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
+                    "bytes": batch.bytes,
+                    "prescription": batch.prescription.to_string(),
+                })
+            })
+            .collect();
+        json!({
+            "input_dir": self.input_dir.to_string_lossy(),
+            "output_dir": self.output_dir.to_string_lossy(),
+            "target_bytes": self.target_bytes,
+            "batches": batches,
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let path = |v: &Value, field: &str| {
+            v[field]
+                .as_str()
+                .map(PathBuf::from)
+                .with_context(|| format!("compaction plan is missing `{field}`"))
+        };
+        let batches = value["batches"]
+            .as_array()
+            .context("compaction plan is missing `batches`")?
+            .iter()
+            .map(|batch| {
+                let inputs = batch["inputs"]
+                    .as_array()
+                    .context("compaction batch is missing `inputs`")?
+                    .iter()
+                    .map(|p| p.as_str().map(PathBuf::from).context("input is not a path"))
+                    .collect::<Result<_>>()?;
+                let prescription = batch["prescription"].as_str().unwrap_or_default();
+                Ok(CompactionBatch {
+                    output: path(batch, "output")?,
+                    inputs,
+                    bytes: batch["bytes"].as_u64().unwrap_or_default(),
+                    prescription: Prescription::parse(prescription)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            input_dir: path(value, "input_dir")?,
+            output_dir: path(value, "output_dir")?,
+            target_bytes: value["target_bytes"]
+                .as_u64()
+                .unwrap_or(DEFAULT_TARGET_BYTES),
+            batches,
+        })
+    }
+}
+
+impl fmt::Display for CompactionPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, batch) in self.batches.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{} <- {} file(s), {:.1} MB",
+                batch.output.display(),
+                batch.inputs.len(),
+                batch.bytes as f64 / 1048576.0
+            )?;
+            for directive in batch.prescription.directives() {
+                write!(f, "\n  {directive}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Int32Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn write_file(path: &Path, values: Vec<i32>) -> Result<()> {
+        let batch = RecordBatch::try_from_iter([("a", Arc::new(Int32Array::from(values)) as _)])?;
+        let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merges_small_files_per_directory() -> Result<()> {
+        let input = tempfile::tempdir()?;
+        let output = tempfile::tempdir()?;
+        std::fs::create_dir_all(input.path().join("day=1"))?;
+        for file in [
+            "day=1/a.parquet",
+            "day=1/b.parquet",
+            "day=1/c.parquet",
+            "solo.parquet",
+        ] {
+            write_file(&input.path().join(file), vec![1, 2, 3])?;
+        }
+        let prescription = DatasetPrescription::parse("set file compression zstd(3)")?;
+        let plan = CompactionPlanner {
+            input_dir: input.path(),
+            output_dir: output.path(),
+            target_bytes: DEFAULT_TARGET_BYTES,
+            prescription: &prescription,
+        }
+        .plan()
+        .await?;
+        let plan = CompactionPlan::from_json(&plan.to_json())?;
+        assert_eq!(plan.batches.len(), 1);
+        assert_eq!(plan.batches[0].inputs.len(), 3);
+
+        let reports = plan.run().await;
+        assert_eq!(reports[0].result.as_ref().unwrap(), &9);
+        assert!(output.path().join("day=1/compacted-00000.parquet").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Differences between the writer configurations of two files, as far as
+//! their footers show them: the settings a rewrite would infer (codecs,
+//! encodings, dictionary, statistics levels, bloom filters) and sizes.
+
+use std::fmt;
+
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
+
+use crate::fix::infer_writer_properties;
+
+/// One setting whose value differs, as displayed for each file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingDiff {
+    pub setting: &'static str,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDiff {
+    pub path: ColumnPath,
+    pub settings: Vec<SettingDiff>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    pub file: Vec<SettingDiff>,
+    /// Columns of either file with at least one difference, in `a`'s
+    /// column order followed by columns only `b` has.
+    pub columns: Vec<ColumnDiff>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.file.is_empty() && self.columns.is_empty()
+    }
+}
+
+/// The values one file has for the compared settings.
+struct Settings {
+    file: Vec<(&'static str, String)>,
+    columns: Vec<(ColumnPath, Vec<(&'static str, String)>)>,
+}
+
+impl Settings {
+    fn of(metadata: &ParquetMetaData) -> Self {
+        let props = infer_writer_properties(metadata);
+        let file = vec![
+            ("writer_version", format!("{:?}", props.writer_version())),
+            ("row_groups", metadata.num_row_groups().to_string()),
+            ("max_row_group_size", props.max_row_group_size().to_string()),
+        ];
+        let schema = metadata.file_metadata().schema_descr();
+        let columns = (0..schema.num_columns())
+            .map(|col| {
+                let path = schema.column(col).path().clone();
+                let settings = column_settings(metadata, &props, col, &path);
+                (path, settings)
+            })
+            .collect();
+        Self { file, columns }
+    }
+}
+
+fn column_settings(
+    metadata: &ParquetMetaData,
+    props: &WriterProperties,
+    col: usize,
+    path: &ColumnPath,
+) -> Vec<(&'static str, String)> {
+    let chunks = metadata.row_groups().iter().map(|rg| rg.column(col));
+    let (compressed, uncompressed) = chunks.fold((0, 0), |(c, u), chunk| {
+        (c + chunk.compressed_size(), u + chunk.uncompressed_size())
+    });
+    let encoding = props
+        .encoding(path)
+        .map_or("default".to_string(), |e| e.to_string());
+    vec![
+        ("compression", props.compression(path).to_string()),
+        ("encoding", encoding),
+        ("dictionary", props.dictionary_enabled(path).to_string()),
+        (
+            "statistics",
+            format!("{:?}", props.statistics_enabled(path)),
+        ),
+        (
+            "bloom_filter",
+            props.bloom_filter_properties(path).is_some().to_string(),
+        ),
+        ("compressed_size", compressed.to_string()),
+        ("uncompressed_size", uncompressed.to_string()),
+    ]
+}
+
+fn diff_settings(a: &[(&'static str, String)], b: &[(&'static str, String)]) -> Vec<SettingDiff> {
+    a.iter()
+        .zip(b)
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((setting, a), (_, b))| SettingDiff {
+            setting,
+            a: a.clone(),
+            b: b.clone(),
+        })
+        .collect()
+}
+
+/// Compare the writer configurations of `a` and `b`. Columns are matched by
+/// path; a column only one file has shows as a `column` setting that is
+/// `present` in one file and `missing` in the other.
+pub fn writer_config_diff(a: &ParquetMetaData, b: &ParquetMetaData) -> ConfigDiff {
+    let (a, b) = (Settings::of(a), Settings::of(b));
+    let mut diff = ConfigDiff {
+        file: diff_settings(&a.file, &b.file),
+        columns: Vec::new(),
+    };
+    let presence = |path: &ColumnPath, a: &str, b: &str| ColumnDiff {
+        path: path.clone(),
+        settings: vec![SettingDiff {
+            setting: "column",
+            a: a.to_string(),
+            b: b.to_string(),
+        }],
+    };
+    for (path, a_settings) in &a.columns {
+        let settings = match b.columns.iter().find(|(p, _)| p == path) {
+            Some((_, b_settings)) => diff_settings(a_settings, b_settings),
+            None => {
+                diff.columns.push(presence(path, "present", "missing"));
+                continue;
+            }
+        };
+        if !settings.is_empty() {
+            diff.columns.push(ColumnDiff {
+                path: path.clone(),
+                settings,
+            });
+        }
+    }
+    for (path, _) in &b.columns {
+        if !a.columns.iter().any(|(p, _)| p == path) {
+            diff.columns.push(presence(path, "missing", "present"));
+        }
+    }
+    diff
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        for s in &self.file {
+            lines.push(format!("file {}: {} -> {}", s.setting, s.a, s.b));
+        }
+        for column in &self.columns {
+            for s in &column.settings {
+                lines.push(format!(
+                    "column {} {}: {} -> {}",
+                    column.path.string(),
+                    s.setting,
+                    s.a,
+                    s.b
+                ));
+            }
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Int32Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::basic::{Compression, ZstdLevel};
+    use std::sync::Arc;
+
+    fn metadata(props: WriterProperties) -> anyhow::Result<Arc<ParquetMetaData>> {
+        let batch = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(Int32Array::from_iter_values(0..1000)) as _,
+        )])?;
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(buffer))?;
+        Ok(builder.metadata().clone())
+    }
+
+    #[test]
+    fn reports_changed_column_settings() -> anyhow::Result<()> {
+        let a = metadata(WriterProperties::default())?;
+        let b = metadata(
+            WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build(),
+        )?;
+        assert!(writer_config_diff(&a, &a).is_empty());
+
+        let diff = writer_config_diff(&a, &b);
+        assert!(diff.file.is_empty());
+        assert_eq!(diff.columns.len(), 1);
+        assert_eq!(diff.columns[0].settings[0].setting, "compression");
+        assert_eq!(
+            diff.to_string().lines().next(),
+            Some("column a compression: UNCOMPRESSED -> ZSTD(ZstdLevel(1))")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Per-project linter configuration from a `.parquet-linter.toml` file:
+//! rules to disable, severity overrides and per-rule thresholds.
+//!
+//! ```toml
+//! preset = "aggressive"
+//!
+//! [rules.compression-codec-upgrade]
+//! min_column_bytes = "16MB"
+//! severity = "warning"
+//!
+//! [rules]
+//! join-key-bloom-filter.enabled = false
+//! sorting-order.min_row_groups = 8
+//! sorting-order.sorted_fraction = 0.8
+//! ```
+//!
+//! Byte thresholds also take sizes such as `"16MB"` (powers of 1024) and
+//! ratio thresholds fractions such as `0.25`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::diagnostic::Severity;
+use crate::options::LintOptions;
+use crate::rule::RuleMetadata;
+use crate::rules;
+use crate::thresholds::{ParameterKind, Preset};
+use toml::{Table, Value};
+
+/// Name of the file `Config::discover` looks for.
+pub const CONFIG_FILE_NAME: &str = ".parquet-linter.toml";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub preset: Option<Preset>,
+    /// Settings keyed by rule name.
+    pub rules: BTreeMap<String, RuleConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleConfig {
+    /// `Some(false)` disables the rule.
+    pub enabled: Option<bool>,
+    pub severity: Option<Severity>,
+    /// Threshold values keyed by parameter name.
+    pub thresholds: BTreeMap<String, f64>,
+}
+
+impl Config {
+    /// The config file in `dir`, if there is one.
+    pub fn discover(dir: &Path) -> Option<PathBuf> {
+        Some(dir.join(CONFIG_FILE_NAME)).filter(|path| path.is_file())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    /// Parse config text, checking every rule, parameter and value against
+    /// the rules that exist.
+    pub fn parse(text: &str) -> Result<Self> {
+        let table: Table = text.parse()?;
+        let metadata: Vec<RuleMetadata> = rules::all_rules().iter().map(|r| r.metadata()).collect();
+        let mut config = Config::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("preset", Value::String(preset)) => config.preset = Some(preset.parse()?),
+                ("rules", Value::Table(rules)) => {
+                    for (rule, settings) in rules {
+                        let Value::Table(settings) = settings else {
+                            bail!("unknown or mistyped setting 'rules.{rule}'");
+                        };
+                        config.set_rule(&rule, settings, &metadata)?;
+                    }
+                }
+                (key, Value::Table(_)) => {
+                    bail!("unknown table [{key}], expected [rules] or [rules.<rule>]")
+                }
+                (key, _) => bail!("unknown or mistyped setting '{key}'"),
+            }
+        }
+        Ok(config)
+    }
+
+    fn set_rule(&mut self, rule: &str, settings: Table, metadata: &[RuleMetadata]) -> Result<()> {
+        let Some(rule_metadata) = metadata.iter().find(|m| m.name == rule) else {
+            bail!("unknown rule '{rule}'");
+        };
+        let entry = self.rules.entry(rule.to_string()).or_default();
+        for (key, value) in settings {
+            match (key.as_str(), value) {
+                ("enabled", Value::Boolean(enabled)) => entry.enabled = Some(enabled),
+                ("severity", Value::String(severity)) => {
+                    entry.severity = Some(
+                        severity
+                            .parse()
+                            .map_err(|e: String| anyhow::anyhow!("rules.{rule}.severity: {e}"))?,
+                    );
+                }
+                (parameter, value) => {
+                    let Some(parameter) = rule_metadata
+                        .parameters
+                        .iter()
+                        .find(|p| p.name == parameter)
+                    else {
+                        bail!(
+                            "unknown setting '{parameter}' for rule '{rule}' \
+                             (expected enabled, severity or a parameter listed by rules-manifest)"
+                        );
+                    };
+                    let number = match value {
+                        Value::Integer(n) => Some(n as f64),
+                        Value::Float(n) => Some(n),
+                        Value::String(text) if parameter.kind == ParameterKind::Bytes => {
+                            parse_size(&text)
+                        }
+                        _ => None,
+                    };
+                    let Some(number) = number.filter(|n| parameter.accepts(*n)) else {
+                        bail!(
+                            "rules.{rule}.{}: must be {}",
+                            parameter.name,
+                            parameter.expected()
+                        );
+                    };
+                    entry.thresholds.insert(parameter.name.to_string(), number);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply to `options`: disabled rules, severities and thresholds are
+    /// added to what they already hold. The preset only replaces the
+    /// default one.
+    pub fn apply(&self, options: &mut LintOptions) {
+        if let Some(preset) = self.preset
+            && options.thresholds.preset == Preset::Default
+        {
+            options.thresholds.preset = preset;
+        }
+        for (rule, config) in &self.rules {
+            if config.enabled == Some(false) {
+                options.disabled_rules.push(rule.clone());
+            }
+            if let Some(severity) = config.severity {
+                options.severities.insert(rule.clone(), severity);
+            }
+            for (parameter, value) in &config.thresholds {
+                options.thresholds.set(rule, parameter, *value);
+            }
+        }
+    }
+}
+
+/// Bytes in a size such as `16MB`, `512 KiB` or `1048576`.
+fn parse_size(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '_'))
+        .unwrap_or(text.len());
+    let number: f64 = text[..split].replace('_', "").parse().ok()?;
+    let unit = match text[split..].trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "KB" | "KIB" | "K" => 1024.0,
+        "MB" | "MIB" | "M" => 1024.0 * 1024.0,
+        "GB" | "GIB" | "G" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(number * unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thresholds::Parameter;
+
+    #[test]
+    fn parses_tables_dotted_keys_and_sizes() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            preset = "aggressive" # scale everything
+            [rules.compression-codec-upgrade]
+            min_column_bytes = "16MB"
+            severity = "warning"
+
+            [rules]
+            join-key-bloom-filter.enabled = false
+            "sorting-order".min_row_groups = 8
+            sorting-order.sorted_fraction = 0.8
+            "#,
+        )?;
+        let mut options = LintOptions::default();
+        config.apply(&mut options);
+        assert_eq!(options.thresholds.preset, Preset::Aggressive);
+        assert_eq!(options.disabled_rules, ["join-key-bloom-filter"]);
+        assert_eq!(
+            options.severities.get("compression-codec-upgrade"),
+            Some(&Severity::Warning)
+        );
+        let min_column_bytes = Parameter::above(
+            "min_column_bytes",
+            ParameterKind::Bytes,
+            32.0 * 1024.0 * 1024.0,
+        );
+        assert_eq!(
+            options
+                .thresholds
+                .get("compression-codec-upgrade", &min_column_bytes),
+            16.0 * 1024.0 * 1024.0
+        );
+        let sorted_fraction = Parameter::above("sorted_fraction", ParameterKind::Ratio, 0.9);
+        assert_eq!(
+            options.thresholds.get("sorting-order", &sorted_fraction),
+            0.8
+        );
+        assert_eq!(parse_size("512 KiB"), Some(524_288.0));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_rules_parameters_and_values() {
+        for (text, message) in [
+            (
+                "[rules]\nno-such-rule.enabled = false",
+                "unknown rule 'no-such-rule'",
+            ),
+            (
+                "[rules.sorting-order]\nmin_rows = 8",
+                "unknown setting 'min_rows'",
+            ),
+            (
+                "[rules.sorting-order]\nmin_row_groups = \"8MB\"",
+                "must be a count of at least 1",
+            ),
+            (
+                "[rules.sorting-order]\nsorted_fraction = 2",
+                "must be a ratio between 0 and 1",
+            ),
+            (
+                "[rules.sorting-order]\nseverity = \"fatal\"",
+                "unknown severity",
+            ),
+            ("[workload]", "unknown table"),
+            (
+                "[rules]\n\"sorting-order.min_row_groups\" = 8",
+                "unknown or mistyped setting 'rules.sorting-order.min_row_groups'",
+            ),
+            (
+                "[rules]\nsorting-order.min_row_groups = 8\n\
+                 [rules.sorting-order]\nmin_row_groups = 4",
+                "duplicate key",
+            ),
+            (
+                "[rules.sorting-order]\nenabled = true\n[rules.sorting-order]",
+                "at line 3",
+            ),
+        ] {
+            let err = format!("{:#}", Config::parse(text).unwrap_err());
+            assert!(err.contains(message), "{text}: {err}");
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Scan cost models. Rules that trade size against decode speed ask a
+//! `CostModel` instead of hard-coding which codec or encoding is faster, so
+//! a calibrated model (or a different execution target) can be swapped in
+//! through `LintOptions::cost_model`.
+
+use std::fmt::Debug;
+
+use parquet::basic::{Compression, Encoding};
+
+use crate::diagnostic::Category;
+
+/// What a cost model sees of a column (or of a sampled trial write of it).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnShape {
+    pub codec: Compression,
+    /// Data page encoding (`RLE_DICTIONARY` for dictionary-encoded data).
+    pub encoding: Encoding,
+    pub uncompressed_size: i64,
+    pub compressed_size: i64,
+    pub num_values: u64,
+    /// Estimated distinct values, when known.
+    pub distinct_values: Option<u64>,
+}
+
+pub trait CostModel: Debug + Send + Sync {
+    /// Estimated seconds to fetch `bytes` from storage.
+    fn fetch_cost(&self, bytes: i64) -> f64;
+    /// Estimated seconds to decompress and decode the column.
+    fn decode_cost(&self, column: &ColumnShape) -> f64;
+
+    /// Estimated seconds to fetch and decode the column.
+    fn scan_cost(&self, column: &ColumnShape) -> f64 {
+        self.fetch_cost(column.compressed_size) + self.decode_cost(column)
+    }
+    /// Category of diagnostics whose advice follows from this model's costs.
+    fn category(&self) -> Category {
+        Category::Performance
+    }
+}
+
+/// Rough single-core CPU throughputs reading from object storage.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuCostModel {
+    /// Storage read bandwidth in bytes per second.
+    pub bandwidth: f64,
+}
+
+impl Default for CpuCostModel {
+    fn default() -> Self {
+        Self { bandwidth: 200e6 }
+    }
+}
+
+impl CostModel for CpuCostModel {
+    fn fetch_cost(&self, bytes: i64) -> f64 {
+        bytes as f64 / self.bandwidth
+    }
+
+    fn decode_cost(&self, column: &ColumnShape) -> f64 {
+        // Decompression throughput over uncompressed bytes.
+        let decompress = match column.codec {
+            Compression::UNCOMPRESSED => f64::INFINITY,
+            Compression::LZ4_RAW | Compression::LZ4 => 4e9,
+            Compression::SNAPPY => 1.5e9,
+            Compression::ZSTD(_) => 1e9,
+            Compression::BROTLI(_) => 0.4e9,
+            Compression::GZIP(_) => 0.3e9,
+            _ => 0.5e9,
+        };
+        let decode = match column.encoding {
+            Encoding::PLAIN | Encoding::BYTE_STREAM_SPLIT => 4e9,
+            Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY => {
+                dictionary_throughput(column, 3e9, 1e9)
+            }
+            Encoding::DELTA_BINARY_PACKED | Encoding::DELTA_LENGTH_BYTE_ARRAY => 1.5e9,
+            Encoding::DELTA_BYTE_ARRAY => 0.5e9,
+            _ => 1e9,
+        };
+        let bytes = column.uncompressed_size as f64;
+        bytes / decompress + bytes / decode
+    }
+}
+
+/// GPU decoding (nvCOMP-style codecs, parallel page decode) behind a PCIe
+/// link. Codecs without a GPU decompressor run on the CPU.
+#[derive(Debug, Clone, Copy)]
+pub struct GpuCostModel {
+    /// Storage read bandwidth in bytes per second.
+    pub bandwidth: f64,
+    /// Host-to-device bandwidth in bytes per second.
+    pub pcie_bandwidth: f64,
+}
+
+impl Default for GpuCostModel {
+    fn default() -> Self {
+        Self {
+            bandwidth: 200e6,
+            pcie_bandwidth: 12e9,
+        }
+    }
+}
+
+impl CostModel for GpuCostModel {
+    fn fetch_cost(&self, bytes: i64) -> f64 {
+        let bytes = bytes as f64;
+        bytes / self.bandwidth + bytes / self.pcie_bandwidth
+    }
+
+    fn category(&self) -> Category {
+        Category::Gpu
+    }
+
+    fn decode_cost(&self, column: &ColumnShape) -> f64 {
+        let decompress = match column.codec {
+            Compression::UNCOMPRESSED => f64::INFINITY,
+            Compression::LZ4_RAW | Compression::LZ4 => 60e9,
+            Compression::SNAPPY => 50e9,
+            Compression::ZSTD(_) => 30e9,
+            Compression::GZIP(_) => 10e9,
+            // No GPU decompressor: decode on the CPU.
+            _ => 0.4e9,
+        };
+        let decode = match column.encoding {
+            Encoding::PLAIN | Encoding::BYTE_STREAM_SPLIT => 200e9,
+            Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY => {
+                dictionary_throughput(column, 100e9, 50e9)
+            }
+            Encoding::DELTA_BINARY_PACKED | Encoding::DELTA_LENGTH_BYTE_ARRAY => 50e9,
+            // Prefix chains decode serially within a page.
+            Encoding::DELTA_BYTE_ARRAY => 5e9,
+            _ => 20e9,
+        };
+        let bytes = column.uncompressed_size as f64;
+        bytes / decompress + bytes / decode
+    }
+}
+
+/// Dictionary lookups are fast while the dictionary fits in cache and slow
+/// down once most values are distinct.
+fn dictionary_throughput(column: &ColumnShape, small: f64, large: f64) -> f64 {
+    match column.distinct_values {
+        Some(distinct) if column.num_values > 0 => {
+            let ratio = (distinct as f64 / column.num_values as f64).min(1.0);
+            small + (large - small) * ratio
+        }
+        _ => large,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(codec: Compression) -> ColumnShape {
+        ColumnShape {
+            codec,
+            encoding: Encoding::PLAIN,
+            uncompressed_size: 64 << 20,
+            compressed_size: 32 << 20,
+            num_values: 1 << 20,
+            distinct_values: None,
+        }
+    }
+
+    #[test]
+    fn lz4_decodes_faster_than_snappy_on_cpu_but_not_meaningfully_on_gpu() {
+        let cpu = CpuCostModel::default();
+        let saved = cpu.decode_cost(&shape(Compression::SNAPPY))
+            - cpu.decode_cost(&shape(Compression::LZ4_RAW));
+        assert!(saved > 0.01);
+
+        let gpu = GpuCostModel::default();
+        let saved = gpu.decode_cost(&shape(Compression::SNAPPY))
+            - gpu.decode_cost(&shape(Compression::LZ4_RAW));
+        assert!(saved < 0.001);
+    }
+}
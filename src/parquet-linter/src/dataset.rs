@@ -0,0 +1,128 @@
+//! Checks across the data files of a dataset (a Delta or Iceberg table),
+//! from what each file's footer says about itself.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use parquet::file::metadata::ParquetMetaData;
+
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::Prescription;
+
+/// More distinct values than this make a poor partition key.
+const MAX_PARTITION_VALUES: usize = 10_000;
+
+/// Top-level columns holding a single non-null value throughout a file,
+/// keyed by name, with that value's statistics bytes.
+pub type ConstantColumns = BTreeMap<String, Vec<u8>>;
+
+/// Columns whose exact min equals max, with no nulls, in every row group.
+pub fn constant_columns(metadata: &ParquetMetaData) -> ConstantColumns {
+    let schema = metadata.file_metadata().schema_descr();
+    let mut constants = ConstantColumns::new();
+    if metadata.num_row_groups() == 0 {
+        return constants;
+    }
+    for col_idx in 0..schema.num_columns() {
+        let column = schema.column(col_idx);
+        if column.path().parts().len() != 1 || column.max_rep_level() > 0 {
+            continue;
+        }
+        let mut value: Option<&[u8]> = None;
+        let constant = metadata.row_groups().iter().all(|rg| {
+            let Some(stats) = rg.column(col_idx).statistics() else {
+                return false;
+            };
+            let (Some(min), Some(max)) = (stats.min_bytes_opt(), stats.max_bytes_opt()) else {
+                return false;
+            };
+            let exact = stats.min_is_exact() && stats.max_is_exact();
+            let same = value.is_none_or(|v| v == min);
+            value = Some(min);
+            exact && min == max && same && stats.null_count_opt() == Some(0)
+        });
+        if constant && let Some(value) = value {
+            constants.insert(column.name().to_string(), value.to_vec());
+        }
+    }
+    constants
+}
+
+/// Columns constant within every file but varying across files, such as
+/// `date` or `region`: readers could skip whole files by directory name.
+pub fn check(files: &[ConstantColumns]) -> Vec<Diagnostic> {
+    let Some(first) = files.first() else {
+        return Vec::new();
+    };
+    if files.len() < 2 {
+        return Vec::new();
+    }
+    let mut diagnostics = Vec::new();
+    for name in first.keys() {
+        let values: Option<BTreeSet<&[u8]>> = files
+            .iter()
+            .map(|f| f.get(name).map(Vec::as_slice))
+            .collect();
+        let Some(values) = values else {
+            continue;
+        };
+        if values.len() < 2 || values.len() > MAX_PARTITION_VALUES {
+            continue;
+        }
+        diagnostics.push(Diagnostic {
+            rule_name: "dataset-partition-column",
+            severity: Severity::Suggestion,
+            category: Category::Performance,
+            location: Location::File,
+            message: format!(
+                "column '{name}' is constant within each of {} files but takes {} values \
+                 across them; promote it to a hive partition key ({name}=<value>/) and drop \
+                 it from the data files",
+                files.len(),
+                values.len()
+            ),
+            prescription: Prescription::new(),
+            related: Vec::new(),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch, StringArray};
+    use parquet::arrow::ArrowWriter;
+
+    fn file(region: &str, ids: std::ops::Range<i64>) -> anyhow::Result<ParquetMetaData> {
+        let rows = ids.end - ids.start;
+        let batch = RecordBatch::try_from_iter([
+            (
+                "region",
+                Arc::new(StringArray::from(vec![region; rows as usize])) as _,
+            ),
+            ("id", Arc::new(Int64Array::from_iter_values(ids)) as _),
+        ])?;
+        let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), None)?;
+        writer.write(&batch)?;
+        Ok(writer.close()?)
+    }
+
+    #[test]
+    fn flags_columns_constant_per_file_only() -> anyhow::Result<()> {
+        let files = [
+            constant_columns(&file("eu", 0..100)?),
+            constant_columns(&file("us", 100..200)?),
+        ];
+        assert!(files[0].contains_key("region"));
+        assert!(!files[0].contains_key("id"));
+        let diagnostics = check(&files);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("'region'"));
+
+        let same_region = [files[0].clone(), files[0].clone()];
+        assert!(check(&same_region).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,580 @@
+//! Delta Lake table linting: replay `_delta_log` (latest checkpoint plus the
+//! JSON commits after it) to find the active data files, lint them, and check
+//! table-level issues such as small files, mixed codecs and missing skipping
+//! statistics.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures::{StreamExt, TryStreamExt};
+use object_store::ObjectStore;
+use object_store::path::Path as ObjectPath;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use serde_json::Value;
+
+use crate::dataset::{self, ConstantColumns};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::loader::{self, LoadedFile};
+use crate::options::{LintOptions, Workload};
+use crate::prescription::Prescription;
+
+/// Data files linted at the same time.
+const LINT_CONCURRENCY: usize = 8;
+/// Files below this size are worth compacting with `OPTIMIZE`.
+const SMALL_FILE_BYTES: i64 = 8 * 1024 * 1024;
+/// Delta's default for `delta.dataSkippingNumIndexedCols`.
+const DEFAULT_INDEXED_COLUMNS: usize = 32;
+
+/// An active data file of the latest table version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataFile {
+    /// Path as written in the `add` action (relative to the table or a URI).
+    pub uri: String,
+    pub path: ObjectPath,
+    /// Partition values as `name=value` pairs joined by `/`, or empty.
+    pub partition: String,
+    pub size: i64,
+    /// Parsed `stats` of the `add` action, if the writer recorded any.
+    pub stats: Option<Value>,
+}
+
+pub struct DeltaTable {
+    pub store: Arc<dyn ObjectStore>,
+    pub root: ObjectPath,
+    /// Version of the last commit replayed.
+    pub version: u64,
+    /// Table properties from the latest `metaData` action.
+    pub configuration: BTreeMap<String, String>,
+    pub data_files: Vec<DataFile>,
+}
+
+impl DeltaTable {
+    /// Load the latest version of the table rooted at `location`.
+    pub async fn load(location: &str) -> Result<Self> {
+        let (store, root) = loader::parse(location)?;
+        let log = root.child("_delta_log");
+        let mut replay = Replay::default();
+
+        let listing: Vec<ObjectPath> = store
+            .list(Some(&log))
+            .map_ok(|meta| meta.location)
+            .try_collect()
+            .await?;
+        // Without `_last_checkpoint`, the newest complete checkpoint in the
+        // log; the commits before it may have been cleaned up.
+        let checkpoint = match last_checkpoint(store.as_ref(), &log).await? {
+            Some(checkpoint) => Some(checkpoint),
+            None => newest_checkpoint(&listing),
+        };
+        if let Some((version, parts)) = checkpoint {
+            for part in checkpoint_parts(&log, version, parts) {
+                let bytes = store.get(&part).await?.bytes().await?;
+                let reader = SerializedFileReader::new(bytes)
+                    .with_context(|| format!("invalid Delta checkpoint: {part}"))?;
+                for row in reader.get_row_iter(None)? {
+                    replay.apply(&row?.to_json_value());
+                }
+            }
+            replay.version = Some(version);
+        }
+
+        let mut commits: Vec<(u64, ObjectPath)> = listing
+            .into_iter()
+            .filter_map(|path| Some((path.filename().and_then(commit_version)?, path)))
+            .collect();
+        commits.retain(|(v, _)| checkpoint.is_none_or(|(cp, _)| *v > cp));
+        commits.sort_by_key(|(v, _)| *v);
+        for (version, path) in commits {
+            let bytes = store.get(&path).await?.bytes().await?;
+            for line in std::str::from_utf8(&bytes)?.lines() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let action: Value = serde_json::from_str(line)
+                    .with_context(|| format!("invalid Delta commit: {path}"))?;
+                replay.apply(&action);
+            }
+            replay.version = Some(version);
+        }
+
+        let version = replay
+            .version
+            .with_context(|| format!("no Delta log found under {log}"))?;
+        let data_files = replay
+            .files
+            .into_iter()
+            .map(|(uri, add)| data_file(&root, uri, &add))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            store,
+            root,
+            version,
+            configuration: replay.configuration,
+            data_files,
+        })
+    }
+
+    /// Table-level issues derived from the log alone.
+    pub fn check(&self, workload: &Workload) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let total = self.data_files.len();
+
+        let small = self
+            .data_files
+            .iter()
+            .filter(|f| f.size < SMALL_FILE_BYTES)
+            .count();
+        // A single small file is the normal tail of a write.
+        if small > 1 {
+            diagnostics.push(table_diagnostic(
+                "delta-small-files",
+                Severity::Suggestion,
+                format!(
+                    "{small}/{total} data files are smaller than {} MiB; \
+                     compact them with OPTIMIZE to cut per-file overhead",
+                    SMALL_FILE_BYTES / (1024 * 1024),
+                ),
+            ));
+        }
+
+        let without_stats = self.data_files.iter().filter(|f| f.stats.is_none()).count();
+        if without_stats > 0 {
+            diagnostics.push(table_diagnostic(
+                "delta-missing-stats",
+                Severity::Warning,
+                format!(
+                    "{without_stats}/{total} data files have no statistics in the log, \
+                     so data skipping must read all of them"
+                ),
+            ));
+        }
+
+        for column in &workload.filter_columns {
+            let missing = self
+                .data_files
+                .iter()
+                .filter_map(|f| f.stats.as_ref())
+                .filter(|stats| lookup(&stats["minValues"], column).is_none())
+                .count();
+            if missing > 0 {
+                diagnostics.push(table_diagnostic(
+                    "delta-missing-stats",
+                    Severity::Warning,
+                    format!(
+                        "filter column '{column}' has no min/max statistics in {missing}/{total} \
+                         data files; list it in delta.dataSkippingStatsColumns or raise \
+                         delta.dataSkippingNumIndexedCols (currently {})",
+                        self.indexed_columns(),
+                    ),
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    fn indexed_columns(&self) -> usize {
+        self.configuration
+            .get("delta.dataSkippingNumIndexedCols")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_INDEXED_COLUMNS)
+    }
+}
+
+/// Diagnostics for one data file.
+pub struct FileReport {
+    pub file: DataFile,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+pub struct DeltaReport {
+    pub files: Vec<FileReport>,
+    /// Issues that concern the table rather than a single file.
+    pub table_diagnostics: Vec<Diagnostic>,
+}
+
+impl DeltaReport {
+    pub fn all_diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.table_diagnostics
+            .iter()
+            .chain(self.files.iter().flat_map(|f| &f.diagnostics))
+    }
+}
+
+/// Lint every active data file of `table` and run the table-level checks.
+pub async fn lint_table(
+    table: &DeltaTable,
+    rule_names: Option<&[String]>,
+    options: &LintOptions,
+) -> Result<DeltaReport> {
+    let linter = &crate::linter(rule_names, options);
+    let linted: Vec<(FileReport, String, ConstantColumns)> =
+        futures::stream::iter(table.data_files.iter().cloned())
+            .map(|file| async move {
+                let loaded = LoadedFile::open(table.store.clone(), file.path.clone())
+                    .await
+                    .with_context(|| format!("failed to open {}", file.uri))?;
+                let codecs = file_codecs(&loaded);
+                let constants = dataset::constant_columns(&loaded.metadata);
+                let diagnostics = linter.lint_file(&loaded).await?;
+                anyhow::Ok((FileReport { file, diagnostics }, codecs, constants))
+            })
+            .buffered(LINT_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+    let mut table_diagnostics = table.check(&options.workload);
+    let mut by_codec: BTreeMap<&str, usize> = BTreeMap::new();
+    for (_, codecs, _) in &linted {
+        *by_codec.entry(codecs).or_default() += 1;
+    }
+    if by_codec.len() > 1 {
+        let counts: Vec<String> = by_codec
+            .iter()
+            .map(|(codecs, n)| format!("{codecs} ({n} files)"))
+            .collect();
+        table_diagnostics.push(table_diagnostic(
+            "delta-mixed-codecs",
+            Severity::Suggestion,
+            format!(
+                "data files written by different versions use different codecs: {}; \
+                 rewrite the older files so scans behave uniformly",
+                counts.join(", "),
+            ),
+        ));
+    }
+    let constants: Vec<ConstantColumns> = linted.iter().map(|(_, _, c)| c.clone()).collect();
+    table_diagnostics.extend(dataset::check(&constants));
+    Ok(DeltaReport {
+        files: linted.into_iter().map(|(report, _, _)| report).collect(),
+        table_diagnostics,
+    })
+}
+
+/// Log state accumulated while replaying actions in order.
+#[derive(Default)]
+struct Replay {
+    version: Option<u64>,
+    configuration: BTreeMap<String, String>,
+    /// Active `add` actions by path.
+    files: BTreeMap<String, Value>,
+}
+
+impl Replay {
+    fn apply(&mut self, action: &Value) {
+        if let Some(add) = action.get("add").filter(|a| !a.is_null())
+            && let Some(path) = add["path"].as_str()
+        {
+            self.files.insert(path.to_string(), add.clone());
+        }
+        if let Some(path) = action["remove"]["path"].as_str() {
+            self.files.remove(path);
+        }
+        if let Some(config) = action["metaData"]["configuration"].as_object() {
+            self.configuration = config
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect();
+        }
+    }
+}
+
+fn data_file(root: &ObjectPath, uri: String, add: &Value) -> Result<DataFile> {
+    let path = match url::Url::parse(&uri) {
+        Ok(url) => ObjectPath::from_url_path(url.path())?,
+        // Relative paths are URL-encoded and resolved against the table root.
+        Err(_) => root
+            .parts()
+            .chain(ObjectPath::from_url_path(&uri)?.parts())
+            .collect(),
+    };
+    let partition = add["partitionValues"]
+        .as_object()
+        .map(|values| {
+            values
+                .iter()
+                .map(|(k, v)| format!("{k}={}", v.as_str().unwrap_or("null")))
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default();
+    let stats = add["stats"]
+        .as_str()
+        .and_then(|s| serde_json::from_str(s).ok());
+    Ok(DataFile {
+        uri,
+        path,
+        partition,
+        size: add["size"].as_i64().unwrap_or(0),
+        stats,
+    })
+}
+
+/// Version and part count from `_last_checkpoint`, if the table has one.
+async fn last_checkpoint(
+    store: &dyn ObjectStore,
+    log: &ObjectPath,
+) -> Result<Option<(u64, Option<u64>)>> {
+    let result = match store.get(&log.child("_last_checkpoint")).await {
+        Ok(result) => result,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e).context("failed to read _last_checkpoint"),
+    };
+    let value: Value = serde_json::from_slice(&result.bytes().await?)?;
+    let version = value["version"]
+        .as_u64()
+        .context("_last_checkpoint is missing version")?;
+    Ok(Some((version, value["parts"].as_u64())))
+}
+
+fn checkpoint_parts(log: &ObjectPath, version: u64, parts: Option<u64>) -> Vec<ObjectPath> {
+    match parts {
+        Some(n) if n > 1 => (1..=n)
+            .map(|i| log.child(format!("{version:020}.checkpoint.{i:010}.{n:010}.parquet")))
+            .collect(),
+        _ => vec![log.child(format!("{version:020}.checkpoint.parquet"))],
+    }
+}
+
+/// Version and part count of the newest checkpoint in `listing` whose parts
+/// are all present.
+fn newest_checkpoint(listing: &[ObjectPath]) -> Option<(u64, Option<u64>)> {
+    let mut found: BTreeMap<(u64, Option<u64>), u64> = BTreeMap::new();
+    for path in listing {
+        if let Some(checkpoint) = path.filename().and_then(checkpoint_file) {
+            *found.entry(checkpoint).or_default() += 1;
+        }
+    }
+    found
+        .into_iter()
+        .filter(|&((_, parts), count)| count == parts.unwrap_or(1))
+        .map(|(checkpoint, _)| checkpoint)
+        .max_by_key(|&(version, _)| version)
+}
+
+/// Version and part count of a checkpoint file, named
+/// `00000000000000000012.checkpoint.parquet` or, in parts,
+/// `00000000000000000012.checkpoint.0000000001.0000000002.parquet`.
+fn checkpoint_file(filename: &str) -> Option<(u64, Option<u64>)> {
+    let (version, rest) = filename.split_once(".checkpoint.")?;
+    if version.len() != 20 {
+        return None;
+    }
+    let version = version.parse().ok()?;
+    if rest == "parquet" {
+        return Some((version, None));
+    }
+    let (part, parts) = rest.strip_suffix(".parquet")?.split_once('.')?;
+    if part.len() != 10 || parts.len() != 10 {
+        return None;
+    }
+    part.parse::<u64>().ok()?;
+    Some((version, Some(parts.parse().ok()?)))
+}
+
+/// Version of a `00000000000000000012.json` commit file.
+fn commit_version(filename: &str) -> Option<u64> {
+    let stem = filename.strip_suffix(".json")?;
+    if stem.len() != 20 {
+        return None;
+    }
+    stem.parse().ok()
+}
+
+/// Codec names used by a file's column chunks, e.g. `SNAPPY` or `SNAPPY+ZSTD`.
+fn file_codecs(file: &LoadedFile) -> String {
+    let names: BTreeSet<String> = file
+        .metadata
+        .row_groups()
+        .iter()
+        .flat_map(|rg| rg.columns())
+        .map(|c| {
+            let name = c.compression().to_string();
+            name.split('(').next().unwrap_or_default().to_string()
+        })
+        .collect();
+    names.into_iter().collect::<Vec<_>>().join("+")
+}
+
+/// Value at a dotted column path inside nested stats objects.
+fn lookup<'a>(value: &'a Value, column: &str) -> Option<&'a Value> {
+    column.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+fn table_diagnostic(rule_name: &'static str, severity: Severity, message: String) -> Diagnostic {
+    Diagnostic {
+        rule_name,
+        severity,
+        category: Category::Performance,
+        location: Location::File,
+        message,
+        prescription: Prescription::new(),
+        related: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Int64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::{Compression, ZstdLevel};
+    use parquet::file::properties::WriterProperties;
+    use serde_json::json;
+
+    #[test]
+    fn commit_versions_need_twenty_digits() {
+        assert_eq!(commit_version("00000000000000000012.json"), Some(12));
+        assert_eq!(commit_version("_last_checkpoint"), None);
+        assert_eq!(commit_version("12.json"), None);
+    }
+
+    #[test]
+    fn checkpoint_files_need_every_part() {
+        assert_eq!(
+            checkpoint_file("00000000000000000010.checkpoint.parquet"),
+            Some((10, None))
+        );
+        let log = ObjectPath::from("t/_delta_log");
+        let listing: Vec<ObjectPath> = [
+            "00000000000000000010.checkpoint.parquet",
+            "00000000000000000020.checkpoint.0000000001.0000000002.parquet",
+            "00000000000000000020.checkpoint.0000000002.0000000002.parquet",
+            "00000000000000000030.checkpoint.0000000001.0000000002.parquet",
+            "00000000000000000031.json",
+        ]
+        .into_iter()
+        .map(|name| log.child(name))
+        .collect();
+        assert_eq!(newest_checkpoint(&listing), Some((20, Some(2))));
+    }
+
+    #[tokio::test]
+    async fn replays_checkpoint_without_last_checkpoint_file() -> Result<()> {
+        use arrow_array::{ArrayRef, StringArray, StructArray};
+        use arrow_schema::{DataType, Field};
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        let log = root.join("_delta_log");
+        std::fs::create_dir_all(&log)?;
+
+        // Version 1 checkpointed a.parquet and c.parquet; commits 0 and 1
+        // were cleaned up.
+        let add = StructArray::from(vec![
+            (
+                Arc::new(Field::new("path", DataType::Utf8, true)),
+                Arc::new(StringArray::from(vec!["a.parquet", "c.parquet"])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("size", DataType::Int64, true)),
+                Arc::new(Int64Array::from(vec![1000, 2000])) as ArrayRef,
+            ),
+        ]);
+        let batch = RecordBatch::try_from_iter([("add", Arc::new(add) as ArrayRef)])?;
+        let file = std::fs::File::create(log.join(format!("{:020}.checkpoint.parquet", 1)))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let commit = [
+            json!({"remove": {"path": "c.parquet"}}),
+            json!({"add": {"path": "b.parquet", "partitionValues": {}, "size": 3000}}),
+        ]
+        .map(|action| action.to_string())
+        .join("\n");
+        std::fs::write(log.join(format!("{:020}.json", 2)), commit)?;
+
+        let table = DeltaTable::load(root.to_str().unwrap()).await?;
+        assert_eq!(table.version, 2);
+        let files: Vec<(&str, i64)> = table
+            .data_files
+            .iter()
+            .map(|f| (f.uri.as_str(), f.size))
+            .collect();
+        assert_eq!(files, [("a.parquet", 1000), ("b.parquet", 3000)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replays_log_and_flags_table_issues() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        std::fs::create_dir_all(root.join("_delta_log"))?;
+        std::fs::create_dir_all(root.join("region=eu"))?;
+
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        let zstd = Compression::ZSTD(ZstdLevel::default());
+        for (name, codec) in [
+            ("region=eu/a.parquet", Compression::SNAPPY),
+            ("b.parquet", zstd),
+        ] {
+            let props = WriterProperties::builder().set_compression(codec).build();
+            let file = std::fs::File::create(root.join(name))?;
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+
+        let add = |path: &str, partition: Value, stats: Option<&str>| {
+            json!({"add": {"path": path, "partitionValues": partition, "size": 1000,
+                           "dataChange": true, "stats": stats}})
+        };
+        let commits = [
+            vec![
+                json!({"metaData": {"configuration": {"delta.dataSkippingNumIndexedCols": "1"}}}),
+                add("region%3Deu/a.parquet", json!({"region": "eu"}), None),
+                add("c.parquet", json!({}), None),
+            ],
+            vec![
+                json!({"remove": {"path": "c.parquet"}}),
+                add(
+                    "b.parquet",
+                    json!({}),
+                    Some(r#"{"numRecords":100,"minValues":{}}"#),
+                ),
+            ],
+        ];
+        for (version, actions) in commits.iter().enumerate() {
+            let lines: Vec<String> = actions.iter().map(Value::to_string).collect();
+            let path = root.join(format!("_delta_log/{version:020}.json"));
+            std::fs::write(path, lines.join("\n"))?;
+        }
+
+        let table = DeltaTable::load(root.to_str().unwrap()).await?;
+        assert_eq!(table.version, 1);
+        let partitions: Vec<&str> = table
+            .data_files
+            .iter()
+            .map(|f| f.partition.as_str())
+            .collect();
+        assert_eq!(partitions, ["", "region=eu"]);
+
+        let options = LintOptions {
+            workload: Workload {
+                filter_columns: vec!["id".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let report = lint_table(&table, None, &options).await?;
+        let rules: Vec<&str> = report
+            .table_diagnostics
+            .iter()
+            .map(|d| d.rule_name)
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                "delta-small-files",
+                "delta-missing-stats",
+                "delta-missing-stats",
+                "delta-mixed-codecs"
+            ]
+        );
+        assert!(report.table_diagnostics[2].message.contains("currently 1"));
+        Ok(())
+    }
+}
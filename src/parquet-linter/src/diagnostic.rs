@@ -1,5 +1,7 @@
 use colored::Colorize;
 use parquet::schema::types::ColumnPath;
+use serde::{Serialize, Serializer};
+use serde_json::{Value, json};
 use std::fmt;
 
 use crate::prescription::Prescription;
@@ -33,11 +35,58 @@ impl std::str::FromStr for Severity {
     }
 }
 
+/// What kind of problem a diagnostic reports, independent of how severe it
+/// is: broken or misleading data, slow reads, readers that cannot open the
+/// file, or slow reads on GPU decoders specifically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Correctness,
+    Performance,
+    Compatibility,
+    Gpu,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Correctness => write!(f, "correctness"),
+            Category::Performance => write!(f, "performance"),
+            Category::Compatibility => write!(f, "compatibility"),
+            Category::Gpu => write!(f, "gpu"),
+        }
+    }
+}
+
+impl std::str::FromStr for Category {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correctness" => Ok(Category::Correctness),
+            "performance" => Ok(Category::Performance),
+            "compatibility" => Ok(Category::Compatibility),
+            "gpu" => Ok(Category::Gpu),
+            _ => Err(format!("unknown category: {s}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Location {
     File,
-    RowGroup { index: usize },
-    Column { column: usize, path: ColumnPath },
+    RowGroup {
+        index: usize,
+    },
+    Column {
+        column: usize,
+        path: ColumnPath,
+    },
+    /// A data page, numbered within its column chunk.
+    Page {
+        row_group: usize,
+        column: usize,
+        path: ColumnPath,
+        page: usize,
+    },
 }
 
 impl fmt::Display for Location {
@@ -48,6 +97,15 @@ impl fmt::Display for Location {
             Location::Column { column, path } => {
                 write!(f, "column[{column}]({path})")
             }
+            Location::Page {
+                row_group,
+                column,
+                path,
+                page,
+            } => write!(
+                f,
+                "row_group[{row_group}].column[{column}]({path}).page[{page}]"
+            ),
         }
     }
 }
@@ -55,9 +113,13 @@ impl fmt::Display for Location {
 pub struct Diagnostic {
     pub rule_name: &'static str,
     pub severity: Severity,
+    pub category: Category,
     pub location: Location,
     pub message: String,
     pub prescription: Prescription,
+    /// Other locations the message refers to, e.g. the row groups behind a
+    /// column diagnostic.
+    pub related: Vec<Location>,
 }
 
 impl Diagnostic {
@@ -71,11 +133,238 @@ impl Diagnostic {
         let location = format!("{}", self.location).cyan();
         println!("{severity_str} {rule}");
         println!("  {} {location}", "-->".dimmed());
+        if !self.related.is_empty() {
+            let related: Vec<String> = self.related.iter().map(Location::to_string).collect();
+            println!("  {} {}", "see:".dimmed(), related.join(", ").cyan());
+        }
         println!("  {}", self.message);
         for directive in self.prescription.directives() {
             println!("  {} {directive}", "fix:".green().bold());
         }
     }
+
+    /// GitHub Actions workflow command (`::warning file=...::message`) that
+    /// surfaces this diagnostic as an annotation on `file`.
+    pub fn to_github_annotation(&self, file: &str) -> String {
+        let level = match self.severity {
+            Severity::Suggestion => "notice",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let title = format!("{} @ {}", self.rule_name, self.location);
+        let mut message = self.message.clone();
+        for directive in self.prescription.directives() {
+            message.push_str(&format!("\nfix: {directive}"));
+        }
+        format!(
+            "::{level} file={},title={}::{}",
+            escape_property(file),
+            escape_property(&title),
+            escape_data(&message)
+        )
+    }
+}
+
+/// Column diagnostics of one rule and severity that recommend the same
+/// change, so wide tables print one entry per recommendation rather than one
+/// per column. Other diagnostics form groups of one.
+pub struct DiagnosticGroup<'a> {
+    pub diagnostics: Vec<&'a Diagnostic>,
+}
+
+/// Columns listed by name in a grouped diagnostic before summarizing.
+const GROUP_COLUMNS_SHOWN: usize = 10;
+
+impl DiagnosticGroup<'_> {
+    pub fn print_colored(&self) {
+        let [first, rest @ ..] = self.diagnostics.as_slice() else {
+            return;
+        };
+        if rest.is_empty() {
+            first.print_colored();
+            return;
+        }
+        let severity_str = match first.severity {
+            Severity::Suggestion => "suggestion".blue().bold(),
+            Severity::Warning => "warning".yellow().bold(),
+            Severity::Error => "error".red().bold(),
+        };
+        println!("{severity_str} {}", first.rule_name.dimmed());
+        let names: Vec<String> = self
+            .diagnostics
+            .iter()
+            .filter_map(|d| column_path(d).map(ColumnPath::string))
+            .collect();
+        let mut columns = names[..names.len().min(GROUP_COLUMNS_SHOWN)].join(", ");
+        if names.len() > GROUP_COLUMNS_SHOWN {
+            columns.push_str(&format!(
+                ", … and {} more",
+                names.len() - GROUP_COLUMNS_SHOWN
+            ));
+        }
+        let header = format!("{} columns: {columns}", names.len());
+        println!("  {} {}", "-->".dimmed(), header.cyan());
+        println!("  {}: {}", names[0], first.message);
+        let placeholder = ColumnPath::from("<column>");
+        for directive in first.prescription.directives() {
+            let directive = directive.for_column(&placeholder);
+            println!("  {} {directive}", "fix:".green().bold());
+        }
+    }
+}
+
+/// Group `diagnostics` (keeping their order) by rule, severity and
+/// recommended change with the column abstracted away.
+pub fn group<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> Vec<DiagnosticGroup<'a>> {
+    let mut groups: Vec<(Option<String>, DiagnosticGroup<'a>)> = Vec::new();
+    for d in diagnostics {
+        let key = group_key(d);
+        match groups.iter_mut().find(|(k, _)| key.is_some() && *k == key) {
+            Some((_, group)) => group.diagnostics.push(d),
+            None => groups.push((
+                key,
+                DiagnosticGroup {
+                    diagnostics: vec![d],
+                },
+            )),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// `None` for diagnostics that never group: non-column locations and fixes
+/// that touch columns other than the diagnostic's own.
+fn group_key(d: &Diagnostic) -> Option<String> {
+    let path = column_path(d)?;
+    let placeholder = ColumnPath::from("<column>");
+    let mut key = format!("{}\0{}", d.rule_name, d.severity);
+    for directive in d.prescription.directives() {
+        if directive.column().is_some_and(|c| c != path) {
+            return None;
+        }
+        key.push_str(&format!("\0{}", directive.for_column(&placeholder)));
+    }
+    Some(key)
+}
+
+fn column_path(d: &Diagnostic) -> Option<&ColumnPath> {
+    match &d.location {
+        Location::Column { path, .. } => Some(path),
+        _ => None,
+    }
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Version of the JSON produced by [`json_report`] and
+/// [`Diagnostic::to_json`], described by `doc/lint-output.schema.json`.
+/// Adding fields, rules, settings or enum values keeps the version; renaming
+/// or removing a field or changing its type bumps it.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+impl Location {
+    /// JSON object for one location (the schema's `location` definition).
+    pub fn to_json(&self) -> Value {
+        match self {
+            Location::File => json!({"kind": "file"}),
+            Location::RowGroup { index } => json!({"kind": "row_group", "index": index}),
+            Location::Column { column, path } => {
+                json!({"kind": "column", "column": column, "path": path.parts()})
+            }
+            Location::Page {
+                row_group,
+                column,
+                path,
+                page,
+            } => json!({
+                "kind": "page",
+                "row_group": row_group,
+                "column": column,
+                "path": path.parts(),
+                "page": page,
+            }),
+        }
+    }
+
+    /// Inverse of [`Location::to_json`]; `None` for unknown kinds.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let path = || {
+            Some(ColumnPath::new(
+                value["path"]
+                    .as_array()?
+                    .iter()
+                    .map(|p| p.as_str().map(str::to_string))
+                    .collect::<Option<_>>()?,
+            ))
+        };
+        let location = match value["kind"].as_str()? {
+            "file" => Location::File,
+            "row_group" => Location::RowGroup {
+                index: value["index"].as_u64()? as usize,
+            },
+            "column" => Location::Column {
+                column: value["column"].as_u64()? as usize,
+                path: path()?,
+            },
+            "page" => Location::Page {
+                row_group: value["row_group"].as_u64()? as usize,
+                column: value["column"].as_u64()? as usize,
+                path: path()?,
+                page: value["page"].as_u64()? as usize,
+            },
+            _ => return None,
+        };
+        Some(location)
+    }
+}
+
+impl Diagnostic {
+    /// JSON object for one diagnostic (the schema's `diagnostic` definition).
+    pub fn to_json(&self) -> Value {
+        json!({
+            "rule_name": self.rule_name,
+            "severity": self.severity.to_string(),
+            "category": self.category.to_string(),
+            "location": self.location,
+            "message": self.message,
+            "directives": self.prescription,
+            "related": self.related,
+        })
+    }
+}
+
+// Serialization goes through `to_json`, so serde consumers get exactly the
+// documented schema.
+impl Serialize for Location {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl Serialize for Diagnostic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// Versioned JSON document with every diagnostic for `file`.
+pub fn json_report<'a>(file: &str, diagnostics: impl IntoIterator<Item = &'a Diagnostic>) -> Value {
+    let diagnostics: Vec<&Diagnostic> = diagnostics.into_iter().collect();
+    json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "file": file,
+        "diagnostics": diagnostics,
+    })
 }
 
 impl fmt::Display for Diagnostic {
@@ -91,3 +380,115 @@ impl fmt::Display for Diagnostic {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prescription::{Codec, Directive};
+
+    fn zstd_on(column: usize, name: &str, level: i32) -> Diagnostic {
+        let path = ColumnPath::from(name);
+        let mut prescription = Prescription::new();
+        prescription.push(Directive::SetColumnCompression(
+            path.clone(),
+            Codec::Zstd(level),
+        ));
+        Diagnostic {
+            rule_name: "compression-codec-upgrade",
+            severity: Severity::Warning,
+            category: Category::Performance,
+            location: Location::Column { column, path },
+            message: format!("{name} is snappy"),
+            prescription,
+            related: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_matches_the_published_schema_enums() {
+        let schema: Value =
+            serde_json::from_str(include_str!("../../../doc/lint-output.schema.json")).unwrap();
+        let defs = &schema["$defs"];
+        let report = json_report("t.parquet", &[zstd_on(0, "a", 3)]);
+        assert_eq!(report["schema_version"], json!(JSON_SCHEMA_VERSION));
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            json!(JSON_SCHEMA_VERSION)
+        );
+
+        let enum_values = |def: &str| defs[def]["enum"].as_array().unwrap().clone();
+        let severities = [Severity::Suggestion, Severity::Warning, Severity::Error];
+        assert_eq!(
+            enum_values("severity"),
+            severities.map(|s| json!(s.to_string()))
+        );
+        let categories = [
+            Category::Correctness,
+            Category::Performance,
+            Category::Compatibility,
+            Category::Gpu,
+        ];
+        assert_eq!(
+            enum_values("category"),
+            categories.map(|c| json!(c.to_string()))
+        );
+        let diagnostic = &report["diagnostics"][0];
+        assert!(enum_values("setting").contains(&diagnostic["directives"][0]["setting"]));
+        let required = defs["diagnostic"]["required"].as_array().unwrap();
+        for key in required {
+            assert!(!diagnostic[key.as_str().unwrap()].is_null(), "{key}");
+        }
+    }
+
+    #[test]
+    fn serializes_as_the_schema_json() {
+        let diagnostic = zstd_on(4, "a", 3);
+        let value = serde_json::to_value(&diagnostic).unwrap();
+        assert_eq!(value, diagnostic.to_json());
+        assert_eq!(
+            value["location"],
+            json!({"kind": "column", "column": 4, "path": ["a"]})
+        );
+        assert_eq!(
+            serde_json::to_value(&diagnostic.prescription).unwrap(),
+            json!([{
+                "scope": "column",
+                "column": ["a"],
+                "setting": "compression",
+                "value": "zstd(3)",
+                "text": "set column a compression zstd(3)",
+            }])
+        );
+    }
+
+    #[test]
+    fn same_fix_on_many_columns_groups() {
+        let diagnostics = [zstd_on(0, "a", 3), zstd_on(1, "b", 9), zstd_on(2, "c", 3)];
+        let groups = group(&diagnostics);
+        let sizes: Vec<usize> = groups.iter().map(|g| g.diagnostics.len()).collect();
+        assert_eq!(sizes, [2, 1]);
+        assert_eq!(groups[0].diagnostics[1].message, "c is snappy");
+    }
+
+    #[test]
+    fn github_annotation_escapes_properties_and_message() {
+        let mut prescription = Prescription::new();
+        prescription.push(Directive::SetFileCompression(
+            crate::prescription::Codec::Snappy,
+        ));
+        let diagnostic = Diagnostic {
+            rule_name: "compression-codec",
+            severity: Severity::Suggestion,
+            category: Category::Performance,
+            location: Location::File,
+            message: "100% uncompressed".to_string(),
+            prescription,
+            related: Vec::new(),
+        };
+        assert_eq!(
+            diagnostic.to_github_annotation("s3://b/a,b.parquet"),
+            "::notice file=s3%3A//b/a%2Cb.parquet,title=compression-codec @ file\
+             ::100%25 uncompressed%0Afix: set file compression snappy"
+        );
+    }
+}
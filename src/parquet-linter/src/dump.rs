@@ -0,0 +1,197 @@
+//! Footer structure as JSON, for attaching to bug reports. With
+//! `anonymize`, column names are replaced by stable hashes and values that
+//! may come from the data (statistics, key-value metadata values) are
+//! reduced to their lengths, so the dump shows the shape of a file without
+//! its contents.
+
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::ColumnDescriptor;
+use serde_json::{Value, json};
+
+use crate::cache::fnv1a;
+
+pub fn metadata_json(metadata: &ParquetMetaData, anonymize: bool) -> Value {
+    let file = metadata.file_metadata();
+    let schema = file.schema_descr();
+    let columns: Vec<Value> = schema
+        .columns()
+        .iter()
+        .map(|descr| column_json(descr, anonymize))
+        .collect();
+    let key_value_metadata: Vec<Value> = file
+        .key_value_metadata()
+        .into_iter()
+        .flatten()
+        .map(|kv| {
+            let value = kv.value.as_deref().unwrap_or_default();
+            if anonymize {
+                json!({ "key": kv.key, "value_len": value.len() })
+            } else {
+                json!({ "key": kv.key, "value": value })
+            }
+        })
+        .collect();
+    let row_groups: Vec<Value> = metadata
+        .row_groups()
+        .iter()
+        .map(|rg| {
+            let chunks: Vec<Value> = rg
+                .columns()
+                .iter()
+                .map(|chunk| chunk_json(chunk, anonymize))
+                .collect();
+            json!({
+                "num_rows": rg.num_rows(),
+                "total_byte_size": rg.total_byte_size(),
+                "compressed_size": rg.compressed_size(),
+                "sorting_columns": rg.sorting_columns().map(|sorting| {
+                    sorting
+                        .iter()
+                        .map(|s| json!({
+                            "column": s.column_idx,
+                            "descending": s.descending,
+                            "nulls_first": s.nulls_first,
+                        }))
+                        .collect::<Vec<_>>()
+                }),
+                "columns": chunks,
+            })
+        })
+        .collect();
+    json!({
+        "anonymized": anonymize,
+        "version": file.version(),
+        "created_by": file.created_by(),
+        "num_rows": file.num_rows(),
+        "key_value_metadata": key_value_metadata,
+        "columns": columns,
+        "row_groups": row_groups,
+    })
+}
+
+/// `name` itself, or a stable hash of it. Equal names hash equally, so
+/// nested paths keep their shared prefixes.
+fn name(name: &str, anonymize: bool) -> String {
+    if anonymize {
+        format!("c_{:08x}", fnv1a(name.as_bytes()) as u32)
+    } else {
+        name.to_string()
+    }
+}
+
+fn path(descr: &ColumnDescriptor, anonymize: bool) -> String {
+    descr
+        .path()
+        .parts()
+        .iter()
+        .map(|part| name(part, anonymize))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn column_json(descr: &ColumnDescriptor, anonymize: bool) -> Value {
+    let repetition = descr
+        .self_type()
+        .get_basic_info()
+        .has_repetition()
+        .then(|| descr.self_type().get_basic_info().repetition().to_string());
+    json!({
+        "path": path(descr, anonymize),
+        "physical_type": descr.physical_type().to_string(),
+        "logical_type": descr.logical_type_ref().map(|t| format!("{t:?}")),
+        "converted_type": descr.converted_type().to_string(),
+        "type_length": descr.type_length(),
+        "repetition": repetition,
+        "max_def_level": descr.max_def_level(),
+        "max_rep_level": descr.max_rep_level(),
+    })
+}
+
+fn chunk_json(chunk: &ColumnChunkMetaData, anonymize: bool) -> Value {
+    let encodings: Vec<String> = chunk.encodings().map(|e| e.to_string()).collect();
+    json!({
+        "path": path(chunk.column_descr(), anonymize),
+        "compression": chunk.compression().to_string(),
+        "encodings": encodings,
+        "num_values": chunk.num_values(),
+        "compressed_size": chunk.compressed_size(),
+        "uncompressed_size": chunk.uncompressed_size(),
+        "has_dictionary_page": chunk.dictionary_page_offset().is_some(),
+        "has_column_index": chunk.column_index_offset().is_some(),
+        "has_offset_index": chunk.offset_index_offset().is_some(),
+        "bloom_filter_length": chunk.bloom_filter_length(),
+        "statistics": chunk.statistics().map(|stats| statistics_json(stats, anonymize)),
+    })
+}
+
+fn statistics_json(stats: &Statistics, anonymize: bool) -> Value {
+    let mut value = json!({
+        "null_count": stats.null_count_opt(),
+        "distinct_count": stats.distinct_count_opt(),
+        "min_len": stats.min_bytes_opt().map(<[u8]>::len),
+        "max_len": stats.max_bytes_opt().map(<[u8]>::len),
+        "min_is_exact": stats.min_is_exact(),
+        "max_is_exact": stats.max_is_exact(),
+    });
+    if !anonymize && let Some((min, max)) = min_max(stats) {
+        value["min"] = min.into();
+        value["max"] = max.into();
+    }
+    value
+}
+
+fn min_max(stats: &Statistics) -> Option<(String, String)> {
+    fn pair<T: ToString>(min: Option<&T>, max: Option<&T>) -> Option<(String, String)> {
+        Some((min?.to_string(), max?.to_string()))
+    }
+    fn bytes(min: Option<&[u8]>, max: Option<&[u8]>) -> Option<(String, String)> {
+        let text = |b: &[u8]| String::from_utf8_lossy(b).into_owned();
+        Some((text(min?), text(max?)))
+    }
+    match stats {
+        Statistics::Boolean(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Int32(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Int64(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Int96(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Float(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Double(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::ByteArray(_) | Statistics::FixedLenByteArray(_) => {
+            bytes(stats.min_bytes_opt(), stats.max_bytes_opt())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow_array::{RecordBatch, StringArray};
+
+    use crate::testing::TestFile;
+
+    #[tokio::test]
+    async fn anonymized_dump_hides_names_and_values() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "customer_email",
+            Arc::new(StringArray::from(vec![
+                "alice@example.com",
+                "bob@example.com",
+            ])) as _,
+        )])?;
+        let file = TestFile::new(batch).open().await?;
+
+        let plain = metadata_json(&file.metadata, false).to_string();
+        assert!(plain.contains("customer_email") && plain.contains("alice@example.com"));
+
+        let dump = metadata_json(&file.metadata, true);
+        let text = dump.to_string();
+        assert!(!text.contains("customer_email"));
+        assert!(!text.contains("example.com"));
+        let chunk = &dump["row_groups"][0]["columns"][0];
+        assert_eq!(chunk["path"], dump["columns"][0]["path"]);
+        assert_eq!(chunk["statistics"]["min_len"], 17);
+        Ok(())
+    }
+}
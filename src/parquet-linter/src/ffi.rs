@@ -0,0 +1,207 @@
+//! C ABI for embedding the linter in non-Rust services (enabled by the `ffi`
+//! feature). See `include/parquet_linter.h` for the C declarations.
+//!
+//! Strings returned to the caller are owned by the library and must be
+//! released with `parquet_linter_string_free`. Failed calls record a message
+//! readable with `parquet_linter_last_error` on the same thread; a panic
+//! inside the library is reported the same way rather than unwinding into
+//! the caller.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::future::Future;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::diagnostic::Diagnostic;
+use crate::loader::{self, LoadedFile};
+use crate::options::LintOptions;
+use crate::prescription::Prescription;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Lint the file at `path` (local path or URL) and return its diagnostics as
+/// a JSON array of `Diagnostic::to_json` objects, or NULL on error.
+///
+/// # Safety
+/// `path` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parquet_linter_lint_json(path: *const c_char) -> *mut c_char {
+    let result = unsafe { read_str(path) }.and_then(|path| {
+        guarded(|| {
+            let diagnostics = block_on(async {
+                let (store, path) = loader::parse(path)?;
+                crate::lint(store, path, None).await
+            })?;
+            let json =
+                serde_json::Value::Array(diagnostics.iter().map(Diagnostic::to_json).collect());
+            Ok(CString::new(json.to_string())?)
+        })
+    });
+    match result {
+        Ok(json) => json.into_raw(),
+        Err(e) => {
+            set_last_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Rewrite `path` into the local file `output`. Applies `prescription` (DSL
+/// text) when non-NULL, otherwise every fix suggested by linting the file.
+/// Returns 0 on success and -1 on error.
+///
+/// # Safety
+/// `path` and `output` must be valid NUL-terminated strings; `prescription`
+/// must be NULL or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parquet_linter_rewrite(
+    path: *const c_char,
+    output: *const c_char,
+    prescription: *const c_char,
+) -> c_int {
+    let args = unsafe {
+        (
+            read_str(path),
+            read_str(output),
+            read_optional_str(prescription),
+        )
+    };
+    let result = match args {
+        (Ok(path), Ok(output), Ok(prescription)) => {
+            guarded(|| block_on(rewrite(path, output, prescription)))
+        }
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => Err(e),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(e);
+            -1
+        }
+    }
+}
+
+/// The message of the last failed call on this thread, or NULL. The pointer
+/// stays valid until the next failed call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn parquet_linter_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |s| s.as_ptr()))
+}
+
+/// Release a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `s` must be NULL or a pointer returned by this library, freed only once.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parquet_linter_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+async fn rewrite(path: &str, output: &str, prescription: Option<&str>) -> Result<()> {
+    let (store, object_path) = loader::parse(path)?;
+    let file = LoadedFile::open(store, object_path).await?;
+    let prescription = match prescription {
+        Some(text) => Prescription::parse(text)?,
+        None => {
+            let mut merged = Prescription::new();
+            for d in crate::lint_file(&file, None, &LintOptions::default()).await? {
+                merged.extend(d.prescription);
+            }
+            merged
+        }
+    };
+    crate::fix::rewrite_file(&file, Path::new(output), &prescription).await
+}
+
+/// Run `f`, turning a panic into an error: unwinding across `extern "C"`
+/// would abort the host process.
+fn guarded<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        Err(anyhow!("panicked: {message}"))
+    })
+}
+
+fn block_on<T>(fut: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(fut)
+}
+
+/// # Safety
+/// `s` must be NULL or a valid NUL-terminated string.
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str> {
+    unsafe { read_optional_str(s) }?.context("unexpected NULL string argument")
+}
+
+/// # Safety
+/// `s` must be NULL or a valid NUL-terminated string.
+unsafe fn read_optional_str<'a>(s: *const c_char) -> Result<Option<&'a str>> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(unsafe { CStr::from_ptr(s) }.to_str()?))
+}
+
+fn set_last_error(e: anyhow::Error) {
+    // Interior NULs cannot cross the C boundary; drop them.
+    let message = format!("{e:#}").replace('\0', "");
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = CString::new(message).ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_error_is_reported_through_last_error() {
+        let path = CString::new("/definitely/missing.parquet").unwrap();
+        let json = unsafe { parquet_linter_lint_json(path.as_ptr()) };
+        assert!(json.is_null());
+        let message = unsafe { CStr::from_ptr(parquet_linter_last_error()) };
+        assert!(message.to_str().unwrap().contains("file not found"));
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        let err = guarded::<()>(|| panic!("bad footer {}", 7)).unwrap_err();
+        assert_eq!(err.to_string(), "panicked: bad footer 7");
+    }
+
+    #[test]
+    fn lint_json_returns_array() -> Result<()> {
+        use arrow_array::{Int64Array, RecordBatch};
+        use parquet::arrow::ArrowWriter;
+        use std::sync::Arc;
+
+        let tempdir = tempfile::tempdir()?;
+        let file = tempdir.path().join("t.parquet");
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&file)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let path = CString::new(file.to_str().unwrap())?;
+        let json = unsafe { parquet_linter_lint_json(path.as_ptr()) };
+        assert!(!json.is_null());
+        let value: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(json) }.to_str()?)?;
+        unsafe { parquet_linter_string_free(json) };
+        assert!(value.is_array());
+        Ok(())
+    }
+}
@@ -1,20 +1,32 @@
 use std::fs::File;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use arrow_array::{Array, BinaryArray, FixedSizeBinaryArray, LargeBinaryArray, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
 use futures::StreamExt;
-use object_store::ObjectStore;
 use object_store::path::Path as ObjectPath;
-use parquet::arrow::ArrowWriter;
+use object_store::{ObjectStore, PutMode, UpdateVersion};
+use parquet::arrow::arrow_reader::ArrowReaderOptions;
+use parquet::arrow::arrow_writer::ArrowWriterOptions;
 use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use parquet::arrow::{ARROW_SCHEMA_META_KEY, ArrowWriter};
 use parquet::basic::{Compression, Encoding};
 use parquet::file::metadata::{ParquetMetaData, SortingColumn};
 use parquet::file::properties::{EnabledStatistics, WriterProperties, WriterVersion};
+use tokio_util::sync::CancellationToken;
 
-use crate::prescription::Prescription;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::loader::LoadedFile;
+use crate::options::LintOptions;
+use crate::prescription::{
+    ArrowSchemaMode, CastType, DatasetPrescription, Directive, Prescription,
+};
+use crate::sort::{FileSort, RowGroupSort, SortOrder};
 
-fn build_writer_properties_with_base(
+pub(crate) fn build_writer_properties_with_base(
     metadata: &ParquetMetaData,
     prescription: &Prescription,
 ) -> WriterProperties {
@@ -22,7 +34,7 @@ fn build_writer_properties_with_base(
     prescription.apply(base.into_builder()).build()
 }
 
-fn infer_writer_properties(metadata: &ParquetMetaData) -> WriterProperties {
+pub(crate) fn infer_writer_properties(metadata: &ParquetMetaData) -> WriterProperties {
     let mut builder = WriterProperties::builder();
     let file_meta = metadata.file_metadata();
 
@@ -77,7 +89,7 @@ fn infer_writer_version(version: i32) -> Option<WriterVersion> {
     }
 }
 
-fn infer_sorting_columns(metadata: &ParquetMetaData) -> Option<Vec<SortingColumn>> {
+pub(crate) fn infer_sorting_columns(metadata: &ParquetMetaData) -> Option<Vec<SortingColumn>> {
     let mut inferred: Option<Vec<SortingColumn>> = None;
 
     for row_group in metadata.row_groups() {
@@ -216,23 +228,549 @@ pub async fn rewrite(
     output: &Path,
     prescription: &Prescription,
 ) -> Result<()> {
-    let reader = ParquetObjectReader::new(store, path);
-    let builder = ParquetRecordBatchStreamBuilder::new(reader).await?;
+    let file = LoadedFile::open(store, path).await?;
+    rewrite_file(&file, output, prescription).await
+}
+
+/// Rewrite an already-opened file, reusing its reader and footer metadata.
+pub async fn rewrite_file(
+    file: &LoadedFile,
+    output: &Path,
+    prescription: &Prescription,
+) -> Result<()> {
+    rewrite_file_until_cancelled(file, output, prescription, &CancellationToken::new()).await
+}
+
+/// Like `rewrite_file`, but stops between batches once `cancel` fires and
+/// removes the partial output.
+#[tracing::instrument(
+    name = "rewrite",
+    skip_all,
+    fields(path = %file.path, output = %output.display())
+)]
+pub async fn rewrite_file_until_cancelled(
+    file: &LoadedFile,
+    output: &Path,
+    prescription: &Prescription,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let builder = input_stream_builder(file, prescription)?;
     let props = build_writer_properties_with_base(builder.metadata(), prescription);
-    let schema = builder.schema().clone();
+    let schema = SchemaRewrite::new(builder.schema(), prescription);
     let mut stream = builder.build()?;
 
     let output_file = File::create(output)?;
-    let mut writer = ArrowWriter::try_new(output_file, schema, Some(props))?;
+    let mut writer = arrow_writer(output_file, props, prescription, &schema)?;
 
-    while let Some(batch) = stream.next().await {
-        let batch = batch?;
-        writer.write(&batch)?;
+    loop {
+        let Some(next) = cancel.run_until_cancelled(stream.next()).await else {
+            drop(writer);
+            std::fs::remove_file(output)?;
+            bail!("rewrite cancelled");
+        };
+        let Some(batch) = next else { break };
+        writer.write(&schema.apply(batch?)?)?;
     }
     writer.close()?;
     Ok(())
 }
 
+/// Lint a rewritten file with the rules whose prescriptions `before` carried
+/// and return the warnings and errors they still report. Anything returned
+/// means a fix did not silence the rule that asked for it.
+pub async fn unsilenced_diagnostics(
+    before: &[Diagnostic],
+    rewritten: &LoadedFile,
+    options: &LintOptions,
+) -> Result<Vec<Diagnostic>> {
+    let mut rules: Vec<String> = before
+        .iter()
+        .filter(|d| !d.prescription.is_empty())
+        .map(|d| d.rule_name.to_string())
+        .collect();
+    rules.sort();
+    rules.dedup();
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+    let after = crate::lint_file(rewritten, Some(&rules), options).await?;
+    Ok(after
+        .into_iter()
+        .filter(|d| matches!(d.severity, Severity::Warning | Severity::Error))
+        .collect())
+}
+
+fn arrow_schema_mode(prescription: &Prescription) -> ArrowSchemaMode {
+    prescription
+        .directives()
+        .iter()
+        .rev()
+        .find_map(|d| match d {
+            Directive::SetFileArrowSchema(mode) => Some(*mode),
+            _ => None,
+        })
+        .unwrap_or(ArrowSchemaMode::Preserve)
+}
+
+/// Stream over the input of a rewrite. With `set file arrow_schema
+/// normalize` the embedded Arrow schema is ignored, so batches (and the
+/// output's embedded schema) take the types the Parquet schema implies;
+/// `set file read_batch_size` sets the rows per batch.
+pub(crate) fn input_stream_builder(
+    file: &LoadedFile,
+    prescription: &Prescription,
+) -> Result<ParquetRecordBatchStreamBuilder<ParquetObjectReader>> {
+    let normalize = arrow_schema_mode(prescription) == ArrowSchemaMode::Normalize;
+    let options = ArrowReaderOptions::new().with_skip_arrow_metadata(normalize);
+    let mut builder =
+        crate::loader::stream_builder_with_options(&file.reader, &file.metadata, options)?;
+    let batch_size = prescription
+        .directives()
+        .iter()
+        .rev()
+        .find_map(|d| match d {
+            Directive::SetFileReadBatchSize(rows) => Some(*rows),
+            _ => None,
+        });
+    if let Some(rows) = batch_size {
+        builder = builder.with_batch_size(rows);
+    }
+    Ok(builder)
+}
+
+/// Arrow writer for a rewrite. `props` carries the input's key-value
+/// metadata, `ARROW:schema` included; it is kept as is unless the rewrite
+/// changes types, when the writer encodes the new schema instead. Re-encoding
+/// an unchanged schema is not guaranteed to give the same bytes.
+pub(crate) fn arrow_writer<W: Write + Send>(
+    output: W,
+    props: WriterProperties,
+    prescription: &Prescription,
+    schema: &SchemaRewrite,
+) -> Result<RewriteWriter<W>> {
+    let mode = arrow_schema_mode(prescription);
+    let props = if mode == ArrowSchemaMode::Strip {
+        let key_value = props.key_value_metadata().map(|kv| {
+            kv.iter()
+                .filter(|e| e.key != ARROW_SCHEMA_META_KEY)
+                .cloned()
+                .collect()
+        });
+        props
+            .into_builder()
+            .set_key_value_metadata(key_value)
+            .build()
+    } else {
+        props
+    };
+    let keep = mode == ArrowSchemaMode::Preserve && !schema.changed;
+    RewriteWriter::try_new(
+        output,
+        schema.schema(),
+        props,
+        prescription,
+        keep || mode == ArrowSchemaMode::Strip,
+    )
+}
+
+/// An `ArrowWriter` that sorts rows as the prescription asks: the whole
+/// file with `set file sort_by`, each row group with `set file
+/// sorting_columns`, or both.
+pub(crate) struct RewriteWriter<W: Write + Send> {
+    writer: ArrowWriter<W>,
+    file_sort: Option<FileSort>,
+    row_group_sort: Option<RowGroupSort>,
+}
+
+impl<W: Write + Send> RewriteWriter<W> {
+    /// Either sort order is declared in the output's `sorting_columns`,
+    /// replacing any in `props`; `sorting_columns` wins if both are given.
+    pub(crate) fn try_new(
+        output: W,
+        schema: &SchemaRef,
+        props: WriterProperties,
+        prescription: &Prescription,
+        skip_arrow_metadata: bool,
+    ) -> Result<Self> {
+        let mut sort_by = None;
+        let mut sorting_columns = None;
+        for directive in prescription.directives() {
+            match directive {
+                Directive::SetFileSortBy(keys) => sort_by = Some(keys),
+                Directive::SetFileSortingColumns(keys) => sorting_columns = Some(keys),
+                _ => {}
+            }
+        }
+        let file_order = sort_by
+            .map(|keys| SortOrder::new(schema, keys))
+            .transpose()?;
+        let row_group_order = sorting_columns
+            .map(|keys| SortOrder::new(schema, keys))
+            .transpose()?;
+        let props = match row_group_order.as_ref().or(file_order.as_ref()) {
+            Some(order) => props
+                .into_builder()
+                .set_sorting_columns(Some(order.sorting_columns.clone()))
+                .build(),
+            None => props,
+        };
+        let max_rows = props.max_row_group_size();
+        let options = ArrowWriterOptions::new()
+            .with_properties(props)
+            .with_skip_arrow_metadata(skip_arrow_metadata);
+        let writer = ArrowWriter::try_new_with_options(output, schema.clone(), options)?;
+        Ok(Self {
+            writer,
+            file_sort: file_order.map(|order| FileSort::new(order, schema.clone())),
+            row_group_sort: row_group_order.map(|order| RowGroupSort::new(order, max_rows)),
+        })
+    }
+
+    pub(crate) fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match &mut self.file_sort {
+            Some(sort) => sort.push(batch.clone()),
+            None => Self::write_row_groups(&mut self.writer, &mut self.row_group_sort, batch),
+        }
+    }
+
+    fn write_row_groups(
+        writer: &mut ArrowWriter<W>,
+        sort: &mut Option<RowGroupSort>,
+        batch: &RecordBatch,
+    ) -> Result<()> {
+        let Some(sort) = sort else {
+            return Ok(writer.write(batch)?);
+        };
+        for row_group in sort.push(batch.clone())? {
+            writer.write(&row_group)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn close(mut self) -> Result<()> {
+        if let Some(sort) = self.file_sort.take() {
+            let (writer, row_group_sort) = (&mut self.writer, &mut self.row_group_sort);
+            sort.finish(&mut |batch| Self::write_row_groups(writer, row_group_sort, &batch))?;
+        }
+        if let Some(sort) = self.row_group_sort.take()
+            && let Some(rows) = sort.finish()?
+        {
+            self.writer.write(&rows)?;
+        }
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// The schema changes of `nullable` and `cast` directives, which writer
+/// properties cannot express, applied to each batch before it is written.
+/// Only top-level fields change.
+pub(crate) struct SchemaRewrite {
+    schema: SchemaRef,
+    /// Fields cast from binary to fixed-size binary, with their width.
+    fixed_len: Vec<(usize, i32)>,
+    changed: bool,
+}
+
+impl SchemaRewrite {
+    pub(crate) fn new(schema: &SchemaRef, prescription: &Prescription) -> Self {
+        let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+        let mut fixed_len = Vec::new();
+        let mut changed = false;
+        for directive in prescription.directives() {
+            let Some(column) = directive.column() else {
+                continue;
+            };
+            let [name] = column.parts() else {
+                continue;
+            };
+            let Some(index) = fields.iter().position(|f| f.name() == name) else {
+                continue;
+            };
+            let field = &mut fields[index];
+            match directive {
+                Directive::SetColumnNullable(_, nullable) if field.is_nullable() != *nullable => {
+                    field.set_nullable(*nullable);
+                }
+                Directive::SetColumnCast(_, CastType::FixedLenByteArray(length)) => {
+                    field.set_data_type(DataType::FixedSizeBinary(*length as i32));
+                    fixed_len.retain(|&(i, _)| i != index);
+                    fixed_len.push((index, *length as i32));
+                }
+                _ => continue,
+            }
+            changed = true;
+        }
+        let schema = if changed {
+            Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+        } else {
+            schema.clone()
+        };
+        Self {
+            schema,
+            fixed_len,
+            changed,
+        }
+    }
+
+    pub(crate) fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    pub(crate) fn apply(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        if !self.changed {
+            return Ok(batch);
+        }
+        let mut columns = batch.columns().to_vec();
+        for &(index, length) in &self.fixed_len {
+            let name = self.schema.field(index).name();
+            let array = columns[index].as_any();
+            let fixed = if let Some(binary) = array.downcast_ref::<BinaryArray>() {
+                FixedSizeBinaryArray::try_from_sparse_iter_with_size(binary.iter(), length)
+            } else if let Some(binary) = array.downcast_ref::<LargeBinaryArray>() {
+                FixedSizeBinaryArray::try_from_sparse_iter_with_size(binary.iter(), length)
+            } else {
+                bail!("cannot cast {name} to fixed_len_byte_array: it is not binary");
+            }
+            .with_context(|| format!("{name} has values that are not {length} bytes long"))?;
+            columns[index] = Arc::new(fixed);
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+/// Replace a file with its rewrite. Local files are written to a temporary
+/// sibling, synced and renamed over the original; remote objects are
+/// uploaded with a conditional put on the version opened, where the store
+/// supports it, so a concurrent writer is not silently overwritten. The
+/// upload is a single put, since stores take no condition on multipart
+/// uploads, so the rewritten object is held in memory while it is sent.
+pub struct InPlaceRewrite<'a> {
+    /// Path or URL the file was opened from.
+    pub location: &'a str,
+    /// Keep the original at its location plus this suffix (e.g. `.bak`).
+    pub backup_suffix: Option<&'a str>,
+}
+
+impl InPlaceRewrite<'_> {
+    pub async fn run(&self, file: &LoadedFile, prescription: &Prescription) -> Result<()> {
+        let url = crate::loader::parse_location(self.location)?;
+        if url.scheme() == "file" {
+            let target = url
+                .to_file_path()
+                .map_err(|_| anyhow::anyhow!("invalid file path: {}", self.location))?;
+            self.replace_local(file, &target, prescription).await
+        } else {
+            let (store, path) = crate::loader::parse(self.location)?;
+            self.replace_object(file, store.as_ref(), &path, prescription)
+                .await
+        }
+    }
+
+    async fn replace_local(
+        &self,
+        file: &LoadedFile,
+        target: &Path,
+        prescription: &Prescription,
+    ) -> Result<()> {
+        let tmp = with_suffix(target, ".rewrite.tmp");
+        let written = async {
+            rewrite_file(file, &tmp, prescription).await?;
+            File::open(&tmp)?
+                .sync_all()
+                .with_context(|| format!("failed to sync {}", tmp.display()))
+        };
+        if let Err(e) = written.await {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+        if let Some(suffix) = self.backup_suffix {
+            // Link rather than move, so the original never disappears.
+            let backup = with_suffix(target, suffix);
+            let _ = std::fs::remove_file(&backup);
+            if std::fs::hard_link(target, &backup).is_err() {
+                std::fs::copy(target, &backup)
+                    .with_context(|| format!("failed to back up to {}", backup.display()))?;
+            }
+        }
+        std::fs::rename(&tmp, target)
+            .with_context(|| format!("failed to replace {}", target.display()))?;
+        // Persist the rename; directories cannot be opened on every platform.
+        if let Some(dir) = target.parent()
+            && let Ok(dir) = File::open(dir)
+        {
+            let _ = dir.sync_all();
+        }
+        Ok(())
+    }
+
+    async fn replace_object(
+        &self,
+        file: &LoadedFile,
+        store: &dyn ObjectStore,
+        path: &ObjectPath,
+        prescription: &Prescription,
+    ) -> Result<()> {
+        // The version the footer was read from: a change after opening must
+        // fail the conditional put, not be overwritten with stale data.
+        let meta = match &file.object {
+            Some(meta) => meta.clone(),
+            None => store.head(path).await?,
+        };
+        let name = path.filename().unwrap_or("file.parquet");
+        let tmp =
+            std::env::temp_dir().join(format!("parquet-linter-{}-{name}", std::process::id()));
+        let rewritten = rewrite_file(file, &tmp, prescription).await.and_then(|()| {
+            std::fs::read(&tmp).with_context(|| format!("failed to read {}", tmp.display()))
+        });
+        let _ = std::fs::remove_file(&tmp);
+        let payload = object_store::PutPayload::from(rewritten?);
+
+        if let Some(suffix) = self.backup_suffix {
+            let backup = ObjectPath::from(format!("{path}{suffix}"));
+            store.copy(path, &backup).await?;
+        }
+        let mode = match (meta.e_tag.clone(), meta.version.clone()) {
+            (None, None) => PutMode::Overwrite,
+            (e_tag, version) => PutMode::Update(UpdateVersion { e_tag, version }),
+        };
+        match store.put_opts(path, payload.clone(), mode.into()).await {
+            Ok(_) => Ok(()),
+            Err(object_store::Error::NotImplemented) => {
+                tracing::warn!(
+                    "{path}: the store has no conditional puts; replacing it unconditionally"
+                );
+                // Narrow the race as far as the store allows.
+                let current = store.head(path).await?;
+                if (&current.e_tag, current.last_modified, current.size)
+                    != (&meta.e_tag, meta.last_modified, meta.size)
+                {
+                    bail!("{path} changed while it was being rewritten; not replaced");
+                }
+                store.put(path, payload).await?;
+                Ok(())
+            }
+            Err(object_store::Error::Precondition { .. }) => {
+                bail!("{path} changed while it was being rewritten; not replaced")
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Rewrite every Parquet file below `input_dir` into the same relative path
+/// below `output_dir`. Each file gets the common directives of
+/// `prescription` plus its matching file section, matched against the
+/// relative path; files left with no directives are copied unchanged.
+pub struct DirRewrite<'a> {
+    pub input_dir: &'a Path,
+    pub output_dir: &'a Path,
+    pub prescription: &'a DatasetPrescription,
+    /// Files rewritten at the same time.
+    pub jobs: usize,
+}
+
+pub struct DirFileReport {
+    /// Path below both the input and output directory.
+    pub relative: PathBuf,
+    /// Directives applied, or `Ok(0)` when the file was copied unchanged.
+    pub result: Result<usize>,
+}
+
+impl DirRewrite<'_> {
+    /// Per-file outcomes in path order. One failed file does not stop the
+    /// others; only an unreadable input tree is an error.
+    pub async fn run(&self) -> Result<Vec<DirFileReport>> {
+        let (input_dir, output_dir) = (self.input_dir.canonicalize()?, absolute(self.output_dir)?);
+        if input_dir == output_dir {
+            bail!("output directory must differ from input directory");
+        }
+        let mut files = Vec::new();
+        collect_parquet_files(self.input_dir, Path::new(""), &mut files)?;
+        // An output directory nested in the input holds earlier results.
+        files.retain(|file| !input_dir.join(file).starts_with(&output_dir));
+        files.sort();
+        let reports = futures::stream::iter(files)
+            .map(|relative| async move {
+                let result = self.rewrite_one(&relative).await;
+                DirFileReport { relative, result }
+            })
+            .buffered(self.jobs.max(1))
+            .collect()
+            .await;
+        Ok(reports)
+    }
+
+    async fn rewrite_one(&self, relative: &Path) -> Result<usize> {
+        let input = self.input_dir.join(relative);
+        let output = self.output_dir.join(relative);
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let key = relative.to_string_lossy().replace('\\', "/");
+        let prescription = self.prescription.for_file(&key);
+        if prescription.is_empty() {
+            std::fs::copy(&input, &output)
+                .with_context(|| format!("failed to copy {}", input.display()))?;
+            return Ok(0);
+        }
+        let (store, path) = crate::loader::parse(&input.to_string_lossy())?;
+        rewrite(store, path, &output, &prescription).await?;
+        Ok(prescription.directives().len())
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    Ok(match path.canonicalize() {
+        Ok(path) => path,
+        Err(_) => std::env::current_dir()?.join(path),
+    })
+}
+
+/// `*.parquet` files below `root`, relative to it and sorted, skipping
+/// entries named `.*` or `_*`.
+pub fn parquet_files_below(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    collect_parquet_files(root, Path::new(""), &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// `*.parquet` files below `dir`, relative to the walk root. Entries named
+/// `.*` or `_*` (Spark `_SUCCESS`, `_temporary`, Delta `_delta_log`) are
+/// skipped.
+pub(crate) fn collect_parquet_files(
+    root: &Path,
+    relative: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let dir = root.join(relative);
+    let entries =
+        std::fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name.starts_with('_') {
+            continue;
+        }
+        let path = relative.join(&*name);
+        if entry.file_type()?.is_dir() {
+            collect_parquet_files(root, &path, files)?;
+        } else if name.ends_with(".parquet") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,7 +780,7 @@ mod tests {
     use parquet::schema::types::ColumnPath;
     use std::sync::Arc;
 
-    use crate::prescription::{Codec, Directive, Prescription};
+    use crate::prescription::{Codec, Directive};
 
     fn write_two_column_file(path: &Path, props: WriterProperties) -> Result<()> {
         let schema = Arc::new(Schema::new(vec![
@@ -303,4 +841,398 @@ mod tests {
         assert_eq!(read_column_compression(&output, 1)?, Compression::SNAPPY);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn rewrite_marks_columns_required() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        write_two_column_file(&input, WriterProperties::default())?;
+        // Written from non-nullable fields, so make `a` optional first.
+        let optional = Prescription::parse("set column a nullable true")?;
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        rewrite(store, path, &output, &optional).await?;
+
+        let required = Prescription::parse("set column a nullable false")?;
+        let (store, path) = crate::loader::parse(output.to_str().unwrap())?;
+        rewrite(store, path, &input, &required).await?;
+
+        let file = std::fs::File::open(&input)?;
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)?;
+        assert!(!builder.schema().field(0).is_nullable());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rewrite_casts_binary_to_fixed_len() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        let batch = RecordBatch::try_from_iter([(
+            "h",
+            Arc::new(BinaryArray::from_iter_values([[1u8; 4], [2; 4]])) as _,
+        )])?;
+        let mut writer = ArrowWriter::try_new(File::create(&input)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let prescription = Prescription::parse("set column h cast fixed_len_byte_array(4)")?;
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        rewrite(store, path, &output, &prescription).await?;
+
+        let file = File::open(&output)?;
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let column = builder.metadata().file_metadata().schema_descr().column(0);
+        assert_eq!(
+            column.physical_type(),
+            parquet::basic::Type::FIXED_LEN_BYTE_ARRAY
+        );
+        assert_eq!(column.type_length(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn applied_fixes_silence_their_rules() -> Result<()> {
+        use arrow_array::{Int64Array, StringArray};
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let ids: Vec<i64> = (0..200_000).collect();
+        let names: Vec<String> = ids.iter().map(|i| format!("user-{}", i % 7)).collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_dictionary_enabled(false)
+            .build();
+        let mut writer = ArrowWriter::try_new(File::create(&input)?, schema, Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let options = LintOptions::default();
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        let file = LoadedFile::open(store, path).await?;
+        let before = crate::lint_file(&file, None, &options).await?;
+        let plan = crate::plan::FixPlan::new(&before, &file.metadata);
+        assert!(!plan.is_empty());
+        rewrite_file(&file, &output, &plan.prescription()).await?;
+
+        let (store, path) = crate::loader::parse(output.to_str().unwrap())?;
+        let rewritten = LoadedFile::open(store, path).await?;
+        let remaining = unsilenced_diagnostics(&before, &rewritten, &options).await?;
+        let remaining: Vec<String> = remaining.iter().map(|d| d.to_string()).collect();
+        assert!(remaining.is_empty(), "{remaining:#?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rewrite_keeps_arrow_schema_bytes() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        let field = Field::new("a", DataType::LargeUtf8, true)
+            .with_metadata([("origin".to_string(), "upstream".to_string())].into());
+        let schema = Arc::new(Schema::new_with_metadata(
+            vec![field],
+            [("pandas".to_string(), "{}".to_string())].into(),
+        ));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(arrow_array::LargeStringArray::from(vec!["x"]))],
+        )?;
+        let mut writer = ArrowWriter::try_new(File::create(&input)?, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let key_value = |path: &Path| -> Result<Vec<(String, Option<String>)>> {
+            let file = File::open(path)?;
+            let builder =
+                parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)?;
+            let kv = builder.metadata().file_metadata().key_value_metadata();
+            Ok(kv
+                .into_iter()
+                .flatten()
+                .map(|e| (e.key.clone(), e.value.clone()))
+                .collect())
+        };
+        for (text, expect_arrow_schema) in [
+            ("set file compression zstd(3)", true),
+            ("set file arrow_schema strip", false),
+        ] {
+            let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+            rewrite(store, path, &output, &Prescription::parse(text)?).await?;
+            let mut expected = key_value(&input)?;
+            if !expect_arrow_schema {
+                expected.retain(|(key, _)| key != ARROW_SCHEMA_META_KEY);
+            }
+            assert_eq!(key_value(&output)?, expected, "{text}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_batch_size_bounds_page_rows() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        let batch = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(Int32Array::from_iter_values(0..1000)) as _,
+        )])?;
+        let mut writer = ArrowWriter::try_new(File::create(&input)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        // Row count limits are checked between write batches only.
+        let prescription = Prescription::parse(
+            "set file data_page_row_count_limit 100\n\
+             set file write_batch_size 100\n\
+             set file read_batch_size 250",
+        )?;
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        rewrite(store, path, &output, &prescription).await?;
+
+        let options = parquet::arrow::arrow_reader::ArrowReaderOptions::new().with_page_index(true);
+        let builder =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new_with_options(
+                File::open(&output)?,
+                options,
+            )?;
+        let pages = builder.metadata().offset_index().unwrap()[0][0]
+            .page_locations()
+            .len();
+        // With the default batch of 1024 rows, all 1000 rows land in one page.
+        assert!(pages > 1, "{pages} pages");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sorting_columns_sort_each_row_group() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        let batch = RecordBatch::try_from_iter([
+            (
+                "a",
+                Arc::new(Int32Array::from_iter_values(
+                    (0..1000).map(|i| i * 7 % 1000),
+                )) as _,
+            ),
+            (
+                "b",
+                Arc::new(Int32Array::from_iter_values((0..1000).map(|i| i % 2))) as _,
+            ),
+        ])?;
+        let mut writer = ArrowWriter::try_new(File::create(&input)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let prescription = Prescription::parse(
+            "set file max_row_group_size 300
+             set file read_batch_size 128
+             set file sorting_columns b:desc,a:asc",
+        )?;
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        rewrite(store, path, &output, &prescription).await?;
+
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            File::open(&output)?,
+        )?;
+        let metadata = builder.metadata().clone();
+        let sizes: Vec<i64> = metadata
+            .row_groups()
+            .iter()
+            .map(|rg| rg.num_rows())
+            .collect();
+        assert_eq!(sizes, [300, 300, 300, 100]);
+        for rg in metadata.row_groups() {
+            assert_eq!(
+                rg.sorting_columns().unwrap(),
+                &[
+                    SortingColumn {
+                        column_idx: 1,
+                        descending: true,
+                        nulls_first: true,
+                    },
+                    SortingColumn {
+                        column_idx: 0,
+                        descending: false,
+                        nulls_first: true,
+                    },
+                ]
+            );
+        }
+        let reader = builder.with_batch_size(300).build()?;
+        for batch in reader {
+            let batch = batch?;
+            let column = |i: usize| {
+                batch
+                    .column(i)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            };
+            let rows: Vec<(i32, i32)> = column(1).into_iter().zip(column(0)).collect();
+            assert!(rows.is_sorted_by(|x, y| x.0 > y.0 || (x.0 == y.0 && x.1 <= y.1)));
+        }
+
+        let missing = Prescription::parse("set file sorting_columns c")?;
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        let err = rewrite(store, path, &output, &missing).await.unwrap_err();
+        assert_eq!(err.to_string(), "cannot sort by c: no such column");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sort_by_sorts_the_whole_file() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        let output = tempdir.path().join("output.parquet");
+        let batch = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(Int32Array::from_iter_values(
+                (0..1000).map(|i| i * 7 % 1000),
+            )) as _,
+        )])?;
+        let mut writer = ArrowWriter::try_new(File::create(&input)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let prescription =
+            Prescription::parse("set file max_row_group_size 300; set file sort_by a:desc")?;
+        let (store, path) = crate::loader::parse(input.to_str().unwrap())?;
+        rewrite(store, path, &output, &prescription).await?;
+
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            File::open(&output)?,
+        )?;
+        let declared = builder.metadata().row_group(3).sorting_columns().cloned();
+        assert_eq!(
+            declared,
+            Some(vec![SortingColumn {
+                column_idx: 0,
+                descending: true,
+                nulls_first: true,
+            }])
+        );
+        let mut values = Vec::new();
+        for batch in builder.build()? {
+            let batch = batch?;
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            values.extend_from_slice(column.values());
+        }
+        assert_eq!(values, (0..1000).rev().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn in_place_rewrite_replaces_file_and_keeps_backup() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        write_two_column_file(&input, WriterProperties::default())?;
+        let location = input.to_str().unwrap();
+        let prescription = Prescription::parse("set column a compression gzip(6)")?;
+
+        let (store, path) = crate::loader::parse(location)?;
+        let file = LoadedFile::open(store, path).await?;
+        InPlaceRewrite {
+            location,
+            backup_suffix: Some(".bak"),
+        }
+        .run(&file, &prescription)
+        .await?;
+
+        assert_eq!(
+            read_column_compression(&input, 0)?,
+            Compression::GZIP(GzipLevel::default())
+        );
+        let backup = tempdir.path().join("input.parquet.bak");
+        assert_eq!(
+            read_column_compression(&backup, 0)?,
+            Compression::UNCOMPRESSED
+        );
+        assert!(!tempdir.path().join("input.parquet.rewrite.tmp").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn in_place_rewrite_refuses_objects_changed_after_open() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let input = tempdir.path().join("input.parquet");
+        write_two_column_file(&input, WriterProperties::default())?;
+        let store = Arc::new(object_store::memory::InMemory::new());
+        let path = ObjectPath::from("t.parquet");
+        let original = bytes::Bytes::from(std::fs::read(&input)?);
+        store.put(&path, original.clone().into()).await?;
+        let file = LoadedFile::open(store.clone(), path.clone()).await?;
+
+        // Same bytes, new version: another writer replaced the object.
+        store.put(&path, original.clone().into()).await?;
+        let prescription = Prescription::parse("set column a compression gzip(6)")?;
+        let rewrite = InPlaceRewrite {
+            location: "memory:///t.parquet",
+            backup_suffix: None,
+        };
+        let err = rewrite
+            .replace_object(&file, store.as_ref(), &path, &prescription)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("changed while it was being rewritten")
+        );
+        assert_eq!(store.get(&path).await?.bytes().await?, original);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dir_rewrite_keeps_relative_paths_and_scopes_sections() -> Result<()> {
+        let input = tempfile::tempdir()?;
+        let output = tempfile::tempdir()?;
+        std::fs::create_dir_all(input.path().join("logs/2024"))?;
+        std::fs::create_dir_all(input.path().join("_temporary"))?;
+        for file in ["logs/2024/a.parquet", "b.parquet", "_temporary/c.parquet"] {
+            write_two_column_file(&input.path().join(file), WriterProperties::default())?;
+        }
+        let prescription =
+            DatasetPrescription::parse("file \"logs/*\":\n    set column a compression gzip(6)")?;
+
+        let reports = DirRewrite {
+            input_dir: input.path(),
+            output_dir: output.path(),
+            prescription: &prescription,
+            jobs: 2,
+        }
+        .run()
+        .await?;
+
+        let outcomes: Vec<(String, usize)> = reports
+            .into_iter()
+            .map(|r| (r.relative.display().to_string(), r.result.unwrap()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [("b.parquet".into(), 0), ("logs/2024/a.parquet".into(), 1)]
+        );
+        assert_eq!(
+            read_column_compression(&output.path().join("logs/2024/a.parquet"), 0)?,
+            Compression::GZIP(GzipLevel::default())
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,506 @@
+//! Iceberg table linting: read the current snapshot's manifests, lint every
+//! live Parquet data file, and check table-level issues such as files
+//! written before the current sort order or partition spec.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use futures::{StreamExt, TryStreamExt};
+use object_store::ObjectStore;
+use object_store::path::Path as ObjectPath;
+use serde_json::Value;
+
+use crate::avro;
+use crate::dataset::{self, ConstantColumns};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::loader::{self, LoadedFile};
+use crate::options::LintOptions;
+use crate::prescription::Prescription;
+
+/// Data files linted at the same time.
+const LINT_CONCURRENCY: usize = 8;
+/// Manifest entry status for files removed in the snapshot.
+const STATUS_DELETED: i64 = 2;
+/// Example paths listed in a table-level diagnostic.
+const MAX_EXAMPLES: usize = 3;
+
+/// A live data file of the current snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataFile {
+    /// Location as written in the manifest.
+    pub uri: String,
+    pub path: ObjectPath,
+    pub spec_id: i64,
+    /// Partition values as `name=value` pairs joined by `/`, or empty.
+    pub partition: String,
+    pub record_count: i64,
+    pub sort_order_id: Option<i64>,
+}
+
+pub struct IcebergTable {
+    pub store: Arc<dyn ObjectStore>,
+    pub metadata_location: ObjectPath,
+    pub default_spec_id: i64,
+    pub default_sort_order_id: i64,
+    /// True when the default sort order has at least one field.
+    pub sorted: bool,
+    pub data_files: Vec<DataFile>,
+}
+
+impl IcebergTable {
+    /// Load a table from its root location (resolving the latest metadata
+    /// file) or from a `*.metadata.json` location.
+    pub async fn load(location: &str) -> Result<Self> {
+        let (store, path) = loader::parse(location)?;
+        let metadata_location = if path.as_ref().ends_with(".metadata.json") {
+            path
+        } else {
+            latest_metadata(store.as_ref(), &path).await?
+        };
+        let metadata: Value =
+            serde_json::from_slice(&store.get(&metadata_location).await?.bytes().await?)
+                .with_context(|| format!("invalid Iceberg metadata: {metadata_location}"))?;
+
+        let default_spec_id = metadata["default-spec-id"].as_i64().unwrap_or(0);
+        let default_sort_order_id = metadata["default-sort-order-id"].as_i64().unwrap_or(0);
+        let sorted = metadata["sort-orders"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|order| order["order-id"].as_i64() == Some(default_sort_order_id))
+            .and_then(|order| order["fields"].as_array())
+            .is_some_and(|fields| !fields.is_empty());
+
+        let mut table = Self {
+            store,
+            metadata_location,
+            default_spec_id,
+            default_sort_order_id,
+            sorted,
+            data_files: Vec::new(),
+        };
+        let Some(snapshot) = current_snapshot(&metadata) else {
+            return Ok(table);
+        };
+        for (manifest, spec_id) in table.manifests(snapshot).await? {
+            let spec_id = spec_id.unwrap_or(default_spec_id);
+            table.read_manifest(&manifest, spec_id).await?;
+        }
+        Ok(table)
+    }
+
+    /// Manifest locations of a snapshot, with their partition spec if known.
+    async fn manifests(&self, snapshot: &Value) -> Result<Vec<(String, Option<i64>)>> {
+        if let Some(list) = snapshot["manifest-list"].as_str() {
+            let entries = avro::read_container(&self.read(list).await?)?;
+            return Ok(entries
+                .iter()
+                // Content 1 lists delete files, which are not Parquet data.
+                .filter(|m| m["content"].as_i64().unwrap_or(0) == 0)
+                .filter_map(|m| {
+                    let path = m["manifest_path"].as_str()?.to_string();
+                    Some((path, m["partition_spec_id"].as_i64()))
+                })
+                .collect());
+        }
+        // Format v1 may inline the manifest paths instead.
+        Ok(snapshot["manifests"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| Some((m.as_str()?.to_string(), None)))
+            .collect())
+    }
+
+    async fn read_manifest(&mut self, manifest: &str, spec_id: i64) -> Result<()> {
+        for entry in avro::read_container(&self.read(manifest).await?)? {
+            let file = &entry["data_file"];
+            if entry["status"].as_i64() == Some(STATUS_DELETED)
+                || file["content"].as_i64().unwrap_or(0) != 0
+            {
+                continue;
+            }
+            if !file["file_format"]
+                .as_str()
+                .is_some_and(|f| f.eq_ignore_ascii_case("parquet"))
+            {
+                continue;
+            }
+            let uri = file["file_path"]
+                .as_str()
+                .context("manifest entry is missing file_path")?
+                .to_string();
+            self.data_files.push(DataFile {
+                path: object_path(&uri)?,
+                uri,
+                spec_id,
+                partition: partition_label(&file["partition"]),
+                record_count: file["record_count"].as_i64().unwrap_or(0),
+                sort_order_id: file["sort_order_id"].as_i64(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn read(&self, uri: &str) -> Result<bytes::Bytes> {
+        let path = object_path(uri)?;
+        Ok(self.store.get(&path).await?.bytes().await?)
+    }
+
+    /// Table-level issues derived from the manifests alone.
+    pub fn check(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if self.sorted {
+            let stale: Vec<&DataFile> = self
+                .data_files
+                .iter()
+                .filter(|f| f.sort_order_id != Some(self.default_sort_order_id))
+                .collect();
+            if !stale.is_empty() {
+                diagnostics.push(table_diagnostic(
+                    "iceberg-stale-sort-order",
+                    Severity::Warning,
+                    format!(
+                        "{}/{} data files were not written with the current sort order {}, \
+                         so sort-based pruning skips little; rewrite them with a sort strategy",
+                        stale.len(),
+                        self.data_files.len(),
+                        self.default_sort_order_id,
+                    ),
+                    &stale,
+                ));
+            }
+        }
+        let old_spec: Vec<&DataFile> = self
+            .data_files
+            .iter()
+            .filter(|f| f.spec_id != self.default_spec_id)
+            .collect();
+        if !old_spec.is_empty() {
+            diagnostics.push(table_diagnostic(
+                "iceberg-stale-partition-spec",
+                Severity::Suggestion,
+                format!(
+                    "{}/{} data files use an older partition spec than the default spec {}; \
+                     rewriting them lets queries prune on the current partitioning",
+                    old_spec.len(),
+                    self.data_files.len(),
+                    self.default_spec_id,
+                ),
+                &old_spec,
+            ));
+        }
+        diagnostics
+    }
+}
+
+/// Diagnostics for one data file.
+pub struct FileReport {
+    pub file: DataFile,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+pub struct IcebergReport {
+    pub files: Vec<FileReport>,
+    /// Issues that concern the table rather than a single file.
+    pub table_diagnostics: Vec<Diagnostic>,
+}
+
+/// Lint results rolled up over one partition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionSummary {
+    pub spec_id: i64,
+    pub partition: String,
+    pub files: usize,
+    pub suggestions: usize,
+    pub warnings: usize,
+    pub errors: usize,
+    /// Diagnostic count per rule, most frequent first.
+    pub rules: Vec<(&'static str, usize)>,
+}
+
+impl IcebergReport {
+    /// Roll file diagnostics up per partition, ordered by spec and partition.
+    pub fn partitions(&self) -> Vec<PartitionSummary> {
+        let mut groups: BTreeMap<(i64, &str), Vec<&FileReport>> = BTreeMap::new();
+        for report in &self.files {
+            let key = (report.file.spec_id, report.file.partition.as_str());
+            groups.entry(key).or_default().push(report);
+        }
+        groups
+            .into_iter()
+            .map(|((spec_id, partition), reports)| {
+                let diagnostics = reports.iter().flat_map(|r| &r.diagnostics);
+                let count = |severity| {
+                    diagnostics
+                        .clone()
+                        .filter(|d| d.severity == severity)
+                        .count()
+                };
+                let mut rules: BTreeMap<&'static str, usize> = BTreeMap::new();
+                for d in diagnostics.clone() {
+                    *rules.entry(d.rule_name).or_default() += 1;
+                }
+                let mut rules: Vec<(&'static str, usize)> = rules.into_iter().collect();
+                rules.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+                PartitionSummary {
+                    spec_id,
+                    partition: partition.to_string(),
+                    files: reports.len(),
+                    suggestions: count(Severity::Suggestion),
+                    warnings: count(Severity::Warning),
+                    errors: count(Severity::Error),
+                    rules,
+                }
+            })
+            .collect()
+    }
+
+    pub fn all_diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.table_diagnostics
+            .iter()
+            .chain(self.files.iter().flat_map(|f| &f.diagnostics))
+    }
+}
+
+/// Lint every data file of `table` and run the table-level checks.
+pub async fn lint_table(
+    table: &IcebergTable,
+    rule_names: Option<&[String]>,
+    options: &LintOptions,
+) -> Result<IcebergReport> {
+    let linter = &crate::linter(rule_names, options);
+    let linted: Vec<(FileReport, ConstantColumns)> =
+        futures::stream::iter(table.data_files.iter().cloned())
+            .map(|file| async move {
+                let loaded = LoadedFile::open(table.store.clone(), file.path.clone())
+                    .await
+                    .with_context(|| format!("failed to open {}", file.uri))?;
+                let constants = dataset::constant_columns(&loaded.metadata);
+                let diagnostics = linter.lint_file(&loaded).await?;
+                anyhow::Ok((FileReport { file, diagnostics }, constants))
+            })
+            .buffered(LINT_CONCURRENCY)
+            .try_collect()
+            .await?;
+    let (files, constants): (Vec<_>, Vec<_>) = linted.into_iter().unzip();
+    let mut table_diagnostics = table.check();
+    table_diagnostics.extend(dataset::check(&constants));
+    Ok(IcebergReport {
+        files,
+        table_diagnostics,
+    })
+}
+
+fn table_diagnostic(
+    rule_name: &'static str,
+    severity: Severity,
+    message: String,
+    examples: &[&DataFile],
+) -> Diagnostic {
+    let mut message = message;
+    let shown: Vec<&str> = examples
+        .iter()
+        .take(MAX_EXAMPLES)
+        .map(|f| f.uri.as_str())
+        .collect();
+    message.push_str(&format!(" (e.g. {})", shown.join(", ")));
+    Diagnostic {
+        rule_name,
+        severity,
+        category: Category::Performance,
+        location: Location::File,
+        message,
+        prescription: Prescription::new(),
+        related: Vec::new(),
+    }
+}
+
+/// Pick the newest metadata file under `<root>/metadata`, preferring
+/// `version-hint.text` when the table has one.
+async fn latest_metadata(store: &dyn ObjectStore, root: &ObjectPath) -> Result<ObjectPath> {
+    let dir = root.child("metadata");
+    if let Ok(hint) = store.get(&dir.child("version-hint.text")).await {
+        let version = String::from_utf8(hint.bytes().await?.to_vec())?;
+        return Ok(dir.child(format!("v{}.metadata.json", version.trim())));
+    }
+    let files: Vec<ObjectPath> = store
+        .list(Some(&dir))
+        .map_ok(|meta| meta.location)
+        .try_collect()
+        .await?;
+    files
+        .into_iter()
+        .filter_map(|path| Some((metadata_version(path.filename()?)?, path)))
+        .max_by_key(|(version, _)| *version)
+        .map(|(_, path)| path)
+        .with_context(|| format!("no Iceberg metadata found under {dir}"))
+}
+
+/// Version of `v3.metadata.json` or `00003-<uuid>.metadata.json`.
+fn metadata_version(filename: &str) -> Option<u64> {
+    let stem = filename.strip_suffix(".metadata.json")?;
+    let stem = stem.strip_prefix('v').unwrap_or(stem);
+    stem.split('-').next()?.parse().ok()
+}
+
+fn current_snapshot(metadata: &Value) -> Option<&Value> {
+    let id = metadata["current-snapshot-id"].as_i64()?;
+    metadata["snapshots"]
+        .as_array()?
+        .iter()
+        .find(|s| s["snapshot-id"].as_i64() == Some(id))
+}
+
+/// Object path for a manifest URI (`s3://bucket/key`, `file:/tmp/x`, or a
+/// bare absolute path), relative to the table's store.
+fn object_path(uri: &str) -> Result<ObjectPath> {
+    match url::Url::parse(uri) {
+        Ok(url) => Ok(ObjectPath::from_url_path(url.path())?),
+        Err(url::ParseError::RelativeUrlWithoutBase) => Ok(ObjectPath::from(uri)),
+        Err(e) => bail!("invalid data file location {uri}: {e}"),
+    }
+}
+
+fn partition_label(partition: &Value) -> String {
+    let Some(fields) = partition.as_object() else {
+        return String::new();
+    };
+    fields
+        .iter()
+        .map(|(name, value)| match value {
+            Value::String(s) => format!("{name}={s}"),
+            other => format!("{name}={other}"),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avro::writer::write_container;
+    use arrow_array::{Int64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use serde_json::json;
+
+    #[test]
+    fn metadata_versions_parse_both_naming_schemes() {
+        assert_eq!(metadata_version("v12.metadata.json"), Some(12));
+        assert_eq!(metadata_version("00003-1a2b.metadata.json"), Some(3));
+        assert_eq!(metadata_version("snap-1.avro"), None);
+    }
+
+    #[test]
+    fn object_paths_strip_scheme_and_bucket() -> Result<()> {
+        assert_eq!(
+            object_path("s3://bucket/t/a.parquet")?.as_ref(),
+            "t/a.parquet"
+        );
+        assert_eq!(
+            object_path("file:/tmp/t/a.parquet")?.as_ref(),
+            "tmp/t/a.parquet"
+        );
+        assert_eq!(object_path("/tmp/t/a.parquet")?.as_ref(), "tmp/t/a.parquet");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lints_live_files_and_flags_stale_sort_order() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        std::fs::create_dir_all(root.join("metadata"))?;
+        std::fs::create_dir_all(root.join("data"))?;
+        let uri = |name: &str| format!("file:{}", root.join(name).display());
+
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        for name in ["data/a.parquet", "data/b.parquet"] {
+            let file = std::fs::File::create(root.join(name))?;
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+
+        let entry_schema = json!({
+            "type": "record", "name": "manifest_entry",
+            "fields": [
+                {"name": "status", "type": "int"},
+                {"name": "data_file", "type": {
+                    "type": "record", "name": "r2",
+                    "fields": [
+                        {"name": "file_path", "type": "string"},
+                        {"name": "file_format", "type": "string"},
+                        {"name": "partition", "type": {"type": "record", "name": "r102", "fields": [
+                            {"name": "region", "type": ["null", "string"]}
+                        ]}},
+                        {"name": "record_count", "type": "long"},
+                        {"name": "sort_order_id", "type": ["null", "int"]},
+                    ]
+                }},
+            ]
+        });
+        let entry = |status, path: &str, sort_order| {
+            json!({"status": status, "data_file": {
+                "file_path": uri(path), "file_format": "PARQUET",
+                "partition": {"region": "eu"}, "record_count": 100,
+                "sort_order_id": sort_order,
+            }})
+        };
+        let manifest = write_container(
+            &entry_schema,
+            &[
+                entry(1, "data/a.parquet", json!(1)),
+                entry(0, "data/b.parquet", json!(null)),
+                entry(2, "data/gone.parquet", json!(1)),
+            ],
+        );
+        std::fs::write(root.join("metadata/m0.avro"), manifest)?;
+
+        let list_schema = json!({
+            "type": "record", "name": "manifest_file",
+            "fields": [
+                {"name": "manifest_path", "type": "string"},
+                {"name": "partition_spec_id", "type": "int"},
+            ]
+        });
+        let list = write_container(
+            &list_schema,
+            &[json!({"manifest_path": uri("metadata/m0.avro"), "partition_spec_id": 0})],
+        );
+        std::fs::write(root.join("metadata/snap-1.avro"), list)?;
+
+        let metadata = json!({
+            "format-version": 2,
+            "current-snapshot-id": 1,
+            "snapshots": [{"snapshot-id": 1, "manifest-list": uri("metadata/snap-1.avro")}],
+            "default-spec-id": 0,
+            "default-sort-order-id": 1,
+            "sort-orders": [
+                {"order-id": 0, "fields": []},
+                {"order-id": 1, "fields": [{"source-id": 1}]},
+            ],
+        });
+        std::fs::write(root.join("metadata/v1.metadata.json"), metadata.to_string())?;
+        std::fs::write(root.join("metadata/version-hint.text"), "1")?;
+
+        let table = IcebergTable::load(root.to_str().unwrap()).await?;
+        assert_eq!(table.data_files.len(), 2);
+        assert_eq!(table.data_files[0].partition, "region=eu");
+
+        let report = lint_table(&table, None, &LintOptions::default()).await?;
+        assert_eq!(report.partitions().len(), 1);
+        assert_eq!(report.partitions()[0].files, 2);
+        let rules: Vec<&str> = report
+            .table_diagnostics
+            .iter()
+            .map(|d| d.rule_name)
+            .collect();
+        assert_eq!(rules, ["iceberg-stale-sort-order"]);
+        assert!(report.table_diagnostics[0].message.starts_with("1/2"));
+        Ok(())
+    }
+}
@@ -1,48 +1,94 @@
+mod avro;
+pub mod cache;
 pub mod cardinality;
 pub mod column_context;
+pub mod compaction;
+pub mod compare;
+pub mod config;
+pub mod cost;
+pub mod dataset;
+pub mod delta;
 pub mod diagnostic;
+pub mod dump;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod fix;
+pub mod iceberg;
+pub mod linter;
 pub mod loader;
+pub mod metrics;
+pub mod options;
+pub mod page_cache;
+pub mod plan;
 pub mod prescription;
+pub mod probe;
 pub mod rule;
 pub mod rules;
+pub mod sarif;
+mod sort;
+pub mod summary;
+pub mod target;
+pub mod testing;
+pub mod thresholds;
+pub mod tune;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 use std::sync::Arc;
 
+pub use linter::{Linter, LinterBuilder, Progress};
+
 use diagnostic::{Diagnostic, Severity};
+use loader::LoadedFile;
+use metrics::LintMetrics;
 use object_store::ObjectStore;
 use object_store::path::Path as ObjectPath;
-use parquet::arrow::async_reader::ParquetObjectReader;
-use rule::RuleContext;
+use options::LintOptions;
 
 pub async fn lint(
     store: Arc<dyn ObjectStore>,
     path: ObjectPath,
     rule_names: Option<&[String]>,
 ) -> anyhow::Result<Vec<Diagnostic>> {
-    let reader = ParquetObjectReader::new(store, path);
-    lint_reader(reader, rule_names).await
+    lint_with_options(store, path, rule_names, &LintOptions::default()).await
+}
+
+pub async fn lint_with_options(
+    store: Arc<dyn ObjectStore>,
+    path: ObjectPath,
+    rule_names: Option<&[String]>,
+    options: &LintOptions,
+) -> anyhow::Result<Vec<Diagnostic>> {
+    let file = LoadedFile::open(store, path).await?;
+    lint_file(&file, rule_names, options).await
 }
 
-async fn lint_reader(
-    reader: ParquetObjectReader,
+/// Lint an already-opened file without re-fetching its footer.
+pub async fn lint_file(
+    file: &LoadedFile,
     rule_names: Option<&[String]>,
+    options: &LintOptions,
 ) -> anyhow::Result<Vec<Diagnostic>> {
-    use parquet::arrow::async_reader::AsyncFileReader;
-    let metadata = reader.clone().get_metadata(None).await?;
-    let columns = column_context::build(&reader, &metadata).await?;
-    let ctx = RuleContext {
-        metadata,
-        columns,
-        reader,
-    };
-    let rules = rules::get_rules(rule_names);
-    let mut diagnostics: Vec<Diagnostic> = Vec::new();
-    for r in &rules {
-        diagnostics.extend(r.check(&ctx).await);
+    linter(rule_names, options).lint_file(file).await
+}
+
+/// Like `lint_file`, also reporting time and IO per phase and per rule.
+pub async fn lint_file_with_metrics(
+    file: &LoadedFile,
+    rule_names: Option<&[String]>,
+    options: &LintOptions,
+) -> anyhow::Result<(Vec<Diagnostic>, LintMetrics)> {
+    linter(rule_names, options)
+        .lint_file_with_metrics(file)
+        .await
+}
+
+pub(crate) fn linter(rule_names: Option<&[String]>, options: &LintOptions) -> Linter {
+    let mut builder = Linter::builder().options(options.clone());
+    if let Some(names) = rule_names {
+        builder = builder.rules(names.iter().cloned());
     }
-    diagnostics.sort_by_key(|d| d.severity);
-    Ok(diagnostics)
+    builder.build()
 }
 
 pub fn has_warnings_or_errors(diagnostics: &[Diagnostic]) -> bool {
@@ -50,3 +96,22 @@ pub fn has_warnings_or_errors(diagnostics: &[Diagnostic]) -> bool {
         .iter()
         .any(|d| matches!(d.severity, Severity::Warning | Severity::Error))
 }
+
+/// Exit code of a run that could not finish: bad arguments, an unreadable
+/// file or a failed request.
+pub const EXIT_INTERNAL_ERROR: i32 = 10;
+
+/// Exit code summarizing `diagnostics` by the most severe one, so scripts
+/// can branch without parsing output: 0 when there are none, 1 for
+/// suggestions only, 2 for warnings and 3 for errors.
+pub fn exit_code<'a>(diagnostics: impl IntoIterator<Item = &'a Diagnostic>) -> i32 {
+    diagnostics
+        .into_iter()
+        .map(|d| match d.severity {
+            Severity::Suggestion => 1,
+            Severity::Warning => 2,
+            Severity::Error => 3,
+        })
+        .max()
+        .unwrap_or(0)
+}
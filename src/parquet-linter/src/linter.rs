@@ -0,0 +1,844 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use futures::{Stream, StreamExt};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectMeta, ObjectStore};
+use tokio_util::sync::CancellationToken;
+
+use crate::cache::{self, ResultCache};
+use crate::column_context::LazyColumns;
+use crate::diagnostic::{Diagnostic, Location};
+use crate::fix;
+use crate::loader::{self, LoadedFile};
+use crate::metrics::LintMetrics;
+use crate::options::{LintOptions, Workload};
+use crate::prescription::Prescription;
+use crate::rule::{Blackboard, Rule, RuleContext};
+use crate::rules;
+
+/// Reported after each rule finishes on a file.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress<'a> {
+    pub path: &'a ObjectPath,
+    pub rule: &'static str,
+    /// Rules finished on this file so far, including `rule`.
+    pub done: usize,
+    pub total: usize,
+}
+
+type ProgressFn = dyn Fn(Progress<'_>) + Send + Sync;
+
+/// A configured linter. Build once and reuse it across files: the rule set
+/// is instantiated once and object stores are shared between files in the
+/// same bucket or host.
+///
+/// ```no_run
+/// # async fn example() -> anyhow::Result<()> {
+/// let linter = parquet_linter::Linter::builder()
+///     .rules(["missing-page-statistics", "compression-codec-upgrade"])
+///     .build();
+/// for location in ["s3://bucket/a.parquet", "s3://bucket/b.parquet"] {
+///     let diagnostics = linter.lint(location).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+    /// The selection and custom rules `rules` was built from, part of the
+    /// cache key.
+    rule_names: Option<Vec<String>>,
+    options: LintOptions,
+    progress: Option<Arc<ProgressFn>>,
+    cache: Option<ResultCache>,
+    cancel: CancellationToken,
+    /// Stores by scheme and authority (`s3://bucket`, `file://`).
+    stores: Mutex<HashMap<String, Arc<dyn ObjectStore>>>,
+}
+
+#[derive(Default)]
+pub struct LinterBuilder {
+    rules: Option<Vec<String>>,
+    custom_rules: Vec<Box<dyn Rule>>,
+    removed_rules: Vec<String>,
+    options: LintOptions,
+    progress: Option<Arc<ProgressFn>>,
+    cache: Option<ResultCache>,
+    cancel: CancellationToken,
+}
+
+impl LinterBuilder {
+    /// Only run the named rules (default: all).
+    pub fn rules<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.rules = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Register a rule of your own next to the built-in ones. It replaces
+    /// the built-in rule of the same name, if any, and like those it is
+    /// subject to `rules` and `without_rule`.
+    pub fn with_rule(mut self, rule: Box<dyn Rule>) -> Self {
+        self.custom_rules.retain(|r| r.name() != rule.name());
+        self.custom_rules.push(rule);
+        self
+    }
+
+    /// Never run the named rule, built-in or registered with `with_rule`.
+    pub fn without_rule(mut self, name: impl Into<String>) -> Self {
+        self.removed_rules.push(name.into());
+        self
+    }
+
+    pub fn options(mut self, options: LintOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Replace the workload hints of the current options.
+    pub fn workload(mut self, workload: Workload) -> Self {
+        self.options.workload = workload;
+        self
+    }
+
+    /// Only lint the named columns (see `LintOptions::columns`).
+    pub fn columns<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.options.columns = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn progress(mut self, f: impl Fn(Progress<'_>) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(f));
+        self
+    }
+
+    /// Reuse results of earlier runs on unchanged objects in `Linter::lint`.
+    pub fn cache(mut self, cache: ResultCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Abort lints and rewrites once `token` is cancelled. In-flight reads
+    /// are dropped at their next await point; dropping a lint future is
+    /// equally safe.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    pub fn build(self) -> Linter {
+        let custom: Vec<&'static str> = self.custom_rules.iter().map(|r| r.name()).collect();
+        let mut all = rules::all_rules();
+        all.retain(|r| !custom.contains(&r.name()));
+        all.extend(self.custom_rules);
+        all.retain(|r| !self.removed_rules.iter().any(|name| name == r.name()));
+        let rules = rules::select_rules(all, self.rules.as_deref());
+
+        let mut rule_names = self.rules;
+        if !custom.is_empty() || !self.removed_rules.is_empty() {
+            // Custom rules change what a selection runs, so results cached
+            // for the built-ins alone must not be reused.
+            let key = rule_names.get_or_insert_with(|| vec!["*".to_string()]);
+            key.extend(custom.iter().map(|name| format!("+{name}")));
+            key.extend(self.removed_rules.iter().map(|name| format!("-{name}")));
+        }
+        Linter {
+            rules,
+            rule_names,
+            options: self.options,
+            progress: self.progress,
+            cache: self.cache,
+            cancel: self.cancel,
+            stores: Mutex::default(),
+        }
+    }
+}
+
+impl Linter {
+    pub fn builder() -> LinterBuilder {
+        LinterBuilder::default()
+    }
+
+    pub fn options(&self) -> &LintOptions {
+        &self.options
+    }
+
+    /// Open a local path or URL, reusing the store of earlier files on the
+    /// same bucket or host.
+    pub async fn open(&self, location: &str) -> Result<LoadedFile> {
+        let (store, path) = self.resolve(location)?;
+        match self
+            .cancel
+            .run_until_cancelled(LoadedFile::open(store, path))
+            .await
+        {
+            Some(file) => file,
+            None => bail!("lint cancelled"),
+        }
+    }
+
+    /// Lint a local path or URL. With a cache configured, an unchanged
+    /// object costs one `HEAD` request.
+    pub async fn lint(&self, location: &str) -> Result<Vec<Diagnostic>> {
+        let (store, path) = self.resolve(location)?;
+        let mut cache_key = None;
+        if let Some(cache) = &self.cache {
+            let key = self.cache_key(location, &store.head(&path).await?)?;
+            if let Some(diagnostics) = self.cached(cache, &key) {
+                return Ok(diagnostics);
+            }
+            cache_key = Some(key);
+        }
+        let file = self.open(location).await?;
+        let diagnostics = self.lint_file(&file).await?;
+        if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+            store_cached(cache, &key, location, &diagnostics);
+        }
+        Ok(diagnostics)
+    }
+
+    /// Like `lint_file`, but going through the cache as `lint` does for
+    /// `location`, the place `file` was opened from. The object seen at open
+    /// identifies the version, so a hit costs no request at all.
+    pub async fn lint_file_cached(
+        &self,
+        location: &str,
+        file: &LoadedFile,
+    ) -> Result<Vec<Diagnostic>> {
+        let (Some(cache), Some(object)) = (&self.cache, &file.object) else {
+            return self.lint_file(file).await;
+        };
+        let key = self.cache_key(location, object)?;
+        if let Some(diagnostics) = self.cached(cache, &key) {
+            return Ok(diagnostics);
+        }
+        let diagnostics = self.lint_file(file).await?;
+        store_cached(cache, &key, location, &diagnostics);
+        Ok(diagnostics)
+    }
+
+    fn cache_key(&self, location: &str, object: &ObjectMeta) -> Result<String> {
+        let url = loader::parse_location(location)?;
+        Ok(cache::key(
+            url.as_str(),
+            object,
+            self.rule_names.as_deref(),
+            &self.options,
+        ))
+    }
+
+    fn cached(&self, cache: &ResultCache, key: &str) -> Option<Vec<Diagnostic>> {
+        let names: Vec<&'static str> = self.rules.iter().map(|r| r.name()).collect();
+        cache.get(key, &names)
+    }
+
+    /// Lint `locations` with up to `concurrency` files in flight, yielding
+    /// each location's result in input order. One failed file does not stop
+    /// the others.
+    pub fn lint_all<'a>(
+        &'a self,
+        locations: &'a [String],
+        concurrency: usize,
+    ) -> impl Stream<Item = (&'a str, Result<Vec<Diagnostic>>)> + 'a {
+        futures::stream::iter(locations)
+            .map(move |location| async move { (location.as_str(), self.lint(location).await) })
+            .buffered(concurrency.max(1))
+    }
+
+    /// Like `lint_all`, but yielding each result as soon as its file is done,
+    /// so one slow file does not hold back the ones behind it.
+    pub fn lint_all_unordered<'a>(
+        &'a self,
+        locations: &'a [String],
+        concurrency: usize,
+    ) -> impl Stream<Item = (&'a str, Result<Vec<Diagnostic>>)> + 'a {
+        futures::stream::iter(locations)
+            .map(move |location| async move { (location.as_str(), self.lint(location).await) })
+            .buffer_unordered(concurrency.max(1))
+    }
+
+    /// Lint an already-opened file without re-fetching its footer.
+    pub async fn lint_file(&self, file: &LoadedFile) -> Result<Vec<Diagnostic>> {
+        let (diagnostics, _) = self.lint_file_with_metrics(file).await?;
+        Ok(diagnostics)
+    }
+
+    /// Like `lint_file`, also reporting time and IO per phase and per rule.
+    #[tracing::instrument(name = "lint", skip_all, fields(path = %file.path))]
+    pub async fn lint_file_with_metrics(
+        &self,
+        file: &LoadedFile,
+    ) -> Result<(Vec<Diagnostic>, LintMetrics)> {
+        let restricted;
+        let file = match &self.options.row_groups {
+            Some(indices) => {
+                restricted = file.with_row_groups(indices)?;
+                &restricted
+            }
+            None => file,
+        };
+        check_columns_exist(file, &self.options)?;
+        file.pages
+            .set_memory_limit(self.options.sample_memory_limit);
+        let ctx = RuleContext {
+            metadata: file.metadata.clone(),
+            columns: LazyColumns::new(file, &self.options),
+            reader: file.reader.clone(),
+            pages: file.pages.clone(),
+            options: self.options.clone(),
+            blackboard: Blackboard::default(),
+        };
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        let mut rule_metrics = Vec::with_capacity(self.rules.len());
+        let rules = self
+            .rules
+            .iter()
+            .filter(|r| !self.options.disabled_rules.iter().any(|n| n == r.name()));
+        let total = rules.clone().count();
+        for (i, r) in rules.enumerate() {
+            let checked = file.metrics.measure(r.name(), r.check(&ctx));
+            let Some((mut found, stage)) = self.cancel.run_until_cancelled(checked).await else {
+                bail!("lint cancelled");
+            };
+            for d in &mut found {
+                d.severity = ctx.severity(r.as_ref(), d.severity);
+            }
+            diagnostics.extend(found);
+            rule_metrics.push(stage);
+            if let Some(progress) = &self.progress {
+                progress(Progress {
+                    path: &file.path,
+                    rule: r.name(),
+                    done: i + 1,
+                    total,
+                });
+            }
+        }
+        if let Some(e) = ctx.columns.take_error() {
+            return Err(e);
+        }
+        if let Some(selected) = &self.options.row_groups {
+            for d in &mut diagnostics {
+                for location in std::iter::once(&mut d.location).chain(&mut d.related) {
+                    renumber_row_group(location, selected);
+                }
+            }
+        }
+        if let Some(target) = self.options.target {
+            // Drop the directives the target cannot read, and a finding only
+            // when nothing of its fix is left.
+            diagnostics.retain_mut(|d| {
+                if d.prescription.is_empty() {
+                    return true;
+                }
+                d.prescription.retain(|directive| target.reads(directive));
+                !d.prescription.is_empty()
+            });
+        }
+        diagnostics.sort_by_key(|d| d.severity);
+        let metrics = LintMetrics {
+            phases: file.metrics.phases(),
+            rules: rule_metrics,
+        };
+        Ok((diagnostics, metrics))
+    }
+
+    /// Rewrite `file` into `output`, honoring the cancellation token.
+    pub async fn rewrite_file(
+        &self,
+        file: &LoadedFile,
+        output: &Path,
+        prescription: &Prescription,
+    ) -> Result<()> {
+        fix::rewrite_file_until_cancelled(file, output, prescription, &self.cancel).await
+    }
+
+    fn resolve(&self, location: &str) -> Result<(Arc<dyn ObjectStore>, ObjectPath)> {
+        let url = loader::parse_location(location)?;
+        let key = url[..url::Position::BeforePath].to_string();
+        let mut stores = self.stores.lock().expect("store cache poisoned");
+        if let Some(store) = stores.get(&key) {
+            return Ok((store.clone(), ObjectPath::from_url_path(url.path())?));
+        }
+        let (store, path) = loader::parse(location)?;
+        stores.insert(key, store.clone());
+        Ok((store, path))
+    }
+}
+
+/// Point a location inside a `LintOptions::row_groups` view back at the
+/// row group's index in the file.
+fn renumber_row_group(location: &mut Location, selected: &[usize]) {
+    match location {
+        Location::RowGroup { index } => *index = selected[*index],
+        Location::Page { row_group, .. } => *row_group = selected[*row_group],
+        Location::File | Location::Column { .. } => {}
+    }
+}
+
+/// Store `diagnostics` under `key`; a cache that cannot be written only
+/// costs the next run a lint.
+fn store_cached(cache: &ResultCache, key: &str, location: &str, diagnostics: &[Diagnostic]) {
+    if let Err(e) = cache.put(key, diagnostics) {
+        tracing::warn!("failed to cache lint results for {location}: {e:#}");
+    }
+}
+
+/// Fail on `--columns` names that match no leaf, which are almost always
+/// typos that would otherwise silently lint nothing.
+fn check_columns_exist(file: &LoadedFile, options: &LintOptions) -> Result<()> {
+    let Some(names) = &options.columns else {
+        return Ok(());
+    };
+    let schema = file.metadata.file_metadata().schema_descr();
+    for name in names {
+        let only = LintOptions {
+            columns: Some(vec![name.clone()]),
+            ..LintOptions::default()
+        };
+        if !schema.columns().iter().any(|c| only.selects(c.path())) {
+            bail!("column '{name}' not found in {}", file.path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Int64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn reuses_store_and_reports_progress() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        let mut locations = Vec::new();
+        for name in ["a.parquet", "b.parquet"] {
+            let path = dir.path().join(name);
+            let mut writer =
+                ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+            locations.push(path.to_str().unwrap().to_string());
+        }
+
+        let finished = Arc::new(AtomicUsize::new(0));
+        let counter = finished.clone();
+        let linter = Linter::builder()
+            .rules(["missing-page-statistics", "compression-codec-upgrade"])
+            .progress(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .build();
+        for location in &locations {
+            linter.lint(location).await?;
+        }
+        assert_eq!(finished.load(Ordering::Relaxed), 4);
+        assert_eq!(linter.stores.lock().unwrap().len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn options_disable_rules_and_override_severities() -> Result<()> {
+        use crate::diagnostic::Severity;
+        use crate::testing::TestFile;
+
+        let batch = RecordBatch::try_from_iter([(
+            "ts",
+            Arc::new(Int64Array::from_iter_values(0..4000)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings("set file max_row_group_size 1000");
+        let mut options = LintOptions::default();
+        options
+            .severities
+            .insert("sorting-order".to_string(), Severity::Warning);
+        let found = file
+            .lint_with_options(&["sorting-order"], options.clone())
+            .await?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].severity, Severity::Warning);
+
+        options.disabled_rules.push("sorting-order".to_string());
+        assert!(
+            file.lint_with_options(&["sorting-order"], options)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reports_original_indices_of_selected_row_groups() -> Result<()> {
+        use crate::testing::TestFile;
+
+        const ROWS: i64 = 20_000;
+        // Four repetitive row groups, then one of pseudo-random values.
+        let values = (0..5 * ROWS).map(|i| {
+            if i < 4 * ROWS {
+                i % 10
+            } else {
+                i.wrapping_mul(0x2545_F491_4F6C_DD1D)
+            }
+        });
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int64Array::from_iter_values(values)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings(
+            "set file max_row_group_size 20000
+             set file compression zstd(3)
+             set column v dictionary false",
+        );
+        let options = LintOptions {
+            row_groups: Some(vec![1, 2, 3, 4]),
+            ..LintOptions::default()
+        };
+        let found = file
+            .lint_with_options(&["low-compression-ratio"], options)
+            .await?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].related[0].to_string(), "row_group[4]");
+        assert!(found[0].message.starts_with("row groups 4 compress"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn registers_overrides_and_removes_rules() -> Result<()> {
+        use crate::diagnostic::{Category, Location, Severity};
+        use crate::rule::RuleMetadata;
+        use crate::testing::TestFile;
+
+        struct NoIdColumn(&'static str);
+
+        #[async_trait::async_trait]
+        impl Rule for NoIdColumn {
+            fn name(&self) -> &'static str {
+                self.0
+            }
+
+            fn metadata(&self) -> RuleMetadata {
+                RuleMetadata {
+                    name: self.name(),
+                    code: "X001",
+                    description: "Files with an id column",
+                    category: Category::Correctness,
+                    default_severity: Severity::Warning,
+                    parameters: &[],
+                    directives: &[],
+                }
+            }
+
+            async fn check(&self, _ctx: &RuleContext) -> Vec<Diagnostic> {
+                vec![Diagnostic {
+                    rule_name: self.name(),
+                    severity: Severity::Warning,
+                    category: Category::Correctness,
+                    location: Location::File,
+                    message: "has an id column".to_string(),
+                    prescription: Prescription::new(),
+                    related: Vec::new(),
+                }]
+            }
+        }
+
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        let file = TestFile::new(batch).open().await?;
+        let names = |linter: &Linter| -> Vec<&'static str> {
+            linter.rules.iter().map(|r| r.name()).collect()
+        };
+
+        let linter = Linter::builder()
+            .with_rule(Box::new(NoIdColumn("no-id-column")))
+            .without_rule("sorting-order")
+            .build();
+        let found = linter.lint_file(&file).await?;
+        assert!(found.iter().any(|d| d.rule_name == "no-id-column"));
+        assert!(names(&linter).contains(&"missing-page-statistics"));
+        assert!(!names(&linter).contains(&"sorting-order"));
+
+        let linter = Linter::builder()
+            .rules(["missing-page-statistics"])
+            .with_rule(Box::new(NoIdColumn("missing-page-statistics")))
+            .build();
+        let found = linter.lint_file(&file).await?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message, "has an id column");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lints_every_parquet_file_below_a_prefix() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        for name in ["b.parquet", "sub/a.parquet", "_temporary/c.parquet"] {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            let mut writer =
+                ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+        std::fs::write(dir.path().join("notes.txt"), "")?;
+
+        let root = dir.path().to_str().unwrap();
+        assert!(loader::is_prefix(root));
+        let mut locations = loader::list_parquet(root).await?;
+        assert_eq!(locations.len(), 2);
+        assert!(locations[1].ends_with("a.parquet"));
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+        let listed = loader::list_parquet(url.as_str()).await?;
+        assert_eq!(listed.len(), 2);
+        assert!(listed[0].starts_with("file:///") && listed[1].ends_with("sub/a.parquet"));
+
+        locations.push(format!("{root}/missing.parquet"));
+        let linter = Linter::builder().rules(["missing-page-statistics"]).build();
+        let results: Vec<_> = linter.lint_all(&locations, 2).collect().await;
+        let ok: Vec<bool> = results.iter().map(|(_, r)| r.is_ok()).collect();
+        assert_eq!(ok, [true, true, false]);
+        assert_eq!(results[2].0, locations[2]);
+        let unordered: Vec<_> = linter.lint_all_unordered(&locations, 2).collect().await;
+        assert_eq!(unordered.len(), 3);
+        assert!(
+            unordered
+                .iter()
+                .any(|(l, r)| *l == locations[2] && r.is_err())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn columns_restrict_column_rules() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("t.parquet");
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int64Array::from_iter_values(0..100)) as _),
+            ("b", Arc::new(Int64Array::from_iter_values(0..100)) as _),
+        ])?;
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_statistics_enabled(parquet::file::properties::EnabledStatistics::None)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let location = path.to_str().unwrap();
+
+        let linter = Linter::builder()
+            .rules(["missing-chunk-statistics"])
+            .columns(["b"])
+            .build();
+        let diagnostics = linter.lint(location).await?;
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            diagnostics[0].location,
+            crate::diagnostic::Location::Column { column: 1, .. }
+        ));
+
+        let typo = Linter::builder().columns(["c"]).build();
+        let err = typo.lint(location).await.err().unwrap();
+        assert!(err.to_string().contains("column 'c' not found"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn target_reader_flags_and_filters_unreadable_settings() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("t.parquet");
+        let values = (0..10_000).map(|i| (i as f64).sin());
+        let batch = RecordBatch::try_from_iter([(
+            "f",
+            Arc::new(arrow_array::Float64Array::from_iter_values(values)) as _,
+        )])?;
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::BROTLI(Default::default()))
+            .set_dictionary_enabled(false)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let location = path.to_str().unwrap();
+        let rules = ["reader-compatibility", "float-byte-stream-split"];
+
+        let untargeted = Linter::builder().rules(rules).build();
+        let names: Vec<_> = untargeted
+            .lint(location)
+            .await?
+            .iter()
+            .map(|d| d.rule_name)
+            .collect();
+        assert_eq!(names, ["float-byte-stream-split"]);
+
+        let options = LintOptions {
+            target: Some(crate::target::TargetReader::Spark35),
+            ..Default::default()
+        };
+        let targeted = Linter::builder().rules(rules).options(options).build();
+        let diagnostics = targeted.lint(location).await?;
+        let fixes: Vec<_> = diagnostics
+            .iter()
+            .map(|d| d.prescription.to_string())
+            .collect();
+        assert_eq!(fixes, ["set column f compression zstd(3)"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn target_reader_keeps_readable_part_of_mixed_fixes() -> Result<()> {
+        use crate::diagnostic::{Category, Location, Severity};
+        use crate::prescription::{Codec, DataEncoding, Directive};
+        use crate::rule::RuleMetadata;
+        use crate::testing::TestFile;
+        use parquet::schema::types::ColumnPath;
+
+        struct Fixes;
+
+        #[async_trait::async_trait]
+        impl Rule for Fixes {
+            fn name(&self) -> &'static str {
+                "fixes"
+            }
+
+            fn metadata(&self) -> RuleMetadata {
+                RuleMetadata {
+                    name: self.name(),
+                    code: "X001",
+                    description: "Findings with and without fixes",
+                    category: Category::Performance,
+                    default_severity: Severity::Suggestion,
+                    parameters: &[],
+                    directives: &[],
+                }
+            }
+
+            async fn check(&self, _ctx: &RuleContext) -> Vec<Diagnostic> {
+                let id = ColumnPath::from("id");
+                let split = Directive::SetColumnEncoding(id.clone(), DataEncoding::ByteStreamSplit);
+                let zstd = Directive::SetColumnCompression(id, Codec::Zstd(3));
+                let finding = |message: &str, directives: &[&Directive]| {
+                    let mut prescription = Prescription::new();
+                    for directive in directives {
+                        prescription.push((*directive).clone());
+                    }
+                    Diagnostic {
+                        rule_name: self.name(),
+                        severity: Severity::Suggestion,
+                        category: Category::Performance,
+                        location: Location::File,
+                        message: message.to_string(),
+                        prescription,
+                        related: Vec::new(),
+                    }
+                };
+                vec![
+                    finding("mixed", &[&split, &zstd]),
+                    finding("unreadable", &[&split]),
+                    finding("advisory", &[]),
+                ]
+            }
+        }
+
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        let file = TestFile::new(batch).open().await?;
+        let options = LintOptions {
+            target: Some(crate::target::TargetReader::Spark35),
+            ..Default::default()
+        };
+        let linter = Linter::builder()
+            .rules(["fixes"])
+            .with_rule(Box::new(Fixes))
+            .options(options)
+            .build();
+        let found: Vec<_> = linter
+            .lint_file(&file)
+            .await?
+            .iter()
+            .map(|d| format!("{}: {}", d.message, d.prescription.to_inline()))
+            .collect();
+        assert_eq!(
+            found,
+            ["mixed: set column id compression zstd(3)", "advisory: "]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancelled_linter_stops_before_reading() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("t.parquet");
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as _,
+        )])?;
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let token = CancellationToken::new();
+        let linter = Linter::builder().cancellation(token.clone()).build();
+        let file = linter.open(path.to_str().unwrap()).await?;
+        token.cancel();
+        let err = linter.lint_file(&file).await.err().unwrap();
+        assert_eq!(err.to_string(), "lint cancelled");
+
+        let output = dir.path().join("out.parquet");
+        let err = linter
+            .rewrite_file(&file, &output, &Prescription::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "rewrite cancelled");
+        assert!(!output.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cached_results_survive_until_the_file_changes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("t.parquet");
+        let write = |n: i64| -> Result<()> {
+            let batch = RecordBatch::try_from_iter([(
+                "id",
+                Arc::new(Int64Array::from_iter_values(0..n)) as _,
+            )])?;
+            let mut writer =
+                ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+            Ok(())
+        };
+        write(100)?;
+        let location = path.to_str().unwrap();
+        let cache = ResultCache::new(dir.path().join("cache"));
+        let linter = Linter::builder().cache(cache.clone()).build();
+
+        linter.lint(location).await?;
+        assert_eq!(std::fs::read_dir(cache.dir())?.count(), 1);
+        linter.lint(location).await?;
+        assert_eq!(std::fs::read_dir(cache.dir())?.count(), 1);
+        write(200)?;
+        linter.lint(location).await?;
+        assert_eq!(std::fs::read_dir(cache.dir())?.count(), 2);
+        // An opened file shares the entry `lint` wrote for its version.
+        let file = linter.open(location).await?;
+        linter.lint_file_cached(location, &file).await?;
+        assert_eq!(std::fs::read_dir(cache.dir())?.count(), 2);
+        Ok(())
+    }
+}
@@ -1,8 +1,165 @@
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
-use object_store::ObjectStore;
+use anyhow::{Context, Result, bail};
+use bytes::Bytes;
+use futures::TryStreamExt;
+use object_store::memory::InMemory;
 use object_store::path::Path as ObjectPath;
+use object_store::{ObjectMeta, ObjectStore};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::arrow::async_reader::{
+    AsyncFileReader, ParquetObjectReader, ParquetRecordBatchStreamBuilder,
+};
+use parquet::file::FOOTER_SIZE;
+use parquet::file::metadata::{
+    FileMetaData, FooterTail, ParquetMetaData, ParquetMetaDataBuilder, ParquetMetaDataReader,
+};
+
+use crate::metrics::{MetricsRecorder, PHASE_METADATA};
+use crate::page_cache::PageCache;
+
+/// A Parquet file whose footer has been fetched once, so linting and
+/// rewriting can share the reader and metadata.
+#[derive(Clone)]
+pub struct LoadedFile {
+    /// Location of the file within its store.
+    pub path: ObjectPath,
+    /// The object as it was when opened; its e-tag and version guard
+    /// in-place rewrites against concurrent changes. `None` when built
+    /// from a footer alone.
+    pub object: Option<ObjectMeta>,
+    pub reader: ParquetObjectReader,
+    pub metadata: Arc<ParquetMetaData>,
+    /// Column-chunk bytes fetched so far, shared by context building and rules.
+    pub pages: PageCache,
+    /// Time and IO spent on this file, including the footer fetch.
+    pub metrics: MetricsRecorder,
+}
+
+impl LoadedFile {
+    #[tracing::instrument(name = "open", skip(store), fields(path = %path))]
+    pub async fn open(store: Arc<dyn ObjectStore>, path: ObjectPath) -> Result<Self> {
+        let metrics = MetricsRecorder::default();
+        let store = metrics.instrument(store);
+        let (object, metadata) = metrics
+            .phase(PHASE_METADATA, async {
+                let object = store.head(&path).await?;
+                let metadata = ParquetObjectReader::new(store.clone(), path.clone())
+                    .with_file_size(object.size)
+                    .get_metadata(None)
+                    .await?;
+                anyhow::Ok((object, metadata))
+            })
+            .await?;
+        let reader = ParquetObjectReader::new(store, path.clone()).with_file_size(object.size);
+        let pages = PageCache::new(reader.clone(), metadata.clone());
+        Ok(Self {
+            path,
+            object: Some(object),
+            reader,
+            metadata,
+            pages,
+            metrics,
+        })
+    }
+
+    /// Build from the tail of a file (footer plus its 8-byte trailer) without
+    /// any object store IO. Only lint with `LintOptions::metadata_only` set:
+    /// the reader holds just the tail, so data-page reads would fail.
+    pub fn from_footer(tail: Bytes) -> Result<Self> {
+        let trailer_start = tail
+            .len()
+            .checked_sub(FOOTER_SIZE)
+            .context("parquet footer is shorter than its trailer")?;
+        let metadata_len = FooterTail::try_from(&tail[trailer_start..])?.metadata_length();
+        let metadata_start = trailer_start
+            .checked_sub(metadata_len)
+            .context("parquet footer is truncated")?;
+        let metadata =
+            ParquetMetaDataReader::decode_metadata(&tail[metadata_start..trailer_start])?;
+        let metadata = Arc::new(metadata);
+
+        let store = Arc::new(InMemory::new());
+        let path = ObjectPath::from("footer.parquet");
+        let metrics = MetricsRecorder::default();
+        let reader = ParquetObjectReader::new(metrics.instrument(store), path.clone())
+            .with_file_size(tail.len() as u64);
+        let pages = PageCache::new(reader.clone(), metadata.clone());
+        Ok(Self {
+            path,
+            object: None,
+            reader,
+            metadata,
+            pages,
+            metrics,
+        })
+    }
+
+    /// A view of the file holding only the given row groups, renumbered from
+    /// zero in the order given. Everything built from it (contexts, samples,
+    /// rules) sees just those groups; data is still read from the same object.
+    /// `Linter` maps the row groups of its findings back to the original
+    /// indices.
+    pub fn with_row_groups(&self, indices: &[usize]) -> Result<Self> {
+        let metadata = self.metadata.as_ref();
+        let count = metadata.num_row_groups();
+        if let Some(i) = indices.iter().find(|&&i| i >= count) {
+            bail!("row group {i} out of range: {} has {count}", self.path);
+        }
+        let file = metadata.file_metadata();
+        let num_rows = indices
+            .iter()
+            .map(|&i| metadata.row_group(i).num_rows())
+            .sum();
+        let file = FileMetaData::new(
+            file.version(),
+            num_rows,
+            file.created_by().map(str::to_string),
+            file.key_value_metadata().cloned(),
+            file.schema_descr_ptr(),
+            file.column_orders().cloned(),
+        );
+        let metadata = ParquetMetaDataBuilder::new(file)
+            .set_row_groups(pick(metadata.row_groups(), indices))
+            .set_column_index(metadata.column_index().map(|all| pick(all, indices)))
+            .set_offset_index(metadata.offset_index().map(|all| pick(all, indices)))
+            .build();
+        let metadata = Arc::new(metadata);
+        Ok(Self {
+            path: self.path.clone(),
+            object: self.object.clone(),
+            reader: self.reader.clone(),
+            pages: PageCache::new(self.reader.clone(), metadata.clone()),
+            metadata,
+            metrics: self.metrics.clone(),
+        })
+    }
+}
+
+fn pick<T: Clone>(all: &[T], indices: &[usize]) -> Vec<T> {
+    indices.iter().map(|&i| all[i].clone()).collect()
+}
+
+/// Record batch stream builder over `reader` that reuses the parsed footer
+/// instead of fetching it again.
+pub(crate) fn stream_builder(
+    reader: &ParquetObjectReader,
+    metadata: &Arc<ParquetMetaData>,
+) -> Result<ParquetRecordBatchStreamBuilder<ParquetObjectReader>> {
+    stream_builder_with_options(reader, metadata, ArrowReaderOptions::default())
+}
+
+pub(crate) fn stream_builder_with_options(
+    reader: &ParquetObjectReader,
+    metadata: &Arc<ParquetMetaData>,
+    options: ArrowReaderOptions,
+) -> Result<ParquetRecordBatchStreamBuilder<ParquetObjectReader>> {
+    let arrow_metadata = ArrowReaderMetadata::try_new(metadata.clone(), options)?;
+    Ok(ParquetRecordBatchStreamBuilder::new_with_metadata(
+        reader.clone(),
+        arrow_metadata,
+    ))
+}
 
 /// Parse a location string into an object store and path.
 ///
@@ -16,7 +173,51 @@ pub fn parse(location: &str) -> Result<(Arc<dyn ObjectStore>, ObjectPath)> {
     Ok((Arc::from(store), path))
 }
 
-fn parse_location(location: &str) -> Result<url::Url> {
+/// Whether `location` names a set of files rather than one: a local
+/// directory, or a URL ending in `/` (an object store prefix).
+pub fn is_prefix(location: &str) -> bool {
+    match url::Url::parse(location) {
+        Ok(url) if url.scheme() != "file" => location.ends_with('/'),
+        Ok(url) => url.to_file_path().is_ok_and(|path| path.is_dir()),
+        Err(_) => std::path::Path::new(location).is_dir(),
+    }
+}
+
+/// Locations of the `*.parquet` files below a local directory or object
+/// store prefix, sorted. As for directory rewrites, entries named `.*` or
+/// `_*` are skipped.
+pub async fn list_parquet(location: &str) -> Result<Vec<String>> {
+    if let Err(url::ParseError::RelativeUrlWithoutBase) = url::Url::parse(location) {
+        let root = std::path::Path::new(location);
+        return Ok(crate::fix::parquet_files_below(root)?
+            .into_iter()
+            .map(|relative| root.join(relative).to_string_lossy().into_owned())
+            .collect());
+    }
+    let url = parse_location(location)?;
+    let (store, prefix) = parse(location)?;
+    let base = &url[..url::Position::BeforePath];
+    let depth = prefix.parts().count();
+    let mut locations: Vec<String> = store
+        .list(Some(&prefix))
+        .try_filter_map(|object| {
+            let hidden = object
+                .location
+                .parts()
+                .skip(depth)
+                .any(|part| part.as_ref().starts_with(['.', '_']));
+            let parquet = object.location.as_ref().ends_with(".parquet");
+            let location = (parquet && !hidden).then(|| format!("{base}/{}", object.location));
+            futures::future::ready(Ok(location))
+        })
+        .try_collect()
+        .await
+        .with_context(|| format!("failed to list {location}"))?;
+    locations.sort();
+    Ok(locations)
+}
+
+pub(crate) fn parse_location(location: &str) -> Result<url::Url> {
     match url::Url::parse(location) {
         Ok(url) => Ok(url),
         Err(url::ParseError::RelativeUrlWithoutBase) => {
@@ -29,3 +230,61 @@ fn parse_location(location: &str) -> Result<url::Url> {
         Err(e) => Err(e.into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::LintOptions;
+    use arrow_array::{Int64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+
+    #[tokio::test]
+    async fn row_group_view_keeps_only_selected_groups() -> Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..400)) as _,
+        )])?;
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_max_row_group_size(100)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let store = Arc::new(InMemory::new());
+        let path = ObjectPath::from("t.parquet");
+        store.put(&path, Bytes::from(buffer).into()).await?;
+
+        let file = LoadedFile::open(store, path).await?;
+        let view = file.with_row_groups(&[1, 3])?;
+        assert_eq!(view.metadata.num_row_groups(), 2);
+        assert_eq!(view.metadata.file_metadata().num_rows(), 200);
+        let first = view.metadata.row_group(0).column(0).statistics().unwrap();
+        assert_eq!(first.min_bytes_opt(), Some(&100i64.to_le_bytes()[..]));
+        assert!(file.with_row_groups(&[4]).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn footer_only_lint_issues_no_reads() -> Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..10_000)) as _,
+        )])?;
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let tail = Bytes::from(buffer.split_off(buffer.len() - 2048));
+
+        let file = LoadedFile::from_footer(tail)?;
+        assert_eq!(file.metadata.file_metadata().num_rows(), 10_000);
+        let options = LintOptions {
+            metadata_only: true,
+            ..LintOptions::default()
+        };
+        let (_, metrics) = crate::lint_file_with_metrics(&file, None, &options).await?;
+        assert_eq!(metrics.total().requests, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+//! Wall time and IO accounting for a lint run, so slow lints can be traced
+//! to the phase or rule that spent the time.
+//!
+//! Column contexts are built lazily, so the cardinality and sampling phases
+//! run inside whichever rule first asks for a column; their time and IO are
+//! reported both as phases and as part of that rule.
+
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult,
+};
+// `std::time::Instant` panics on wasm32-unknown-unknown.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+pub const PHASE_METADATA: &str = "metadata";
+pub const PHASE_CARDINALITY: &str = "cardinality";
+pub const PHASE_SAMPLING: &str = "sampling";
+
+/// Time and IO spent in one phase or rule.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageMetrics {
+    pub name: String,
+    pub wall_time: Duration,
+    pub bytes_fetched: u64,
+    pub requests: u64,
+}
+
+impl StageMetrics {
+    fn add(&mut self, other: &StageMetrics) {
+        self.wall_time += other.wall_time;
+        self.bytes_fetched += other.bytes_fetched;
+        self.requests += other.requests;
+    }
+}
+
+/// Per-phase and per-rule accounting for one lint run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintMetrics {
+    /// Metadata, cardinality and sampling, in first-seen order.
+    pub phases: Vec<StageMetrics>,
+    /// One entry per rule, in the order the rules ran.
+    pub rules: Vec<StageMetrics>,
+}
+
+impl LintMetrics {
+    /// Sum over every rule plus the metadata phase, which runs before them.
+    pub fn total(&self) -> StageMetrics {
+        let mut total = StageMetrics {
+            name: "total".to_string(),
+            ..StageMetrics::default()
+        };
+        let metadata = self.phases.iter().filter(|p| p.name == PHASE_METADATA);
+        for stage in metadata.chain(&self.rules) {
+            total.add(stage);
+        }
+        total
+    }
+}
+
+impl Display for LintMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<40} {:>10} {:>12} {:>9}",
+            "stage", "time", "bytes", "requests"
+        )?;
+        let rows = self.phases.iter().map(|p| ("phase", p));
+        let rows = rows.chain(self.rules.iter().map(|r| ("rule", r)));
+        let total = self.total();
+        for (kind, stage) in rows.chain([("", &total)]) {
+            let label = if kind.is_empty() {
+                stage.name.clone()
+            } else {
+                format!("{kind} {}", stage.name)
+            };
+            writeln!(
+                f,
+                "{:<40} {:>8.1}ms {:>12} {:>9}",
+                label,
+                stage.wall_time.as_secs_f64() * 1000.0,
+                stage.bytes_fetched,
+                stage.requests
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct IoCounters {
+    bytes: AtomicU64,
+    requests: AtomicU64,
+}
+
+impl IoCounters {
+    fn record(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> (u64, u64) {
+        (
+            self.bytes.load(Ordering::Relaxed),
+            self.requests.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Shared recorder behind a `LoadedFile`: counts requests made through its
+/// store and accumulates phase timings.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRecorder {
+    io: Arc<IoCounters>,
+    phases: Arc<Mutex<Vec<StageMetrics>>>,
+}
+
+impl MetricsRecorder {
+    /// Wrap `store` so every read through it is counted.
+    pub(crate) fn instrument(&self, store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(CountingStore {
+            inner: store,
+            io: self.io.clone(),
+        })
+    }
+
+    /// Run `fut` inside a `stage` tracing span and return its output with the
+    /// time and IO it took. The span records the IO as fields on exit.
+    pub(crate) async fn measure<T>(
+        &self,
+        name: &str,
+        fut: impl Future<Output = T>,
+    ) -> (T, StageMetrics) {
+        let span = tracing::info_span!(
+            "stage",
+            stage = name,
+            bytes_fetched = tracing::field::Empty,
+            requests = tracing::field::Empty,
+        );
+        let (bytes, requests) = self.io.snapshot();
+        let start = Instant::now();
+        let output = tracing::Instrument::instrument(fut, span.clone()).await;
+        let wall_time = start.elapsed();
+        let (bytes_after, requests_after) = self.io.snapshot();
+        let stage = StageMetrics {
+            name: name.to_string(),
+            wall_time,
+            bytes_fetched: bytes_after - bytes,
+            requests: requests_after - requests,
+        };
+        span.record("bytes_fetched", stage.bytes_fetched);
+        span.record("requests", stage.requests);
+        (output, stage)
+    }
+
+    /// Run `fut` and add its time and IO to the named phase.
+    pub(crate) async fn phase<T>(&self, name: &str, fut: impl Future<Output = T>) -> T {
+        let (output, stage) = self.measure(name, fut).await;
+        let mut phases = self.phases.lock().expect("metrics poisoned");
+        match phases.iter_mut().find(|p| p.name == name) {
+            Some(existing) => existing.add(&stage),
+            None => phases.push(stage),
+        }
+        output
+    }
+
+    /// Phase totals recorded so far.
+    pub fn phases(&self) -> Vec<StageMetrics> {
+        self.phases.lock().expect("metrics poisoned").clone()
+    }
+}
+
+/// Object store wrapper that counts read requests and the bytes they return.
+#[derive(Debug)]
+struct CountingStore {
+    inner: Arc<dyn ObjectStore>,
+    io: Arc<IoCounters>,
+}
+
+impl Display for CountingStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CountingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CountingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        let head = options.head;
+        let result = self.inner.get_opts(location, options).await?;
+        let bytes = if head {
+            0
+        } else {
+            result.range.end - result.range.start
+        };
+        self.io.record(bytes);
+        Ok(result)
+    }
+
+    // `get_ranges` keeps the default, which coalesces and then calls this once
+    // per merged range, so each request is counted.
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> object_store::Result<Bytes> {
+        let bytes = self.inner.get_range(location, range).await?;
+        self.io.record(bytes.len() as u64);
+        Ok(bytes)
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        let meta = self.inner.head(location).await?;
+        self.io.record(0);
+        Ok(meta)
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn counting_store_tracks_bytes_and_requests() -> anyhow::Result<()> {
+        let recorder = MetricsRecorder::default();
+        let store = recorder.instrument(Arc::new(InMemory::new()));
+        let path = Path::from("f");
+        store.put(&path, vec![0u8; 100].into()).await?;
+
+        let (_, stage) = recorder
+            .measure("read", async {
+                store.get_range(&path, 0..10).await?;
+                store.get_ranges(&path, &[20..30, 40..45]).await
+            })
+            .await;
+        assert_eq!(stage.bytes_fetched, 10 + 25);
+        assert_eq!(stage.requests, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn phases_accumulate_by_name() {
+        let recorder = MetricsRecorder::default();
+        recorder.phase(PHASE_SAMPLING, async {}).await;
+        recorder.phase(PHASE_SAMPLING, async {}).await;
+        recorder.phase(PHASE_CARDINALITY, async {}).await;
+        let names: Vec<String> = recorder.phases().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, [PHASE_SAMPLING, PHASE_CARDINALITY]);
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use parquet::schema::types::ColumnPath;
+
+use crate::cost::{CostModel, CpuCostModel};
+use crate::diagnostic::Severity;
+use crate::target::TargetReader;
+use crate::thresholds::Thresholds;
+
+/// Knobs controlling how much work a lint run does.
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    /// Keep per-row-group slices on each `ColumnContext`. Cheap (metadata
+    /// only), but can be disabled for files with very many row groups.
+    pub row_group_contexts: bool,
+    /// Columns that queries actually touch, used by rules to prioritize.
+    pub workload: Workload,
+    /// Sample every flat column (not only those with missing statistics) to
+    /// build value distributions. Costs one extra row-group read.
+    pub profile_values: bool,
+    /// Re-compress a sample of each large column with candidate codecs to
+    /// measure real ratios. Costs one extra row-group read plus CPU.
+    pub probe_compression: bool,
+    /// Re-encode a sample of each large column with candidate encodings to
+    /// measure real sizes. Shares the read with `probe_compression`.
+    pub probe_encodings: bool,
+    /// Number of row groups (spread first to last) sampled to estimate
+    /// cardinality. More groups cost more IO but handle time-ordered files.
+    pub cardinality_row_groups: usize,
+    /// How many rows distinct counts are estimated from, when neither
+    /// statistics nor dictionaries settle them.
+    pub cardinality: CardinalityMode,
+    /// Approximate cap, in bytes, on decoded sample data held at once. Wide
+    /// files are sampled in column groups that fit under it. Also caps the
+    /// column-chunk bytes cached for page-level rules.
+    pub sample_memory_limit: usize,
+    /// Lint from the footer alone: no dictionary, page or sample reads.
+    /// Cardinality then comes only from column statistics.
+    pub metadata_only: bool,
+    /// Fetch and decode costs that rules weigh against file size when
+    /// choosing between codecs or encodings.
+    pub cost_model: Arc<dyn CostModel>,
+    /// Only lint these columns: dotted paths, where a name also selects every
+    /// leaf nested below it. `None` lints every column.
+    pub columns: Option<Vec<String>>,
+    /// Never lint or sample columns matching these patterns: dotted paths
+    /// where `*` matches any run of characters and `?` one character. A
+    /// pattern also matches every leaf nested below a matching path.
+    pub ignore_columns: Vec<String>,
+    /// Only lint these row groups (see `LoadedFile::with_row_groups`).
+    /// Metadata walks, samples and probes then skip every other group.
+    pub row_groups: Option<Vec<usize>>,
+    /// Reader the file must stay readable by. Enables the
+    /// `reader-compatibility` rule and drops fixes the target cannot read.
+    pub target: Option<TargetReader>,
+    /// Sizes and counts at which rules start to flag columns.
+    pub thresholds: Thresholds,
+    /// Rules that never run, even when selected by name.
+    pub disabled_rules: Vec<String>,
+    /// Severity every diagnostic of a rule is reported with, keyed by rule
+    /// name, instead of the rule's own.
+    pub severities: BTreeMap<String, Severity>,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            row_group_contexts: true,
+            workload: Workload::default(),
+            profile_values: false,
+            probe_compression: false,
+            probe_encodings: false,
+            cardinality_row_groups: 3,
+            cardinality: CardinalityMode::default(),
+            sample_memory_limit: 256 * 1024 * 1024,
+            metadata_only: false,
+            cost_model: Arc::new(CpuCostModel::default()),
+            columns: None,
+            ignore_columns: Vec::new(),
+            row_groups: None,
+            target: None,
+            thresholds: Thresholds::default(),
+            disabled_rules: Vec::new(),
+            severities: BTreeMap::new(),
+        }
+    }
+}
+
+impl LintOptions {
+    /// Whether column rules should look at this leaf (see `columns` and
+    /// `ignore_columns`).
+    pub fn selects(&self, path: &ColumnPath) -> bool {
+        let ignored = self.ignore_columns.iter().any(|pattern| {
+            let path = path.parts();
+            (1..=path.len()).any(|n| glob_matches(pattern, &path[..n].join(".")))
+        });
+        !ignored
+            && self
+                .columns
+                .as_ref()
+                .is_none_or(|names| matches_any(names, path))
+    }
+}
+
+/// Accuracy of distinct-count estimates for columns whose statistics and
+/// dictionaries do not settle them. Every mode counts with a HyperLogLog
+/// sketch; they differ in how many rows they feed it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CardinalityMode {
+    /// The first rows of each sampled row group, extrapolated to the file.
+    #[default]
+    Fast,
+    /// Every row of each sampled row group, extrapolated to the file.
+    /// Costs a full read of the sampled groups.
+    Balanced,
+    /// Every row of the file: small columns are counted exactly, larger ones
+    /// sketched. Costs a full read of the selected columns.
+    Exact,
+}
+
+impl fmt::Display for CardinalityMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CardinalityMode::Fast => "fast",
+            CardinalityMode::Balanced => "balanced",
+            CardinalityMode::Exact => "exact",
+        })
+    }
+}
+
+impl FromStr for CardinalityMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "fast" => CardinalityMode::Fast,
+            "balanced" => CardinalityMode::Balanced,
+            "exact" => CardinalityMode::Exact,
+            _ => bail!("unknown cardinality mode {s:?} (expected fast, balanced or exact)"),
+        })
+    }
+}
+
+/// Query-pattern hints. Names are dotted column paths; a name also matches
+/// every leaf nested below it (`user` matches `user.id`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Workload {
+    /// Columns used in predicates (candidates for statistics and sorting).
+    pub filter_columns: Vec<String>,
+    /// Columns read by queries.
+    pub projection_columns: Vec<String>,
+    /// Columns used as join or point-lookup keys (candidates for bloom filters).
+    pub join_keys: Vec<String>,
+}
+
+impl Workload {
+    pub fn is_empty(&self) -> bool {
+        self.filter_columns.is_empty()
+            && self.projection_columns.is_empty()
+            && self.join_keys.is_empty()
+    }
+
+    pub fn is_filter_column(&self, path: &ColumnPath) -> bool {
+        matches_any(&self.filter_columns, path)
+    }
+
+    pub fn is_projected(&self, path: &ColumnPath) -> bool {
+        matches_any(&self.projection_columns, path)
+    }
+
+    pub fn is_join_key(&self, path: &ColumnPath) -> bool {
+        matches_any(&self.join_keys, path)
+    }
+
+    /// Whether any query reads, filters, or joins on this column.
+    pub fn touches(&self, path: &ColumnPath) -> bool {
+        self.is_filter_column(path) || self.is_projected(path) || self.is_join_key(path)
+    }
+
+    pub fn extend(&mut self, other: Workload) {
+        self.filter_columns.extend(other.filter_columns);
+        self.projection_columns.extend(other.projection_columns);
+        self.join_keys.extend(other.join_keys);
+    }
+
+    /// Parse a query log summary, one `<kind> <col>[, <col>...]` per line where
+    /// kind is `filter`, `project`, or `join`. `#` starts a comment.
+    ///
+    /// ```text
+    /// filter event_date, user_id
+    /// project event_date, payload
+    /// join user_id
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut workload = Workload::default();
+        for (index, raw_line) in text.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (kind, columns) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let columns: Vec<String> = columns
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string)
+                .collect();
+            if columns.is_empty() {
+                bail!("invalid query log at line {}: missing columns", index + 1);
+            }
+            match kind {
+                "filter" => workload.filter_columns.extend(columns),
+                "project" => workload.projection_columns.extend(columns),
+                "join" => workload.join_keys.extend(columns),
+                _ => bail!(
+                    "invalid query log at line {}: unknown kind '{kind}', expected 'filter', 'project', or 'join'",
+                    index + 1
+                ),
+            }
+        }
+        Ok(workload)
+    }
+}
+
+/// Parquet numbers row groups with an i16 ordinal, so no file has more.
+const MAX_ROW_GROUP_INDEX: usize = i16::MAX as usize;
+
+/// Parse a row group list such as `0,5,17-20` (ranges are inclusive).
+pub fn parse_row_groups(text: &str) -> Result<Vec<usize>> {
+    let mut groups = Vec::new();
+    for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parse = |n: &str| {
+            let index = n
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| anyhow::anyhow!("invalid row group '{n}' in '{text}'"))?;
+            if index > MAX_ROW_GROUP_INDEX {
+                bail!("row group {index} is beyond the last possible one ({MAX_ROW_GROUP_INDEX})");
+            }
+            Ok(index)
+        };
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    bail!("invalid row group range '{part}': start is after end");
+                }
+                groups.extend(start..=end);
+            }
+            None => groups.push(parse(part)?),
+        }
+    }
+    if groups.is_empty() {
+        bail!("no row groups given");
+    }
+    groups.sort_unstable();
+    groups.dedup();
+    Ok(groups)
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?`
+/// any single character.
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), text.chars().collect());
+    // Position after the last `*` and the text position it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if let Some((after_star, tried)) = star {
+            p = after_star;
+            t = tried + 1;
+            star = Some((after_star, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn matches_any(names: &[String], path: &ColumnPath) -> bool {
+    let path = path.string();
+    names.iter().any(|name| {
+        path == *name
+            || path
+                .strip_prefix(name.as_str())
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_query_log() {
+        let workload = Workload::parse(
+            "# nightly report\nfilter event_date, user_id\nproject payload\njoin user_id\n",
+        )
+        .unwrap();
+        assert_eq!(workload.filter_columns, vec!["event_date", "user_id"]);
+        assert_eq!(workload.projection_columns, vec!["payload"]);
+        assert_eq!(workload.join_keys, vec!["user_id"]);
+    }
+
+    #[test]
+    fn parse_query_log_rejects_unknown_kind() {
+        let err = Workload::parse("group_by a").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn parse_row_group_lists_and_ranges() {
+        assert_eq!(
+            parse_row_groups("0,5,17-20").unwrap(),
+            [0, 5, 17, 18, 19, 20]
+        );
+        assert_eq!(parse_row_groups("3, 1-2,3").unwrap(), [1, 2, 3]);
+        assert!(parse_row_groups("4-2").is_err());
+        assert!(parse_row_groups("a").is_err());
+        assert!(parse_row_groups("0-18446744073709551615").is_err());
+    }
+
+    #[test]
+    fn ignored_columns_match_globs_and_nested_leaves() {
+        let options = LintOptions {
+            ignore_columns: vec!["debug_*".to_string(), "_raw".to_string()],
+            ..Default::default()
+        };
+        assert!(!options.selects(&ColumnPath::from("debug_trace")));
+        assert!(!options.selects(&ColumnPath::new(vec![
+            "_raw".to_string(),
+            "bytes".to_string()
+        ])));
+        assert!(options.selects(&ColumnPath::from("raw")));
+        assert!(options.selects(&ColumnPath::from("user_debug_id")));
+        assert!(glob_matches("a?c*", "abcdef"));
+        assert!(!glob_matches("a*z", "abc"));
+    }
+
+    #[test]
+    fn names_match_nested_leaves_but_not_siblings() {
+        let workload = Workload {
+            filter_columns: vec!["user".to_string()],
+            ..Default::default()
+        };
+        assert!(workload.is_filter_column(&ColumnPath::from("user")));
+        assert!(
+            workload.is_filter_column(&ColumnPath::new(vec!["user".to_string(), "id".to_string()]))
+        );
+        assert!(!workload.is_filter_column(&ColumnPath::from("user_id")));
+        assert!(!workload.touches(&ColumnPath::from("payload")));
+    }
+}
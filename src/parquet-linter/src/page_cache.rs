@@ -0,0 +1,306 @@
+//! Shared cache of column-chunk bytes. Callers declare the chunks they are
+//! about to read with [`PageCache::prefetch`], which issues one coalesced
+//! `get_byte_ranges` call instead of a request per chunk. The cache holds
+//! at most [`PageCache::set_memory_limit`] bytes, evicting the least
+//! recently read chunks first; an evicted chunk is fetched again on demand.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use bytes::Bytes;
+use parquet::arrow::async_reader::{AsyncFileReader, ParquetObjectReader};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::serialized_reader::SerializedPageReader;
+
+use crate::rule::ColumnChunk;
+
+/// Ranges closer than this are merged into one request.
+const COALESCE_GAP_BYTES: u64 = 1024 * 1024; // 1 MB
+
+/// Which part of a column chunk to fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkSpan {
+    /// Only the dictionary page (the whole chunk if its offset is unknown).
+    Dictionary,
+    /// Every page of the chunk.
+    Full,
+    /// At most this many bytes from the start of the chunk, for rules that
+    /// only read its first page.
+    Prefix(u64),
+}
+
+/// Default byte budget, matching `LintOptions::sample_memory_limit`.
+const DEFAULT_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// Cached chunk bytes, with the file offset they start at.
+struct CachedChunk {
+    start: u64,
+    bytes: Bytes,
+    last_used: u64,
+}
+
+/// Cached chunks per `(row_group, column)`, evicted least recently used
+/// first once they hold more than `limit` bytes.
+struct ChunkMap {
+    entries: HashMap<(usize, usize), CachedChunk>,
+    bytes: usize,
+    limit: usize,
+    clock: u64,
+}
+
+impl Default for ChunkMap {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            bytes: 0,
+            limit: DEFAULT_MEMORY_LIMIT,
+            clock: 0,
+        }
+    }
+}
+
+impl ChunkMap {
+    fn get(&mut self, key: (usize, usize), range: &Range<u64>) -> Option<(u64, Bytes)> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(&key)?;
+        if entry.start > range.start || range.end > entry.start + entry.bytes.len() as u64 {
+            return None;
+        }
+        entry.last_used = self.clock;
+        Some((entry.start, entry.bytes.clone()))
+    }
+
+    fn insert(&mut self, key: (usize, usize), start: u64, bytes: Bytes) {
+        self.clock += 1;
+        self.bytes += bytes.len();
+        let chunk = CachedChunk {
+            start,
+            bytes,
+            last_used: self.clock,
+        };
+        if let Some(old) = self.entries.insert(key, chunk) {
+            self.bytes -= old.bytes.len();
+        }
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.bytes > self.limit {
+            let Some(&oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, chunk)| chunk.last_used)
+                .map(|(key, _)| key)
+            else {
+                break;
+            };
+            let chunk = self.entries.remove(&oldest).expect("oldest key is cached");
+            self.bytes -= chunk.bytes.len();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PageCache {
+    reader: ParquetObjectReader,
+    metadata: Arc<ParquetMetaData>,
+    chunks: Arc<Mutex<ChunkMap>>,
+}
+
+impl PageCache {
+    pub fn new(reader: ParquetObjectReader, metadata: Arc<ParquetMetaData>) -> Self {
+        Self {
+            reader,
+            metadata,
+            chunks: Arc::default(),
+        }
+    }
+
+    /// Cap the cached bytes at `limit`, evicting least recently read chunks
+    /// to get under it.
+    pub fn set_memory_limit(&self, limit: usize) {
+        let mut chunks = self.chunks.lock().expect("page cache poisoned");
+        chunks.limit = limit;
+        chunks.evict();
+    }
+
+    /// Fetch every requested `(row_group, column, span)` that is not cached
+    /// yet, coalescing nearby byte ranges into as few requests as possible.
+    pub async fn prefetch(&self, requests: &[(usize, usize, ChunkSpan)]) -> Result<()> {
+        let mut missing: Vec<((usize, usize), Range<u64>)> = requests
+            .iter()
+            .map(|&(rg, col, span)| ((rg, col), self.span_range(rg, col, span)))
+            .filter(|(key, range)| self.cached(*key, range).is_none())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort_by_key(|(_, range)| range.start);
+
+        let merged = coalesce(missing.iter().map(|(_, range)| range.clone()));
+        let fetched = self.reader.clone().get_byte_ranges(merged.clone()).await?;
+
+        let mut chunks = self.chunks.lock().expect("page cache poisoned");
+        for (key, range) in missing {
+            let (block, data) = merged
+                .iter()
+                .zip(&fetched)
+                .find(|(block, _)| block.start <= range.start && range.end <= block.end)
+                .expect("coalesced ranges cover every request");
+            let local = (range.start - block.start) as usize..(range.end - block.start) as usize;
+            // Copy the chunk out so that evicting it frees its memory; a
+            // slice would keep the whole block, gaps included, alive.
+            chunks.insert(key, range.start, Bytes::copy_from_slice(&data[local]));
+        }
+        Ok(())
+    }
+
+    /// Page reader over a cached chunk, fetching it first if needed. With
+    /// `ChunkSpan::Dictionary` only the first page is readable, and with
+    /// `ChunkSpan::Prefix` only the pages that fit in it.
+    pub async fn page_reader(
+        &self,
+        rg: usize,
+        col: usize,
+        span: ChunkSpan,
+    ) -> Result<SerializedPageReader<ColumnChunk>> {
+        self.page_reader_with(rg, col, span, ReaderProperties::builder().build())
+            .await
+    }
+
+    /// Like [`PageCache::page_reader`], but decodes the statistics in data
+    /// page headers, which readers skip by default.
+    pub async fn page_reader_with_statistics(
+        &self,
+        rg: usize,
+        col: usize,
+        span: ChunkSpan,
+    ) -> Result<SerializedPageReader<ColumnChunk>> {
+        let props = ReaderProperties::builder()
+            .set_read_page_statistics(true)
+            .build();
+        self.page_reader_with(rg, col, span, props).await
+    }
+
+    /// Raw bytes of a chunk span, starting at its first page header,
+    /// fetching them first if needed.
+    pub async fn bytes(&self, rg: usize, col: usize, span: ChunkSpan) -> Result<Bytes> {
+        let range = self.span_range(rg, col, span);
+        let (start, bytes) = self.fetch(rg, col, &range).await?;
+        let local = (range.start - start) as usize..(range.end - start) as usize;
+        Ok(bytes.slice(local))
+    }
+
+    async fn fetch(&self, rg: usize, col: usize, range: &Range<u64>) -> Result<(u64, Bytes)> {
+        if let Some(hit) = self.cached((rg, col), range) {
+            return Ok(hit);
+        }
+        // Read the span directly rather than through the cache, which may
+        // evict it again before it is used when the budget is tight.
+        let bytes = self.reader.clone().get_bytes(range.clone()).await?;
+        self.chunks.lock().expect("page cache poisoned").insert(
+            (rg, col),
+            range.start,
+            bytes.clone(),
+        );
+        Ok((range.start, bytes))
+    }
+
+    async fn page_reader_with(
+        &self,
+        rg: usize,
+        col: usize,
+        span: ChunkSpan,
+        props: ReaderProperties,
+    ) -> Result<SerializedPageReader<ColumnChunk>> {
+        let range = self.span_range(rg, col, span);
+        let (start, bytes) = self.fetch(rg, col, &range).await?;
+        let row_group = self.metadata.row_group(rg);
+        Ok(SerializedPageReader::new_with_properties(
+            Arc::new(ColumnChunk::new(bytes, start)),
+            row_group.column(col),
+            row_group.num_rows() as usize,
+            None,
+            Arc::new(props),
+        )?)
+    }
+
+    fn span_range(&self, rg: usize, col: usize, span: ChunkSpan) -> Range<u64> {
+        let chunk = self.metadata.row_group(rg).column(col);
+        let (offset, length) = chunk.byte_range();
+        match (span, chunk.dictionary_page_offset()) {
+            (ChunkSpan::Dictionary, Some(dict)) if dict < chunk.data_page_offset() => {
+                dict as u64..chunk.data_page_offset() as u64
+            }
+            (ChunkSpan::Prefix(limit), _) => offset..offset + length.min(limit),
+            _ => offset..offset + length,
+        }
+    }
+
+    fn cached(&self, key: (usize, usize), range: &Range<u64>) -> Option<(u64, Bytes)> {
+        self.chunks
+            .lock()
+            .expect("page cache poisoned")
+            .get(key, range)
+    }
+}
+
+/// Merge sorted ranges whose gap is at most `COALESCE_GAP_BYTES`.
+fn coalesce(sorted: impl IntoIterator<Item = Range<u64>>) -> Vec<Range<u64>> {
+    let mut merged: Vec<Range<u64>> = Vec::new();
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end + COALESCE_GAP_BYTES => {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use super::*;
+    use crate::testing::TestFile;
+
+    #[test]
+    fn coalesce_merges_nearby_ranges() {
+        let far = 10 * COALESCE_GAP_BYTES;
+        assert_eq!(
+            coalesce([0..10, 20..30, 25..28, far..far + 5]),
+            vec![0..30, far..far + 5]
+        );
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_read_chunks_over_the_limit() -> Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int64Array::from_iter_values(0..10_000)) as _),
+            ("b", Arc::new(Int64Array::from_iter_values(0..10_000)) as _),
+        ])?;
+        let file = TestFile::new(batch).open().await?;
+        let pages = &file.pages;
+        let size = |col: usize| file.metadata.row_group(0).column(col).byte_range().1 as usize;
+        pages.set_memory_limit(size(0).max(size(1)));
+
+        pages
+            .prefetch(&[(0, 0, ChunkSpan::Full), (0, 1, ChunkSpan::Full)])
+            .await?;
+        let cached = |col: usize| {
+            let chunks = pages.chunks.lock().unwrap();
+            chunks.entries.contains_key(&(0, col))
+        };
+        assert!(!cached(0) && cached(1));
+
+        // An evicted chunk is read again, and evicts the colder one in turn.
+        assert_eq!(pages.bytes(0, 0, ChunkSpan::Full).await?.len(), size(0));
+        assert!(cached(0) && !cached(1));
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! Fix plans: the directives a set of diagnostics prescribes, each kept
+//! with the rule and message that asked for it, so a rewrite can explain
+//! itself instead of applying an anonymous merged prescription.
+
+use std::fmt;
+
+use parquet::file::metadata::ParquetMetaData;
+use serde_json::{Value, json};
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::prescription::{Directive, Prescription};
+
+#[derive(Debug, Clone)]
+pub struct FixStep {
+    pub directive: Directive,
+    pub rule_name: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// Compressed bytes the directive rewrites: its column across all row
+    /// groups, or the whole file for file-level settings.
+    pub affected_bytes: i64,
+}
+
+/// Steps in diagnostic order, which is also the order `prescription`
+/// applies them in, so later steps win on conflict.
+#[derive(Debug, Clone, Default)]
+pub struct FixPlan {
+    pub steps: Vec<FixStep>,
+    /// Compressed size of the whole file, for relating `affected_bytes`.
+    pub file_bytes: i64,
+}
+
+impl FixPlan {
+    pub fn new<'a>(
+        diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+        metadata: &ParquetMetaData,
+    ) -> Self {
+        let chunks = || metadata.row_groups().iter().flat_map(|rg| rg.columns());
+        let file_bytes = chunks().map(|c| c.compressed_size()).sum();
+        let steps = diagnostics
+            .into_iter()
+            .flat_map(|d| {
+                d.prescription.directives().iter().map(|directive| FixStep {
+                    directive: directive.clone(),
+                    rule_name: d.rule_name,
+                    severity: d.severity,
+                    message: d.message.clone(),
+                    affected_bytes: match directive.column() {
+                        Some(path) => chunks()
+                            .filter(|c| c.column_path() == path)
+                            .map(|c| c.compressed_size())
+                            .sum(),
+                        None => file_bytes,
+                    },
+                })
+            })
+            .collect();
+        Self { steps, file_bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The directives alone, in plan order.
+    pub fn prescription(&self) -> Prescription {
+        let mut prescription = Prescription::new();
+        for step in &self.steps {
+            prescription.push(step.directive.clone());
+        }
+        prescription
+    }
+
+    pub fn to_json(&self) -> Value {
+        let steps: Vec<Value> = self
+            .steps
+            .iter()
+            .map(|step| {
+                json!({
+                    "directive": step.directive.to_string(),
+                    "rule_name": step.rule_name,
+                    "severity": step.severity.to_string(),
+                    "message": step.message,
+                    "affected_bytes": step.affected_bytes,
+                })
+            })
+            .collect();
+        json!({ "file_bytes": self.file_bytes, "steps": steps })
+    }
+}
+
+impl fmt::Display for FixPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let share = if self.file_bytes == 0 {
+                0.0
+            } else {
+                step.affected_bytes as f64 / self.file_bytes as f64 * 100.0
+            };
+            writeln!(f, "{}", step.directive)?;
+            write!(
+                f,
+                "    {} ({share:.0}% of file): {}",
+                step.rule_name, step.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use parquet::schema::types::ColumnPath;
+
+    use crate::diagnostic::{Category, Location};
+    use crate::prescription::Codec;
+
+    #[test]
+    fn steps_keep_their_rule_and_column_bytes() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+            ("b", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+        ])?;
+        let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), None)?;
+        writer.write(&batch)?;
+        let metadata = writer.close()?;
+
+        let mut prescription = Prescription::new();
+        prescription.push(Directive::SetColumnCompression(
+            ColumnPath::from("a"),
+            Codec::Zstd(3),
+        ));
+        prescription.push(Directive::SetFileMaxRowGroupSize(1024));
+        let diagnostic = Diagnostic {
+            rule_name: "compression-codec-upgrade",
+            severity: Severity::Warning,
+            category: Category::Performance,
+            location: Location::File,
+            message: "switch codec".to_string(),
+            prescription,
+            related: Vec::new(),
+        };
+
+        let plan = FixPlan::new([&diagnostic], &metadata);
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[0].rule_name, "compression-codec-upgrade");
+        assert!(plan.steps[0].affected_bytes < plan.file_bytes);
+        assert_eq!(plan.steps[1].affected_bytes, plan.file_bytes);
+        assert_eq!(
+            plan.prescription().to_string(),
+            diagnostic.prescription.to_string()
+        );
+        assert_eq!(plan.to_json()["steps"][0]["severity"], "warning");
+        Ok(())
+    }
+}
@@ -2,8 +2,14 @@ use std::collections::HashMap;
 use std::fmt;
 
 use parquet::basic::{BrotliLevel, Compression, Encoding, GzipLevel, ZstdLevel};
-use parquet::file::properties::{EnabledStatistics, WriterPropertiesBuilder};
+use parquet::file::properties::{
+    DEFAULT_STATISTICS_TRUNCATE_LENGTH, EnabledStatistics, WriterPropertiesBuilder,
+};
 use parquet::schema::types::ColumnPath;
+use serde::{Serialize, Serializer};
+use serde_json::{Value, json};
+
+use crate::options::glob_matches;
 
 /// Compression codec - excludes deprecated LZ4 and unsupported LZO.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,6 +35,23 @@ impl fmt::Display for Codec {
     }
 }
 
+impl Codec {
+    /// The codec a chunk was written with, if a prescription can express it.
+    /// Footers do not record levels, so leveled codecs get common defaults.
+    /// Deprecated LZ4 maps to LZ4_RAW; LZO has no equivalent.
+    pub fn from_compression(compression: Compression) -> Option<Self> {
+        Some(match compression {
+            Compression::UNCOMPRESSED => Codec::Uncompressed,
+            Compression::SNAPPY => Codec::Snappy,
+            Compression::GZIP(_) => Codec::Gzip(6),
+            Compression::BROTLI(_) => Codec::Brotli(5),
+            Compression::ZSTD(_) => Codec::Zstd(3),
+            Compression::LZ4 | Compression::LZ4_RAW => Codec::Lz4Raw,
+            Compression::LZO => return None,
+        })
+    }
+}
+
 impl From<Codec> for Compression {
     fn from(value: Codec) -> Self {
         match value {
@@ -70,6 +93,21 @@ impl fmt::Display for DataEncoding {
     }
 }
 
+impl DataEncoding {
+    /// The data encoding a prescription can request for `encoding`; level
+    /// and dictionary encodings have none.
+    pub fn from_encoding(encoding: Encoding) -> Option<Self> {
+        Some(match encoding {
+            Encoding::PLAIN => DataEncoding::Plain,
+            Encoding::DELTA_BINARY_PACKED => DataEncoding::DeltaBinaryPacked,
+            Encoding::DELTA_LENGTH_BYTE_ARRAY => DataEncoding::DeltaLengthByteArray,
+            Encoding::DELTA_BYTE_ARRAY => DataEncoding::DeltaByteArray,
+            Encoding::BYTE_STREAM_SPLIT => DataEncoding::ByteStreamSplit,
+            _ => return None,
+        })
+    }
+}
+
 impl From<DataEncoding> for Encoding {
     fn from(value: DataEncoding) -> Self {
         match value {
@@ -82,6 +120,43 @@ impl From<DataEncoding> for Encoding {
     }
 }
 
+/// Physical type a rewrite casts a column to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastType {
+    /// From BYTE_ARRAY whose values all have this length.
+    FixedLenByteArray(usize),
+}
+
+impl fmt::Display for CastType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CastType::FixedLenByteArray(length) => write!(f, "fixed_len_byte_array({length})"),
+        }
+    }
+}
+
+/// Arrow schema a rewrite embeds in the output's `ARROW:schema` metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowSchemaMode {
+    /// Keep the input's embedded Arrow schema, byte for byte unless another
+    /// directive changes types.
+    Preserve,
+    /// Drop its types for the ones the Parquet schema implies.
+    Normalize,
+    /// Write no embedded Arrow schema.
+    Strip,
+}
+
+impl fmt::Display for ArrowSchemaMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrowSchemaMode::Preserve => write!(f, "preserve"),
+            ArrowSchemaMode::Normalize => write!(f, "normalize"),
+            ArrowSchemaMode::Strip => write!(f, "strip"),
+        }
+    }
+}
+
 /// Statistics level.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatisticsConfig {
@@ -110,13 +185,43 @@ impl From<StatisticsConfig> for EnabledStatistics {
     }
 }
 
+/// One column of a sort order. Nulls sort first in either direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    /// A top-level column.
+    pub column: ColumnPath,
+    pub descending: bool,
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = if self.descending { "desc" } else { "asc" };
+        write!(f, "{}:{direction}", column_text(&self.column))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Directive {
     // File-scope
     SetFileCompression(Codec),
     SetFileMaxRowGroupSize(usize),
     SetFileDataPageSizeLimit(usize),
+    SetFileDataPageRowCountLimit(usize),
+    /// Rows the writer hands to each column writer at a time; page size
+    /// limits are only checked between such batches.
+    SetFileWriteBatchSize(usize),
+    /// Not a writer property: rows per batch rewrites read the input in.
+    SetFileReadBatchSize(usize),
     SetFileStatisticsTruncateLength(Option<usize>),
+    /// Not a writer property: decides the schema rewrites read the input with.
+    SetFileArrowSchema(ArrowSchemaMode),
+    /// Not a writer property: rewrites sort each row group by these columns
+    /// and declare the order in its `sorting_columns`.
+    SetFileSortingColumns(Vec<SortKey>),
+    /// Not a writer property: rewrites sort every row of the file by these
+    /// columns, which also becomes the declared `sorting_columns` unless
+    /// that is set too.
+    SetFileSortBy(Vec<SortKey>),
 
     // Column-scope
     SetColumnCompression(ColumnPath, Codec),
@@ -124,24 +229,98 @@ pub enum Directive {
     SetColumnDictionary(ColumnPath, bool),
     SetColumnDictionaryPageSizeLimit(ColumnPath, usize),
     SetColumnStatistics(ColumnPath, StatisticsConfig),
+    /// parquet-rs only has a file-wide truncate length; applying widens it
+    /// to the longest length any column asks for.
+    SetColumnStatisticsTruncateLength(ColumnPath, Option<usize>),
     SetColumnBloomFilter(ColumnPath, bool),
     SetColumnBloomFilterNdv(ColumnPath, u64),
     SetColumnBloomFilterFpp(ColumnPath, f64),
+    /// Not a writer property: rewrites change the column's schema, and
+    /// fail if it does hold nulls. Only top-level columns can change.
+    SetColumnNullable(ColumnPath, bool),
+    /// Also applied by rewrites to the schema, to top-level columns only.
+    SetColumnCast(ColumnPath, CastType),
+
+    // Rule-scope
+    /// Not a writer property: pins a rule's threshold parameter (rule name,
+    /// parameter name, value) for lint runs seeded from the prescription.
+    /// Rewrites ignore it.
+    ConfigureRule(String, String, f64),
 }
 
 impl Directive {
     fn column_text(column: &ColumnPath) -> String {
-        column.string()
+        column_text(column)
+    }
+
+    /// The column a column-scope directive applies to.
+    pub fn column(&self) -> Option<&ColumnPath> {
+        match self {
+            Directive::SetFileCompression(_)
+            | Directive::SetFileMaxRowGroupSize(_)
+            | Directive::SetFileDataPageSizeLimit(_)
+            | Directive::SetFileDataPageRowCountLimit(_)
+            | Directive::SetFileWriteBatchSize(_)
+            | Directive::SetFileReadBatchSize(_)
+            | Directive::SetFileStatisticsTruncateLength(_)
+            | Directive::SetFileArrowSchema(_)
+            | Directive::SetFileSortingColumns(_)
+            | Directive::SetFileSortBy(_)
+            | Directive::ConfigureRule(..) => None,
+            Directive::SetColumnCompression(col, _)
+            | Directive::SetColumnEncoding(col, _)
+            | Directive::SetColumnDictionary(col, _)
+            | Directive::SetColumnDictionaryPageSizeLimit(col, _)
+            | Directive::SetColumnStatistics(col, _)
+            | Directive::SetColumnStatisticsTruncateLength(col, _)
+            | Directive::SetColumnBloomFilter(col, _)
+            | Directive::SetColumnBloomFilterNdv(col, _)
+            | Directive::SetColumnBloomFilterFpp(col, _)
+            | Directive::SetColumnNullable(col, _)
+            | Directive::SetColumnCast(col, _) => Some(col),
+        }
+    }
+
+    /// The same setting applied to `column` instead (file-scope directives
+    /// are returned unchanged).
+    pub fn for_column(&self, column: &ColumnPath) -> Directive {
+        let col = column.clone();
+        match self {
+            Directive::SetColumnCompression(_, v) => Directive::SetColumnCompression(col, *v),
+            Directive::SetColumnEncoding(_, v) => Directive::SetColumnEncoding(col, *v),
+            Directive::SetColumnDictionary(_, v) => Directive::SetColumnDictionary(col, *v),
+            Directive::SetColumnDictionaryPageSizeLimit(_, v) => {
+                Directive::SetColumnDictionaryPageSizeLimit(col, *v)
+            }
+            Directive::SetColumnStatistics(_, v) => Directive::SetColumnStatistics(col, *v),
+            Directive::SetColumnStatisticsTruncateLength(_, v) => {
+                Directive::SetColumnStatisticsTruncateLength(col, *v)
+            }
+            Directive::SetColumnBloomFilter(_, v) => Directive::SetColumnBloomFilter(col, *v),
+            Directive::SetColumnBloomFilterNdv(_, v) => Directive::SetColumnBloomFilterNdv(col, *v),
+            Directive::SetColumnBloomFilterFpp(_, v) => Directive::SetColumnBloomFilterFpp(col, *v),
+            Directive::SetColumnNullable(_, v) => Directive::SetColumnNullable(col, *v),
+            Directive::SetColumnCast(_, v) => Directive::SetColumnCast(col, *v),
+            file_scope => file_scope.clone(),
+        }
     }
 
-    fn conflict_key(&self) -> String {
+    pub(crate) fn conflict_key(&self) -> String {
         match self {
             Directive::SetFileCompression(_) => "file compression".to_string(),
             Directive::SetFileMaxRowGroupSize(_) => "file max_row_group_size".to_string(),
             Directive::SetFileDataPageSizeLimit(_) => "file data_page_size_limit".to_string(),
+            Directive::SetFileDataPageRowCountLimit(_) => {
+                "file data_page_row_count_limit".to_string()
+            }
+            Directive::SetFileWriteBatchSize(_) => "file write_batch_size".to_string(),
+            Directive::SetFileReadBatchSize(_) => "file read_batch_size".to_string(),
             Directive::SetFileStatisticsTruncateLength(_) => {
                 "file statistics_truncate_length".to_string()
             }
+            Directive::SetFileArrowSchema(_) => "file arrow_schema".to_string(),
+            Directive::SetFileSortingColumns(_) => "file sorting_columns".to_string(),
+            Directive::SetFileSortBy(_) => "file sort_by".to_string(),
             Directive::SetColumnCompression(col, _) => {
                 format!("column {} compression", Self::column_text(col))
             }
@@ -160,6 +339,12 @@ impl Directive {
             Directive::SetColumnStatistics(col, _) => {
                 format!("column {} statistics", Self::column_text(col))
             }
+            Directive::SetColumnStatisticsTruncateLength(col, _) => {
+                format!(
+                    "column {} statistics_truncate_length",
+                    Self::column_text(col)
+                )
+            }
             Directive::SetColumnBloomFilter(col, _) => {
                 format!("column {} bloom_filter", Self::column_text(col))
             }
@@ -169,15 +354,58 @@ impl Directive {
             Directive::SetColumnBloomFilterFpp(col, _) => {
                 format!("column {} bloom_filter_fpp", Self::column_text(col))
             }
+            Directive::SetColumnNullable(col, _) => {
+                format!("column {} nullable", Self::column_text(col))
+            }
+            Directive::SetColumnCast(col, _) => {
+                format!("column {} cast", Self::column_text(col))
+            }
+            Directive::ConfigureRule(rule, parameter, _) => format!("rule {rule} {parameter}"),
         }
     }
 
-    fn conflict_value(&self) -> String {
+    /// The setting a directive changes, independent of scope and column.
+    pub fn setting(&self) -> &'static str {
+        match self {
+            Directive::SetFileCompression(_) | Directive::SetColumnCompression(..) => "compression",
+            Directive::SetFileMaxRowGroupSize(_) => "max_row_group_size",
+            Directive::SetFileDataPageSizeLimit(_) => "data_page_size_limit",
+            Directive::SetFileDataPageRowCountLimit(_) => "data_page_row_count_limit",
+            Directive::SetFileWriteBatchSize(_) => "write_batch_size",
+            Directive::SetFileReadBatchSize(_) => "read_batch_size",
+            Directive::SetFileArrowSchema(_) => "arrow_schema",
+            Directive::SetFileSortingColumns(_) => "sorting_columns",
+            Directive::SetFileSortBy(_) => "sort_by",
+            Directive::SetFileStatisticsTruncateLength(_)
+            | Directive::SetColumnStatisticsTruncateLength(..) => "statistics_truncate_length",
+            Directive::SetColumnEncoding(..) => "encoding",
+            Directive::SetColumnDictionary(..) => "dictionary",
+            Directive::SetColumnDictionaryPageSizeLimit(..) => "dictionary_page_size_limit",
+            Directive::SetColumnStatistics(..) => "statistics",
+            Directive::SetColumnBloomFilter(..) => "bloom_filter",
+            Directive::SetColumnBloomFilterNdv(..) => "bloom_filter_ndv",
+            Directive::SetColumnBloomFilterFpp(..) => "bloom_filter_fpp",
+            Directive::SetColumnNullable(..) => "nullable",
+            Directive::SetColumnCast(..) => "cast",
+            Directive::ConfigureRule(..) => "configure",
+        }
+    }
+
+    /// The new value as it appears in the DSL.
+    pub fn value(&self) -> String {
         match self {
             Directive::SetFileCompression(v) => v.to_string(),
             Directive::SetFileMaxRowGroupSize(v) => v.to_string(),
             Directive::SetFileDataPageSizeLimit(v) => v.to_string(),
-            Directive::SetFileStatisticsTruncateLength(v) => match v {
+            Directive::SetFileDataPageRowCountLimit(v) => v.to_string(),
+            Directive::SetFileWriteBatchSize(v) => v.to_string(),
+            Directive::SetFileReadBatchSize(v) => v.to_string(),
+            Directive::SetFileArrowSchema(v) => v.to_string(),
+            Directive::SetFileSortingColumns(keys) | Directive::SetFileSortBy(keys) => {
+                sort_keys_text(keys)
+            }
+            Directive::SetFileStatisticsTruncateLength(v)
+            | Directive::SetColumnStatisticsTruncateLength(_, v) => match v {
                 Some(v) => v.to_string(),
                 None => "none".to_string(),
             },
@@ -189,7 +417,40 @@ impl Directive {
             Directive::SetColumnBloomFilter(_, v) => v.to_string(),
             Directive::SetColumnBloomFilterNdv(_, v) => v.to_string(),
             Directive::SetColumnBloomFilterFpp(_, v) => v.to_string(),
+            Directive::SetColumnNullable(_, v) => v.to_string(),
+            Directive::SetColumnCast(_, v) => v.to_string(),
+            Directive::ConfigureRule(_, _, v) => v.to_string(),
+        }
+    }
+
+    /// JSON object for one directive (the lint output schema's `directive`
+    /// definition).
+    pub fn to_json(&self) -> Value {
+        let scope = match self {
+            Directive::ConfigureRule(..) => "rule",
+            _ if self.column().is_some() => "column",
+            _ => "file",
+        };
+        let mut directive = json!({
+            "scope": scope,
+            "setting": self.setting(),
+            "value": self.value(),
+            "text": self.to_string(),
+        });
+        if let Some(column) = self.column() {
+            directive["column"] = json!(column.parts());
         }
+        if let Directive::ConfigureRule(rule, parameter, _) = self {
+            directive["rule"] = json!(rule);
+            directive["parameter"] = json!(parameter);
+        }
+        directive
+    }
+}
+
+impl Serialize for Directive {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
     }
 }
 
@@ -201,12 +462,24 @@ impl fmt::Display for Directive {
             Directive::SetFileDataPageSizeLimit(n) => {
                 write!(f, "set file data_page_size_limit {n}")
             }
+            Directive::SetFileDataPageRowCountLimit(n) => {
+                write!(f, "set file data_page_row_count_limit {n}")
+            }
+            Directive::SetFileWriteBatchSize(n) => write!(f, "set file write_batch_size {n}"),
+            Directive::SetFileReadBatchSize(n) => write!(f, "set file read_batch_size {n}"),
             Directive::SetFileStatisticsTruncateLength(Some(n)) => {
                 write!(f, "set file statistics_truncate_length {n}")
             }
             Directive::SetFileStatisticsTruncateLength(None) => {
                 write!(f, "set file statistics_truncate_length none")
             }
+            Directive::SetFileArrowSchema(mode) => write!(f, "set file arrow_schema {mode}"),
+            Directive::SetFileSortingColumns(keys) => {
+                write!(f, "set file sorting_columns {}", sort_keys_text(keys))
+            }
+            Directive::SetFileSortBy(keys) => {
+                write!(f, "set file sort_by {}", sort_keys_text(keys))
+            }
             Directive::SetColumnCompression(col, c) => {
                 write!(f, "set column {} compression {c}", Self::column_text(col))
             }
@@ -230,6 +503,14 @@ impl fmt::Display for Directive {
                     Self::column_text(col)
                 )
             }
+            Directive::SetColumnStatisticsTruncateLength(col, length) => {
+                let length = length.map_or("none".to_string(), |n| n.to_string());
+                write!(
+                    f,
+                    "set column {} statistics_truncate_length {length}",
+                    Self::column_text(col)
+                )
+            }
             Directive::SetColumnBloomFilter(col, enabled) => {
                 write!(
                     f,
@@ -251,6 +532,19 @@ impl fmt::Display for Directive {
                     Self::column_text(col)
                 )
             }
+            Directive::SetColumnNullable(col, nullable) => {
+                write!(
+                    f,
+                    "set column {} nullable {nullable}",
+                    Self::column_text(col)
+                )
+            }
+            Directive::SetColumnCast(col, cast) => {
+                write!(f, "set column {} cast {cast}", Self::column_text(col))
+            }
+            Directive::ConfigureRule(rule, parameter, value) => {
+                write!(f, "configure rule {rule} {parameter} {value}")
+            }
         }
     }
 }
@@ -271,6 +565,16 @@ impl Prescription {
         &self.0
     }
 
+    /// The DSL on one `;`-separated line, which `parse` reads back. It is not
+    /// quoted for a shell.
+    pub fn to_inline(&self) -> String {
+        self.0
+            .iter()
+            .map(Directive::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -279,17 +583,32 @@ impl Prescription {
         self.0.extend(other.0);
     }
 
-    pub fn parse(text: &str) -> Result<Self, ParseError> {
-        let mut prescription = Prescription::new();
+    /// Keep only the directives `keep` accepts.
+    pub fn retain(&mut self, keep: impl FnMut(&Directive) -> bool) {
+        self.0.retain(keep);
+    }
 
-        for (index, raw_line) in text.lines().enumerate() {
-            let line_no = index + 1;
-            let line = raw_line.split('#').next().unwrap_or("").trim();
-            if line.is_empty() {
-                continue;
+    /// Parse prescription text given as raw bytes, e.g. a request body.
+    /// Like `parse`, this never panics, and neither does `apply` on the
+    /// result: values the `parquet` writer would reject are parse errors.
+    pub fn parse_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::parse(text),
+            Err(err) => {
+                let line = bytes[..err.valid_up_to()]
+                    .iter()
+                    .filter(|&&b| b == b'\n')
+                    .count();
+                Err(ParseError::new(line + 1, "prescription is not valid UTF-8"))
             }
+        }
+    }
 
-            let directive = parse_directive(line, line_no)?;
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut prescription = Prescription::new();
+
+        for (line_no, statement) in statements(text) {
+            let directive = parse_directive(statement, line_no)?;
             prescription.push(directive);
         }
 
@@ -301,7 +620,7 @@ impl Prescription {
 
         for directive in &self.0 {
             let key = directive.conflict_key();
-            let value = directive.conflict_value();
+            let value = directive.value();
             let text = directive.to_string();
 
             if let Some((first_value, first_text)) = seen.get(&key) {
@@ -321,6 +640,8 @@ impl Prescription {
     }
 
     pub fn apply(&self, mut builder: WriterPropertiesBuilder) -> WriterPropertiesBuilder {
+        let mut file_truncate_length = DEFAULT_STATISTICS_TRUNCATE_LENGTH;
+        let mut column_truncate_length = None;
         for directive in &self.0 {
             builder = match directive {
                 Directive::SetFileCompression(codec) => builder.set_compression((*codec).into()),
@@ -328,7 +649,12 @@ impl Prescription {
                 Directive::SetFileDataPageSizeLimit(bytes) => {
                     builder.set_data_page_size_limit(*bytes)
                 }
+                Directive::SetFileDataPageRowCountLimit(rows) => {
+                    builder.set_data_page_row_count_limit(*rows)
+                }
+                Directive::SetFileWriteBatchSize(rows) => builder.set_write_batch_size(*rows),
                 Directive::SetFileStatisticsTruncateLength(length) => {
+                    file_truncate_length = *length;
                     builder.set_statistics_truncate_length(*length)
                 }
                 Directive::SetColumnCompression(col, codec) => {
@@ -346,6 +672,13 @@ impl Prescription {
                 Directive::SetColumnStatistics(col, stats) => {
                     builder.set_column_statistics_enabled(col.clone(), (*stats).into())
                 }
+                Directive::SetColumnStatisticsTruncateLength(_, length) => {
+                    column_truncate_length = Some(match column_truncate_length {
+                        Some(widest) => wider_truncate_length(widest, *length),
+                        None => *length,
+                    });
+                    builder
+                }
                 Directive::SetColumnBloomFilter(col, enabled) => {
                     builder.set_column_bloom_filter_enabled(col.clone(), *enabled)
                 }
@@ -355,12 +688,44 @@ impl Prescription {
                 Directive::SetColumnBloomFilterFpp(col, fpp) => {
                     builder.set_column_bloom_filter_fpp(col.clone(), *fpp)
                 }
+                Directive::SetFileReadBatchSize(_)
+                | Directive::SetFileArrowSchema(_)
+                | Directive::SetFileSortingColumns(_)
+                | Directive::SetFileSortBy(_)
+                | Directive::SetColumnNullable(..)
+                | Directive::SetColumnCast(..)
+                | Directive::ConfigureRule(..) => builder,
+            }
+        }
+        if let Some(length) = column_truncate_length {
+            let widest = wider_truncate_length(file_truncate_length, length);
+            if widest != file_truncate_length {
+                builder = builder.set_statistics_truncate_length(widest);
             }
         }
         builder
     }
 }
 
+fn sort_keys_text(keys: &[SortKey]) -> String {
+    keys.iter()
+        .map(SortKey::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The longer of two truncate lengths, where `None` means untruncated.
+fn wider_truncate_length(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    a.zip(b).map(|(a, b)| a.max(b))
+}
+
+/// Serializes as the list of its directives.
+impl Serialize for Prescription {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
 impl fmt::Display for Prescription {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (index, directive) in self.0.iter().enumerate() {
@@ -373,6 +738,99 @@ impl fmt::Display for Prescription {
     }
 }
 
+/// A prescription for a whole dataset. Directives before the first section
+/// apply to every file; each `file "<glob>":` section adds directives for
+/// the files it matches.
+///
+/// ```text
+/// set file compression zstd(3)
+///
+/// file "logs/*.parquet":
+///     set column message encoding delta_byte_array
+/// file "events/*":
+///     set file max_row_group_size 1048576
+/// ```
+///
+/// A plain prescription parses as a dataset prescription without sections.
+#[derive(Debug, Clone, Default)]
+pub struct DatasetPrescription {
+    pub common: Prescription,
+    pub sections: Vec<FileSection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileSection {
+    /// Glob where `*` matches any run of characters (including `/`) and `?`
+    /// one character.
+    pub pattern: String,
+    pub prescription: Prescription,
+}
+
+impl FileSection {
+    /// Whether the pattern matches `path` or any of its `/`-separated
+    /// suffixes, so `logs/*.parquet` matches `s3://bucket/logs/a.parquet`.
+    pub fn matches(&self, path: &str) -> bool {
+        std::iter::once(path)
+            .chain(path.match_indices('/').map(|(i, _)| &path[i + 1..]))
+            .any(|suffix| glob_matches(&self.pattern, suffix))
+    }
+}
+
+impl DatasetPrescription {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut dataset = DatasetPrescription::default();
+
+        for (line_no, line) in statements(text) {
+            if let Some(pattern) = parse_section_header(line, line_no)? {
+                dataset.sections.push(FileSection {
+                    pattern,
+                    prescription: Prescription::new(),
+                });
+                continue;
+            }
+            let directive = parse_directive(line, line_no)?;
+            match dataset.sections.last_mut() {
+                Some(_) if matches!(directive, Directive::ConfigureRule(..)) => {
+                    return Err(ParseError::new(
+                        line_no,
+                        "configure directives apply to every file and must precede file sections",
+                    ));
+                }
+                Some(section) => section.prescription.push(directive),
+                None => dataset.common.push(directive),
+            }
+        }
+
+        Ok(dataset)
+    }
+
+    /// The first section matching `path`.
+    pub fn section(&self, path: &str) -> Option<&FileSection> {
+        self.sections.iter().find(|section| section.matches(path))
+    }
+
+    /// Common directives followed by those of the first matching section, so
+    /// section directives win on conflict.
+    pub fn for_file(&self, path: &str) -> Prescription {
+        let mut prescription = self.common.clone();
+        if let Some(section) = self.section(path) {
+            prescription.extend(section.prescription.clone());
+        }
+        prescription
+    }
+}
+
+impl fmt::Display for DatasetPrescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines: Vec<String> = self.common.0.iter().map(ToString::to_string).collect();
+        for section in &self.sections {
+            lines.push(format!("file \"{}\":", section.pattern));
+            lines.extend(section.prescription.0.iter().map(|d| format!("    {d}")));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
 #[derive(Debug)]
 pub struct ConflictError {
     pub key: String,
@@ -474,13 +932,90 @@ impl From<ConflictError> for PrescriptionError {
     }
 }
 
+/// Non-empty statements of DSL text with their line numbers. Statements end
+/// at a newline or `;`, and `#` comments out the rest of a line, unless
+/// either is inside a quoted name.
+fn statements(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines().enumerate().flat_map(|(index, line)| {
+        let line = unquoted(line, |c| c == '#')
+            .first()
+            .map_or(line, |&i| &line[..i]);
+        split_unquoted(line, |c| c == ';')
+            .into_iter()
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(move |statement| (index + 1, statement))
+    })
+}
+
+/// Byte offsets of the characters `is_delimiter` accepts in `text`, outside
+/// double quotes. Inside quotes, a backslash escapes the next character.
+fn unquoted(text: &str, is_delimiter: impl Fn(char) -> bool) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let (mut quoted, mut escaped) = (false, false);
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if !quoted && is_delimiter(c) => offsets.push(i),
+            _ => {}
+        }
+    }
+    offsets
+}
+
+/// `text` split at the delimiters `unquoted` finds.
+fn split_unquoted(text: &str, is_delimiter: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in unquoted(text, is_delimiter) {
+        parts.push(&text[start..i]);
+        // Skip the delimiter, which may be a multi-byte space.
+        start = i + text[i..].chars().next().map_or(1, char::len_utf8);
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// A column path as written in directives: parts joined by `.`, each one
+/// double-quoted, with `"` and `\` escaped, if it is empty or holds a
+/// character the DSL splits on.
+fn column_text(column: &ColumnPath) -> String {
+    column
+        .parts()
+        .iter()
+        .map(|part| {
+            let plain = !part.is_empty()
+                && !part
+                    .chars()
+                    .any(|c| c.is_whitespace() || ".;#\",:\\".contains(c));
+            if plain {
+                part.clone()
+            } else {
+                format!("\"{}\"", part.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
 fn parse_directive(line: &str, line_no: usize) -> Result<Directive, ParseError> {
-    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let tokens: Vec<&str> = split_unquoted(line, char::is_whitespace)
+        .into_iter()
+        .filter(|token| !token.is_empty())
+        .collect();
     let Some(head) = tokens.first() else {
         return Err(ParseError::new(line_no, "empty directive"));
     };
+    if *head == "configure" {
+        return parse_configure_directive(&tokens, line_no);
+    }
     if *head != "set" {
-        return Err(ParseError::new(line_no, "directive must start with 'set'"));
+        return Err(ParseError::new(
+            line_no,
+            "directive must start with 'set' or 'configure'",
+        ));
     }
 
     let scope = tokens
@@ -497,6 +1032,50 @@ fn parse_directive(line: &str, line_no: usize) -> Result<Directive, ParseError>
     }
 }
 
+/// `configure rule <rule> <parameter> <value>`. Rule and parameter names
+/// are checked when the directive seeds `Thresholds`, not here.
+fn parse_configure_directive(tokens: &[&str], line_no: usize) -> Result<Directive, ParseError> {
+    if tokens.len() != 5 || tokens[1] != "rule" {
+        return Err(ParseError::new(
+            line_no,
+            "configure directive must be: configure rule <rule> <parameter> <value>",
+        ));
+    }
+    let value = tokens[4]
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .ok_or_else(|| {
+            ParseError::new(
+                line_no,
+                format!("invalid number for {} ('{}')", tokens[3], tokens[4]),
+            )
+        })?;
+    Ok(Directive::ConfigureRule(
+        tokens[2].to_string(),
+        tokens[3].to_string(),
+        value,
+    ))
+}
+
+/// `file "<glob>":` starts a section; any other line is a directive.
+fn parse_section_header(line: &str, line_no: usize) -> Result<Option<String>, ParseError> {
+    let Some(rest) = line.strip_prefix("file") else {
+        return Ok(None);
+    };
+    let rest = rest.trim();
+    let pattern = rest
+        .strip_suffix(':')
+        .map(str::trim)
+        .and_then(|quoted| quoted.strip_prefix('"'))
+        .and_then(|quoted| quoted.strip_suffix('"'))
+        .ok_or_else(|| ParseError::new(line_no, "file section must be: file \"<glob>\":"))?;
+    if pattern.is_empty() {
+        return Err(ParseError::new(line_no, "empty file pattern"));
+    }
+    Ok(Some(pattern.to_string()))
+}
+
 fn parse_file_directive(tokens: &[&str], line_no: usize) -> Result<Directive, ParseError> {
     if tokens.len() != 4 {
         return Err(ParseError::new(
@@ -511,20 +1090,25 @@ fn parse_file_directive(tokens: &[&str], line_no: usize) -> Result<Directive, Pa
     match property {
         "compression" => parse_codec(value, line_no).map(Directive::SetFileCompression),
         "max_row_group_size" => {
-            parse_usize(value, line_no, property).map(Directive::SetFileMaxRowGroupSize)
+            parse_positive(value, line_no, property).map(Directive::SetFileMaxRowGroupSize)
         }
         "data_page_size_limit" => {
             parse_usize(value, line_no, property).map(Directive::SetFileDataPageSizeLimit)
         }
-        "statistics_truncate_length" => {
-            if value == "none" {
-                Ok(Directive::SetFileStatisticsTruncateLength(None))
-            } else {
-                parse_usize(value, line_no, property)
-                    .map(Some)
-                    .map(Directive::SetFileStatisticsTruncateLength)
-            }
+        "data_page_row_count_limit" => {
+            parse_usize(value, line_no, property).map(Directive::SetFileDataPageRowCountLimit)
+        }
+        "write_batch_size" => {
+            parse_positive(value, line_no, property).map(Directive::SetFileWriteBatchSize)
+        }
+        "read_batch_size" => {
+            parse_positive(value, line_no, property).map(Directive::SetFileReadBatchSize)
         }
+        "statistics_truncate_length" => parse_truncate_length(value, line_no, property)
+            .map(Directive::SetFileStatisticsTruncateLength),
+        "arrow_schema" => parse_arrow_schema(value, line_no).map(Directive::SetFileArrowSchema),
+        "sorting_columns" => parse_sort_keys(value, line_no).map(Directive::SetFileSortingColumns),
+        "sort_by" => parse_sort_keys(value, line_no).map(Directive::SetFileSortBy),
         _ => Err(ParseError::new(
             line_no,
             format!("unknown file property '{}'", property),
@@ -556,12 +1140,17 @@ fn parse_column_directive(tokens: &[&str], line_no: usize) -> Result<Directive,
             .map(|size| Directive::SetColumnDictionaryPageSizeLimit(column, size)),
         "statistics" => parse_statistics(value, line_no)
             .map(|stats| Directive::SetColumnStatistics(column, stats)),
+        "statistics_truncate_length" => parse_truncate_length(value, line_no, property)
+            .map(|length| Directive::SetColumnStatisticsTruncateLength(column, length)),
         "bloom_filter" => parse_bool(value, line_no, property)
             .map(|enabled| Directive::SetColumnBloomFilter(column, enabled)),
         "bloom_filter_ndv" => parse_u64(value, line_no, property)
             .map(|ndv| Directive::SetColumnBloomFilterNdv(column, ndv)),
-        "bloom_filter_fpp" => parse_f64(value, line_no, property)
+        "bloom_filter_fpp" => parse_fpp(value, line_no, property)
             .map(|fpp| Directive::SetColumnBloomFilterFpp(column, fpp)),
+        "nullable" => parse_bool(value, line_no, property)
+            .map(|nullable| Directive::SetColumnNullable(column, nullable)),
+        "cast" => parse_cast(value, line_no).map(|cast| Directive::SetColumnCast(column, cast)),
         _ => Err(ParseError::new(
             line_no,
             format!("unknown column property '{}'", property),
@@ -569,13 +1158,42 @@ fn parse_column_directive(tokens: &[&str], line_no: usize) -> Result<Directive,
     }
 }
 
+fn parse_truncate_length(
+    value: &str,
+    line_no: usize,
+    property: &str,
+) -> Result<Option<usize>, ParseError> {
+    if value == "none" {
+        Ok(None)
+    } else {
+        parse_positive(value, line_no, property).map(Some)
+    }
+}
+
+/// `a.b.c`, where a part may be double-quoted to hold `.`, spaces or other
+/// characters the DSL splits on.
 fn parse_column_path(value: &str, line_no: usize) -> Result<ColumnPath, ParseError> {
-    let parts: Vec<String> = value.split('.').map(|part| part.to_string()).collect();
-    if parts.is_empty() || parts.iter().any(|part| part.is_empty()) {
-        return Err(ParseError::new(
-            line_no,
-            format!("invalid column path '{}'", value),
-        ));
+    let invalid = || ParseError::new(line_no, format!("invalid column path '{}'", value));
+    let mut parts = Vec::new();
+    for part in split_unquoted(value, |c| c == '.') {
+        let Some(quoted) = part.strip_prefix('"') else {
+            if part.is_empty() || part.contains('"') {
+                return Err(invalid());
+            }
+            parts.push(part.to_string());
+            continue;
+        };
+        let quoted = quoted.strip_suffix('"').ok_or_else(invalid)?;
+        let mut name = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => name.push(chars.next().ok_or_else(invalid)?),
+                '"' => return Err(invalid()),
+                c => name.push(c),
+            }
+        }
+        parts.push(name);
     }
     Ok(ColumnPath::new(parts))
 }
@@ -621,6 +1239,20 @@ fn parse_codec(value: &str, line_no: usize) -> Result<Codec, ParseError> {
     }
 }
 
+fn parse_cast(value: &str, line_no: usize) -> Result<CastType, ParseError> {
+    match parse_wrapped_i32(value, "fixed_len_byte_array", line_no)? {
+        Some(length) if length > 0 => Ok(CastType::FixedLenByteArray(length as usize)),
+        Some(_) => Err(ParseError::new(
+            line_no,
+            "fixed_len_byte_array length must be positive",
+        )),
+        None => Err(ParseError::new(
+            line_no,
+            format!("unknown cast '{}'", value),
+        )),
+    }
+}
+
 fn parse_data_encoding(value: &str, line_no: usize) -> Result<DataEncoding, ParseError> {
     match value {
         "plain" => Ok(DataEncoding::Plain),
@@ -635,6 +1267,48 @@ fn parse_data_encoding(value: &str, line_no: usize) -> Result<DataEncoding, Pars
     }
 }
 
+fn parse_arrow_schema(value: &str, line_no: usize) -> Result<ArrowSchemaMode, ParseError> {
+    match value {
+        "preserve" => Ok(ArrowSchemaMode::Preserve),
+        "normalize" => Ok(ArrowSchemaMode::Normalize),
+        "strip" => Ok(ArrowSchemaMode::Strip),
+        _ => Err(ParseError::new(
+            line_no,
+            format!("unknown arrow_schema mode '{}'", value),
+        )),
+    }
+}
+
+/// `a:asc,b:desc`; the direction defaults to ascending.
+fn parse_sort_keys(value: &str, line_no: usize) -> Result<Vec<SortKey>, ParseError> {
+    split_unquoted(value, |c| c == ',')
+        .into_iter()
+        .map(|key| {
+            let (column, direction) = match unquoted(key, |c| c == ':').last() {
+                Some(&i) => (&key[..i], &key[i + 1..]),
+                None => (key, "asc"),
+            };
+            let descending = match direction {
+                "asc" => false,
+                "desc" => true,
+                _ => {
+                    return Err(ParseError::new(
+                        line_no,
+                        format!(
+                            "unknown sort direction '{}', expected 'asc' or 'desc'",
+                            direction
+                        ),
+                    ));
+                }
+            };
+            Ok(SortKey {
+                column: parse_column_path(column, line_no)?,
+                descending,
+            })
+        })
+        .collect()
+}
+
 fn parse_statistics(value: &str, line_no: usize) -> Result<StatisticsConfig, ParseError> {
     match value {
         "none" => Ok(StatisticsConfig::None),
@@ -690,6 +1364,16 @@ fn parse_usize(value: &str, line_no: usize, property: &str) -> Result<usize, Par
     })
 }
 
+fn parse_positive(value: &str, line_no: usize, property: &str) -> Result<usize, ParseError> {
+    match parse_usize(value, line_no, property)? {
+        0 => Err(ParseError::new(
+            line_no,
+            format!("{property} must be positive"),
+        )),
+        rows => Ok(rows),
+    }
+}
+
 fn parse_u64(value: &str, line_no: usize, property: &str) -> Result<u64, ParseError> {
     value.parse::<u64>().map_err(|err| {
         ParseError::new(
@@ -708,6 +1392,16 @@ fn parse_f64(value: &str, line_no: usize, property: &str) -> Result<f64, ParseEr
     })
 }
 
+fn parse_fpp(value: &str, line_no: usize, property: &str) -> Result<f64, ParseError> {
+    match parse_f64(value, line_no, property)? {
+        fpp if fpp > 0.0 && fpp < 1.0 => Ok(fpp),
+        _ => Err(ParseError::new(
+            line_no,
+            format!("{property} must be between 0 and 1 exclusive"),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -729,6 +1423,18 @@ mod tests {
                 Directive::SetFileDataPageSizeLimit(1_048_576),
                 "set file data_page_size_limit 1048576",
             ),
+            (
+                Directive::SetFileDataPageRowCountLimit(20_000),
+                "set file data_page_row_count_limit 20000",
+            ),
+            (
+                Directive::SetFileWriteBatchSize(256),
+                "set file write_batch_size 256",
+            ),
+            (
+                Directive::SetFileReadBatchSize(8192),
+                "set file read_batch_size 8192",
+            ),
             (
                 Directive::SetFileStatisticsTruncateLength(Some(64)),
                 "set file statistics_truncate_length 64",
@@ -737,6 +1443,26 @@ mod tests {
                 Directive::SetFileStatisticsTruncateLength(None),
                 "set file statistics_truncate_length none",
             ),
+            (
+                Directive::SetFileSortingColumns(vec![
+                    SortKey {
+                        column: ColumnPath::from("day"),
+                        descending: false,
+                    },
+                    SortKey {
+                        column: column.clone(),
+                        descending: true,
+                    },
+                ]),
+                "set file sorting_columns day:asc,user_id:desc",
+            ),
+            (
+                Directive::SetFileSortBy(vec![SortKey {
+                    column: ColumnPath::from("day"),
+                    descending: false,
+                }]),
+                "set file sort_by day:asc",
+            ),
             (
                 Directive::SetColumnCompression(column.clone(), Codec::Snappy),
                 "set column user_id compression snappy",
@@ -757,6 +1483,10 @@ mod tests {
                 Directive::SetColumnStatistics(column.clone(), StatisticsConfig::Page),
                 "set column user_id statistics page",
             ),
+            (
+                Directive::SetColumnStatisticsTruncateLength(column.clone(), Some(128)),
+                "set column user_id statistics_truncate_length 128",
+            ),
             (
                 Directive::SetColumnBloomFilter(column.clone(), true),
                 "set column user_id bloom_filter true",
@@ -769,10 +1499,22 @@ mod tests {
                 Directive::SetColumnBloomFilterFpp(column.clone(), 0.01),
                 "set column user_id bloom_filter_fpp 0.01",
             ),
+            (
+                Directive::ConfigureRule(
+                    "compression-codec".to_string(),
+                    "min_column_bytes".to_string(),
+                    16_777_216.0,
+                ),
+                "configure rule compression-codec min_column_bytes 16777216",
+            ),
         ];
 
         for (directive, expected) in cases {
             assert_eq!(directive.to_string(), expected);
+            assert_eq!(
+                Prescription::parse(expected).unwrap().directives(),
+                [directive]
+            );
         }
     }
 
@@ -818,6 +1560,47 @@ mod tests {
         assert!(prescription.validate().is_ok());
     }
 
+    #[test]
+    fn quoted_names_round_trip() {
+        let odd = ColumnPath::new(vec!["a; b".into(), "x.y#z".into(), "say \"hi\"".into()]);
+        let mut prescription = Prescription::new();
+        prescription.push(Directive::SetColumnDictionary(odd.clone(), false));
+        prescription.push(Directive::SetFileSortBy(vec![SortKey {
+            column: ColumnPath::new(vec!["k:v,w".into()]),
+            descending: true,
+        }]));
+        let inline = prescription.to_inline();
+        assert_eq!(
+            inline,
+            r#"set column "a; b"."x.y#z"."say \"hi\"" dictionary false; set file sort_by "k:v,w":desc"#
+        );
+        let parsed = Prescription::parse(&format!("{inline} # done")).unwrap();
+        assert_eq!(parsed.directives(), prescription.directives());
+
+        for text in [
+            "set column \"a dictionary false",
+            "set column a\"b dictionary false",
+            "set column a..b dictionary false",
+        ] {
+            assert!(Prescription::parse(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn inline_text_round_trips() {
+        let text = "set file compression zstd(3)\nset column a.b dictionary false # keep\n";
+        let prescription = Prescription::parse(text).unwrap();
+        let inline = prescription.to_inline();
+        assert_eq!(
+            inline,
+            "set file compression zstd(3); set column a.b dictionary false"
+        );
+        assert_eq!(
+            Prescription::parse(&inline).unwrap().to_string(),
+            prescription.to_string()
+        );
+    }
+
     #[test]
     fn apply_builds_writer_properties() {
         let mut prescription = Prescription::new();
@@ -889,6 +1672,29 @@ mod tests {
         assert!((bloom_filter.fpp - 0.01).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn column_truncate_length_widens_file_length() {
+        let apply = |text: &str| {
+            let prescription = Prescription::parse(text).unwrap();
+            prescription
+                .apply(WriterProperties::builder())
+                .build()
+                .statistics_truncate_length()
+        };
+        assert_eq!(
+            apply("set column id statistics_truncate_length 128"),
+            Some(128)
+        );
+        assert_eq!(
+            apply(
+                "set file statistics_truncate_length 256\n\
+                 set column id statistics_truncate_length 128"
+            ),
+            Some(256)
+        );
+        assert_eq!(apply("set column id statistics_truncate_length none"), None);
+    }
+
     #[test]
     fn from_codec_covers_all_variants() {
         assert_eq!(
@@ -956,6 +1762,34 @@ set column user_id bloom_filter_ndv 50000"
         );
     }
 
+    #[test]
+    fn dataset_prescription_scopes_sections_by_file() {
+        let text = r#"
+set file compression zstd(3)
+file "logs/*.parquet":
+    set column message encoding delta_byte_array
+file "*":
+    set file max_row_group_size 1024
+"#;
+        let dataset = DatasetPrescription::parse(text).unwrap();
+        assert_eq!(dataset.sections.len(), 2);
+        assert_eq!(
+            dataset.for_file("s3://bucket/logs/a.parquet").to_string(),
+            "set file compression zstd(3)\nset column message encoding delta_byte_array"
+        );
+        assert_eq!(dataset.section("events/b.parquet").unwrap().pattern, "*");
+        assert_eq!(
+            DatasetPrescription::parse(&dataset.to_string())
+                .unwrap()
+                .to_string(),
+            dataset.to_string()
+        );
+        let err = DatasetPrescription::parse("file logs:").unwrap_err();
+        assert_eq!(err.line, 1);
+        let err = DatasetPrescription::parse("file \"*\":\n    configure rule r p 1").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
     #[test]
     fn parse_reports_invalid_property_with_line_number() {
         let text = "set column user_id not_a_property true";
@@ -964,6 +1798,22 @@ set column user_id bloom_filter_ndv 50000"
         assert!(error.message.contains("unknown column property"));
     }
 
+    #[test]
+    fn parse_rejects_values_the_writer_panics_on() {
+        for text in [
+            "set file max_row_group_size 0",
+            "set file statistics_truncate_length 0",
+            "set column a statistics_truncate_length 0",
+            "set column a bloom_filter_fpp 1",
+            "set column a bloom_filter_fpp NaN",
+        ] {
+            assert!(Prescription::parse(text).is_err(), "{text}");
+        }
+        let error =
+            Prescription::parse_bytes(b"set file compression snappy\nset \xff").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
     #[test]
     fn ext_apply_prescription_on_builder() {
         let props = WriterProperties::builder()
@@ -0,0 +1,291 @@
+//! Opt-in probes that re-write sampled column data with candidate writer
+//! settings and measure the result, so rules can rely on observed sizes
+//! rather than heuristics.
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_schema::Schema;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::async_reader::ParquetObjectReader;
+use parquet::basic::{Compression, Encoding, Type as PhysicalType, ZstdLevel};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::WriterProperties;
+
+use crate::cardinality;
+use crate::column_context::ColumnContext;
+use crate::loader;
+use crate::options::LintOptions;
+
+const SAMPLE_ROWS: usize = 16_384;
+/// Columns smaller than this are not worth the extra read and re-encode.
+pub(crate) const MIN_PROBE_COLUMN_BYTES: i64 = 4 * 1024 * 1024; // 4 MB
+/// Small columns that are UNCOMPRESSED and dictionary encoded throughout
+/// are still probed for compression between these sizes: the dictionary and
+/// its indices often compress well even when the column is tiny.
+const MIN_SMALL_PROBE_COLUMN_BYTES: i64 = 16 * 1024; // 16 KB
+pub(crate) const MAX_SMALL_COLUMN_BYTES: i64 = 1024 * 1024; // 1 MB
+
+/// Size of the sampled data after writing it with one candidate codec.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionTrial {
+    pub codec: Compression,
+    pub uncompressed_size: i64,
+    pub compressed_size: i64,
+}
+
+impl CompressionTrial {
+    /// Compressed over uncompressed size (lower is better).
+    pub fn ratio(&self) -> f64 {
+        if self.uncompressed_size == 0 {
+            1.0
+        } else {
+            self.compressed_size as f64 / self.uncompressed_size as f64
+        }
+    }
+}
+
+/// Size of the sampled data after writing it with one candidate encoding,
+/// compressed with the column's current codec. Dictionary encoding is
+/// reported as `RLE_DICTIONARY`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodingTrial {
+    pub encoding: Encoding,
+    pub uncompressed_size: i64,
+    pub compressed_size: i64,
+}
+
+fn candidate_codecs() -> [Compression; 3] {
+    [
+        Compression::ZSTD(ZstdLevel::try_new(3).expect("valid zstd level")),
+        Compression::LZ4_RAW,
+        Compression::UNCOMPRESSED,
+    ]
+}
+
+/// Codecs tried on small columns, where footer and page overhead matter more
+/// than decode speed.
+fn small_column_codecs() -> [Compression; 3] {
+    [
+        Compression::ZSTD(ZstdLevel::try_new(3).expect("valid zstd level")),
+        Compression::SNAPPY,
+        Compression::UNCOMPRESSED,
+    ]
+}
+
+/// Whether a column below `MIN_PROBE_COLUMN_BYTES` is still worth a
+/// compression probe: small but not trivial, stored UNCOMPRESSED and
+/// dictionary encoded in every row group.
+fn small_uncompressed_dictionary(metadata: &ParquetMetaData, column: &ColumnContext) -> bool {
+    (MIN_SMALL_PROBE_COLUMN_BYTES..MAX_SMALL_COLUMN_BYTES).contains(&column.uncompressed_size)
+        && metadata.row_groups().iter().all(|rg| {
+            let chunk = rg.column(column.column_index);
+            chunk.compression() == Compression::UNCOMPRESSED
+                && cardinality::fully_dictionary_encoded(chunk)
+        })
+}
+
+/// Encodings worth trying for a physical type. `PLAIN` is the baseline.
+pub(crate) fn candidate_encodings(physical_type: PhysicalType) -> &'static [Encoding] {
+    match physical_type {
+        PhysicalType::INT32 | PhysicalType::INT64 => &[
+            Encoding::PLAIN,
+            Encoding::DELTA_BINARY_PACKED,
+            Encoding::BYTE_STREAM_SPLIT,
+            Encoding::RLE_DICTIONARY,
+        ],
+        PhysicalType::FLOAT | PhysicalType::DOUBLE | PhysicalType::FIXED_LEN_BYTE_ARRAY => &[
+            Encoding::PLAIN,
+            Encoding::BYTE_STREAM_SPLIT,
+            Encoding::RLE_DICTIONARY,
+        ],
+        PhysicalType::BYTE_ARRAY => &[
+            Encoding::PLAIN,
+            Encoding::DELTA_LENGTH_BYTE_ARRAY,
+            Encoding::RLE_DICTIONARY,
+        ],
+        _ => &[],
+    }
+}
+
+/// Re-write a sample of every large flat column with candidate codecs and/or
+/// encodings and record the results in `ColumnContext::compression_trials`
+/// and `ColumnContext::encoding_trials`.
+pub(crate) async fn run(
+    reader: &ParquetObjectReader,
+    metadata: &Arc<ParquetMetaData>,
+    columns: &mut [ColumnContext],
+    options: &LintOptions,
+) -> anyhow::Result<()> {
+    if !options.probe_compression && !options.probe_encodings {
+        return Ok(());
+    }
+    let mut probe_cols: Vec<&mut ColumnContext> = columns
+        .iter_mut()
+        .filter(|c| {
+            c.uncompressed_size >= MIN_PROBE_COLUMN_BYTES
+                || (options.probe_compression && small_uncompressed_dictionary(metadata, c))
+        })
+        .collect();
+    let leaves: Vec<usize> = probe_cols.iter().map(|c| c.column_index).collect();
+    let Some(batches) = read_sample(reader, metadata, &leaves).await? else {
+        return Ok(());
+    };
+
+    let sample_rg = metadata.row_group(cardinality::pick_sample_row_group(metadata));
+    for (i, c) in probe_cols.iter_mut().enumerate() {
+        let chunk = sample_rg.column(c.column_index);
+        let large = c.uncompressed_size >= MIN_PROBE_COLUMN_BYTES;
+        if options.probe_compression {
+            let dictionary = chunk.dictionary_page_offset().is_some();
+            let codecs = if large {
+                candidate_codecs()
+            } else {
+                small_column_codecs()
+            };
+            for codec in codecs {
+                let props = WriterProperties::builder()
+                    .set_compression(codec)
+                    .set_dictionary_enabled(dictionary)
+                    .build();
+                let (uncompressed_size, compressed_size) = trial_write(&batches, i, props)?;
+                c.compression_trials.push(CompressionTrial {
+                    codec,
+                    uncompressed_size,
+                    compressed_size,
+                });
+            }
+        }
+        if options.probe_encodings && large {
+            for &encoding in candidate_encodings(c.physical_type) {
+                let builder = WriterProperties::builder().set_compression(chunk.compression());
+                let builder = if encoding == Encoding::RLE_DICTIONARY {
+                    builder.set_dictionary_enabled(true)
+                } else {
+                    builder.set_dictionary_enabled(false).set_encoding(encoding)
+                };
+                // The writer rejects encodings it cannot apply to this type.
+                let Ok((uncompressed_size, compressed_size)) =
+                    trial_write(&batches, i, builder.build())
+                else {
+                    continue;
+                };
+                c.encoding_trials.push(EncodingTrial {
+                    encoding,
+                    uncompressed_size,
+                    compressed_size,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read up to `SAMPLE_ROWS` rows of the given leaf columns from the sample
+/// row group. Returns `None` when there is nothing to read or the projection
+/// does not map 1:1 onto Arrow columns (nested schemas).
+async fn read_sample(
+    reader: &ParquetObjectReader,
+    metadata: &Arc<ParquetMetaData>,
+    leaves: &[usize],
+) -> anyhow::Result<Option<Vec<RecordBatch>>> {
+    use futures::TryStreamExt;
+
+    if leaves.is_empty() || metadata.num_row_groups() == 0 {
+        return Ok(None);
+    }
+    let mask = parquet::arrow::ProjectionMask::leaves(
+        metadata.file_metadata().schema_descr(),
+        leaves.iter().copied(),
+    );
+    let batches: Vec<RecordBatch> = loader::stream_builder(reader, metadata)?
+        .with_row_groups(vec![cardinality::pick_sample_row_group(metadata)])
+        .with_batch_size(SAMPLE_ROWS)
+        .with_limit(SAMPLE_ROWS)
+        .with_projection(mask)
+        .build()?
+        .try_collect()
+        .await?;
+    if batches.iter().any(|b| b.num_columns() != leaves.len()) {
+        return Ok(None);
+    }
+    Ok(Some(batches))
+}
+
+/// Write column `i` of the sampled batches to an in-memory file and return
+/// its (uncompressed, compressed) chunk size.
+fn trial_write(
+    batches: &[RecordBatch],
+    i: usize,
+    props: WriterProperties,
+) -> anyhow::Result<(i64, i64)> {
+    let Some(first) = batches.first() else {
+        return Ok((0, 0));
+    };
+    let schema = Arc::new(Schema::new(vec![first.schema().field(i).clone()]));
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema.clone(), Some(props))?;
+    for batch in batches {
+        writer.write(&RecordBatch::try_new(
+            schema.clone(),
+            vec![batch.column(i).clone()],
+        )?)?;
+    }
+    let metadata = writer.close()?;
+    Ok(metadata
+        .row_groups()
+        .iter()
+        .map(|rg| {
+            (
+                rg.column(0).uncompressed_size(),
+                rg.column(0).compressed_size(),
+            )
+        })
+        .fold((0, 0), |(u, c), (ru, rc)| (u + ru, c + rc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Int64Array, StringArray};
+    use arrow_schema::{DataType, Field};
+
+    #[test]
+    fn trial_write_compresses_repetitive_strings() -> anyhow::Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, false)]));
+        let values: StringArray = (0..4096).map(|i| Some(format!("row-{}", i % 7))).collect();
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(values)])?;
+
+        let props = |codec| {
+            WriterProperties::builder()
+                .set_compression(codec)
+                .set_dictionary_enabled(false)
+                .build()
+        };
+        let batches = [batch];
+        let (raw_u, raw_c) = trial_write(&batches, 0, props(Compression::UNCOMPRESSED))?;
+        let (_, zstd_c) = trial_write(&batches, 0, props(candidate_codecs()[0]))?;
+
+        assert_eq!(raw_u, raw_c);
+        assert!(zstd_c * 4 < raw_c);
+        Ok(())
+    }
+
+    #[test]
+    fn delta_encoding_shrinks_sequential_ints() -> anyhow::Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("i", DataType::Int64, false)]));
+        let values = Int64Array::from_iter_values(0..4096);
+        let batches = [RecordBatch::try_new(schema, vec![Arc::new(values)])?];
+
+        let props = |encoding| {
+            WriterProperties::builder()
+                .set_dictionary_enabled(false)
+                .set_encoding(encoding)
+                .build()
+        };
+        let (plain, _) = trial_write(&batches, 0, props(Encoding::PLAIN))?;
+        let (delta, _) = trial_write(&batches, 0, props(Encoding::DELTA_BINARY_PACKED))?;
+
+        assert!(delta * 10 < plain);
+        Ok(())
+    }
+}
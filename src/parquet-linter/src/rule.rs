@@ -1,24 +1,263 @@
-use std::sync::Arc;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use bytes::{Buf, Bytes};
-use parquet::arrow::async_reader::ParquetObjectReader;
+use parquet::arrow::async_reader::{AsyncFileReader, ParquetObjectReader};
+use parquet::bloom_filter::Sbbf;
 use parquet::errors::ParquetError;
 use parquet::file::metadata::ParquetMetaData;
 use parquet::file::reader::{ChunkReader, Length, SerializedPageReader};
+use serde_json::{Value, json};
 
-use crate::column_context::ColumnContext;
-use crate::diagnostic::Diagnostic;
+use crate::column_context::LazyColumns;
+use crate::diagnostic::{Category, Diagnostic, Severity};
+use crate::options::LintOptions;
+use crate::page_cache::PageCache;
+use crate::thresholds::Parameter;
 
 pub struct RuleContext {
     pub metadata: Arc<ParquetMetaData>,
-    pub columns: Vec<ColumnContext>,
+    /// Per-column contexts, built on first access.
+    pub columns: LazyColumns,
     pub reader: ParquetObjectReader,
+    /// Shared chunk cache; prefer it over `column_page_reader` so reads can be
+    /// batched with `PageCache::prefetch`.
+    pub pages: PageCache,
+    pub options: LintOptions,
+    /// Intermediate results rules publish for the rules that depend on them.
+    pub blackboard: Blackboard,
 }
 
 #[async_trait::async_trait]
 pub trait Rule: Send + Sync {
     fn name(&self) -> &'static str;
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic>;
+
+    /// What the rule checks and how it can be tuned, for configuration UIs
+    /// (see `rules::rules_manifest`).
+    fn metadata(&self) -> RuleMetadata;
+
+    /// Rules whose blackboard artifacts this rule reads. When selected too,
+    /// they run first; otherwise the artifacts are missing and the rule falls
+    /// back to computing what it needs itself.
+    fn dependencies(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Static description of a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleMetadata {
+    pub name: &'static str,
+    /// Stable short identifier, `PL` followed by three digits.
+    pub code: &'static str,
+    pub description: &'static str,
+    pub category: Category,
+    /// Highest severity the rule reports with default options.
+    pub default_severity: Severity,
+    /// Thresholds that presets scale and overrides can set.
+    pub parameters: &'static [Parameter],
+    /// Settings of the directives the rule prescribes, as named by
+    /// `Directive::setting`.
+    pub directives: &'static [&'static str],
+}
+
+impl RuleMetadata {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "code": self.code,
+            "description": self.description,
+            "category": self.category.to_string(),
+            "default_severity": self.default_severity.to_string(),
+            "parameters": self.parameters.iter().map(Parameter::to_json).collect::<Vec<_>>(),
+            "directives": self.directives,
+        })
+    }
+}
+
+/// Artifacts shared between the rules of one lint run, one value per type.
+#[derive(Default)]
+pub struct Blackboard {
+    entries: Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl Blackboard {
+    /// Store `value`, replacing an earlier artifact of the same type.
+    pub fn publish<T: Any + Send + Sync>(&self, value: T) {
+        self.entries
+            .lock()
+            .expect("blackboard poisoned")
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        let entry = self
+            .entries
+            .lock()
+            .expect("blackboard poisoned")
+            .get(&TypeId::of::<T>())?
+            .clone();
+        entry.downcast().ok()
+    }
+}
+
+impl RuleContext {
+    /// `rule`'s value for `parameter` under `LintOptions::thresholds`.
+    pub fn threshold(&self, rule: &dyn Rule, parameter: &Parameter) -> f64 {
+        self.options.thresholds.get(rule.name(), parameter)
+    }
+
+    /// The severity `rule` reports a finding of `severity` with, under
+    /// `LintOptions::severities`.
+    pub fn severity(&self, rule: &dyn Rule, severity: Severity) -> Severity {
+        self.options
+            .severities
+            .get(rule.name())
+            .copied()
+            .unwrap_or(severity)
+    }
+
+    /// The index of row group `index` in the file itself. Under
+    /// `LintOptions::row_groups` rules only see the selected groups,
+    /// renumbered from zero; messages should name the original ones.
+    pub fn row_group_number(&self, index: usize) -> usize {
+        match &self.options.row_groups {
+            Some(selected) => selected[index],
+            None => index,
+        }
+    }
+
+    /// Row groups as compact ranges of their original indices, e.g. `0-2, 5`.
+    pub fn row_group_ranges(&self, indices: &[usize]) -> String {
+        let numbers: Vec<usize> = indices.iter().map(|&i| self.row_group_number(i)).collect();
+        crate::rules::row_group_ranges(&numbers)
+    }
+
+    /// Leaf columns that column rules should check, honoring
+    /// `LintOptions::columns`.
+    pub fn column_indices(&self) -> Vec<usize> {
+        let schema = self.metadata.file_metadata().schema_descr();
+        (0..schema.num_columns())
+            .filter(|&i| self.options.selects(schema.column(i).path()))
+            .collect()
+    }
+
+    /// Fetch and parse a column chunk's bloom filter with targeted range reads.
+    ///
+    /// Returns `Ok(None)` when the chunk has no bloom filter.
+    pub async fn bloom_filter(
+        &self,
+        rg_idx: usize,
+        col_idx: usize,
+    ) -> anyhow::Result<Option<BloomFilterInfo>> {
+        let col = self.metadata.row_group(rg_idx).column(col_idx);
+        let Some(offset) = col.bloom_filter_offset() else {
+            return Ok(None);
+        };
+        let offset = u64::try_from(offset)?;
+        let mut reader = self.reader.clone();
+
+        let length = match col.bloom_filter_length() {
+            Some(length) => u64::try_from(length)?,
+            None => {
+                // Older writers omit the length; size the read from the header's
+                // leading `num_bytes` field instead.
+                let header = reader
+                    .get_bytes(offset..offset + BLOOM_FILTER_HEADER_SIZE_ESTIMATE)
+                    .await?;
+                let num_bytes = bloom_filter_header_num_bytes(&header)
+                    .ok_or_else(|| anyhow::anyhow!("invalid bloom filter header"))?;
+                BLOOM_FILTER_HEADER_SIZE_ESTIMATE + num_bytes
+            }
+        };
+        let bytes = reader.get_bytes(offset..offset + length).await?;
+        let chunk = ColumnChunk::new(bytes, offset);
+        let Some(filter) = Sbbf::read_from_column_chunk(col, &chunk)? else {
+            return Ok(None);
+        };
+        Ok(Some(BloomFilterInfo::new(filter)?))
+    }
+}
+
+/// Upper bound for a serialized bloom filter header (mirrors the parquet crate).
+const BLOOM_FILTER_HEADER_SIZE_ESTIMATE: u64 = 20;
+/// Split-block bloom filters set one bit in each of the 8 words of a block per insert.
+const BLOOM_FILTER_BITS_PER_INSERT: i32 = 8;
+const BLOOM_FILTER_BLOCK_BYTES: usize = 32;
+
+/// A parsed column-chunk bloom filter plus its bitset occupancy, which lets
+/// rules estimate the NDV and false-positive rate the filter actually achieves.
+pub struct BloomFilterInfo {
+    pub filter: Sbbf,
+    /// Bitset size in bytes (excluding the header).
+    pub num_bytes: usize,
+    /// Number of bits set in the bitset.
+    pub set_bits: u64,
+}
+
+impl BloomFilterInfo {
+    fn new(filter: Sbbf) -> anyhow::Result<Self> {
+        let mut serialized = Vec::new();
+        filter.write(&mut serialized)?;
+        // The bitset is a power of two of at least 32 bytes and the header is
+        // always shorter than that, so the bitset is the largest power of two
+        // that fits in the serialized form.
+        let num_bytes = 1usize << serialized.len().ilog2();
+        let bitset = &serialized[serialized.len() - num_bytes..];
+        let set_bits = bitset.iter().map(|b| u64::from(b.count_ones())).sum();
+        Ok(Self {
+            filter,
+            num_bytes,
+            set_bits,
+        })
+    }
+
+    pub fn fill_ratio(&self) -> f64 {
+        if self.num_bytes == 0 {
+            0.0
+        } else {
+            self.set_bits as f64 / (self.num_bytes * 8) as f64
+        }
+    }
+
+    /// Estimated number of distinct values inserted into the filter.
+    ///
+    /// Each insert sets one of 32 bits in each word of a single block, so a
+    /// given bit stays unset with probability `(1 - 1 / (32 * blocks))^n`.
+    pub fn estimated_ndv(&self) -> u64 {
+        let fill = self.fill_ratio();
+        if fill >= 1.0 {
+            return u64::MAX;
+        }
+        let blocks = (self.num_bytes / BLOOM_FILTER_BLOCK_BYTES) as f64;
+        (-32.0 * blocks * (1.0 - fill).ln()).round() as u64
+    }
+
+    /// Estimated false-positive probability: a miss requires all eight probed
+    /// bits to already be set.
+    pub fn estimated_fpp(&self) -> f64 {
+        self.fill_ratio().powi(BLOOM_FILTER_BITS_PER_INSERT)
+    }
+}
+
+/// Decode `BloomFilterHeader.num_bytes`, which is always the first field of
+/// the thrift compact struct (field header `0x15`, then a zigzag varint).
+fn bloom_filter_header_num_bytes(header: &[u8]) -> Option<u64> {
+    let (&field, rest) = header.split_first()?;
+    if field != 0x15 {
+        return None;
+    }
+    let mut value = 0u64;
+    for (i, &byte) in rest.iter().take(5).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let decoded = (value >> 1) as i64 ^ -((value & 1) as i64);
+            return u64::try_from(decoded).ok();
+        }
+    }
+    None
 }
 
 /// A byte slice from a column chunk, implementing `ChunkReader` so that
@@ -61,8 +300,6 @@ pub async fn column_page_reader(
     rg_idx: usize,
     col_idx: usize,
 ) -> anyhow::Result<SerializedPageReader<ColumnChunk>> {
-    use parquet::arrow::async_reader::AsyncFileReader;
-
     let rg = metadata.row_group(rg_idx);
     let col = rg.column(col_idx);
     let (offset, length) = col.byte_range();
@@ -75,3 +312,58 @@ pub async fn column_page_reader(
         None,
     )?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestFile;
+    use arrow_array::{Int64Array, RecordBatch};
+
+    #[test]
+    fn blackboard_keys_artifacts_by_type() {
+        let blackboard = Blackboard::default();
+        assert!(blackboard.get::<Vec<usize>>().is_none());
+        blackboard.publish(vec![1usize, 2]);
+        blackboard.publish(String::from("kept apart"));
+        assert_eq!(*blackboard.get::<Vec<usize>>().unwrap(), [1, 2]);
+        assert_eq!(*blackboard.get::<String>().unwrap(), "kept apart");
+    }
+
+    #[test]
+    fn header_num_bytes_decodes_zigzag_varint() {
+        // num_bytes = 1024 -> zigzag 2048 -> varint [0x80, 0x10]
+        assert_eq!(
+            bloom_filter_header_num_bytes(&[0x15, 0x80, 0x10]),
+            Some(1024)
+        );
+        assert_eq!(bloom_filter_header_num_bytes(&[0x16, 0x80, 0x10]), None);
+    }
+
+    #[tokio::test]
+    async fn bloom_filter_estimates_written_ndv() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..10_000)) as _,
+        )])?;
+        let file = TestFile::new(batch)
+            .settings("set column id bloom_filter true\nset column id bloom_filter_ndv 10000")
+            .open()
+            .await?;
+        let options = LintOptions::default();
+        let ctx = RuleContext {
+            metadata: file.metadata.clone(),
+            columns: LazyColumns::new(&file, &options),
+            reader: file.reader.clone(),
+            pages: file.pages.clone(),
+            options,
+            blackboard: Blackboard::default(),
+        };
+
+        let info = ctx.bloom_filter(0, 0).await?.expect("bloom filter written");
+        assert!(info.filter.check(&42i64));
+        let ndv = info.estimated_ndv();
+        assert!((9_000..=11_000).contains(&ndv), "estimated ndv {ndv}");
+        assert!(info.estimated_fpp() < 0.05);
+        Ok(())
+    }
+}
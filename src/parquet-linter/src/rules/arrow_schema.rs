@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{ArrowSchemaMode, Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::arrow::{ARROW_SCHEMA_META_KEY, parquet_to_arrow_schema};
+
+pub struct ArrowSchemaRule;
+
+#[async_trait::async_trait]
+impl Rule for ArrowSchemaRule {
+    fn name(&self) -> &'static str {
+        "arrow-type-fidelity"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL027",
+            description: "Arrow types that the Parquet schema alone does not preserve",
+            category: Category::Compatibility,
+            default_severity: Severity::Warning,
+            parameters: &[],
+            directives: &["arrow_schema"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let file_metadata = ctx.metadata.file_metadata();
+        let key_value = file_metadata.key_value_metadata();
+        if !key_value.is_some_and(|kv| kv.iter().any(|e| e.key == ARROW_SCHEMA_META_KEY)) {
+            return diagnostics;
+        }
+        let descr = file_metadata.schema_descr();
+        let (Ok(hinted), Ok(implied)) = (
+            parquet_to_arrow_schema(descr, key_value),
+            parquet_to_arrow_schema(descr, None),
+        ) else {
+            return diagnostics;
+        };
+
+        // Top-level fields are reported at their first leaf column.
+        let mut first_leaf = HashMap::new();
+        for col_idx in ctx.column_indices() {
+            first_leaf
+                .entry(descr.get_column_root_idx(col_idx))
+                .or_insert(col_idx);
+        }
+        for (root, (hinted, implied)) in hinted.fields().iter().zip(implied.fields()).enumerate() {
+            if hinted.data_type() == implied.data_type() {
+                continue;
+            }
+            let Some(&col_idx) = first_leaf.get(&root) else {
+                continue;
+            };
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetFileArrowSchema(ArrowSchemaMode::Normalize));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Compatibility,
+                location: Location::Column {
+                    column: col_idx,
+                    path: descr.column(col_idx).path().clone(),
+                },
+                message: format!(
+                    "`{}` reads as {} only because of the embedded {ARROW_SCHEMA_META_KEY}; \
+                     its Parquet type implies {}. Readers and rewriters that ignore the hint \
+                     see a different type; normalize it, or keep it with \
+                     `set file arrow_schema preserve`",
+                    hinted.name(),
+                    hinted.data_type(),
+                    implied.data_type()
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{LargeStringArray, RecordBatch};
+
+    use crate::testing::TestFile;
+
+    #[tokio::test]
+    async fn normalizing_removes_the_mismatch() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "s",
+            Arc::new(LargeStringArray::from(vec!["a", "b"])) as _,
+        )])?;
+        let file = TestFile::new(batch).open().await?;
+        let linter = crate::Linter::builder()
+            .rules(["arrow-type-fidelity"])
+            .build();
+        let diagnostics = linter.lint_file(&file).await?;
+        assert_eq!(diagnostics.len(), 1);
+        let prescription = &diagnostics[0].prescription;
+        assert_eq!(prescription.to_string(), "set file arrow_schema normalize");
+
+        let dir = tempfile::tempdir()?;
+        let output = dir.path().join("out.parquet");
+        linter.rewrite_file(&file, &output, prescription).await?;
+        assert!(linter.lint(output.to_str().unwrap()).await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::column_context::TypeStats;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+
+pub struct BloomFilterRule;
+
+/// Below this many distinct values per row group the dictionary page
+/// already answers "is the key here?" cheaply.
+const MIN_NDV_PER_ROW_GROUP: Parameter =
+    Parameter::above("min_ndv_per_row_group", ParameterKind::Count, 1000.0);
+/// Sorted columns prune point lookups through min/max statistics already.
+const SORTED_THRESHOLD: f64 = 0.9;
+/// Explicit join keys get a tighter filter than name-guessed ones, which
+/// keep the parquet-rs default.
+const JOIN_KEY_FPP: f64 = 0.01;
+const GUESSED_KEY_FPP: f64 = 0.05;
+
+/// `id`, `key`, `*_id`, `*_key`, and camelCase `userId`.
+fn looks_like_key(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower == "id"
+        || lower == "key"
+        || lower.ends_with("_id")
+        || lower.ends_with("_key")
+        || name.ends_with("Id")
+        || name.ends_with("Key")
+}
+
+#[async_trait::async_trait]
+impl Rule for BloomFilterRule {
+    fn name(&self) -> &'static str {
+        "join-key-bloom-filter"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL025",
+            description: "Join and lookup keys without bloom filters",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[MIN_NDV_PER_ROW_GROUP],
+            directives: &["bloom_filter", "bloom_filter_fpp", "bloom_filter_ndv"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+        let workload = &ctx.options.workload;
+        let max_rows = row_groups.iter().map(|rg| rg.num_rows()).max().unwrap_or(0) as u64;
+        let min_ndv = ctx.threshold(self, &MIN_NDV_PER_ROW_GROUP) as u64;
+
+        let mut candidates = Vec::new();
+        for col_idx in ctx.column_indices() {
+            let descr = row_groups[0].column(col_idx).column_descr();
+            let path = descr.path().clone();
+            // Name guessing only stands in when no join keys are given.
+            let explicit = workload.is_join_key(&path);
+            let guessed = workload.join_keys.is_empty() && looks_like_key(descr.name());
+            if !explicit && !guessed {
+                continue;
+            }
+            let missing = row_groups
+                .iter()
+                .filter(|rg| rg.column(col_idx).bloom_filter_offset().is_none())
+                .count();
+            if missing > 0 {
+                candidates.push((col_idx, path, explicit, missing));
+            }
+        }
+        let columns: Vec<usize> = candidates.iter().map(|(col_idx, ..)| *col_idx).collect();
+        ctx.columns.load(&columns).await;
+
+        for (col_idx, path, explicit, missing) in candidates {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            if matches!(
+                col_ctx.type_stats,
+                TypeStats::Boolean(_) | TypeStats::Float(_)
+            ) || col_ctx.sortedness.is_some_and(|s| s >= SORTED_THRESHOLD)
+            {
+                continue;
+            }
+            // Filters are per column chunk, so size them for one row group.
+            let ndv = col_ctx.distinct_count.min(max_rows);
+            if ndv < min_ndv {
+                continue;
+            }
+
+            let (severity, fpp, reason) = if explicit {
+                (Severity::Warning, JOIN_KEY_FPP, "a join key")
+            } else {
+                (Severity::Suggestion, GUESSED_KEY_FPP, "named like a key")
+            };
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnBloomFilter(path.clone(), true));
+            prescription.push(Directive::SetColumnBloomFilterNdv(path.clone(), ndv));
+            prescription.push(Directive::SetColumnBloomFilterFpp(path.clone(), fpp));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "column is {reason} with ~{} distinct values but has no bloom filter in \
+                     {missing}/{} row groups; point lookups and joins must read every row \
+                     group, recommend a bloom filter sized for {ndv} values at fpp {fpp}",
+                    col_ctx.distinct_count,
+                    row_groups.len()
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use super::*;
+    use crate::testing::TestFile;
+
+    #[test]
+    fn key_names() {
+        assert!(looks_like_key("user_id"));
+        assert!(looks_like_key("orderKey"));
+        assert!(looks_like_key("id"));
+        assert!(!looks_like_key("paid"));
+        assert!(!looks_like_key("keyword"));
+    }
+
+    #[tokio::test]
+    async fn sizes_filter_for_unsorted_key_columns() -> anyhow::Result<()> {
+        let ids = (0..20_000i64).map(|i| (i * 7919) % 20_000);
+        let batch = RecordBatch::try_from_iter([(
+            "user_id",
+            Arc::new(Int64Array::from_iter_values(ids)) as _,
+        )])?;
+        let diagnostics = TestFile::new(batch)
+            .lint(&["join-key-bloom-filter"])
+            .await?;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Suggestion);
+        assert!(
+            diagnostics[0]
+                .prescription
+                .to_string()
+                .contains("bloom_filter_fpp 0.05")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription, StatisticsConfig};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::file::metadata::ColumnChunkMetaData;
+
+pub struct ChunkStatisticsRule;
+
+/// Chunks at least this large span several pages with the default 1 MB
+/// page size, so a page index lets readers skip within them.
+const PAGE_INDEX_MIN_CHUNK_BYTES: Parameter = Parameter::above(
+    "page_index_min_chunk_bytes",
+    ParameterKind::Bytes,
+    1024.0 * 1024.0,
+);
+
+/// Whether the chunk has no min/max to prune on. All-null chunks have none
+/// to record, so they count as covered.
+pub(super) fn missing_chunk_statistics(col: &ColumnChunkMetaData) -> bool {
+    match col.statistics() {
+        None => col.num_values() > 0,
+        Some(stats) => {
+            stats.min_bytes_opt().is_none()
+                && stats
+                    .null_count_opt()
+                    .is_none_or(|nulls| (nulls as i64) < col.num_values())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Rule for ChunkStatisticsRule {
+    fn name(&self) -> &'static str {
+        "missing-chunk-statistics"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL004",
+            description: "Column chunks without min/max statistics to prune row groups on",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[PAGE_INDEX_MIN_CHUNK_BYTES],
+            directives: &["statistics"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+        let workload = &ctx.options.workload;
+        let page_index_min_chunk_bytes = ctx.threshold(self, &PAGE_INDEX_MIN_CHUNK_BYTES) as i64;
+
+        for col_idx in ctx.column_indices() {
+            let missing_groups = row_groups
+                .iter()
+                .filter(|rg| missing_chunk_statistics(rg.column(col_idx)))
+                .count();
+            if missing_groups == 0 {
+                continue;
+            }
+
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let avg_chunk_bytes = row_groups
+                .iter()
+                .map(|rg| rg.column(col_idx).compressed_size())
+                .sum::<i64>()
+                / row_groups.len() as i64;
+            // Without hints, size decides; with them, only filtered columns
+            // are worth a page index.
+            let (level, reason) = if workload.is_filter_column(&path) {
+                (StatisticsConfig::Page, "column is filtered on")
+            } else if !workload.is_empty() {
+                (StatisticsConfig::Chunk, "column is not filtered on")
+            } else if avg_chunk_bytes >= page_index_min_chunk_bytes {
+                (StatisticsConfig::Page, "chunks span several pages")
+            } else {
+                (StatisticsConfig::Chunk, "chunks are small")
+            };
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnStatistics(path.clone(), level));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "no chunk statistics (min/max) in {missing_groups}/{} row groups; readers \
+                     cannot prune row groups on this column, recommend {level} statistics \
+                     ({reason})",
+                    row_groups.len()
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use crate::options::{LintOptions, Workload};
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn prescribes_by_size_and_workload() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+            ("b", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+        ])?;
+        let file = TestFile::new(batch)
+            .settings("set column a statistics none\nset column b statistics none");
+        let rules = ["missing-chunk-statistics"];
+        assert_snapshot(
+            &file.lint(&rules).await?,
+            r#"
+                [warning] missing-chunk-statistics @ column[0]("a"): ...
+                  fix: set column a statistics chunk
+                [warning] missing-chunk-statistics @ column[1]("b"): ...
+                  fix: set column b statistics chunk
+            "#,
+        );
+
+        let options = LintOptions {
+            workload: Workload {
+                filter_columns: vec!["b".to_string()],
+                ..Workload::default()
+            },
+            ..LintOptions::default()
+        };
+        assert_snapshot(
+            &file.lint_with_options(&rules, options).await?,
+            r#"
+                [warning] missing-chunk-statistics @ column[0]("a"): ...
+                  fix: set column a statistics chunk
+                [warning] missing-chunk-statistics @ column[1]("b"): ...
+                  fix: set column b statistics page
+            "#,
+        );
+        Ok(())
+    }
+}
@@ -1,14 +1,29 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::cost::{ColumnShape, CostModel};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{Codec, Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
-use parquet::basic::{Compression, LogicalType, Type as PhysicalType};
+use crate::probe::MAX_SMALL_COLUMN_BYTES;
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::{Compression, Encoding, LogicalType, Type as PhysicalType};
 
 pub struct CompressionCodecRule;
 
 const LARGE_UNCOMPRESSED_COLUMN_BYTES: i64 = 4 * 1024 * 1024; // 4 MB
-const MIN_COLUMN_BYTES_FOR_CODEC_CHANGE: i64 = 8 * 1024 * 1024; // 8 MB
-const MIN_SINGLE_ROW_GROUP_BYTES_FOR_ZSTD: i64 = 32 * 1024 * 1024; // 32 MB
-const MIN_TEXT_BYTES_FOR_LZ4_UPGRADE: i64 = 32 * 1024 * 1024; // 32 MB
+const MIN_COLUMN_BYTES_FOR_CODEC_CHANGE: Parameter = Parameter::above(
+    "min_column_bytes",
+    ParameterKind::Bytes,
+    8.0 * 1024.0 * 1024.0,
+);
+const MIN_SINGLE_ROW_GROUP_BYTES_FOR_ZSTD: Parameter = Parameter::above(
+    "min_single_row_group_bytes",
+    ParameterKind::Bytes,
+    32.0 * 1024.0 * 1024.0,
+);
+const MIN_TEXT_BYTES_FOR_LZ4_UPGRADE: Parameter = Parameter::above(
+    "min_text_bytes_for_lz4",
+    ParameterKind::Bytes,
+    32.0 * 1024.0 * 1024.0,
+);
 const MIN_TOTAL_BYTES_FOR_SMALL_CHUNK_LZ4: i64 = 64 * 1024 * 1024; // 64 MB
 const MIN_ROW_GROUPS_FOR_SMALL_CHUNK_LZ4: usize = 64;
 const MAX_AVG_UNCOMPRESSED_CHUNK_BYTES_FOR_LZ4: i64 = 1024 * 1024; // 1 MB
@@ -18,6 +33,9 @@ const MAX_RATIO_FOR_ZSTD_UPGRADE_FROM_SNAPPY: f64 = 0.90;
 const LOW_COMPRESSION_RATIO_SKIP_ZSTD: f64 = 0.95;
 const LOW_COMPRESSION_RATIO_SKIP_LZ4: f64 = 0.98;
 const TARGET_ZSTD_LEVEL: i32 = 3;
+/// LZ4 must cut the cost model's decode time by this share to be worth a
+/// codec change.
+const MIN_LZ4_DECODE_SAVING: f64 = 0.25;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CodecRecommendation {
@@ -41,14 +59,37 @@ impl CodecRecommendation {
     }
 }
 
+/// Whether `model` expects LZ4 to decode `uncompressed_size` bytes
+/// meaningfully faster than `compression`.
+fn lz4_decodes_faster(
+    model: &dyn CostModel,
+    compression: Compression,
+    uncompressed_size: i64,
+) -> bool {
+    let shape = |codec| ColumnShape {
+        codec,
+        encoding: Encoding::PLAIN,
+        uncompressed_size,
+        compressed_size: uncompressed_size,
+        num_values: 0,
+        distinct_values: None,
+    };
+    let current = model.decode_cost(&shape(compression));
+    model.decode_cost(&shape(Compression::LZ4_RAW)) < current * (1.0 - MIN_LZ4_DECODE_SAVING)
+}
+
 fn classify_codec_issue(
     compression: Compression,
     uncompressed_size: i64,
+    model: &dyn CostModel,
 ) -> Option<(CodecRecommendation, &'static str)> {
     let is_target_zstd = matches!(compression, Compression::ZSTD(level) if level.compression_level() == TARGET_ZSTD_LEVEL);
     let speed_sensitive = uncompressed_size > LARGE_UNCOMPRESSED_COLUMN_BYTES;
 
-    if speed_sensitive && matches!(compression, Compression::SNAPPY) {
+    if speed_sensitive
+        && matches!(compression, Compression::SNAPPY)
+        && lz4_decodes_faster(model, compression, uncompressed_size)
+    {
         return Some((
             CodecRecommendation::Lz4,
             "large column chunks are decompression-sensitive",
@@ -128,6 +169,22 @@ impl Rule for CompressionCodecRule {
         "compression-codec-upgrade"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL014",
+            description: "Codecs slower or weaker than ZSTD or LZ4 for the column size",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[
+                MIN_COLUMN_BYTES_FOR_CODEC_CHANGE,
+                MIN_SINGLE_ROW_GROUP_BYTES_FOR_ZSTD,
+                MIN_TEXT_BYTES_FOR_LZ4_UPGRADE,
+            ],
+            directives: &["compression"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -135,8 +192,28 @@ impl Rule for CompressionCodecRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        let model = ctx.options.cost_model.as_ref();
+        let min_column_bytes = ctx.threshold(self, &MIN_COLUMN_BYTES_FOR_CODEC_CHANGE) as i64;
+        let min_single_row_group_bytes =
+            ctx.threshold(self, &MIN_SINGLE_ROW_GROUP_BYTES_FOR_ZSTD) as i64;
+        let min_text_bytes_for_lz4 = ctx.threshold(self, &MIN_TEXT_BYTES_FOR_LZ4_UPGRADE) as i64;
+        let columns = ctx.column_indices();
+        if ctx.options.probe_compression {
+            // Columns past the small-column hand-off consult their trials.
+            let probed: Vec<usize> = columns
+                .iter()
+                .copied()
+                .filter(|&col_idx| {
+                    let total: i64 = row_groups
+                        .iter()
+                        .map(|rg| rg.column(col_idx).uncompressed_size())
+                        .sum();
+                    total >= MAX_SMALL_COLUMN_BYTES
+                })
+                .collect();
+            ctx.columns.load(&probed).await;
+        }
+        for col_idx in columns {
             let col0 = row_groups[0].column(col_idx);
             let descr = col0.column_descr();
             let physical_type = descr.physical_type();
@@ -165,7 +242,7 @@ impl Rule for CompressionCodecRule {
                     total_compressed += compressed_size;
                 }
                 if let Some((recommendation, reason)) =
-                    classify_codec_issue(compression, uncompressed_size)
+                    classify_codec_issue(compression, uncompressed_size, model)
                 {
                     match recommendation {
                         CodecRecommendation::ZstdLevel3 => {
@@ -180,7 +257,14 @@ impl Rule for CompressionCodecRule {
                 }
             }
 
-            if total_uncompressed < MIN_COLUMN_BYTES_FOR_CODEC_CHANGE {
+            // With the probe on, small columns are left to
+            // `uncompressed-small-dictionary`, which measures instead of
+            // guessing from sizes.
+            if ctx.options.probe_compression && total_uncompressed < MAX_SMALL_COLUMN_BYTES {
+                continue;
+            }
+
+            if total_uncompressed < min_column_bytes {
                 zstd_groups = 0;
                 zstd_sample = None;
             }
@@ -201,7 +285,7 @@ impl Rule for CompressionCodecRule {
                 zstd_sample = None;
             }
 
-            if row_groups.len() == 1 && total_uncompressed < MIN_SINGLE_ROW_GROUP_BYTES_FOR_ZSTD {
+            if row_groups.len() == 1 && total_uncompressed < min_single_row_group_bytes {
                 zstd_groups = 0;
                 zstd_sample = None;
             }
@@ -222,9 +306,19 @@ impl Rule for CompressionCodecRule {
                 }
             }
 
-            if is_text_logical_type(logical_type)
-                && total_uncompressed < MIN_TEXT_BYTES_FOR_LZ4_UPGRADE
+            // A measured ZSTD trial overrides the guess from the current codec.
+            if ctx.options.probe_compression
+                && let Some(col_ctx) = ctx.columns.get(col_idx).await
+                && col_ctx.compression_trials.iter().any(|t| {
+                    matches!(t.codec, Compression::ZSTD(_))
+                        && t.ratio() > LOW_COMPRESSION_RATIO_SKIP_ZSTD
+                })
             {
+                zstd_groups = 0;
+                zstd_sample = None;
+            }
+
+            if is_text_logical_type(logical_type) && total_uncompressed < min_text_bytes_for_lz4 {
                 lz4_groups = 0;
                 lz4_sample = None;
             }
@@ -235,14 +329,15 @@ impl Rule for CompressionCodecRule {
                 lz4_sample = None;
             }
 
-            let prefer_lz4_many_small_chunks = prefer_lz4_for_many_small_snappy_byte_array_chunks(
-                physical_type,
-                non_empty_groups,
-                total_uncompressed,
-                avg_chunk_uncompressed,
-                aggregated_ratio,
-                sample_compression,
-            );
+            let prefer_lz4_many_small_chunks =
+                prefer_lz4_for_many_small_snappy_byte_array_chunks(
+                    physical_type,
+                    non_empty_groups,
+                    total_uncompressed,
+                    avg_chunk_uncompressed,
+                    aggregated_ratio,
+                    sample_compression,
+                ) && lz4_decodes_faster(model, Compression::SNAPPY, total_uncompressed);
 
             let chosen = if prefer_lz4_many_small_chunks {
                 Some((
@@ -276,6 +371,7 @@ impl Rule for CompressionCodecRule {
                         CodecRecommendation::ZstdLevel3 => Severity::Suggestion,
                         CodecRecommendation::Lz4 => Severity::Warning,
                     },
+                    category: model.category(),
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -290,6 +386,7 @@ impl Rule for CompressionCodecRule {
                         total_uncompressed as f64 / (1024.0 * 1024.0),
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
@@ -300,11 +397,16 @@ impl Rule for CompressionCodecRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cost::{CpuCostModel, GpuCostModel};
     use parquet::basic::{GzipLevel, ZstdLevel};
 
     #[test]
     fn classify_gzip_as_zstd_level_3() {
-        let got = classify_codec_issue(Compression::GZIP(GzipLevel::default()), 1);
+        let got = classify_codec_issue(
+            Compression::GZIP(GzipLevel::default()),
+            1,
+            &CpuCostModel::default(),
+        );
         assert_eq!(
             got,
             Some((
@@ -319,6 +421,7 @@ mod tests {
         let got = classify_codec_issue(
             Compression::UNCOMPRESSED,
             LARGE_UNCOMPRESSED_COLUMN_BYTES + 1,
+            &CpuCostModel::default(),
         );
         assert_eq!(
             got,
@@ -357,7 +460,11 @@ mod tests {
 
     #[test]
     fn classify_large_snappy_as_lz4() {
-        let got = classify_codec_issue(Compression::SNAPPY, LARGE_UNCOMPRESSED_COLUMN_BYTES + 1);
+        let got = classify_codec_issue(
+            Compression::SNAPPY,
+            LARGE_UNCOMPRESSED_COLUMN_BYTES + 1,
+            &CpuCostModel::default(),
+        );
         assert_eq!(
             got,
             Some((
@@ -367,9 +474,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keep_large_snappy_when_gpu_decodes_both_equally_fast() {
+        let got = classify_codec_issue(
+            Compression::SNAPPY,
+            LARGE_UNCOMPRESSED_COLUMN_BYTES + 1,
+            &GpuCostModel::default(),
+        );
+        assert_eq!(got.map(|(r, _)| r), Some(CodecRecommendation::ZstdLevel3));
+    }
+
     #[test]
     fn classify_small_uncompressed_as_zstd_level_3() {
-        let got = classify_codec_issue(Compression::UNCOMPRESSED, LARGE_UNCOMPRESSED_COLUMN_BYTES);
+        let got = classify_codec_issue(
+            Compression::UNCOMPRESSED,
+            LARGE_UNCOMPRESSED_COLUMN_BYTES,
+            &CpuCostModel::default(),
+        );
         assert_eq!(
             got,
             Some((
@@ -384,6 +505,7 @@ mod tests {
         let got = classify_codec_issue(
             Compression::ZSTD(ZstdLevel::try_new(TARGET_ZSTD_LEVEL).expect("valid zstd level")),
             1,
+            &CpuCostModel::default(),
         );
         assert_eq!(got, None);
     }
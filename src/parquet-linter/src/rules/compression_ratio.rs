@@ -1,31 +1,80 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{Codec, Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
 use parquet::basic::Compression;
 
 pub struct CompressionRatioRule;
 
+/// Chunks smaller than this have too little data for a meaningful ratio.
+const MIN_ANOMALY_CHUNK_BYTES: Parameter = Parameter::above(
+    "min_anomaly_chunk_bytes",
+    ParameterKind::Bytes,
+    64.0 * 1024.0,
+);
+/// Row groups needed to tell an outlier from the norm.
+const MIN_ANOMALY_ROW_GROUPS: usize = 4;
+
+/// Row groups of one column that compress far worse than the column's
+/// median: `(row groups, worst ratio, median ratio)`. Outliers must be a
+/// minority, otherwise there is no norm to deviate from.
+fn ratio_anomaly(ratios: &[(usize, f64)]) -> Option<(Vec<usize>, f64, f64)> {
+    if ratios.len() < MIN_ANOMALY_ROW_GROUPS {
+        return None;
+    }
+    let mut sorted: Vec<f64> = ratios.iter().map(|&(_, r)| r).collect();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted[sorted.len() / 2];
+    let outliers: Vec<(usize, f64)> = ratios
+        .iter()
+        .copied()
+        .filter(|&(_, r)| r >= median * 2.0 && r - median >= 0.25)
+        .collect();
+    if outliers.is_empty() || outliers.len() * 2 >= ratios.len() {
+        return None;
+    }
+    let worst = outliers.iter().map(|&(_, r)| r).fold(0.0, f64::max);
+    Some((
+        outliers.into_iter().map(|(rg, _)| rg).collect(),
+        worst,
+        median,
+    ))
+}
+
 #[async_trait::async_trait]
 impl Rule for CompressionRatioRule {
     fn name(&self) -> &'static str {
         "low-compression-ratio"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL001",
+            description: "Columns that barely compress, and row groups that compress far worse than the rest of their column",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MIN_ANOMALY_CHUNK_BYTES],
+            directives: &["compression"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
         if row_groups.is_empty() {
             return diagnostics;
         }
+        let min_anomaly_chunk_bytes = ctx.threshold(self, &MIN_ANOMALY_CHUNK_BYTES) as i64;
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        for col_idx in ctx.column_indices() {
             let mut compressed_sum = 0i64;
             let mut uncompressed_sum = 0i64;
             let mut compressed_groups = 0usize;
             let mut sample_compression = None;
+            let mut ratios = Vec::new();
 
-            for rg in row_groups {
+            for (rg_idx, rg) in row_groups.iter().enumerate() {
                 let col = rg.column(col_idx);
                 if matches!(col.compression(), Compression::UNCOMPRESSED) {
                     continue;
@@ -38,6 +87,9 @@ impl Rule for CompressionRatioRule {
                 uncompressed_sum += uncompressed;
                 compressed_groups += 1;
                 sample_compression = Some(col.compression());
+                if uncompressed >= min_anomaly_chunk_bytes {
+                    ratios.push((rg_idx, col.compressed_size() as f64 / uncompressed as f64));
+                }
             }
 
             if uncompressed_sum <= 0 {
@@ -58,6 +110,7 @@ impl Rule for CompressionRatioRule {
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Warning,
+                    category: Category::Performance,
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -69,9 +122,71 @@ impl Rule for CompressionRatioRule {
                         row_groups.len()
                     ),
                     prescription,
+                    related: Vec::new(),
+                });
+            } else if let Some((outliers, worst, median)) = ratio_anomaly(&ratios) {
+                let path = row_groups[0].column(col_idx).column_path().clone();
+                diagnostics.push(Diagnostic {
+                    rule_name: self.name(),
+                    severity: Severity::Warning,
+                    category: Category::Performance,
+                    location: Location::Column {
+                        column: col_idx,
+                        path,
+                    },
+                    message: format!(
+                        "row groups {} compress to {worst:.2} of their size, against a median \
+                         of {median:.2}; check them for bad data or an encoder fallback",
+                        ctx.row_group_ranges(&outliers)
+                    ),
+                    prescription: Prescription::new(),
+                    related: outliers
+                        .into_iter()
+                        .map(|index| Location::RowGroup { index })
+                        .collect(),
                 });
             }
         }
         diagnostics
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use crate::testing::TestFile;
+
+    #[tokio::test]
+    async fn points_at_poorly_compressed_row_groups() -> anyhow::Result<()> {
+        const ROWS: i64 = 20_000;
+        // Three repetitive row groups, then one of pseudo-random values.
+        let values = (0..4 * ROWS).map(|i| {
+            if i < 3 * ROWS {
+                i % 10
+            } else {
+                i.wrapping_mul(0x2545_F491_4F6C_DD1D)
+            }
+        });
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int64Array::from_iter_values(values)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings(
+            "set file max_row_group_size 20000
+             set file compression zstd(3)
+             set column v dictionary false",
+        );
+        let diagnostics = file.lint(&["low-compression-ratio"]).await?;
+        assert_eq!(diagnostics.len(), 1);
+        let related: Vec<String> = diagnostics[0]
+            .related
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(related, ["row_group[3]"]);
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use std::collections::HashMap;
+
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
 use crate::prescription::{Directive, Prescription};
-use crate::rule::{self, Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
 use parquet::basic::Encoding;
 use parquet::basic::PageType;
 use parquet::column::page::PageReader;
@@ -9,11 +13,13 @@ use parquet::file::metadata::{ColumnChunkMetaData, PageEncodingStats};
 pub struct DictionaryEncodingRule;
 
 /// Above this ratio (distinct / num_values), dictionary encoding is not worthwhile.
-const HIGH_CARDINALITY_RATIO: f64 = 0.5;
+const HIGH_CARDINALITY_RATIO: Parameter =
+    Parameter::above("high_cardinality_ratio", ParameterKind::Ratio, 0.5);
 /// Below this ratio, dictionary encoding is clearly beneficial.
-const LOW_CARDINALITY_RATIO: f64 = 0.1;
+const LOW_CARDINALITY_RATIO: Parameter =
+    Parameter::below("low_cardinality_ratio", ParameterKind::Ratio, 0.1);
 const LARGE_DICT_PAGE_SIZE: usize = 2 * 1024 * 1024; // 2 MB
-const MAX_DICT_PAGE_SIZE: usize = 16 * 1024 * 1024; // 16 MB
+pub(crate) const MAX_DICT_PAGE_SIZE: usize = 16 * 1024 * 1024; // 16 MB
 const AMBIGUOUS_GROUP_SAMPLE_RATIO: f64 = 0.05;
 const DICTIONARY_PAGE_SIZE_HEADROOM_NUMERATOR: u128 = 5;
 const DICTIONARY_PAGE_SIZE_HEADROOM_DENOMINATOR: u128 = 4;
@@ -84,13 +90,25 @@ impl DataPageEncodingSummary {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum ChunkDictionaryState {
+pub(crate) enum ChunkDictionaryState {
     Fallback,
     DictionaryOnly,
     NoDictionary,
     Unknown,
 }
 
+/// Dictionary state of each column's chunks, published for rules that need to
+/// know whether dictionary encoding fell back. Chunks that could not be
+/// classified (ambiguous row groups outside the sample) are left out.
+#[derive(Default)]
+pub(crate) struct DictionaryStates(HashMap<usize, Vec<ChunkDictionaryState>>);
+
+impl DictionaryStates {
+    pub(crate) fn column(&self, col_idx: usize) -> &[ChunkDictionaryState] {
+        self.0.get(&col_idx).map_or(&[], Vec::as_slice)
+    }
+}
+
 fn summarize_metadata_page_encodings(
     page_stats: &[PageEncodingStats],
 ) -> Option<DataPageEncodingSummary> {
@@ -117,7 +135,7 @@ fn summarize_metadata_page_encodings(
     saw_data_page.then_some(summary)
 }
 
-fn classify_from_metadata(col: &ColumnChunkMetaData) -> ChunkDictionaryState {
+pub(crate) fn classify_from_metadata(col: &ColumnChunkMetaData) -> ChunkDictionaryState {
     let encodings: Vec<Encoding> = col.encodings().collect();
     let metadata_has_dictionary = encodings
         .iter()
@@ -152,6 +170,15 @@ fn classify_from_metadata(col: &ColumnChunkMetaData) -> ChunkDictionaryState {
     }
 }
 
+/// Ambiguous row groups whose pages will be read; none in metadata-only runs.
+fn sample_ambiguous_row_groups(ctx: &RuleContext, indices: &[usize]) -> Vec<usize> {
+    if ctx.options.metadata_only {
+        Vec::new()
+    } else {
+        choose_sample_row_groups(indices)
+    }
+}
+
 fn choose_sample_row_groups(indices: &[usize]) -> Vec<usize> {
     if indices.is_empty() {
         return Vec::new();
@@ -180,7 +207,7 @@ fn div_ceil_u128(numerator: u128, denominator: u128) -> Option<u128> {
         .map(|v| v / denominator)
 }
 
-fn estimate_dictionary_payload_bytes(
+pub(crate) fn estimate_dictionary_payload_bytes(
     distinct_count: u64,
     total_values: u128,
     total_uncompressed_bytes: u128,
@@ -200,7 +227,7 @@ fn estimate_dictionary_payload_bytes(
     )
 }
 
-fn suggested_dictionary_page_size_limit(estimated_payload_bytes: Option<u128>) -> usize {
+pub(crate) fn suggested_dictionary_page_size_limit(estimated_payload_bytes: Option<u128>) -> usize {
     let Some(estimated_payload_bytes) = estimated_payload_bytes else {
         return LARGE_DICT_PAGE_SIZE;
     };
@@ -239,7 +266,7 @@ fn suggested_max_row_group_size(
     scaled.max(1).min(usize::MAX as u128) as usize
 }
 
-fn column_size_totals(
+pub(crate) fn column_size_totals(
     row_groups: &[parquet::file::metadata::RowGroupMetaData],
     col_idx: usize,
 ) -> (u128, u128) {
@@ -276,10 +303,11 @@ async fn summarize_data_page_encodings(
     row_group_idx: usize,
     col_idx: usize,
 ) -> Option<DataPageEncodingSummary> {
-    let mut page_reader =
-        rule::column_page_reader(&ctx.reader, &ctx.metadata, row_group_idx, col_idx)
-            .await
-            .ok()?;
+    let mut page_reader = ctx
+        .pages
+        .page_reader(row_group_idx, col_idx, ChunkSpan::Full)
+        .await
+        .ok()?;
     let mut summary = DataPageEncodingSummary::default();
     let mut seen_data_page = false;
 
@@ -315,21 +343,65 @@ impl Rule for DictionaryEncodingRule {
         "dictionary-encoding-cardinality"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL006",
+            description: "Dictionary encoding that does not fit the column cardinality, or dictionaries that fall back to plain",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[HIGH_CARDINALITY_RATIO, LOW_CARDINALITY_RATIO],
+            directives: &[
+                "dictionary",
+                "dictionary_page_size_limit",
+                "max_row_group_size",
+            ],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
         if row_groups.is_empty() {
             return diagnostics;
         }
-
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        let high_cardinality_ratio = ctx.threshold(self, &HIGH_CARDINALITY_RATIO);
+        let low_cardinality_ratio = ctx.threshold(self, &LOW_CARDINALITY_RATIO);
+
+        let columns = ctx.column_indices();
+
+        // Fetch every ambiguous chunk we are about to sample in one batch.
+        let sampled_chunks: Vec<(usize, usize, ChunkSpan)> = columns
+            .iter()
+            .flat_map(|&col_idx| {
+                let ambiguous: Vec<usize> = row_groups
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, rg)| {
+                        let col = rg.column(col_idx);
+                        col.num_values() > 0
+                            && classify_from_metadata(col) == ChunkDictionaryState::Unknown
+                    })
+                    .map(|(rg_idx, _)| rg_idx)
+                    .collect();
+                sample_ambiguous_row_groups(ctx, &ambiguous)
+                    .into_iter()
+                    .map(move |rg_idx| (rg_idx, col_idx, ChunkSpan::Full))
+            })
+            .collect();
+        let _ = ctx.pages.prefetch(&sampled_chunks).await;
+        ctx.columns.load(&columns).await;
+
+        let mut states = DictionaryStates::default();
+        for &col_idx in &columns {
             let path = row_groups[0].column(col_idx).column_path().clone();
             let mut non_empty_groups = 0usize;
             let mut fallback_groups = 0usize;
             let mut no_dict_groups = 0usize;
             let mut ambiguous_groups = Vec::new();
 
+            let known = states.0.entry(col_idx).or_default();
+
             for (rg_idx, row_group) in row_groups.iter().enumerate() {
                 let col = row_group.column(col_idx);
                 if col.num_values() == 0 {
@@ -337,7 +409,11 @@ impl Rule for DictionaryEncodingRule {
                 }
 
                 non_empty_groups += 1;
-                match classify_from_metadata(col) {
+                let state = classify_from_metadata(col);
+                if state != ChunkDictionaryState::Unknown {
+                    known.push(state);
+                }
+                match state {
                     ChunkDictionaryState::Fallback => fallback_groups += 1,
                     ChunkDictionaryState::NoDictionary => no_dict_groups += 1,
                     ChunkDictionaryState::DictionaryOnly => {}
@@ -349,11 +425,15 @@ impl Rule for DictionaryEncodingRule {
                 continue;
             }
 
-            let sampled_ambiguous_groups = choose_sample_row_groups(&ambiguous_groups);
+            let sampled_ambiguous_groups = sample_ambiguous_row_groups(ctx, &ambiguous_groups);
             let mut sampled_fallback_groups = 0usize;
             let mut sampled_no_dict_groups = 0usize;
             for rg_idx in sampled_ambiguous_groups.iter().copied() {
-                match classify_from_sampled_pages(ctx, rg_idx, col_idx).await {
+                let state = classify_from_sampled_pages(ctx, rg_idx, col_idx).await;
+                if state != ChunkDictionaryState::Unknown {
+                    known.push(state);
+                }
+                match state {
                     ChunkDictionaryState::Fallback => sampled_fallback_groups += 1,
                     ChunkDictionaryState::NoDictionary => sampled_no_dict_groups += 1,
                     ChunkDictionaryState::DictionaryOnly | ChunkDictionaryState::Unknown => {}
@@ -363,7 +443,9 @@ impl Rule for DictionaryEncodingRule {
             fallback_groups += sampled_fallback_groups;
             no_dict_groups += sampled_no_dict_groups;
 
-            let col_ctx = &ctx.columns[col_idx];
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
             let ratio = col_ctx.cardinality_ratio();
             let location = Location::Column {
                 column: col_idx,
@@ -381,12 +463,13 @@ impl Rule for DictionaryEncodingRule {
                         sampled_ambiguous_groups.len()
                     )
                 };
-                if ratio > HIGH_CARDINALITY_RATIO {
+                if ratio > high_cardinality_ratio {
                     let mut prescription = Prescription::new();
                     prescription.push(Directive::SetColumnDictionary(path.clone(), false));
                     diagnostics.push(Diagnostic {
                         rule_name: self.name(),
                         severity: Severity::Warning,
+                        category: Category::Performance,
                         location,
                         message: format!(
                             "dictionary data pages fell back to PLAIN in {fallback_groups}/{non_empty_groups} row groups{sampled_suffix}; \
@@ -395,6 +478,7 @@ impl Rule for DictionaryEncodingRule {
                             col_ctx.distinct_count, col_ctx.non_null_count(), ratio * 100.0
                         ),
                         prescription,
+                        related: Vec::new(),
                     });
                 } else {
                     let (total_values, total_uncompressed_bytes) =
@@ -420,6 +504,7 @@ impl Rule for DictionaryEncodingRule {
                         diagnostics.push(Diagnostic {
                             rule_name: self.name(),
                             severity: Severity::Warning,
+                            category: Category::Performance,
                             location,
                             message: format!(
                                 "dictionary data pages fell back to PLAIN in {fallback_groups}/{non_empty_groups} row groups{sampled_suffix}; \
@@ -432,6 +517,7 @@ impl Rule for DictionaryEncodingRule {
                                 MAX_DICT_PAGE_SIZE / 1024 / 1024
                             ),
                             prescription,
+                            related: Vec::new(),
                         });
                     } else {
                         let mut prescription = Prescription::new();
@@ -442,6 +528,7 @@ impl Rule for DictionaryEncodingRule {
                         diagnostics.push(Diagnostic {
                             rule_name: self.name(),
                             severity: Severity::Warning,
+                            category: Category::Performance,
                             location,
                             message: format!(
                                 "dictionary data pages fell back to PLAIN in {fallback_groups}/{non_empty_groups} row groups{sampled_suffix}; \
@@ -450,6 +537,7 @@ impl Rule for DictionaryEncodingRule {
                                 col_ctx.distinct_count, col_ctx.non_null_count(), ratio * 100.0
                             ),
                             prescription,
+                            related: Vec::new(),
                         });
                     }
                 }
@@ -457,12 +545,13 @@ impl Rule for DictionaryEncodingRule {
             }
 
             // No dictionary, but cardinality is low → suggest enabling.
-            if no_dict_groups > 0 && ratio < LOW_CARDINALITY_RATIO {
+            if no_dict_groups > 0 && ratio < low_cardinality_ratio {
                 let mut prescription = Prescription::new();
                 prescription.push(Directive::SetColumnDictionary(path.clone(), true));
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Suggestion,
+                    category: Category::Performance,
                     location,
                     message: format!(
                         "low cardinality (~{} distinct / {} non-null = {:.0}%) and no dictionary in \
@@ -470,9 +559,11 @@ impl Rule for DictionaryEncodingRule {
                         col_ctx.distinct_count, col_ctx.non_null_count(), ratio * 100.0
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
+        ctx.blackboard.publish(states);
         diagnostics
     }
 }
@@ -0,0 +1,175 @@
+use crate::cardinality;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::column::page::{Page, PageReader};
+use parquet::file::metadata::ColumnChunkMetaData;
+
+pub struct DictionaryPageSizeRule;
+
+/// Default `dictionary_page_size_limit` of parquet-rs and parquet-mr. A
+/// writer falls back to PLAIN once the dictionary outgrows it.
+const DEFAULT_DICT_PAGE_SIZE_LIMIT: usize = 1024 * 1024; // 1 MB
+/// Dictionaries from this size, three quarters of the default limit, are
+/// close to falling back as the dataset grows.
+const NEAR_LIMIT_BYTES: Parameter =
+    Parameter::above("min_dictionary_bytes", ParameterKind::Bytes, 768.0 * 1024.0);
+const MAX_DICT_PAGE_SIZE: usize = 16 * 1024 * 1024; // 16 MB
+
+/// A limit with room for the dictionary to double: the next power of two
+/// at or above twice its size, between 2 MB and 16 MB.
+fn suggested_limit(largest_dictionary: usize) -> usize {
+    (largest_dictionary * 2)
+        .next_power_of_two()
+        .clamp(2 * DEFAULT_DICT_PAGE_SIZE_LIMIT, MAX_DICT_PAGE_SIZE)
+}
+
+/// Chunks whose dictionary could be near the limit and has not fallen back
+/// yet. Fallback is the `dictionary-encoding-cardinality` rule's business.
+fn is_candidate(col: &ColumnChunkMetaData, near_limit: i64) -> bool {
+    let known_fallback = (col.page_encoding_stats_mask().is_some()
+        || col.page_encoding_stats().is_some())
+        && !cardinality::fully_dictionary_encoded(col);
+    col.dictionary_page_offset().is_some()
+        && col.uncompressed_size() >= near_limit
+        && !known_fallback
+}
+
+/// Decompressed size of the chunk's dictionary page, reading only that page.
+async fn dictionary_page_size(ctx: &RuleContext, rg: usize, col: usize) -> Option<usize> {
+    let mut pages = ctx
+        .pages
+        .page_reader(rg, col, ChunkSpan::Dictionary)
+        .await
+        .ok()?;
+    match pages.get_next_page() {
+        Ok(Some(Page::DictionaryPage { buf, .. })) => Some(buf.len()),
+        _ => None,
+    }
+}
+
+#[async_trait::async_trait]
+impl Rule for DictionaryPageSizeRule {
+    fn name(&self) -> &'static str {
+        "dictionary-page-size-limit"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL007",
+            description: "Dictionary pages that exceed or approach the writer dictionary page size limit",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[NEAR_LIMIT_BYTES],
+            directives: &["dictionary_page_size_limit"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() || ctx.options.metadata_only {
+            return diagnostics;
+        }
+
+        let near_limit = ctx.threshold(self, &NEAR_LIMIT_BYTES) as usize;
+        let columns = ctx.column_indices();
+        let candidates: Vec<(usize, usize, ChunkSpan)> = columns
+            .iter()
+            .flat_map(|&col_idx| {
+                row_groups
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, rg)| is_candidate(rg.column(col_idx), near_limit as i64))
+                    .map(move |(rg_idx, _)| (rg_idx, col_idx, ChunkSpan::Dictionary))
+            })
+            .collect();
+        let _ = ctx.pages.prefetch(&candidates).await;
+
+        for col_idx in columns {
+            let mut sizes = Vec::new();
+            for &(rg_idx, _, _) in candidates.iter().filter(|(_, c, _)| *c == col_idx) {
+                if let Some(size) = dictionary_page_size(ctx, rg_idx, col_idx).await {
+                    sizes.push(size);
+                }
+            }
+            let near: Vec<usize> = sizes.into_iter().filter(|&s| s >= near_limit).collect();
+            let Some(&largest) = near.iter().max() else {
+                continue;
+            };
+
+            let limit = suggested_limit(largest);
+            let (severity, state) = if largest > DEFAULT_DICT_PAGE_SIZE_LIMIT {
+                (Severity::Warning, "exceeds")
+            } else {
+                (Severity::Suggestion, "approaches")
+            };
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnDictionaryPageSizeLimit(
+                path.clone(),
+                limit,
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "dictionary page of {} KB {state} the 1MB default dictionary_page_size_limit \
+                     in {}/{} row groups; writers with the default limit will fall back to PLAIN \
+                     as data grows, recommend dictionary_page_size_limit={}MB",
+                    largest / 1024,
+                    near.len(),
+                    row_groups.len(),
+                    limit / 1024 / 1024
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{RecordBatch, StringArray};
+
+    use super::*;
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[test]
+    fn suggested_limit_leaves_room_to_double() {
+        assert_eq!(suggested_limit(800 * 1024), 2 * 1024 * 1024);
+        assert_eq!(suggested_limit(1536 * 1024), 4 * 1024 * 1024);
+        assert_eq!(suggested_limit(12 * 1024 * 1024), MAX_DICT_PAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn flags_dictionary_near_default_limit_before_fallback() -> anyhow::Result<()> {
+        // 30k distinct 30-byte strings: a dictionary just under 1 MB.
+        let values = (0..60_000).map(|i| format!("{:030}", i % 30_000));
+        let batch = RecordBatch::try_from_iter([(
+            "s",
+            Arc::new(StringArray::from_iter_values(values)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings("set column s dictionary_page_size_limit 8388608");
+        assert_snapshot(
+            &file.lint(&["dictionary-page-size-limit"]).await?,
+            r#"
+                [suggestion] dictionary-page-size-limit @ column[0]("s"): ...
+                  fix: set column s dictionary_page_size_limit 2097152
+            "#,
+        );
+        Ok(())
+    }
+}
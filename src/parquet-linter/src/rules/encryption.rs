@@ -0,0 +1,152 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::Prescription;
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::file::column_crypto_metadata::ColumnCryptoMetaData;
+
+pub struct EncryptionRule;
+
+#[async_trait::async_trait]
+impl Rule for EncryptionRule {
+    fn name(&self) -> &'static str {
+        "encryption-config"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL030",
+            description: "Encrypted columns whose statistics leak through a plaintext footer, and encryption the target reader cannot decrypt",
+            category: Category::Correctness,
+            default_severity: Severity::Error,
+            parameters: &[],
+            directives: &[],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+        // Without keys only files with a plaintext footer open at all, so
+        // every encrypted column seen here sits behind a plaintext footer.
+        let crypto = |col_idx: usize| row_groups[0].column(col_idx).crypto_metadata();
+        let num_columns = row_groups[0].num_columns();
+        let encrypted: Vec<usize> = (0..num_columns).filter(|&c| crypto(c).is_some()).collect();
+        if encrypted.is_empty() {
+            return diagnostics;
+        }
+
+        for col_idx in ctx.column_indices() {
+            if crypto(col_idx).is_none() {
+                continue;
+            }
+            let leaking = row_groups
+                .iter()
+                .filter(|rg| rg.column(col_idx).statistics().is_some())
+                .count();
+            if leaking == 0 {
+                continue;
+            }
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Error,
+                category: Category::Correctness,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "column is encrypted but its min/max statistics are readable in the \
+                     plaintext footer in {leaking}/{} row groups; encrypt the footer or \
+                     rewrite with a writer that strips them",
+                    row_groups.len()
+                ),
+                prescription: Prescription::new(),
+                related: Vec::new(),
+            });
+        }
+
+        let uniform = encrypted.len() == num_columns
+            && encrypted.iter().all(|&c| {
+                matches!(
+                    crypto(c),
+                    Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
+                )
+            });
+        if uniform {
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Correctness,
+                location: Location::File,
+                message: "every column is encrypted with the footer key, yet the footer is \
+                          plaintext: schema, row counts and sizes are exposed while no column \
+                          is readable without the key; encrypt the footer"
+                    .to_string(),
+                prescription: Prescription::new(),
+                related: Vec::new(),
+            });
+        }
+
+        if let Some(target) = ctx.options.target
+            && !target.reads_encryption()
+        {
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Error,
+                category: Category::Compatibility,
+                location: Location::File,
+                message: format!(
+                    "target {target} cannot decrypt Parquet modular encryption; {}/{num_columns} \
+                     columns are unreadable to it",
+                    encrypted.len()
+                ),
+                prescription: Prescription::new(),
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, RecordBatch};
+    use parquet::encryption::encrypt::FileEncryptionProperties;
+
+    use crate::diagnostic::{Category, Severity};
+    use crate::options::LintOptions;
+    use crate::target::TargetReader;
+    use crate::testing::TestFile;
+
+    #[tokio::test]
+    async fn flags_uniform_key_behind_plaintext_footer() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            ("secret", Arc::new(Int32Array::from(vec![1, 2, 3])) as _),
+            ("public", Arc::new(Int32Array::from(vec![4, 5, 6])) as _),
+        ])?;
+        let encryption = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_plaintext_footer(true)
+            .build()?;
+        let file = TestFile::new(batch)
+            .properties(move |props| props.with_file_encryption_properties(encryption.clone()));
+        let options = LintOptions {
+            target: Some(TargetReader::Legacy),
+            ..LintOptions::default()
+        };
+        let diagnostics = file
+            .lint_with_options(&["encryption-config"], options)
+            .await?;
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].category, Category::Correctness);
+        assert!(diagnostics[1].message.contains("2/2 columns"));
+        assert_eq!(diagnostics[1].category, Category::Compatibility);
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use crate::column_context::TypeStats;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::{LogicalType, Type as PhysicalType};
+
+pub struct EnumStringRule;
+
+const MAX_ENUM_VALUES: Parameter = Parameter::below("max_enum_values", ParameterKind::Count, 100.0);
+const MAX_ENUM_LENGTH: usize = 32;
+
+#[async_trait::async_trait]
+impl Rule for EnumStringRule {
+    fn name(&self) -> &'static str {
+        "enum-like-string"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL008",
+            description: "Low-cardinality short strings that are not dictionary encoded",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[MAX_ENUM_VALUES],
+            directives: &["dictionary"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+        let workload = &ctx.options.workload;
+        let max_enum_values = ctx.threshold(self, &MAX_ENUM_VALUES) as u64;
+
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let descr = row_groups[0].column(col_idx).column_descr();
+                descr.physical_type() == PhysicalType::BYTE_ARRAY
+                    && !matches!(descr.logical_type_ref(), Some(LogicalType::Enum))
+            })
+            .collect();
+        ctx.columns.load(&candidates).await;
+
+        for col_idx in candidates {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let TypeStats::String(stats) = &col_ctx.type_stats else {
+                continue;
+            };
+            let Some(lengths) = &stats.lengths else {
+                continue;
+            };
+            if col_ctx.distinct_count == 0
+                || col_ctx.distinct_count >= max_enum_values
+                || lengths.max > MAX_ENUM_LENGTH
+            {
+                continue;
+            }
+
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let without_dictionary = row_groups
+                .iter()
+                .filter(|rg| {
+                    let col = rg.column(col_idx);
+                    col.num_values() > 0 && col.dictionary_page_offset().is_none()
+                })
+                .count();
+            let query_heavy = workload.is_filter_column(&path) || workload.is_join_key(&path);
+            // Dictionary-encoded columns only lose out when queries lean on them.
+            if without_dictionary == 0 && !query_heavy {
+                continue;
+            }
+
+            let mut prescription = Prescription::new();
+            let mut message = format!(
+                "string column looks like an enum (~{} distinct values, at most {}B long); \
+                 annotate it as ENUM in the writer schema",
+                col_ctx.distinct_count, lengths.max
+            );
+            if without_dictionary > 0 {
+                prescription.push(Directive::SetColumnDictionary(path.clone(), true));
+                message.push_str(&format!(
+                    " and dictionary-encode it ({without_dictionary}/{} row groups have no \
+                     dictionary)",
+                    row_groups.len()
+                ));
+            }
+            if query_heavy {
+                message.push_str(
+                    "; queries filter or join on it, so mapping values to small integer codes \
+                     would make comparisons cheaper still",
+                );
+            }
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Suggestion,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message,
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{RecordBatch, StringArray};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn suggests_dictionary_for_enum_like_strings() -> anyhow::Result<()> {
+        let statuses = ["open", "closed", "pending"];
+        let values = (0..3000).map(|i| statuses[i % 3]);
+        let batch = RecordBatch::try_from_iter([(
+            "status",
+            Arc::new(StringArray::from_iter_values(values)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings("set column status dictionary false");
+        assert_snapshot(
+            &file.lint(&["enum-like-string"]).await?,
+            r#"
+                [suggestion] enum-like-string @ column[0]("status"): string column looks like an enum (~3 distinct values, at most 7B long); ...
+                  fix: set column status dictionary true
+            "#,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::column_context::TypeStats;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{DataEncoding, Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::basic::{LogicalType, Type as PhysicalType};
+
+pub struct EpochTimestampRule;
+
+/// 2000-01-01 and 2100-01-01 in seconds since the epoch: values of a real
+/// timestamp column land between these, scaled to its unit.
+const MIN_PLAUSIBLE_SECONDS: i64 = 946_684_800;
+const MAX_PLAUSIBLE_SECONDS: i64 = 4_102_444_800;
+
+const TEMPORAL_WORDS: &[&str] = &[
+    "ts",
+    "time",
+    "timestamp",
+    "date",
+    "datetime",
+    "epoch",
+    "at",
+    "ms",
+    "us",
+    "millis",
+    "micros",
+];
+
+/// The timestamp unit whose plausible range holds both bounds.
+fn epoch_unit(min: i64, max: i64) -> Option<&'static str> {
+    [("MILLIS", 1_000), ("MICROS", 1_000_000)]
+        .into_iter()
+        .find(|&(_, scale)| {
+            let range = MIN_PLAUSIBLE_SECONDS * scale..=MAX_PLAUSIBLE_SECONDS * scale;
+            range.contains(&min) && range.contains(&max)
+        })
+        .map(|(unit, _)| unit)
+}
+
+/// Whether a column name has a temporal word in it, splitting on
+/// punctuation and camelCase (`created_at`, `eventTime`, `ts_ms`).
+fn looks_temporal(name: &str) -> bool {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in name.chars() {
+        if !c.is_alphanumeric() || (c.is_uppercase() && !word.is_empty()) {
+            words.push(std::mem::take(&mut word));
+        }
+        if c.is_alphanumeric() {
+            word.push(c.to_ascii_lowercase());
+        }
+    }
+    words.push(word);
+    words.iter().any(|w| TEMPORAL_WORDS.contains(&w.as_str()))
+}
+
+#[async_trait::async_trait]
+impl Rule for EpochTimestampRule {
+    fn name(&self) -> &'static str {
+        "epoch-timestamp-as-int64"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL019",
+            description: "INT64 columns holding epoch timestamps without a timestamp logical type",
+            category: Category::Correctness,
+            default_severity: Severity::Suggestion,
+            parameters: &[],
+            directives: &["encoding"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let descr = row_groups[0].column(col_idx).column_descr();
+                let unannotated = matches!(
+                    descr.logical_type_ref(),
+                    None | Some(LogicalType::Integer { .. })
+                );
+                descr.physical_type() == PhysicalType::INT64
+                    && unannotated
+                    && looks_temporal(descr.name())
+            })
+            .collect();
+        ctx.columns.load(&candidates).await;
+
+        for col_idx in candidates {
+            let descr = row_groups[0].column(col_idx).column_descr();
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let TypeStats::Int(stats) = &col_ctx.type_stats else {
+                continue;
+            };
+            let (Some(min), Some(max)) = (stats.min, stats.max) else {
+                continue;
+            };
+            let Some(unit) = epoch_unit(min, max) else {
+                continue;
+            };
+
+            let path = descr.path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnEncoding(
+                path.clone(),
+                DataEncoding::DeltaBinaryPacked,
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Suggestion,
+                category: Category::Correctness,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "INT64 column without a logical type holds values ({min}..{max}) in the \
+                     epoch-{} range; annotate it as TIMESTAMP({unit}) in the writer schema so \
+                     engines infer a timestamp, and use DELTA_BINARY_PACKED",
+                    unit.to_lowercase()
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_epoch_units_and_temporal_names() {
+        let millis = 1_700_000_000_000;
+        assert_eq!(epoch_unit(millis, millis + 1000), Some("MILLIS"));
+        assert_eq!(epoch_unit(millis * 1000, millis * 1000), Some("MICROS"));
+        assert_eq!(epoch_unit(0, millis), None);
+        assert!(looks_temporal("created_at"));
+        assert!(looks_temporal("eventTime"));
+        assert!(looks_temporal("ts_ms"));
+        assert!(!looks_temporal("user_id"));
+        assert!(!looks_temporal("status"));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::column_context::TypeStats;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{CastType, Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::basic::Type as PhysicalType;
+
+pub struct FixedWidthBinaryRule;
+
+#[async_trait::async_trait]
+impl Rule for FixedWidthBinaryRule {
+    fn name(&self) -> &'static str {
+        "fixed-width-binary"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL023",
+            description: "Binary columns whose values all have the same length",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[],
+            directives: &["cast"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+
+        // The rewrite casts top-level fields only.
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let col0 = row_groups[0].column(col_idx);
+                col0.column_descr().physical_type() == PhysicalType::BYTE_ARRAY
+                    && col0.column_path().parts().len() == 1
+            })
+            .collect();
+        ctx.columns.load(&candidates).await;
+
+        for col_idx in candidates {
+            let col0 = row_groups[0].column(col_idx);
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            // Strings stay strings; only raw binary (hashes, digests, ids) is cast.
+            let TypeStats::Binary(stats) = &col_ctx.type_stats else {
+                continue;
+            };
+            let Some(lengths) = &stats.lengths else {
+                continue;
+            };
+            if lengths.min == 0 || lengths.min != lengths.max {
+                continue;
+            }
+
+            let width = lengths.min;
+            let path = col0.column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnCast(
+                path.clone(),
+                CastType::FixedLenByteArray(width),
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Suggestion,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "every sampled value is {width} bytes long; FIXED_LEN_BYTE_ARRAY({width}) \
+                     drops the 4-byte length prefix per value and decodes without offsets"
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{BinaryArray, RecordBatch};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn suggests_fixed_len_for_digests() -> anyhow::Result<()> {
+        let digests: Vec<[u8; 16]> = (0..1000u128)
+            .map(|i| (i * 0x9E37_79B9).to_le_bytes())
+            .collect();
+        let batch = RecordBatch::try_from_iter([(
+            "h",
+            Arc::new(BinaryArray::from_iter_values(&digests)) as _,
+        )])?;
+        assert_snapshot(
+            &TestFile::new(batch).lint(&["fixed-width-binary"]).await?,
+            r#"
+                [suggestion] fixed-width-binary @ column[0]("h"): every sampled value is 16 bytes long; ...
+                  fix: set column h cast fixed_len_byte_array(16)
+            "#,
+        );
+        Ok(())
+    }
+}
@@ -1,12 +1,24 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::cost::ColumnShape;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{DataEncoding, Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::probe::EncodingTrial;
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
 use parquet::basic::{Encoding, Type as PhysicalType};
+use parquet::schema::types::ColumnDescriptor;
 
 pub struct FloatEncodingRule;
 
 /// Below this ratio, dictionary encoding is better than BYTE_STREAM_SPLIT.
-const LOW_CARDINALITY_RATIO: f64 = 0.1;
+const LOW_CARDINALITY_RATIO: Parameter =
+    Parameter::above("low_cardinality_ratio", ParameterKind::Ratio, 0.1);
+
+fn is_scalar_float(descr: &ColumnDescriptor) -> bool {
+    matches!(
+        descr.physical_type(),
+        PhysicalType::FLOAT | PhysicalType::DOUBLE
+    ) && descr.max_rep_level() == 0
+}
 
 #[async_trait::async_trait]
 impl Rule for FloatEncodingRule {
@@ -14,6 +26,18 @@ impl Rule for FloatEncodingRule {
         "float-byte-stream-split"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL012",
+            description: "Float columns that would shrink with BYTE_STREAM_SPLIT",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[LOW_CARDINALITY_RATIO],
+            directives: &["encoding"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -21,21 +45,40 @@ impl Rule for FloatEncodingRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        let float_columns: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| is_scalar_float(row_groups[0].column(col_idx).column_descr()))
+            .collect();
+        ctx.columns.load(&float_columns).await;
+        let low_cardinality_ratio = ctx.threshold(self, &LOW_CARDINALITY_RATIO);
+        for col_idx in float_columns {
             let col0 = row_groups[0].column(col_idx);
-            let descr = col0.column_descr();
-            let is_scalar_float = matches!(
-                descr.physical_type(),
-                PhysicalType::FLOAT | PhysicalType::DOUBLE
-            ) && descr.max_rep_level() == 0;
-
-            if !is_scalar_float {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            // Low cardinality floats are better served by dictionary encoding
+            if col_ctx.cardinality_ratio() < low_cardinality_ratio {
                 continue;
             }
 
-            // Low cardinality floats are better served by dictionary encoding
-            if ctx.columns[col_idx].cardinality_ratio() < LOW_CARDINALITY_RATIO {
+            // When the encoding probe ran, trust its measured sizes instead.
+            let model = ctx.options.cost_model.as_ref();
+            let scan_cost = |t: &EncodingTrial| {
+                model.scan_cost(&ColumnShape {
+                    codec: col0.compression(),
+                    encoding: t.encoding,
+                    uncompressed_size: t.uncompressed_size,
+                    compressed_size: t.compressed_size,
+                    num_values: col_ctx.num_values,
+                    distinct_values: Some(col_ctx.distinct_count),
+                })
+            };
+            if let Some(bss) = col_ctx.encoding_trial(Encoding::BYTE_STREAM_SPLIT)
+                && col_ctx.encoding_trials.iter().any(|t| {
+                    t.encoding != Encoding::BYTE_STREAM_SPLIT && scan_cost(t) <= scan_cost(bss)
+                })
+            {
                 continue;
             }
 
@@ -73,6 +116,7 @@ impl Rule for FloatEncodingRule {
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Suggestion,
+                    category: Category::Performance,
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -83,6 +127,7 @@ impl Rule for FloatEncodingRule {
                          BYTE_STREAM_SPLIT typically compresses 2-4x better"
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
@@ -0,0 +1,151 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::file::metadata::ParquetMetaData;
+
+pub struct FooterSizeRule;
+
+/// Footers above this dominate latency for planners that only read
+/// metadata, since every query parses the whole thrift blob.
+const MAX_FOOTER_BYTES: Parameter = Parameter::above(
+    "max_footer_bytes",
+    ParameterKind::Bytes,
+    16.0 * 1024.0 * 1024.0,
+);
+/// Rough thrift size of a column chunk's fixed fields (offsets, sizes,
+/// codec, encodings, counts), excluding its path and statistics.
+const CHUNK_OVERHEAD_BYTES: usize = 96;
+/// Truncate statistics to this length when they are most of the footer.
+const FOOTER_TRUNCATE_LENGTH: usize = 16;
+
+/// Estimated serialized footer size, from what the parsed metadata holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FooterEstimate {
+    bytes: usize,
+    /// Min/max statistics payload, part of `bytes`.
+    stats_bytes: usize,
+    row_groups: usize,
+    columns: usize,
+}
+
+impl FooterEstimate {
+    fn from_metadata(metadata: &ParquetMetaData) -> Self {
+        let mut bytes = 0;
+        let mut stats_bytes = 0;
+        for chunk in metadata.row_groups().iter().flat_map(|rg| rg.columns()) {
+            // Each chunk repeats its full path in the footer.
+            let path: usize = chunk.column_path().parts().iter().map(|p| p.len()).sum();
+            let stats = chunk.statistics().map_or(0, |s| {
+                s.min_bytes_opt().map_or(0, |b| b.len()) + s.max_bytes_opt().map_or(0, |b| b.len())
+            });
+            bytes += CHUNK_OVERHEAD_BYTES + path + stats;
+            stats_bytes += stats;
+        }
+        Self {
+            bytes,
+            stats_bytes,
+            row_groups: metadata.num_row_groups(),
+            columns: metadata.file_metadata().schema_descr().num_columns(),
+        }
+    }
+
+    /// Directives that bring the footer under `max_bytes`: truncated
+    /// statistics when they dominate, otherwise fewer, larger row groups.
+    fn prescription(&self, num_rows: i64, max_bytes: usize) -> Prescription {
+        let mut prescription = Prescription::new();
+        let stats_share = self.stats_bytes as f64 / self.bytes.max(1) as f64;
+        if stats_share > 0.5 {
+            prescription.push(Directive::SetFileStatisticsTruncateLength(Some(
+                FOOTER_TRUNCATE_LENGTH,
+            )));
+        } else if self.row_groups > 1 {
+            let target_groups = (self.row_groups * max_bytes / self.bytes).max(1);
+            let rows = (num_rows.max(1) as usize).div_ceil(target_groups);
+            prescription.push(Directive::SetFileMaxRowGroupSize(rows.next_power_of_two()));
+        }
+        prescription
+    }
+}
+
+#[async_trait::async_trait]
+impl Rule for FooterSizeRule {
+    fn name(&self) -> &'static str {
+        "footer-size"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL029",
+            description: "Footers large enough to slow down opening the file",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MAX_FOOTER_BYTES],
+            directives: &["max_row_group_size", "statistics_truncate_length"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let estimate = FooterEstimate::from_metadata(&ctx.metadata);
+        let max_bytes = ctx.threshold(self, &MAX_FOOTER_BYTES) as usize;
+        if estimate.bytes <= max_bytes {
+            return Vec::new();
+        }
+        let prescription =
+            estimate.prescription(ctx.metadata.file_metadata().num_rows(), max_bytes);
+        let advice = if prescription.is_empty() {
+            "split the table into files with fewer columns"
+        } else if estimate.stats_bytes * 2 > estimate.bytes {
+            "truncate statistics, or split the table into files with fewer columns"
+        } else {
+            "write fewer, larger row groups, or split the table into files with fewer columns"
+        };
+        vec![Diagnostic {
+            rule_name: self.name(),
+            severity: Severity::Warning,
+            category: Category::Performance,
+            location: Location::File,
+            message: format!(
+                "footer is about {:.1}MB ({} columns x {} row groups, {:.1}MB of statistics); \
+                 every metadata-only read parses all of it, {advice}",
+                estimate.bytes as f64 / 1048576.0,
+                estimate.columns,
+                estimate.row_groups,
+                estimate.stats_bytes as f64 / 1048576.0,
+            ),
+            prescription,
+            related: Vec::new(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prescribes_for_the_largest_share_of_the_footer() {
+        let max_bytes = MAX_FOOTER_BYTES.default as usize;
+        let many_row_groups = FooterEstimate {
+            bytes: 4 * max_bytes,
+            stats_bytes: max_bytes,
+            row_groups: 1000,
+            columns: 2000,
+        };
+        assert_eq!(
+            many_row_groups
+                .prescription(100_000_000, max_bytes)
+                .to_string(),
+            "set file max_row_group_size 524288"
+        );
+        let large_stats = FooterEstimate {
+            stats_bytes: 3 * max_bytes,
+            ..many_row_groups
+        };
+        assert_eq!(
+            large_stats.prescription(100_000_000, max_bytes).to_string(),
+            "set file statistics_truncate_length 16"
+        );
+    }
+}
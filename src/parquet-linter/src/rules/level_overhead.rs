@@ -0,0 +1,230 @@
+use crate::cardinality;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
+use crate::prescription::Prescription;
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::Encoding;
+use parquet::column::page::{Page, PageReader};
+
+pub struct LevelOverheadRule;
+
+/// Chunks smaller than this cost too little to be worth a schema change.
+const MIN_CHUNK_BYTES: Parameter =
+    Parameter::above("min_chunk_bytes", ParameterKind::Bytes, 16.0 * 1024.0);
+/// Definition levels only run-length encode runs of at least 8 equal
+/// levels; nulls in shorter runs on average leave them bit-packed.
+const MIN_RLE_RUN: f64 = 8.0;
+
+/// Level and value bytes of one decompressed page. V1 pages prefix each
+/// RLE level run with its length; deprecated BIT_PACKED levels have none,
+/// so those pages are skipped.
+fn split_page(page: &Page, max_rep: i16, max_def: i16) -> Option<(usize, usize)> {
+    match page {
+        Page::DataPageV2 {
+            buf,
+            def_levels_byte_len,
+            rep_levels_byte_len,
+            ..
+        } => {
+            let levels = (*def_levels_byte_len + *rep_levels_byte_len) as usize;
+            Some((levels, buf.len().saturating_sub(levels)))
+        }
+        Page::DataPage {
+            buf,
+            rep_level_encoding,
+            def_level_encoding,
+            ..
+        } => {
+            let mut levels = 0;
+            for (max, encoding) in [(max_rep, rep_level_encoding), (max_def, def_level_encoding)] {
+                if max == 0 {
+                    continue;
+                }
+                if *encoding != Encoding::RLE {
+                    return None;
+                }
+                let len = buf.get(levels..levels + 4)?;
+                levels += 4 + u32::from_le_bytes(len.try_into().ok()?) as usize;
+            }
+            Some((levels, buf.len().saturating_sub(levels)))
+        }
+        Page::DictionaryPage { buf, .. } => Some((0, buf.len())),
+    }
+}
+
+/// Level and value bytes of a whole chunk.
+async fn level_bytes(ctx: &RuleContext, rg: usize, col: usize) -> Option<(usize, usize)> {
+    let descr = ctx.metadata.row_group(rg).column(col).column_descr_ptr();
+    let mut pages = ctx.pages.page_reader(rg, col, ChunkSpan::Full).await.ok()?;
+    let (mut levels, mut values) = (0, 0);
+    while let Ok(Some(page)) = pages.get_next_page() {
+        let (l, v) = split_page(&page, descr.max_rep_level(), descr.max_def_level())?;
+        levels += l;
+        values += v;
+    }
+    Some((levels, values))
+}
+
+#[async_trait::async_trait]
+impl Rule for LevelOverheadRule {
+    fn name(&self) -> &'static str {
+        "level-overhead"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL020",
+            description: "Nullable or repeated columns whose definition and repetition levels cost more than their values",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MIN_CHUNK_BYTES],
+            directives: &[],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if ctx.metadata.num_row_groups() == 0 || ctx.options.metadata_only {
+            return diagnostics;
+        }
+        let min_chunk_bytes = ctx.threshold(self, &MIN_CHUNK_BYTES) as i64;
+        let sample_rg = cardinality::pick_sample_row_group(&ctx.metadata);
+        let row_group = ctx.metadata.row_group(sample_rg);
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let col = row_group.column(col_idx);
+                let descr = col.column_descr();
+                (descr.max_def_level() > 0 || descr.max_rep_level() > 0)
+                    && col.uncompressed_size() >= min_chunk_bytes
+            })
+            .collect();
+        let chunks: Vec<_> = candidates
+            .iter()
+            .map(|&col| (sample_rg, col, ChunkSpan::Full))
+            .collect();
+        let _ = ctx.pages.prefetch(&chunks).await;
+
+        let mut flagged = Vec::new();
+        for col_idx in candidates {
+            let Some((levels, values)) = level_bytes(ctx, sample_rg, col_idx).await else {
+                continue;
+            };
+            if levels > values {
+                flagged.push((col_idx, levels, values));
+            }
+        }
+        // Sampled null runs tell scattered nulls from ones that come in blocks.
+        let columns: Vec<usize> = flagged.iter().map(|&(col_idx, ..)| col_idx).collect();
+        ctx.columns.load(&columns).await;
+
+        for (col_idx, levels, values) in flagged {
+            let col = row_group.column(col_idx);
+            let descr = col.column_descr();
+            let no_nulls = ctx.metadata.row_groups().iter().all(|rg| {
+                let stats = rg.column(col_idx).statistics();
+                stats.and_then(|s| s.null_count_opt()) == Some(0)
+            });
+            let null_runs = ctx
+                .columns
+                .get(col_idx)
+                .await
+                .and_then(|c| c.null_runs.clone());
+            let advice = if descr.max_rep_level() > 0 {
+                "flatten the repeated field into its own table or fixed columns".to_string()
+            } else if no_nulls {
+                "the column holds no nulls, declare it and its ancestors required".to_string()
+            } else if let Some(runs) =
+                null_runs.filter(|r| r.num_runs() > 0 && r.avg_run_length() < MIN_RLE_RUN)
+            {
+                format!(
+                    "nulls are scattered (sampled runs average {:.1} rows), so levels stay \
+                     bit-packed; sort or cluster rows so nulls come in blocks",
+                    runs.avg_run_length()
+                )
+            } else {
+                "declare ancestors that are never null as required".to_string()
+            };
+            let share = levels as f64 / (levels + values).max(1) as f64;
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path: col.column_path().clone(),
+                },
+                message: format!(
+                    "repetition/definition levels take {:.0}% of decoded page bytes \
+                     ({} KB levels vs {} KB values, max rep {} def {}); {advice}",
+                    share * 100.0,
+                    levels / 1024,
+                    values / 1024,
+                    descr.max_rep_level(),
+                    descr.max_def_level()
+                ),
+                prescription: Prescription::new(),
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::builder::{Int32Builder, ListBuilder};
+    use arrow_array::{ArrayRef, BooleanArray, RecordBatch};
+
+    use crate::options::LintOptions;
+    use crate::testing::TestFile;
+
+    #[tokio::test]
+    async fn flags_lists_dominated_by_levels() -> anyhow::Result<()> {
+        // Nulls, empty lists and one repeated value: levels without values.
+        let mut lists = ListBuilder::new(Int32Builder::new());
+        for i in 0..200_000 {
+            match i % 3 {
+                0 => lists.append_null(),
+                1 => lists.append(true),
+                _ => {
+                    lists.values().append_value(7);
+                    lists.append(true);
+                }
+            }
+        }
+        let batch = RecordBatch::try_from_iter([("l", Arc::new(lists.finish()) as ArrayRef)])?;
+        let diagnostics = TestFile::new(batch).lint(&["level-overhead"]).await?;
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("flatten"));
+        Ok(())
+    }
+    #[tokio::test]
+    async fn scattered_nulls_suggest_clustering() -> anyhow::Result<()> {
+        // Every other value null: one bit-packed level per row against one
+        // bit per present boolean.
+        let flags: BooleanArray = (0..200_000).map(|i| (i % 2 == 0).then_some(true)).collect();
+        let batch = RecordBatch::try_from_iter([("b", Arc::new(flags) as ArrayRef)])?;
+        let options = LintOptions {
+            profile_values: true,
+            ..LintOptions::default()
+        };
+        let diagnostics = TestFile::new(batch)
+            .lint_with_options(&["level-overhead"], options)
+            .await?;
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("nulls are scattered (sampled runs average 1.0 rows)"),
+            "{}",
+            diagnostics[0].message
+        );
+        Ok(())
+    }
+}
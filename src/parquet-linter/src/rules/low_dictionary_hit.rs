@@ -0,0 +1,214 @@
+use super::dictionary_encoding::{
+    ChunkDictionaryState, DictionaryStates, MAX_DICT_PAGE_SIZE, classify_from_metadata,
+    column_size_totals, estimate_dictionary_payload_bytes, suggested_dictionary_page_size_limit,
+};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::Type as PhysicalType;
+
+pub struct LowDictionaryHitRule;
+
+/// Below this ratio (distinct / non-null), every value repeats often enough
+/// that a dictionary should hold for the whole chunk.
+const VERY_LOW_CARDINALITY_RATIO: f64 = 0.01;
+/// How many times larger than dictionary indices the stored values must be
+/// before the chunk counts as not using its dictionary.
+const MIN_SIZE_EXCESS: f64 = 4.0;
+/// Average uncompressed bytes per row group below which the waste is too
+/// small to matter.
+const MIN_ROW_GROUP_BYTES: Parameter =
+    Parameter::above("min_row_group_bytes", ParameterKind::Bytes, 1024.0 * 1024.0);
+
+/// Bytes per value of dictionary indices for `distinct` values, as bit-packed
+/// by the RLE/bit-packing hybrid.
+fn index_bytes_per_value(distinct: u64) -> f64 {
+    let bits = u64::BITS - distinct.saturating_sub(1).leading_zeros();
+    f64::from(bits.max(1)) / 8.0
+}
+
+/// Columns with so few distinct values that dictionary indices would take a
+/// byte or two per value, stored at many times that size: the writer had
+/// dictionary encoding disabled or gave up on it early. The dictionary
+/// fallback classification says which.
+#[async_trait::async_trait]
+impl Rule for LowDictionaryHitRule {
+    fn name(&self) -> &'static str {
+        "low-dictionary-hit"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL031",
+            description: "Very low-cardinality columns stored far larger than their dictionary indices would be",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MIN_ROW_GROUP_BYTES],
+            directives: &["dictionary", "dictionary_page_size_limit"],
+        }
+    }
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        &["dictionary-encoding-cardinality"]
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+
+        let dictionary_states = ctx.blackboard.get::<DictionaryStates>();
+        let min_row_group_bytes = ctx.threshold(self, &MIN_ROW_GROUP_BYTES);
+        let mut candidates = Vec::new();
+        for col_idx in ctx.column_indices() {
+            if row_groups[0].column(col_idx).column_descr().physical_type() == PhysicalType::BOOLEAN
+            {
+                continue;
+            }
+            let (total_values, total_uncompressed) = column_size_totals(row_groups, col_idx);
+            let non_empty_groups = row_groups
+                .iter()
+                .filter(|rg| rg.column(col_idx).num_values() > 0)
+                .count();
+            if non_empty_groups > 0
+                && (total_uncompressed as f64 / non_empty_groups as f64) >= min_row_group_bytes
+            {
+                candidates.push((col_idx, total_values, total_uncompressed, non_empty_groups));
+            }
+        }
+        let columns: Vec<usize> = candidates.iter().map(|&(col_idx, ..)| col_idx).collect();
+        ctx.columns.load(&columns).await;
+
+        for (col_idx, total_values, total_uncompressed, non_empty_groups) in candidates {
+            let col0 = row_groups[0].column(col_idx);
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let non_null = col_ctx.non_null_count();
+            if col_ctx.distinct_count == 0
+                || non_null == 0
+                || col_ctx.cardinality_ratio() >= VERY_LOW_CARDINALITY_RATIO
+            {
+                continue;
+            }
+            let bytes_per_value = total_uncompressed as f64 / non_null as f64;
+            let index_bytes = index_bytes_per_value(col_ctx.distinct_count);
+            if bytes_per_value < MIN_SIZE_EXCESS * index_bytes {
+                continue;
+            }
+
+            // Without the dictionary rule's data-page classification, fall
+            // back to what chunk metadata can tell on its own.
+            let metadata_states;
+            let states = match dictionary_states
+                .as_deref()
+                .map(|states| states.column(col_idx))
+            {
+                Some(states) if !states.is_empty() => states,
+                _ => {
+                    metadata_states = row_groups
+                        .iter()
+                        .map(|rg| rg.column(col_idx))
+                        .filter(|col| col.num_values() > 0)
+                        .map(classify_from_metadata)
+                        .collect::<Vec<_>>();
+                    &metadata_states
+                }
+            };
+            let count = |state| states.iter().filter(|s| **s == state).count();
+            let (disabled, fallback) = (
+                count(ChunkDictionaryState::NoDictionary),
+                count(ChunkDictionaryState::Fallback),
+            );
+            let cause = match (disabled, fallback) {
+                (0, 0) => continue,
+                (disabled, 0) => format!("dictionary encoding is disabled in {disabled}"),
+                (0, fallback) => format!("the dictionary fell back to PLAIN in {fallback}"),
+                (disabled, fallback) => format!(
+                    "dictionary encoding is disabled in {disabled} and fell back to PLAIN in {fallback}"
+                ),
+            };
+
+            let page_size_limit =
+                suggested_dictionary_page_size_limit(estimate_dictionary_payload_bytes(
+                    col_ctx.distinct_count,
+                    total_values,
+                    total_uncompressed,
+                ))
+                .min(MAX_DICT_PAGE_SIZE);
+            let path = col0.column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnDictionary(path.clone(), true));
+            prescription.push(Directive::SetColumnDictionaryPageSizeLimit(
+                path.clone(),
+                page_size_limit,
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "only ~{} distinct values ({:.2}% of {non_null} non-null) but {:.1} bytes per \
+                     value uncompressed, {:.0}x what dictionary indices would take; {cause} of \
+                     {non_empty_groups} row groups",
+                    col_ctx.distinct_count,
+                    col_ctx.cardinality_ratio() * 100.0,
+                    bytes_per_value,
+                    bytes_per_value / index_bytes,
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn flags_low_cardinality_column_without_dictionary() -> anyhow::Result<()> {
+        let values = Int64Array::from_iter_values((0..300_000).map(|i| (i % 50) * 1_000_003));
+        let batch = RecordBatch::try_from_iter([("status", Arc::new(values) as _)])?;
+        let rules = ["dictionary-encoding-cardinality", "low-dictionary-hit"];
+
+        let plain = TestFile::new(batch.clone()).settings("set column status dictionary false");
+        let diagnostics: Vec<_> = plain
+            .lint(&rules)
+            .await?
+            .into_iter()
+            .filter(|d| d.rule_name == "low-dictionary-hit")
+            .collect();
+        assert_snapshot(
+            &diagnostics,
+            r#"
+                [warning] low-dictionary-hit @ column[0]("status"): only ~...
+                  fix: set column status dictionary true
+                  fix: set column status dictionary_page_size_limit 2097152
+            "#,
+        );
+        assert!(
+            diagnostics[0]
+                .message
+                .ends_with("dictionary encoding is disabled in 1 of 1 row groups")
+        );
+
+        let dictionary = TestFile::new(batch);
+        assert!(dictionary.lint(&["low-dictionary-hit"]).await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Codec, Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::basic::Compression;
+
+pub struct MixedCodecsRule;
+
+/// Row groups of one column written with the same codec.
+struct CodecGroup {
+    codec: Compression,
+    row_groups: Vec<usize>,
+    uncompressed_bytes: i64,
+}
+
+fn group_by_codec(codecs: impl IntoIterator<Item = (Compression, i64)>) -> Vec<CodecGroup> {
+    let mut groups: Vec<CodecGroup> = Vec::new();
+    for (rg_idx, (codec, uncompressed_bytes)) in codecs.into_iter().enumerate() {
+        match groups.iter_mut().find(|g| g.codec == codec) {
+            Some(group) => {
+                group.row_groups.push(rg_idx);
+                group.uncompressed_bytes += uncompressed_bytes;
+            }
+            None => groups.push(CodecGroup {
+                codec,
+                row_groups: vec![rg_idx],
+                uncompressed_bytes,
+            }),
+        }
+    }
+    groups
+}
+
+/// The codec covering the most data that a prescription can express;
+/// ties go to the codec that appears first.
+fn majority_codec(groups: &[CodecGroup]) -> Option<Codec> {
+    groups
+        .iter()
+        .filter_map(|g| Some((Codec::from_compression(g.codec)?, g.uncompressed_bytes)))
+        .rev()
+        .max_by_key(|&(_, bytes)| bytes)
+        .map(|(codec, _)| codec)
+}
+
+#[async_trait::async_trait]
+impl Rule for MixedCodecsRule {
+    fn name(&self) -> &'static str {
+        "mixed-compression-codecs"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL016",
+            description: "Columns compressed with different codecs in different row groups",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[],
+            directives: &["compression"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.len() < 2 {
+            return diagnostics;
+        }
+
+        for col_idx in ctx.column_indices() {
+            let groups = group_by_codec(row_groups.iter().map(|rg| {
+                let col = rg.column(col_idx);
+                (col.compression(), col.uncompressed_size())
+            }));
+            if groups.len() < 2 {
+                continue;
+            }
+            let Some(codec) = majority_codec(&groups) else {
+                continue;
+            };
+
+            let evidence: Vec<String> = groups
+                .iter()
+                .map(|g| {
+                    format!(
+                        "{} in row groups {}",
+                        g.codec,
+                        ctx.row_group_ranges(&g.row_groups)
+                    )
+                })
+                .collect();
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnCompression(path.clone(), codec));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "compression differs between row groups ({}), likely appends with different \
+                     writer configs; recommend {codec} throughout",
+                    evidence.join("; ")
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::basic::ZstdLevel;
+
+    #[test]
+    fn majority_codec_weighs_row_groups_by_bytes() {
+        let zstd = Compression::ZSTD(ZstdLevel::default());
+        let groups = group_by_codec([
+            (Compression::SNAPPY, 100),
+            (zstd, 500),
+            (Compression::SNAPPY, 100),
+        ]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].row_groups, vec![0, 2]);
+        assert_eq!(majority_codec(&groups), Some(Codec::Zstd(3)));
+
+        let tied = group_by_codec([(Compression::SNAPPY, 100), (zstd, 100)]);
+        assert_eq!(majority_codec(&tied), Some(Codec::Snappy));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{DataEncoding, Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::Encoding;
+use parquet::file::metadata::ColumnChunkMetaData;
+
+pub struct MixedEncodingsRule;
+
+/// Below this ratio (distinct / non-null) dictionary encoding is the clear
+/// choice; above the high one it is not worthwhile. In between the encoding
+/// covering most of the data wins.
+const LOW_CARDINALITY_RATIO: Parameter =
+    Parameter::below("low_cardinality_ratio", ParameterKind::Ratio, 0.1);
+const HIGH_CARDINALITY_RATIO: Parameter =
+    Parameter::above("high_cardinality_ratio", ParameterKind::Ratio, 0.5);
+
+/// How a chunk's values are encoded, ignoring level encodings. Chunks that
+/// fell back from a dictionary count as dictionary-encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkEncoding {
+    Dictionary,
+    Values(DataEncoding),
+}
+
+impl ChunkEncoding {
+    fn of(col: &ColumnChunkMetaData) -> Option<Self> {
+        let encodings: Vec<Encoding> = col.encodings().collect();
+        if encodings
+            .iter()
+            .any(|e| matches!(e, Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY))
+        {
+            return Some(ChunkEncoding::Dictionary);
+        }
+        // Prefer a specific value encoding over PLAIN, which writers may
+        // list alongside it.
+        let encodings: Vec<DataEncoding> = encodings
+            .into_iter()
+            .filter_map(DataEncoding::from_encoding)
+            .collect();
+        encodings
+            .iter()
+            .find(|&&e| e != DataEncoding::Plain)
+            .or(encodings.first())
+            .map(|&e| ChunkEncoding::Values(e))
+    }
+}
+
+struct EncodingGroup {
+    encoding: ChunkEncoding,
+    row_groups: Vec<usize>,
+    uncompressed_bytes: i64,
+}
+
+fn group_by_encoding(
+    chunks: impl IntoIterator<Item = (usize, ChunkEncoding, i64)>,
+) -> Vec<EncodingGroup> {
+    let mut groups: Vec<EncodingGroup> = Vec::new();
+    for (rg_idx, encoding, uncompressed_bytes) in chunks {
+        match groups.iter_mut().find(|g| g.encoding == encoding) {
+            Some(group) => {
+                group.row_groups.push(rg_idx);
+                group.uncompressed_bytes += uncompressed_bytes;
+            }
+            None => groups.push(EncodingGroup {
+                encoding,
+                row_groups: vec![rg_idx],
+                uncompressed_bytes,
+            }),
+        }
+    }
+    groups
+}
+
+/// The encoding covering the most data; ties go to the one seen first.
+fn majority<'a>(
+    groups: impl DoubleEndedIterator<Item = &'a EncodingGroup>,
+) -> Option<ChunkEncoding> {
+    groups
+        .rev()
+        .max_by_key(|g| g.uncompressed_bytes)
+        .map(|g| g.encoding)
+}
+
+/// The encoding every row group should use, given the column's cardinality
+/// and the `(low, high)` cardinality ratios.
+fn uniform_encoding(
+    groups: &[EncodingGroup],
+    cardinality_ratio: f64,
+    (low, high): (f64, f64),
+) -> ChunkEncoding {
+    if cardinality_ratio < low {
+        ChunkEncoding::Dictionary
+    } else if cardinality_ratio > high {
+        let values = groups
+            .iter()
+            .filter(|g| g.encoding != ChunkEncoding::Dictionary);
+        majority(values).unwrap_or(ChunkEncoding::Values(DataEncoding::Plain))
+    } else {
+        majority(groups.iter()).unwrap_or(ChunkEncoding::Dictionary)
+    }
+}
+
+impl std::fmt::Display for ChunkEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkEncoding::Dictionary => write!(f, "dictionary"),
+            ChunkEncoding::Values(encoding) => write!(f, "{encoding}"),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Rule for MixedEncodingsRule {
+    fn name(&self) -> &'static str {
+        "mixed-encodings"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL017",
+            description: "Columns encoded differently in different row groups",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[LOW_CARDINALITY_RATIO, HIGH_CARDINALITY_RATIO],
+            directives: &["dictionary", "encoding"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.len() < 2 {
+            return diagnostics;
+        }
+
+        let columns = ctx.column_indices();
+        let mixed: Vec<(usize, Vec<EncodingGroup>)> = columns
+            .iter()
+            .map(|&col_idx| {
+                let chunks = row_groups.iter().enumerate().filter_map(|(rg_idx, rg)| {
+                    let col = rg.column(col_idx);
+                    if col.num_values() == 0 {
+                        return None;
+                    }
+                    Some((rg_idx, ChunkEncoding::of(col)?, col.uncompressed_size()))
+                });
+                (col_idx, group_by_encoding(chunks))
+            })
+            .filter(|(_, groups)| groups.len() > 1)
+            .collect();
+        if mixed.is_empty() {
+            return diagnostics;
+        }
+        let mixed_columns: Vec<usize> = mixed.iter().map(|(col_idx, _)| *col_idx).collect();
+        ctx.columns.load(&mixed_columns).await;
+        let bounds = (
+            ctx.threshold(self, &LOW_CARDINALITY_RATIO),
+            ctx.threshold(self, &HIGH_CARDINALITY_RATIO),
+        );
+
+        for (col_idx, groups) in mixed {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let ratio = col_ctx.cardinality_ratio();
+            let target = uniform_encoding(&groups, ratio, bounds);
+
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let mut prescription = Prescription::new();
+            match target {
+                ChunkEncoding::Dictionary => {
+                    prescription.push(Directive::SetColumnDictionary(path.clone(), true));
+                }
+                ChunkEncoding::Values(encoding) => {
+                    prescription.push(Directive::SetColumnDictionary(path.clone(), false));
+                    prescription.push(Directive::SetColumnEncoding(path.clone(), encoding));
+                }
+            }
+            let evidence: Vec<String> = groups
+                .iter()
+                .map(|g| {
+                    format!(
+                        "{} in row groups {}",
+                        g.encoding,
+                        ctx.row_group_ranges(&g.row_groups)
+                    )
+                })
+                .collect();
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "encoding differs between row groups ({}), which defeats readers' per-column \
+                     fast paths; estimated cardinality is ~{} distinct / {} non-null = {:.0}%, \
+                     recommend {target} throughout",
+                    evidence.join("; "),
+                    col_ctx.distinct_count,
+                    col_ctx.non_null_count(),
+                    ratio * 100.0
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cardinality_decides_the_uniform_encoding() {
+        let bounds = (
+            LOW_CARDINALITY_RATIO.default,
+            HIGH_CARDINALITY_RATIO.default,
+        );
+        let delta = ChunkEncoding::Values(DataEncoding::DeltaBinaryPacked);
+        let groups = group_by_encoding([
+            (0, ChunkEncoding::Dictionary, 100),
+            (1, delta, 300),
+            (2, ChunkEncoding::Dictionary, 100),
+        ]);
+        assert_eq!(groups[0].row_groups, vec![0, 2]);
+        assert_eq!(
+            uniform_encoding(&groups, 0.01, bounds),
+            ChunkEncoding::Dictionary
+        );
+        assert_eq!(uniform_encoding(&groups, 0.3, bounds), delta);
+        assert_eq!(uniform_encoding(&groups, 0.9, bounds), delta);
+
+        let dictionary_and_plain = group_by_encoding([
+            (0, ChunkEncoding::Dictionary, 500),
+            (1, ChunkEncoding::Values(DataEncoding::Plain), 100),
+        ]);
+        assert_eq!(
+            uniform_encoding(&dictionary_and_plain, 0.3, bounds),
+            ChunkEncoding::Dictionary
+        );
+        assert_eq!(
+            uniform_encoding(&dictionary_and_plain, 0.9, bounds),
+            ChunkEncoding::Values(DataEncoding::Plain)
+        );
+    }
+}
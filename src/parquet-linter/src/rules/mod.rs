@@ -1,13 +1,40 @@
+mod arrow_schema;
+mod bloom_filter;
+mod chunk_statistics;
 mod compression_codec;
 mod compression_ratio;
 mod dictionary_encoding;
+mod dictionary_page_size;
+#[cfg(feature = "encryption")]
+mod encryption;
+mod enum_string;
+mod epoch_timestamp;
+mod fixed_width_binary;
 mod float_encoding;
+mod footer_size;
+mod level_overhead;
+mod low_dictionary_hit;
+mod mixed_codecs;
+mod mixed_encodings;
+mod nullable_no_nulls;
+mod oversized_page;
+mod page_count;
+mod page_header_statistics;
 mod page_size;
 mod page_statistics;
+mod reader_compatibility;
+mod sort_pruning;
+mod sorting_order;
 mod string_encoding;
 mod string_statistics;
 mod timestamp_encoding;
+mod truncated_statistics;
+mod uncompressed_dictionary;
 mod vector_embedding;
+mod wide_file;
+mod zstd_level;
+
+use serde_json::{Value, json};
 
 use crate::rule::Rule;
 
@@ -15,24 +42,149 @@ pub fn all_rules() -> Vec<Box<dyn Rule>> {
     vec![
         Box::new(compression_ratio::CompressionRatioRule),
         Box::new(page_statistics::PageStatisticsRule),
+        Box::new(page_header_statistics::PageHeaderStatisticsRule),
+        Box::new(chunk_statistics::ChunkStatisticsRule),
         Box::new(vector_embedding::VectorEmbeddingRule),
         Box::new(dictionary_encoding::DictionaryEncodingRule),
+        Box::new(dictionary_page_size::DictionaryPageSizeRule),
+        Box::new(low_dictionary_hit::LowDictionaryHitRule),
+        Box::new(enum_string::EnumStringRule),
         Box::new(page_size::PageSizeRule),
+        Box::new(page_count::PageCountRule),
+        Box::new(oversized_page::OversizedPageRule),
         Box::new(float_encoding::FloatEncodingRule),
         Box::new(string_encoding::StringEncodingRule),
         Box::new(compression_codec::CompressionCodecRule),
+        Box::new(uncompressed_dictionary::UncompressedDictionaryRule),
+        Box::new(mixed_codecs::MixedCodecsRule),
+        Box::new(zstd_level::ZstdLevelRule),
+        Box::new(mixed_encodings::MixedEncodingsRule),
         Box::new(timestamp_encoding::TimestampEncodingRule),
+        Box::new(epoch_timestamp::EpochTimestampRule),
+        Box::new(level_overhead::LevelOverheadRule),
+        Box::new(nullable_no_nulls::NullableNoNullsRule),
         Box::new(string_statistics::StringStatisticsRule),
+        Box::new(fixed_width_binary::FixedWidthBinaryRule),
+        Box::new(truncated_statistics::TruncatedStatisticsRule),
+        Box::new(bloom_filter::BloomFilterRule),
+        Box::new(sorting_order::SortingOrderRule),
+        Box::new(sort_pruning::SortPruningRule),
+        Box::new(reader_compatibility::ReaderCompatibilityRule),
+        Box::new(arrow_schema::ArrowSchemaRule),
+        Box::new(wide_file::WideFileRule),
+        Box::new(footer_size::FooterSizeRule),
+        #[cfg(feature = "encryption")]
+        Box::new(encryption::EncryptionRule),
     ]
 }
 
+/// Version of the `rules_manifest` layout.
+const MANIFEST_VERSION: u64 = 1;
+
+/// Every built-in rule's `RuleMetadata`, for configuration UIs.
+pub fn rules_manifest() -> Value {
+    let rules: Vec<Value> = all_rules().iter().map(|r| r.metadata().to_json()).collect();
+    json!({
+        "manifest_version": MANIFEST_VERSION,
+        "rules": rules,
+    })
+}
+
 pub fn get_rules(names: Option<&[String]>) -> Vec<Box<dyn Rule>> {
-    let all = all_rules();
-    match names {
+    select_rules(all_rules(), names)
+}
+
+/// The rules of `all` that are named in `names` (default: all of them),
+/// ordered by their dependencies.
+pub(crate) fn select_rules(
+    all: Vec<Box<dyn Rule>>,
+    names: Option<&[String]>,
+) -> Vec<Box<dyn Rule>> {
+    let selected = match names {
         None => all,
         Some(names) => all
             .into_iter()
             .filter(|r| names.iter().any(|n| n == r.name()))
             .collect(),
+    };
+    order_by_dependencies(selected)
+}
+
+/// Order `rules` so each runs after the rules among them it depends on,
+/// otherwise keeping their order. Rules in a dependency cycle keep their
+/// relative order.
+fn order_by_dependencies(mut pending: Vec<Box<dyn Rule>>) -> Vec<Box<dyn Rule>> {
+    let mut ordered = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|rule| {
+                rule.dependencies()
+                    .iter()
+                    .all(|dep| *dep == rule.name() || pending.iter().all(|r| r.name() != *dep))
+            })
+            .unwrap_or(0);
+        ordered.push(pending.remove(ready));
+    }
+    ordered
+}
+
+/// Row group indices as compact ranges, e.g. `0-2, 5, 7-8`.
+pub(crate) fn row_group_ranges(indices: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &index in indices {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == index => *end = index,
+            _ => ranges.push((index, index)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn dependencies_run_first() {
+        let mut rules = all_rules();
+        rules.reverse();
+        let names: Vec<&str> = order_by_dependencies(rules)
+            .iter()
+            .map(|r| r.name())
+            .collect();
+        let position = |name| names.iter().position(|n| *n == name).unwrap();
+        assert!(
+            position("dictionary-encoding-cardinality") < position("string-byte-array-encoding")
+        );
+        assert_eq!(names.len(), all_rules().len());
+    }
+
+    #[test]
+    fn manifest_codes_are_unique() {
+        let manifest = rules_manifest();
+        let rules = manifest["rules"].as_array().unwrap();
+        let codes: HashSet<&str> = rules.iter().map(|r| r["code"].as_str().unwrap()).collect();
+        assert_eq!(codes.len(), all_rules().len());
+        for rule in all_rules() {
+            assert_eq!(rule.metadata().name, rule.name());
+        }
+    }
+
+    #[test]
+    fn row_group_ranges_merge_consecutive_indices() {
+        assert_eq!(row_group_ranges(&[0, 1, 2, 5, 7, 8]), "0-2, 5, 7-8");
+        assert_eq!(row_group_ranges(&[3]), "3");
     }
 }
@@ -0,0 +1,110 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::basic::Repetition;
+
+pub struct NullableNoNullsRule;
+
+#[async_trait::async_trait]
+impl Rule for NullableNoNullsRule {
+    fn name(&self) -> &'static str {
+        "nullable-without-nulls"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL021",
+            description: "Nullable columns that hold no nulls",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[],
+            directives: &["nullable"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+
+        for col_idx in ctx.column_indices() {
+            let col0 = row_groups[0].column(col_idx);
+            let descr = col0.column_descr();
+            // Rewrites can only tighten top-level fields.
+            let top_level_optional = descr.path().parts().len() == 1
+                && descr.self_type().get_basic_info().repetition() == Repetition::OPTIONAL;
+            if !top_level_optional {
+                continue;
+            }
+            // A row group without a null count could hold nulls.
+            let no_nulls = row_groups.iter().all(|rg| {
+                let stats = rg.column(col_idx).statistics();
+                stats.and_then(|s| s.null_count_opt()) == Some(0)
+            });
+            if !no_nulls {
+                continue;
+            }
+
+            let path = col0.column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnNullable(path.clone(), false));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Suggestion,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "column is optional but has no nulls in any of {} row groups; declaring it \
+                     required drops its definition levels and lets planners skip null handling",
+                    row_groups.len()
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn suggests_required_for_optional_column_without_nulls() -> anyhow::Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("dense", DataType::Int32, true),
+            Field::new("sparse", DataType::Int32, true),
+            Field::new("required", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+            ],
+        )?;
+        assert_snapshot(
+            &TestFile::new(batch)
+                .lint(&["nullable-without-nulls"])
+                .await?,
+            r#"
+                [suggestion] nullable-without-nulls @ column[0]("dense"): column is optional but has no nulls in any of 1 row groups; ...
+                  fix: set column dense nullable false
+            "#,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use crate::cardinality;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::rules::page_size::{HARD_MAX_DATA_PAGE_SIZE_LIMIT, IDEAL_DATA_PAGE_SIZE_LIMIT};
+use parquet::column::page::{Page, PageReader};
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaData, ParquetMetaDataReader};
+use parquet::file::page_index::offset_index::OffsetIndexMetaData;
+
+pub struct OversizedPageRule;
+
+const SHOWN_PAGES: usize = 3;
+
+/// A data page over the hard max: `(row_group, page, bytes)`.
+type PageSize = (usize, usize, usize);
+
+/// The footer's offset index, loading it when the file has one that was
+/// not read with the footer.
+async fn offset_index(ctx: &RuleContext) -> Option<Vec<Vec<OffsetIndexMetaData>>> {
+    if let Some(index) = ctx.metadata.offset_index() {
+        return Some(index.clone());
+    }
+    let has_index = ctx
+        .metadata
+        .row_groups()
+        .iter()
+        .flat_map(|rg| rg.columns())
+        .any(|c| c.offset_index_offset().is_some());
+    if !has_index || ctx.options.metadata_only {
+        return None;
+    }
+    let mut reader =
+        ParquetMetaDataReader::new_with_metadata(ParquetMetaData::clone(&ctx.metadata))
+            .with_column_index_policy(PageIndexPolicy::Skip)
+            .with_offset_index_policy(PageIndexPolicy::Optional);
+    reader.load_page_index(&mut ctx.reader.clone()).await.ok()?;
+    reader.finish().ok()?.offset_index().cloned()
+}
+
+/// Decompressed data page sizes of one chunk, read page by page.
+async fn read_page_sizes(ctx: &RuleContext, rg: usize, col: usize) -> Vec<usize> {
+    let Ok(mut pages) = ctx.pages.page_reader(rg, col, ChunkSpan::Full).await else {
+        return Vec::new();
+    };
+    let mut sizes = Vec::new();
+    while let Ok(Some(page)) = pages.get_next_page() {
+        match page {
+            Page::DataPage { buf, .. } | Page::DataPageV2 { buf, .. } => sizes.push(buf.len()),
+            Page::DictionaryPage { .. } => {}
+        }
+    }
+    sizes
+}
+
+fn oversized(rg: usize, sizes: impl IntoIterator<Item = usize>) -> impl Iterator<Item = PageSize> {
+    sizes
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, size)| size > HARD_MAX_DATA_PAGE_SIZE_LIMIT)
+        .map(move |(page, size)| (rg, page, size))
+}
+
+#[async_trait::async_trait]
+impl Rule for OversizedPageRule {
+    fn name(&self) -> &'static str {
+        "oversized-data-page"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL011",
+            description: "Data pages far larger than the page size limit",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[],
+            directives: &["data_page_size_limit"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+        // A chunk can only hold an oversized page if it is oversized itself.
+        let large = |rg: usize, col: usize| {
+            row_groups[rg].column(col).uncompressed_size() > HARD_MAX_DATA_PAGE_SIZE_LIMIT as i64
+        };
+        let columns = ctx.column_indices();
+        let index = offset_index(ctx).await;
+        // Without an offset index, read only the row group sampling reads.
+        let sample_rg = cardinality::pick_sample_row_group(&ctx.metadata);
+        if index.is_none() && !ctx.options.metadata_only {
+            let chunks: Vec<_> = columns
+                .iter()
+                .filter(|&&col| large(sample_rg, col))
+                .map(|&col| (sample_rg, col, ChunkSpan::Full))
+                .collect();
+            let _ = ctx.pages.prefetch(&chunks).await;
+        }
+
+        for col_idx in columns {
+            let mut pages: Vec<PageSize> = Vec::new();
+            match &index {
+                Some(index) => {
+                    for (rg_idx, rg_index) in index.iter().enumerate() {
+                        let sizes = rg_index[col_idx]
+                            .page_locations()
+                            .iter()
+                            .map(|p| p.compressed_page_size as usize);
+                        pages.extend(oversized(rg_idx, sizes));
+                    }
+                }
+                None if !ctx.options.metadata_only && large(sample_rg, col_idx) => {
+                    let sizes = read_page_sizes(ctx, sample_rg, col_idx).await;
+                    pages.extend(oversized(sample_rg, sizes));
+                }
+                None => {}
+            }
+            if pages.is_empty() {
+                continue;
+            }
+            pages.sort_by_key(|&(_, _, size)| std::cmp::Reverse(size));
+
+            let (row_group, page, _) = pages[0];
+            let worst: Vec<String> = pages
+                .iter()
+                .take(SHOWN_PAGES)
+                .map(|(rg, page, size)| {
+                    format!(
+                        "row group {} page {page}: {:.1}MB",
+                        ctx.row_group_number(*rg),
+                        *size as f64 / 1048576.0
+                    )
+                })
+                .collect();
+            let source = if index.is_some() {
+                "compressed, from the offset index"
+            } else {
+                "decompressed, in the sampled row group"
+            };
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetFileDataPageSizeLimit(
+                IDEAL_DATA_PAGE_SIZE_LIMIT,
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Page {
+                    row_group,
+                    column: col_idx,
+                    path,
+                    page,
+                },
+                message: format!(
+                    "{} data page(s) exceed the {}MB hard max ({source}); largest: {}. \
+                     Readers must decode them whole, recommend data_page_size_limit={}MB",
+                    pages.len(),
+                    HARD_MAX_DATA_PAGE_SIZE_LIMIT / 1024 / 1024,
+                    worst.join(", "),
+                    IDEAL_DATA_PAGE_SIZE_LIMIT / 1024 / 1024
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn reports_worst_page_location() -> anyhow::Result<()> {
+        // 1M random-ish i64s: 8MB of PLAIN values in a single page.
+        let values = (0..1_000_000i64).map(|i| i.wrapping_mul(0x2545_F491_4F6C_DD1D));
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int64Array::from_iter_values(values)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings(
+            "set column v dictionary false
+             set file data_page_size_limit 67108864
+             set file data_page_row_count_limit 1000000000",
+        );
+        assert_snapshot(
+            &file.lint(&["oversized-data-page"]).await?,
+            r#"
+                [warning] oversized-data-page @ row_group[0].column[0]("v").page[0]: 1 data page(s) exceed the 4MB hard max ...
+                  fix: set file data_page_size_limit 1048576
+            "#,
+        );
+        Ok(())
+    }
+}
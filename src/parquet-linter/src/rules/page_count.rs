@@ -0,0 +1,174 @@
+use crate::cardinality;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::rules::page_size::IDEAL_DATA_PAGE_SIZE_LIMIT;
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::PageType;
+use parquet::column::page::PageReader;
+use parquet::file::metadata::ParquetMetaData;
+
+pub struct PageCountRule;
+
+/// The default page row count limit of parquet-mr and parquet-rs: a page
+/// index lets readers skip in steps of this many rows.
+const PAGE_ROW_COUNT_LIMIT: usize = 20_000;
+/// Row groups up to this many rows gain little from more than one page.
+const MIN_ROW_GROUP_ROWS: Parameter = Parameter::above(
+    "min_row_group_rows",
+    ParameterKind::Count,
+    PAGE_ROW_COUNT_LIMIT as f64,
+);
+
+/// Data pages in a chunk, from the page encoding stats or, failing that,
+/// the offset index. `None` when the footer records neither.
+fn footer_page_count(metadata: &ParquetMetaData, rg: usize, col: usize) -> Option<usize> {
+    let chunk = metadata.row_group(rg).column(col);
+    if let Some(stats) = chunk.page_encoding_stats() {
+        return Some(
+            stats
+                .iter()
+                .filter(|s| matches!(s.page_type, PageType::DATA_PAGE | PageType::DATA_PAGE_V2))
+                .map(|s| s.count as usize)
+                .sum(),
+        );
+    }
+    metadata
+        .offset_index()
+        .map(|index| index[rg][col].page_locations().len())
+}
+
+/// Data pages in a chunk, counted from page headers without decompressing.
+async fn read_page_count(ctx: &RuleContext, rg: usize, col: usize) -> Option<usize> {
+    let mut pages = ctx.pages.page_reader(rg, col, ChunkSpan::Full).await.ok()?;
+    let mut count = 0;
+    while let Some(page) = pages.peek_next_page().ok()? {
+        if !page.is_dict {
+            count += 1;
+        }
+        pages.skip_next_page().ok()?;
+    }
+    Some(count)
+}
+
+#[async_trait::async_trait]
+impl Rule for PageCountRule {
+    fn name(&self) -> &'static str {
+        "too-few-pages"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL010",
+            description: "Column chunks held in a single data page, which page-index pruning cannot skip within",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MIN_ROW_GROUP_ROWS],
+            directives: &["data_page_row_count_limit", "data_page_size_limit"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let metadata = ctx.metadata.as_ref();
+        let columns = ctx.column_indices();
+        let mut single_page_chunks = 0usize;
+        let mut known_chunks = 0usize;
+        let mut largest_page_bytes = 0i64;
+        let mut most_page_rows = 0i64;
+
+        // Chunks the footer cannot count are read, but only in the row group
+        // column sampling reads anyway.
+        let sample_rg = cardinality::pick_sample_row_group(metadata);
+        let min_row_group_rows = ctx.threshold(self, &MIN_ROW_GROUP_ROWS);
+        let mut chunks = Vec::new();
+        let mut to_read = Vec::new();
+        for (rg_idx, rg) in metadata.row_groups().iter().enumerate() {
+            if (rg.num_rows() as f64) <= min_row_group_rows {
+                continue;
+            }
+            for &col_idx in &columns {
+                let pages = footer_page_count(metadata, rg_idx, col_idx);
+                if pages.is_none() && (ctx.options.metadata_only || rg_idx != sample_rg) {
+                    continue;
+                }
+                if pages.is_none() {
+                    to_read.push((rg_idx, col_idx, ChunkSpan::Full));
+                }
+                chunks.push((rg_idx, col_idx, pages));
+            }
+        }
+        let _ = ctx.pages.prefetch(&to_read).await;
+
+        for (rg_idx, col_idx, pages) in chunks {
+            let pages = match pages {
+                Some(pages) => pages,
+                None => match read_page_count(ctx, rg_idx, col_idx).await {
+                    Some(pages) => pages,
+                    None => continue,
+                },
+            };
+            known_chunks += 1;
+            if pages == 1 {
+                let rg = metadata.row_group(rg_idx);
+                single_page_chunks += 1;
+                largest_page_bytes = largest_page_bytes.max(rg.column(col_idx).uncompressed_size());
+                most_page_rows = most_page_rows.max(rg.num_rows());
+            }
+        }
+        if single_page_chunks == 0 {
+            return Vec::new();
+        }
+
+        let mut prescription = Prescription::new();
+        if largest_page_bytes > IDEAL_DATA_PAGE_SIZE_LIMIT as i64 {
+            prescription.push(Directive::SetFileDataPageSizeLimit(
+                IDEAL_DATA_PAGE_SIZE_LIMIT,
+            ));
+        }
+        prescription.push(Directive::SetFileDataPageRowCountLimit(
+            PAGE_ROW_COUNT_LIMIT,
+        ));
+        vec![Diagnostic {
+            rule_name: self.name(),
+            severity: Severity::Warning,
+            category: Category::Performance,
+            location: Location::File,
+            message: format!(
+                "{single_page_chunks}/{known_chunks} column chunks hold a single data page \
+                 (up to {most_page_rows} rows, {:.1}MB uncompressed); page-index pruning cannot \
+                 skip within them, recommend data_page_row_count_limit={PAGE_ROW_COUNT_LIMIT}",
+                largest_page_bytes as f64 / (1024.0 * 1024.0)
+            ),
+            prescription,
+            related: Vec::new(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, RecordBatch};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn flags_row_groups_written_as_one_page() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int32Array::from_iter_values((0..100_000).map(|i| i % 8))) as _,
+        )])?;
+        let file = TestFile::new(batch).settings("set file data_page_row_count_limit 1000000000");
+        assert_snapshot(
+            &file.lint(&["too-few-pages"]).await?,
+            r#"
+                [warning] too-few-pages @ file: 1/1 column chunks hold a single data page (up to 100000 rows, ...
+                  fix: set file data_page_row_count_limit 20000
+            "#,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use crate::cardinality;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
+use crate::prescription::{Directive, Prescription, StatisticsConfig};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use parquet::column::page::{Page, PageReader};
+
+pub struct PageHeaderStatisticsRule;
+
+/// Data pages of the sampled row group, and how many of them carry min/max
+/// statistics in their header.
+async fn header_statistics(ctx: &RuleContext, rg: usize, col: usize) -> (usize, usize) {
+    let Ok(mut pages) = ctx
+        .pages
+        .page_reader_with_statistics(rg, col, ChunkSpan::Full)
+        .await
+    else {
+        return (0, 0);
+    };
+    let (mut data_pages, mut with_stats) = (0, 0);
+    while let Ok(Some(page)) = pages.get_next_page() {
+        if matches!(page, Page::DictionaryPage { .. }) {
+            continue;
+        }
+        data_pages += 1;
+        if page
+            .statistics()
+            .is_some_and(|s| s.min_bytes_opt().is_some() && s.max_bytes_opt().is_some())
+        {
+            with_stats += 1;
+        }
+    }
+    (data_pages, with_stats)
+}
+
+#[async_trait::async_trait]
+impl Rule for PageHeaderStatisticsRule {
+    fn name(&self) -> &'static str {
+        "page-statistics-without-index"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL003",
+            description: "Page statistics written only to page headers, where readers do not use them for pruning",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[],
+            directives: &["statistics"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() || ctx.options.metadata_only {
+            return diagnostics;
+        }
+        let rg = cardinality::pick_sample_row_group(&ctx.metadata);
+        // Page header statistics only matter where no column index replaces them.
+        let columns: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col| row_groups[rg].column(col).column_index_offset().is_none())
+            .collect();
+        let chunks: Vec<_> = columns
+            .iter()
+            .map(|&col| (rg, col, ChunkSpan::Full))
+            .collect();
+        let _ = ctx.pages.prefetch(&chunks).await;
+
+        for col_idx in columns {
+            let (data_pages, with_stats) = header_statistics(ctx, rg, col_idx).await;
+            if with_stats == 0 {
+                continue;
+            }
+            let path = row_groups[rg].column(col_idx).column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnStatistics(
+                path.clone(),
+                StatisticsConfig::Page,
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "{with_stats}/{data_pages} data pages in row group {} carry statistics in \
+                     their headers, but there is no column index; engines only prune pages \
+                     through the column index, so these statistics go unused",
+                    ctx.row_group_number(rg)
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Int64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::metadata::{ParquetMetaDataReader, ParquetMetaDataWriter};
+    use parquet::file::properties::{EnabledStatistics, WriterProperties};
+    use std::sync::Arc;
+
+    /// A file with page header statistics and no column index, as legacy
+    /// writers produce: written with both, then given a footer that does not
+    /// reference the column index.
+    fn write_legacy_file(path: &std::path::Path) -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int64Array::from_iter_values(0..10_000)) as _,
+        )])?;
+        let props = WriterProperties::builder()
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .set_write_page_header_statistics(true)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let bytes = bytes::Bytes::from(buffer);
+        let metadata = ParquetMetaDataReader::new().parse_and_finish(&bytes)?;
+        let footer = 8 + u32::from_le_bytes(bytes[bytes.len() - 8..][..4].try_into()?) as usize;
+        let mut builder = metadata.into_builder();
+        let row_groups = builder
+            .take_row_groups()
+            .into_iter()
+            .map(|rg| {
+                let columns = rg
+                    .columns()
+                    .iter()
+                    .map(|c| {
+                        c.clone()
+                            .into_builder()
+                            .set_column_index_offset(None)
+                            .set_column_index_length(None)
+                            .build()
+                    })
+                    .collect::<Result<_, _>>()?;
+                rg.into_builder().set_column_metadata(columns).build()
+            })
+            .collect::<Result<_, _>>()?;
+        let metadata = builder.set_row_groups(row_groups).build();
+        let mut out = bytes[..bytes.len() - footer].to_vec();
+        ParquetMetaDataWriter::new(&mut out, &metadata).finish()?;
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn flags_header_statistics_without_column_index() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("t.parquet");
+        write_legacy_file(&path)?;
+
+        let linter = crate::Linter::builder()
+            .rules(["page-statistics-without-index"])
+            .build();
+        let diagnostics = linter.lint(path.to_str().unwrap()).await?;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].prescription.to_string(),
+            "set column v statistics page"
+        );
+        Ok(())
+    }
+}
@@ -1,13 +1,26 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
 
 pub struct PageSizeRule;
 
-const MAX_ROWS_PER_ROW_GROUP: usize = 64 * 1024; // 64K rows
-const MAX_ROW_GROUP_SIZE_BYTES: i64 = 256 * 1024 * 1024; // 256 MB
-const HARD_MAX_DATA_PAGE_SIZE_LIMIT: usize = 4 * 1024 * 1024; // 4 MB
-const IDEAL_DATA_PAGE_SIZE_LIMIT: usize = 1024 * 1024; // 1 MB
+const MAX_ROWS_PER_ROW_GROUP: Parameter =
+    Parameter::above("max_row_group_rows", ParameterKind::Count, 64.0 * 1024.0);
+const MAX_ROW_GROUP_SIZE_BYTES: Parameter = Parameter::above(
+    "max_row_group_bytes",
+    ParameterKind::Bytes,
+    256.0 * 1024.0 * 1024.0,
+);
+pub(super) const HARD_MAX_DATA_PAGE_SIZE_LIMIT: usize = 4 * 1024 * 1024; // 4 MB
+pub(super) const IDEAL_DATA_PAGE_SIZE_LIMIT: usize = 1024 * 1024; // 1 MB
+
+/// Row-group limits, resolved from the rule's parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RowGroupLimits {
+    max_rows: usize,
+    max_bytes: i64,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct RowGroupSuggestion {
@@ -16,21 +29,24 @@ struct RowGroupSuggestion {
     oversized_size_groups: usize,
 }
 
-fn compute_row_group_suggestion(row_groups: &[(i64, i64)]) -> Option<RowGroupSuggestion> {
+fn compute_row_group_suggestion(
+    row_groups: &[(i64, i64)],
+    limits: RowGroupLimits,
+) -> Option<RowGroupSuggestion> {
     let mut oversized_rows_groups = 0usize;
     let mut oversized_size_groups = 0usize;
-    let mut target_max_rows = MAX_ROWS_PER_ROW_GROUP;
+    let mut target_max_rows = limits.max_rows;
 
     for (num_rows, compressed_size) in row_groups {
-        if *num_rows > MAX_ROWS_PER_ROW_GROUP as i64 {
+        if *num_rows > limits.max_rows as i64 {
             oversized_rows_groups += 1;
         }
 
-        if *compressed_size > MAX_ROW_GROUP_SIZE_BYTES {
+        if *compressed_size > limits.max_bytes {
             oversized_size_groups += 1;
             if *num_rows > 0 {
-                // Reduce rows proportionally so compressed size trends toward <= 256MB.
-                let scaled = ((*num_rows as f64) * (MAX_ROW_GROUP_SIZE_BYTES as f64)
+                // Reduce rows proportionally so compressed size trends toward the limit.
+                let scaled = ((*num_rows as f64) * (limits.max_bytes as f64)
                     / (*compressed_size as f64))
                     .floor() as usize;
                 target_max_rows = target_max_rows.min(scaled.max(1));
@@ -49,14 +65,18 @@ fn compute_row_group_suggestion(row_groups: &[(i64, i64)]) -> Option<RowGroupSug
     })
 }
 
-fn build_policy_message(suggestion: RowGroupSuggestion, total_row_groups: usize) -> String {
+fn build_policy_message(
+    suggestion: RowGroupSuggestion,
+    total_row_groups: usize,
+    limits: RowGroupLimits,
+) -> String {
     let mut parts = Vec::new();
     if suggestion.oversized_rows_groups > 0 {
         parts.push(format!(
             "{}/{} row group(s) exceed {}K rows",
             suggestion.oversized_rows_groups,
             total_row_groups,
-            MAX_ROWS_PER_ROW_GROUP / 1024
+            limits.max_rows / 1024
         ));
     }
     if suggestion.oversized_size_groups > 0 {
@@ -64,7 +84,7 @@ fn build_policy_message(suggestion: RowGroupSuggestion, total_row_groups: usize)
             "{}/{} row group(s) exceed {}MB compressed",
             suggestion.oversized_size_groups,
             total_row_groups,
-            MAX_ROW_GROUP_SIZE_BYTES / 1024 / 1024
+            limits.max_bytes / 1024 / 1024
         ));
     }
 
@@ -72,7 +92,7 @@ fn build_policy_message(suggestion: RowGroupSuggestion, total_row_groups: usize)
         "{}; set max_row_group_size={} ({}K rows). Recommended data_page_size_limit={}MB (hard max {}MB).",
         parts.join("; "),
         suggestion.target_max_rows,
-        MAX_ROWS_PER_ROW_GROUP / 1024,
+        limits.max_rows / 1024,
         IDEAL_DATA_PAGE_SIZE_LIMIT / 1024 / 1024,
         HARD_MAX_DATA_PAGE_SIZE_LIMIT / 1024 / 1024,
     )
@@ -84,6 +104,18 @@ impl Rule for PageSizeRule {
         "page-row-group-size"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL009",
+            description: "Row groups with too many rows or bytes for parallel and selective reads",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MAX_ROWS_PER_ROW_GROUP, MAX_ROW_GROUP_SIZE_BYTES],
+            directives: &["data_page_size_limit", "max_row_group_size"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let row_groups: Vec<_> = ctx
             .metadata
@@ -92,7 +124,11 @@ impl Rule for PageSizeRule {
             .map(|rg| (rg.num_rows(), rg.compressed_size()))
             .collect();
 
-        let Some(suggestion) = compute_row_group_suggestion(&row_groups) else {
+        let limits = RowGroupLimits {
+            max_rows: ctx.threshold(self, &MAX_ROWS_PER_ROW_GROUP) as usize,
+            max_bytes: ctx.threshold(self, &MAX_ROW_GROUP_SIZE_BYTES) as i64,
+        };
+        let Some(suggestion) = compute_row_group_suggestion(&row_groups, limits) else {
             return Vec::new();
         };
 
@@ -107,9 +143,11 @@ impl Rule for PageSizeRule {
         vec![Diagnostic {
             rule_name: self.name(),
             severity: Severity::Warning,
+            category: Category::Performance,
             location: Location::File,
-            message: build_policy_message(suggestion, row_groups.len()),
+            message: build_policy_message(suggestion, row_groups.len(), limits),
             prescription,
+            related: Vec::new(),
         }]
     }
 }
@@ -118,17 +156,25 @@ impl Rule for PageSizeRule {
 mod tests {
     use super::*;
 
+    const DEFAULT_LIMITS: RowGroupLimits = RowGroupLimits {
+        max_rows: MAX_ROWS_PER_ROW_GROUP.default as usize,
+        max_bytes: MAX_ROW_GROUP_SIZE_BYTES.default as i64,
+    };
+
     #[test]
     fn no_violation_returns_none() {
         let row_groups = vec![(10_000, 64 * 1024 * 1024), (20_000, 128 * 1024 * 1024)];
-        assert_eq!(compute_row_group_suggestion(&row_groups), None);
+        assert_eq!(
+            compute_row_group_suggestion(&row_groups, DEFAULT_LIMITS),
+            None
+        );
     }
 
     #[test]
     fn rows_violation_caps_at_64k() {
         let row_groups = vec![(70_000, 128 * 1024 * 1024)];
         assert_eq!(
-            compute_row_group_suggestion(&row_groups),
+            compute_row_group_suggestion(&row_groups, DEFAULT_LIMITS),
             Some(RowGroupSuggestion {
                 target_max_rows: 64 * 1024,
                 oversized_rows_groups: 1,
@@ -141,7 +187,7 @@ mod tests {
     fn size_violation_scales_rows_down() {
         let row_groups = vec![(100_000, 512 * 1024 * 1024)];
         assert_eq!(
-            compute_row_group_suggestion(&row_groups),
+            compute_row_group_suggestion(&row_groups, DEFAULT_LIMITS),
             Some(RowGroupSuggestion {
                 target_max_rows: 50_000,
                 oversized_rows_groups: 1,
@@ -159,9 +205,41 @@ mod tests {
                 oversized_size_groups: 0,
             },
             226,
+            DEFAULT_LIMITS,
         );
         assert!(msg.contains("226/226 row group(s) exceed 64K rows"));
         assert!(!msg.contains("exceed 256MB compressed"));
         assert!(msg.contains("data_page_size_limit=1MB"));
     }
+
+    #[tokio::test]
+    async fn honors_configured_row_limit() -> anyhow::Result<()> {
+        use std::sync::Arc;
+
+        use arrow_array::{Int64Array, RecordBatch};
+
+        use crate::options::LintOptions;
+        use crate::testing::TestFile;
+
+        let batch = RecordBatch::try_from_iter([(
+            "v",
+            Arc::new(Int64Array::from_iter_values(0..10_000)) as _,
+        )])?;
+        let file = TestFile::new(batch);
+        assert!(file.lint(&["page-row-group-size"]).await?.is_empty());
+
+        let mut options = LintOptions::default();
+        options
+            .thresholds
+            .set("page-row-group-size", "max_row_group_rows", 4096.0);
+        let found = file
+            .lint_with_options(&["page-row-group-size"], options)
+            .await?;
+        assert!(
+            found[0]
+                .message
+                .starts_with("1/1 row group(s) exceed 4K rows; set max_row_group_size=4096")
+        );
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{Directive, Prescription, StatisticsConfig};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::rules::chunk_statistics::missing_chunk_statistics;
 
 pub struct PageStatisticsRule;
 
@@ -10,6 +11,18 @@ impl Rule for PageStatisticsRule {
         "missing-page-statistics"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL002",
+            description: "Columns without a page index, so readers cannot skip pages by value",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[],
+            directives: &["statistics"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -17,8 +30,15 @@ impl Rule for PageStatisticsRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        for col_idx in ctx.column_indices() {
+            // Columns without chunk statistics are `missing-chunk-statistics`'
+            // business, which also decides whether a page index is worth it.
+            if row_groups
+                .iter()
+                .any(|rg| missing_chunk_statistics(rg.column(col_idx)))
+            {
+                continue;
+            }
             let missing_groups = row_groups
                 .iter()
                 .filter(|rg| rg.column(col_idx).column_index_offset().is_none())
@@ -33,6 +53,7 @@ impl Rule for PageStatisticsRule {
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Warning,
+                    category: Category::Performance,
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -43,9 +64,37 @@ impl Rule for PageStatisticsRule {
                         row_groups.len()
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
         diagnostics
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{TestFile, assert_snapshot};
+    use arrow_array::{Int64Array, RecordBatch};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn flags_chunk_only_statistics() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+            ("b", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+        ])?;
+        let diagnostics = TestFile::new(batch)
+            .settings("set file max_row_group_size 500; set column a statistics chunk")
+            .lint(&["missing-page-statistics"])
+            .await?;
+        assert_snapshot(
+            &diagnostics,
+            r#"
+            [warning] missing-page-statistics @ column[0]("a"): no page-level column index found in 2/2 row groups; page statistics are missing
+              fix: set column a statistics page
+            "#,
+        );
+        Ok(())
+    }
+}
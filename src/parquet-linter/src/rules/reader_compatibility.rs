@@ -0,0 +1,116 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::target::{REPLACEMENT_ENCODING, Support};
+
+pub struct ReaderCompatibilityRule;
+
+#[async_trait::async_trait]
+impl Rule for ReaderCompatibilityRule {
+    fn name(&self) -> &'static str {
+        "reader-compatibility"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL026",
+            description: "Codecs and encodings the --target reader cannot read",
+            category: Category::Compatibility,
+            default_severity: Severity::Error,
+            parameters: &[],
+            directives: &["compression", "encoding"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let Some(target) = ctx.options.target else {
+            return diagnostics;
+        };
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+
+        for col_idx in ctx.column_indices() {
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let location = || Location::Column {
+                column: col_idx,
+                path: path.clone(),
+            };
+
+            let codec_issues: Vec<_> = row_groups
+                .iter()
+                .map(|rg| rg.column(col_idx).compression())
+                .map(|codec| (codec, target.codec_support(codec)))
+                .filter(|(_, support)| *support != Support::Readable)
+                .collect();
+            if let Some(&(codec, support)) = codec_issues.first() {
+                let replacement = target.replacement_codec(codec);
+                let groups = format!("{}/{} row groups", codec_issues.len(), row_groups.len());
+                let (severity, message) = match support {
+                    Support::Ambiguous => (
+                        Severity::Warning,
+                        format!(
+                            "deprecated LZ4 compression in {groups} uses a framing readers \
+                             disagree on; recommend {replacement} for target {target}"
+                        ),
+                    ),
+                    _ => (
+                        Severity::Error,
+                        format!(
+                            "target {target} cannot read {codec} compression in {groups}; \
+                             recommend {replacement}"
+                        ),
+                    ),
+                };
+                let mut prescription = Prescription::new();
+                prescription.push(Directive::SetColumnCompression(path.clone(), replacement));
+                diagnostics.push(Diagnostic {
+                    rule_name: self.name(),
+                    severity,
+                    category: Category::Compatibility,
+                    location: location(),
+                    message,
+                    prescription,
+                    related: Vec::new(),
+                });
+            }
+
+            let mut unreadable_encodings = Vec::new();
+            for encoding in row_groups
+                .iter()
+                .flat_map(|rg| rg.column(col_idx).encodings())
+            {
+                if target.encoding_support(encoding) == Support::Unreadable
+                    && !unreadable_encodings.contains(&encoding)
+                {
+                    unreadable_encodings.push(encoding);
+                }
+            }
+            if !unreadable_encodings.is_empty() {
+                let names: Vec<String> =
+                    unreadable_encodings.iter().map(|e| e.to_string()).collect();
+                let mut prescription = Prescription::new();
+                prescription.push(Directive::SetColumnEncoding(
+                    path.clone(),
+                    REPLACEMENT_ENCODING,
+                ));
+                diagnostics.push(Diagnostic {
+                    rule_name: self.name(),
+                    severity: Severity::Error,
+                    category: Category::Compatibility,
+                    location: location(),
+                    message: format!(
+                        "target {target} cannot read {} encoding; recommend {REPLACEMENT_ENCODING}",
+                        names.join(", ")
+                    ),
+                    prescription,
+                    related: Vec::new(),
+                });
+            }
+        }
+        diagnostics
+    }
+}
@@ -0,0 +1,225 @@
+use crate::column_context::{Histogram, RowGroupColumnContext, StatValue};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription, SortKey};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+
+pub struct SortPruningRule;
+
+/// Columns at least this sorted are left to the `sorting-order` rule.
+const SORTED_THRESHOLD: Parameter = Parameter::below("sorted_fraction", ParameterKind::Ratio, 0.9);
+/// Share of row groups a sort must spare a typical range filter from
+/// reading before a rewrite pays off.
+const MIN_PRUNING_GAIN: Parameter = Parameter::above("min_pruning_gain", ParameterKind::Ratio, 0.5);
+/// With fewer row groups there is little to prune.
+const MIN_ROW_GROUPS: Parameter = Parameter::above("min_row_groups", ParameterKind::Count, 4.0);
+
+fn as_f64(value: &StatValue) -> Option<f64> {
+    match value {
+        StatValue::Int(v) => Some(*v as f64),
+        StatValue::Float(v) if v.is_finite() => Some(*v),
+        _ => None,
+    }
+}
+
+/// Share of row groups a range filter on one histogram bucket must read,
+/// now and once the file is sorted by the column.
+struct PruningEstimate {
+    row_groups: usize,
+    buckets: usize,
+    unsorted: f64,
+    sorted: f64,
+}
+
+impl PruningEstimate {
+    /// Each equi-depth bucket holds the same share of values, so averaging
+    /// over buckets weighs every value alike: dense regions of a skewed
+    /// column count as much as the rows they hold.
+    fn of(row_groups: &[RowGroupColumnContext], histogram: &Histogram) -> Option<Self> {
+        let ranges: Vec<(f64, f64)> = row_groups
+            .iter()
+            .filter_map(|rg| Some((as_f64(rg.min.as_ref()?)?, as_f64(rg.max.as_ref()?)?)))
+            .collect();
+        let buckets = histogram.num_buckets();
+        if ranges.len() < row_groups.len() || buckets == 0 {
+            return None;
+        }
+        let read: usize = histogram
+            .bounds
+            .windows(2)
+            .map(|bucket| {
+                ranges
+                    .iter()
+                    .filter(|&&(min, max)| min <= bucket[1] && max >= bucket[0])
+                    .count()
+            })
+            .sum();
+        let n = ranges.len() as f64;
+        // Sorted, a bucket's rows are contiguous: they span n / buckets row
+        // groups plus one straddled at the edge.
+        Some(Self {
+            row_groups: ranges.len(),
+            buckets,
+            unsorted: read as f64 / (buckets as f64 * n),
+            sorted: ((n / buckets as f64 + 1.0) / n).min(1.0),
+        })
+    }
+
+    fn gain(&self) -> f64 {
+        self.unsorted - self.sorted
+    }
+}
+
+/// Numeric columns whose row groups each span most of the column's values,
+/// so min/max statistics prune nothing, although sorting the file would
+/// give each row group a narrow slice. The value histogram says how many
+/// row groups a filter on a typical slice reads now and would read sorted.
+/// Only filter columns are considered when the workload names any. Needs
+/// `LintOptions::profile_values` for the histogram.
+#[async_trait::async_trait]
+impl Rule for SortPruningRule {
+    fn name(&self) -> &'static str {
+        "sort-for-pruning"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL034",
+            description: "Unsorted columns whose row-group statistics would prune range filters once sorted",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[MIN_ROW_GROUPS, SORTED_THRESHOLD, MIN_PRUNING_GAIN],
+            directives: &["sort_by", "sorting_columns"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let row_groups = ctx.metadata.row_groups();
+        if (row_groups.len() as f64) < ctx.threshold(self, &MIN_ROW_GROUPS) {
+            return Vec::new();
+        }
+        let workload = &ctx.options.workload;
+        let schema = ctx.metadata.file_metadata().schema_descr();
+        let declared = crate::fix::infer_sorting_columns(&ctx.metadata).unwrap_or_default();
+
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let descr = schema.column(col_idx);
+                let path = descr.path();
+                path.parts().len() == 1
+                    && !declared.iter().any(|c| c.column_idx as usize == col_idx)
+                    && (workload.filter_columns.is_empty() || workload.is_filter_column(path))
+            })
+            .collect();
+        ctx.columns.load(&candidates).await;
+        let sorted_threshold = ctx.threshold(self, &SORTED_THRESHOLD);
+        let min_pruning_gain = ctx.threshold(self, &MIN_PRUNING_GAIN);
+
+        let mut best: Option<(usize, PruningEstimate)> = None;
+        for col_idx in candidates {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            if col_ctx.sortedness.is_some_and(|s| s >= sorted_threshold) {
+                continue;
+            }
+            let Some(histogram) = col_ctx
+                .distribution
+                .as_ref()
+                .and_then(|d| d.histogram.as_ref())
+            else {
+                continue;
+            };
+            let Some(estimate) = PruningEstimate::of(&col_ctx.row_groups, histogram) else {
+                continue;
+            };
+            if estimate.gain() >= min_pruning_gain
+                && best
+                    .as_ref()
+                    .is_none_or(|(_, b)| estimate.gain() > b.gain())
+            {
+                best = Some((col_idx, estimate));
+            }
+        }
+        let Some((col_idx, estimate)) = best else {
+            return Vec::new();
+        };
+
+        let path = schema.column(col_idx).path().clone();
+        let key = SortKey {
+            column: path.clone(),
+            descending: false,
+        };
+        let mut prescription = Prescription::new();
+        prescription.push(Directive::SetFileSortBy(vec![key.clone()]));
+        prescription.push(Directive::SetFileSortingColumns(vec![key]));
+        vec![Diagnostic {
+            rule_name: self.name(),
+            severity: Severity::Suggestion,
+            category: Category::Performance,
+            location: Location::Column {
+                column: col_idx,
+                path,
+            },
+            message: format!(
+                "row group ranges overlap: a filter on a typical 1/{} of the values reads {:.0}% \
+                 of {} row groups, but ~{:.0}% once the file is sorted by this column",
+                estimate.buckets,
+                estimate.unsorted * 100.0,
+                estimate.row_groups,
+                estimate.sorted * 100.0
+            ),
+            prescription,
+            related: Vec::new(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use crate::options::LintOptions;
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn flags_unsorted_column_that_would_prune() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "ts",
+            Arc::new(Int64Array::from_iter_values(
+                (0..8000).map(|i| i * 7919 % 8000),
+            )) as _,
+        )])?;
+        let options = LintOptions {
+            profile_values: true,
+            ..LintOptions::default()
+        };
+
+        let unsorted = TestFile::new(batch.clone()).settings("set file max_row_group_size 1000");
+        assert_snapshot(
+            &unsorted
+                .lint_with_options(&["sort-for-pruning"], options.clone())
+                .await?,
+            r#"
+                [suggestion] sort-for-pruning @ column[0]("ts"): row group ranges overlap: a filter on a typical 1/16 of the values reads 100% of 8 row groups, but ~19% once the file is sorted by this column
+                  fix: set file sort_by ts:asc
+                  fix: set file sorting_columns ts:asc
+            "#,
+        );
+
+        let sorted = TestFile::new(batch)
+            .settings("set file max_row_group_size 1000\nset file sort_by ts:asc");
+        assert!(
+            sorted
+                .lint_with_options(&["sort-for-pruning"], options)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use crate::column_context::{RowGroupColumnContext, StatValue};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription, SortKey};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::Type as PhysicalType;
+
+pub struct SortingOrderRule;
+
+/// Share of adjacent row-group pairs whose ranges must not overlap, in the
+/// same direction, for a column to count as sorted.
+const SORTED_THRESHOLD: Parameter = Parameter::above("sorted_fraction", ParameterKind::Ratio, 0.9);
+/// With fewer row groups, non-overlapping ranges are too likely by chance.
+const MIN_ROW_GROUPS: Parameter = Parameter::above("min_row_groups", ParameterKind::Count, 3.0);
+
+/// How a column's row-group ranges follow each other.
+struct RowGroupOrder {
+    /// Adjacent pairs whose ranges do not overlap, in the majority direction.
+    ordered: usize,
+    pairs: usize,
+    descending: bool,
+}
+
+impl RowGroupOrder {
+    fn of(row_groups: &[RowGroupColumnContext]) -> Option<Self> {
+        let ranges: Vec<(&StatValue, &StatValue)> = row_groups
+            .iter()
+            .filter_map(|rg| Some((rg.min.as_ref()?, rg.max.as_ref()?)))
+            .collect();
+        // A column holding one value throughout is trivially "sorted".
+        let first_min = ranges.first()?.0;
+        if ranges
+            .iter()
+            .all(|(min, max)| *min == first_min && *max == first_min)
+        {
+            return None;
+        }
+        let (mut ascending, mut descending) = (0, 0);
+        for pair in ranges.windows(2) {
+            let ((prev_min, prev_max), (next_min, next_max)) = (pair[0], pair[1]);
+            if prev_max <= next_min {
+                ascending += 1;
+            }
+            if prev_min >= next_max {
+                descending += 1;
+            }
+        }
+        Some(Self {
+            ordered: ascending.max(descending),
+            pairs: ranges.len() - 1,
+            descending: descending > ascending,
+        })
+    }
+
+    fn fraction(&self) -> f64 {
+        self.ordered as f64 / self.pairs as f64
+    }
+}
+
+/// Columns whose row-group min/max ranges follow each other in order: the
+/// data is sorted (or clustered) by them, but readers cannot rely on it
+/// unless `sorting_columns` says so. Prescribes declaring the best such
+/// column, after any order the file already declares; rewrites sort each
+/// row group, so a nearly sorted column becomes sorted within row groups.
+#[async_trait::async_trait]
+impl Rule for SortingOrderRule {
+    fn name(&self) -> &'static str {
+        "sorting-order"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL032",
+            description: "Columns sorted across row groups but not declared in sorting_columns",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[MIN_ROW_GROUPS, SORTED_THRESHOLD],
+            directives: &["sorting_columns"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let row_groups = ctx.metadata.row_groups();
+        if (row_groups.len() as f64) < ctx.threshold(self, &MIN_ROW_GROUPS) {
+            return Vec::new();
+        }
+
+        // The declared order, as sort keys the prescription can extend.
+        let schema = ctx.metadata.file_metadata().schema_descr();
+        let declared = crate::fix::infer_sorting_columns(&ctx.metadata).unwrap_or_default();
+        let mut keys = Vec::with_capacity(declared.len() + 1);
+        for column in &declared {
+            let path = schema.column(column.column_idx as usize).path().clone();
+            if path.parts().len() != 1 || !column.nulls_first {
+                return Vec::new();
+            }
+            keys.push(SortKey {
+                column: path,
+                descending: column.descending,
+            });
+        }
+
+        let mut best: Option<(usize, RowGroupOrder)> = None;
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let descr = schema.column(col_idx);
+                descr.path().parts().len() == 1
+                    && descr.physical_type() != PhysicalType::BOOLEAN
+                    && !declared.iter().any(|c| c.column_idx as usize == col_idx)
+            })
+            .collect();
+        ctx.columns.load(&candidates).await;
+        let sorted_threshold = ctx.threshold(self, &SORTED_THRESHOLD);
+        for col_idx in candidates {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let Some(order) = RowGroupOrder::of(&col_ctx.row_groups) else {
+                continue;
+            };
+            if order.pairs > 0
+                && order.fraction() >= sorted_threshold
+                && best
+                    .as_ref()
+                    .is_none_or(|(_, b)| order.fraction() > b.fraction())
+            {
+                best = Some((col_idx, order));
+            }
+        }
+        let Some((col_idx, order)) = best else {
+            return Vec::new();
+        };
+
+        let path = schema.column(col_idx).path().clone();
+        keys.push(SortKey {
+            column: path.clone(),
+            descending: order.descending,
+        });
+        let mut prescription = Prescription::new();
+        prescription.push(Directive::SetFileSortingColumns(keys));
+        let direction = if order.descending {
+            "descending"
+        } else {
+            "ascending"
+        };
+        vec![Diagnostic {
+            rule_name: self.name(),
+            severity: Severity::Suggestion,
+            category: Category::Performance,
+            location: Location::Column {
+                column: col_idx,
+                path,
+            },
+            message: format!(
+                "row group ranges are in {direction} order in {} of {} adjacent pairs, but \
+                 sorting_columns does not declare it",
+                order.ordered, order.pairs
+            ),
+            prescription,
+            related: Vec::new(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn flags_undeclared_sorted_column() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "noise",
+                Arc::new(Int64Array::from_iter_values(
+                    (0..4000).map(|i| i * 7919 % 4000),
+                )) as _,
+            ),
+            (
+                "ts",
+                Arc::new(Int64Array::from_iter_values((0..4000).map(|i| 4000 - i))) as _,
+            ),
+        ])?;
+
+        let undeclared = TestFile::new(batch.clone()).settings("set file max_row_group_size 1000");
+        assert_snapshot(
+            &undeclared.lint(&["sorting-order"]).await?,
+            r#"
+                [suggestion] sorting-order @ column[1]("ts"): row group ranges are in descending order in 3 of 3 adjacent pairs, but sorting_columns does not declare it
+                  fix: set file sorting_columns ts:desc
+            "#,
+        );
+
+        let declared = TestFile::new(batch)
+            .settings("set file max_row_group_size 1000\nset file sorting_columns ts:desc");
+        assert!(declared.lint(&["sorting-order"]).await?.is_empty());
+        Ok(())
+    }
+}
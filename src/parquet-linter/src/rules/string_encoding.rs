@@ -1,21 +1,43 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use super::dictionary_encoding::{ChunkDictionaryState, DictionaryStates};
+use crate::column_context::{StringStats, TypeStats};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{DataEncoding, Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
 use parquet::basic::{Encoding, LogicalType, Type as PhysicalType};
+use std::ops::RangeInclusive;
 
 pub struct StringEncodingRule;
 
-const MIN_TOTAL_BYTES: i64 = 32 * 1024 * 1024; // 32 MB
+const MIN_TOTAL_BYTES: Parameter = Parameter::above(
+    "min_column_bytes",
+    ParameterKind::Bytes,
+    32.0 * 1024.0 * 1024.0,
+);
 const MIN_NON_EMPTY_GROUPS: usize = 2;
 const MAX_NON_EMPTY_GROUPS: usize = 32;
 const MIN_AVG_CHUNK_BYTES: i64 = 4 * 1024 * 1024; // 4 MB
-const MIN_RATIO: f64 = 0.35;
-const MAX_RATIO: f64 = 0.75;
+/// Compressed-to-uncompressed ratios of text that DELTA_LENGTH_BYTE_ARRAY
+/// compresses better than a fallen-back dictionary, for large chunks.
+const MIN_RATIO: Parameter = Parameter::above("min_compression_ratio", ParameterKind::Ratio, 0.35);
+const MAX_RATIO: Parameter = Parameter::below("max_compression_ratio", ParameterKind::Ratio, 0.75);
 const SMALL_CHUNK_MIN_TOTAL_BYTES: i64 = 64 * 1024 * 1024; // 64 MB
 const SMALL_CHUNK_MIN_GROUPS: usize = 64;
 const SMALL_CHUNK_MAX_AVG_CHUNK_BYTES: i64 = 1024 * 1024; // 1 MB
-const SMALL_CHUNK_MIN_RATIO: f64 = 0.55;
-const SMALL_CHUNK_MAX_RATIO: f64 = 0.85;
+/// The same ratios for many small chunks.
+const SMALL_CHUNK_MIN_RATIO: Parameter = Parameter::above(
+    "small_chunk_min_compression_ratio",
+    ParameterKind::Ratio,
+    0.55,
+);
+const SMALL_CHUNK_MAX_RATIO: Parameter = Parameter::below(
+    "small_chunk_max_compression_ratio",
+    ParameterKind::Ratio,
+    0.85,
+);
+/// Share of the average value length that adjacent sorted values must have in
+/// common before DELTA_BYTE_ARRAY beats DELTA_LENGTH_BYTE_ARRAY.
+const MIN_SHARED_PREFIX_SHARE: f64 = 0.3;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct StringColumnSummary {
@@ -42,6 +64,24 @@ impl StringColumnSummary {
     }
 }
 
+/// The thresholds a file is checked against, read once per file.
+struct Limits {
+    min_total_bytes: i64,
+    ratio: RangeInclusive<f64>,
+    small_chunk_ratio: RangeInclusive<f64>,
+}
+
+impl Limits {
+    fn new(threshold: impl Fn(&Parameter) -> f64) -> Self {
+        Self {
+            min_total_bytes: threshold(&MIN_TOTAL_BYTES) as i64,
+            ratio: threshold(&MIN_RATIO)..=threshold(&MAX_RATIO),
+            small_chunk_ratio: threshold(&SMALL_CHUNK_MIN_RATIO)
+                ..=threshold(&SMALL_CHUNK_MAX_RATIO),
+        }
+    }
+}
+
 fn looks_text_column(logical_type: Option<&LogicalType>, path: &str) -> bool {
     if matches!(
         logical_type,
@@ -61,6 +101,7 @@ fn should_prefer_delta_length_byte_array(
     has_plain: bool,
     has_dictionary: bool,
     already_delta_byte_array: bool,
+    limits: &Limits,
 ) -> bool {
     if already_delta_byte_array || !has_plain || !has_dictionary {
         return false;
@@ -73,26 +114,61 @@ fn should_prefer_delta_length_byte_array(
     };
     let avg_chunk = summary.avg_chunk_uncompressed();
 
-    let moderate_multi_group_large_chunks = summary.total_uncompressed >= MIN_TOTAL_BYTES
+    let moderate_multi_group_large_chunks = summary.total_uncompressed >= limits.min_total_bytes
         && (MIN_NON_EMPTY_GROUPS..=MAX_NON_EMPTY_GROUPS).contains(&summary.non_empty_groups)
         && avg_chunk >= MIN_AVG_CHUNK_BYTES
-        && (MIN_RATIO..=MAX_RATIO).contains(&ratio);
+        && limits.ratio.contains(&ratio);
 
     let many_small_chunks = summary.total_uncompressed >= SMALL_CHUNK_MIN_TOTAL_BYTES
         && summary.non_empty_groups >= SMALL_CHUNK_MIN_GROUPS
         && avg_chunk > 0
         && avg_chunk <= SMALL_CHUNK_MAX_AVG_CHUNK_BYTES
-        && (SMALL_CHUNK_MIN_RATIO..=SMALL_CHUNK_MAX_RATIO).contains(&ratio);
+        && limits.small_chunk_ratio.contains(&ratio);
 
     moderate_multi_group_large_chunks || many_small_chunks
 }
 
+/// Pick DELTA_BYTE_ARRAY for values with long shared prefixes (URLs, paths,
+/// prefixed IDs), DELTA_LENGTH_BYTE_ARRAY otherwise.
+fn delta_encoding_for(stats: &StringStats) -> DataEncoding {
+    match (stats.avg_common_prefix, &stats.lengths) {
+        (Some(prefix), Some(lengths))
+            if lengths.avg > 0.0 && prefix / lengths.avg >= MIN_SHARED_PREFIX_SHARE =>
+        {
+            DataEncoding::DeltaByteArray
+        }
+        _ => DataEncoding::DeltaLengthByteArray,
+    }
+}
+
 #[async_trait::async_trait]
 impl Rule for StringEncodingRule {
     fn name(&self) -> &'static str {
         "string-byte-array-encoding"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL013",
+            description: "Text columns that would decode faster with DELTA_LENGTH_BYTE_ARRAY or DELTA_BYTE_ARRAY",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[
+                MIN_TOTAL_BYTES,
+                MIN_RATIO,
+                MAX_RATIO,
+                SMALL_CHUNK_MIN_RATIO,
+                SMALL_CHUNK_MAX_RATIO,
+            ],
+            directives: &["dictionary", "encoding"],
+        }
+    }
+
+    fn dependencies(&self) -> &'static [&'static str] {
+        &["dictionary-encoding-cardinality"]
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -100,8 +176,10 @@ impl Rule for StringEncodingRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        let dictionary_states = ctx.blackboard.get::<DictionaryStates>();
+        let limits = Limits::new(|parameter| ctx.threshold(self, parameter));
+        let mut candidates = Vec::new();
+        for col_idx in ctx.column_indices() {
             let col0 = row_groups[0].column(col_idx);
             let descr = col0.column_descr();
             if descr.physical_type() != PhysicalType::BYTE_ARRAY {
@@ -145,6 +223,27 @@ impl Rule for StringEncodingRule {
                 }
             }
 
+            // Dictionary pages are PLAIN-encoded, so chunk metadata cannot
+            // tell a fallback from a dictionary that held; use the data-page
+            // classification when the dictionary rule has made one.
+            let states = dictionary_states
+                .as_deref()
+                .map_or(&[][..], |states| states.column(col_idx));
+            if !states.is_empty() {
+                has_plain = states.iter().any(|s| {
+                    matches!(
+                        s,
+                        ChunkDictionaryState::Fallback | ChunkDictionaryState::NoDictionary
+                    )
+                });
+                has_dictionary = states.iter().any(|s| {
+                    matches!(
+                        s,
+                        ChunkDictionaryState::Fallback | ChunkDictionaryState::DictionaryOnly
+                    )
+                });
+            }
+
             if !should_prefer_delta_length_byte_array(
                 summary,
                 logical_type,
@@ -152,34 +251,46 @@ impl Rule for StringEncodingRule {
                 has_plain,
                 has_dictionary,
                 already_delta_byte_array,
+                &limits,
             ) {
                 continue;
             }
+            candidates.push((col_idx, summary));
+        }
 
+        // Sampled lengths pick the delta encoding; build them in one batch.
+        let columns: Vec<usize> = candidates.iter().map(|&(col_idx, _)| col_idx).collect();
+        ctx.columns.load(&columns).await;
+        for (col_idx, summary) in candidates {
+            let col0 = row_groups[0].column(col_idx);
             let ratio = summary.aggregated_ratio().unwrap_or(0.0);
+            let encoding = match ctx.columns.get(col_idx).await.map(|c| &c.type_stats) {
+                Some(TypeStats::String(stats)) => delta_encoding_for(stats),
+                _ => DataEncoding::DeltaLengthByteArray,
+            };
             let mut prescription = Prescription::new();
             let path_obj = col0.column_path().clone();
             prescription.push(Directive::SetColumnDictionary(path_obj.clone(), false));
-            prescription.push(Directive::SetColumnEncoding(
-                path_obj.clone(),
-                DataEncoding::DeltaLengthByteArray,
-            ));
+            prescription.push(Directive::SetColumnEncoding(path_obj.clone(), encoding));
             diagnostics.push(Diagnostic {
                 rule_name: self.name(),
                 severity: Severity::Suggestion,
+                category: Category::Performance,
                 location: Location::Column {
                     column: col_idx,
                     path: path_obj.clone(),
                 },
                 message: format!(
                     "text column ({:.1}MB across {}/{} row groups, ratio {:.2}) uses dictionary/plain \
-                     pages; try DELTA_LENGTH_BYTE_ARRAY and disable dictionary",
+                     pages; try {:?} and disable dictionary",
                     summary.total_uncompressed as f64 / (1024.0 * 1024.0),
                     summary.non_empty_groups,
                     row_groups.len(),
                     ratio,
+                    Encoding::from(encoding),
                 ),
                 prescription,
+                related: Vec::new(),
             });
         }
 
@@ -190,6 +301,7 @@ impl Rule for StringEncodingRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::column_context::ByteLengthStats;
 
     #[test]
     fn selects_file4_like_large_multi_group_text() {
@@ -204,10 +316,37 @@ mod tests {
             "reasoning_content",
             true,
             true,
-            false
+            false,
+            &Limits::new(|parameter| parameter.default),
         ));
     }
 
+    #[test]
+    fn shared_prefixes_prefer_delta_byte_array() {
+        let stats = |avg_common_prefix| StringStats {
+            min_value: None,
+            max_value: None,
+            lengths: Some(ByteLengthStats {
+                min: 20,
+                max: 60,
+                avg: 40.0,
+            }),
+            avg_common_prefix,
+        };
+        assert_eq!(
+            delta_encoding_for(&stats(Some(24.0))),
+            DataEncoding::DeltaByteArray
+        );
+        assert_eq!(
+            delta_encoding_for(&stats(Some(2.0))),
+            DataEncoding::DeltaLengthByteArray
+        );
+        assert_eq!(
+            delta_encoding_for(&stats(None)),
+            DataEncoding::DeltaLengthByteArray
+        );
+    }
+
     #[test]
     fn rejects_single_row_group_giant_text() {
         let summary = StringColumnSummary {
@@ -221,7 +360,8 @@ mod tests {
             "content",
             true,
             true,
-            false
+            false,
+            &Limits::new(|parameter| parameter.default),
         ));
     }
 
@@ -238,7 +378,8 @@ mod tests {
             "content",
             true,
             true,
-            false
+            false,
+            &Limits::new(|parameter| parameter.default),
         ));
     }
 }
@@ -1,6 +1,6 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
 use parquet::basic::Type as PhysicalType;
 
 pub struct StringStatisticsRule;
@@ -13,6 +13,18 @@ impl Rule for StringStatisticsRule {
         "oversized-string-statistics"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL022",
+            description: "String statistics long enough to bloat the footer",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[],
+            directives: &["statistics_truncate_length"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -20,8 +32,7 @@ impl Rule for StringStatisticsRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        for col_idx in ctx.column_indices() {
             let col0 = row_groups[0].column(col_idx);
             if !matches!(
                 col0.column_descr().physical_type(),
@@ -62,6 +73,7 @@ impl Rule for StringStatisticsRule {
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Warning,
+                    category: Category::Performance,
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -73,6 +85,7 @@ impl Rule for StringStatisticsRule {
                         row_groups.len()
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
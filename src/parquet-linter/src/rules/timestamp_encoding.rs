@@ -1,6 +1,6 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{DataEncoding, Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
 use parquet::basic::{Encoding, LogicalType, Type as PhysicalType};
 
 pub struct TimestampEncodingRule;
@@ -11,6 +11,18 @@ impl Rule for TimestampEncodingRule {
         "timestamp-delta-encoding"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL018",
+            description: "Timestamp columns that would shrink with DELTA_BINARY_PACKED",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[],
+            directives: &["encoding"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -18,8 +30,7 @@ impl Rule for TimestampEncodingRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        for col_idx in ctx.column_indices() {
             let col0 = row_groups[0].column(col_idx);
             let descr = col0.column_descr();
             let is_int = matches!(
@@ -73,6 +84,7 @@ impl Rule for TimestampEncodingRule {
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Suggestion,
+                    category: Category::Performance,
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -83,6 +95,7 @@ impl Rule for TimestampEncodingRule {
                          DELTA_BINARY_PACKED is typically more efficient for temporal data"
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
@@ -0,0 +1,141 @@
+use crate::column_context::TypeStats;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::Type as PhysicalType;
+
+pub struct TruncatedStatisticsRule;
+
+/// Values up to this long are short enough to keep whole in statistics.
+const MAX_KEY_LENGTH: Parameter = Parameter::below("max_key_length", ParameterKind::Bytes, 256.0);
+/// Above this ratio (distinct / non-null) a column looks like a key.
+const KEY_CARDINALITY_RATIO: f64 = 0.5;
+/// Never suggest less than twice the common 64-byte default.
+const MIN_TRUNCATE_LENGTH: usize = 128;
+
+/// A truncate length that keeps values up to `max_length` bytes whole.
+fn suggested_truncate_length(max_length: usize) -> usize {
+    max_length.next_power_of_two().max(MIN_TRUNCATE_LENGTH)
+}
+
+#[async_trait::async_trait]
+impl Rule for TruncatedStatisticsRule {
+    fn name(&self) -> &'static str {
+        "truncated-key-statistics"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL024",
+            description: "Key columns whose truncated statistics no longer bound their values",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MAX_KEY_LENGTH],
+            directives: &["statistics_truncate_length"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() {
+            return diagnostics;
+        }
+
+        let truncated: Vec<(usize, usize)> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                row_groups[0].column(col_idx).column_descr().physical_type()
+                    == PhysicalType::BYTE_ARRAY
+            })
+            .map(|col_idx| {
+                let inexact = row_groups
+                    .iter()
+                    .filter_map(|rg| rg.column(col_idx).statistics())
+                    .filter(|stats| !stats.min_is_exact() || !stats.max_is_exact())
+                    .count();
+                (col_idx, inexact)
+            })
+            .filter(|&(_, inexact)| inexact > 0)
+            .collect();
+        let max_key_length = ctx.threshold(self, &MAX_KEY_LENGTH) as usize;
+        let columns: Vec<usize> = truncated.iter().map(|&(col_idx, _)| col_idx).collect();
+        ctx.columns.load(&columns).await;
+
+        for (col_idx, inexact) in truncated {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let TypeStats::String(stats) = &col_ctx.type_stats else {
+                continue;
+            };
+            let Some(lengths) = &stats.lengths else {
+                continue;
+            };
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let workload = &ctx.options.workload;
+            let key_like = col_ctx.cardinality_ratio() >= KEY_CARDINALITY_RATIO
+                || workload.is_filter_column(&path)
+                || workload.is_join_key(&path);
+            if !key_like || lengths.max > max_key_length {
+                continue;
+            }
+
+            let length = suggested_truncate_length(lengths.max);
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnStatisticsTruncateLength(
+                path.clone(),
+                Some(length),
+            ));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Warning,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "min/max statistics are truncated in {inexact}/{} row groups, but sampled \
+                     values are at most {}B; truncated bounds on a key column block exact \
+                     pruning, recommend statistics_truncate_length={length}",
+                    row_groups.len(),
+                    lengths.max
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{RecordBatch, StringArray};
+
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn flags_truncated_short_keys() -> anyhow::Result<()> {
+        let keys = (0..1000).map(|i| format!("tenant-{i:04}-{}", "x".repeat(80)));
+        let batch = RecordBatch::try_from_iter([(
+            "key",
+            Arc::new(StringArray::from_iter_values(keys)) as _,
+        )])?;
+        let file = TestFile::new(batch).settings("set file statistics_truncate_length 16");
+        assert_snapshot(
+            &file.lint(&["truncated-key-statistics"]).await?,
+            r#"
+                [warning] truncated-key-statistics @ column[0]("key"): min/max statistics are truncated in 1/1 row groups, ...
+                  fix: set column key statistics_truncate_length 128
+            "#,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::{Codec, Directive, Prescription};
+use crate::probe::MAX_SMALL_COLUMN_BYTES;
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::{Compression, Encoding};
+
+pub struct UncompressedDictionaryRule;
+
+/// Share of the uncompressed trial size a codec must save to be suggested.
+const MIN_SAVING: f64 = 0.25;
+/// Bytes a codec must save across the column; below this the change is not
+/// worth a rewrite.
+const MIN_SAVED_BYTES: Parameter =
+    Parameter::above("min_saved_bytes", ParameterKind::Bytes, 8.0 * 1024.0);
+
+/// Small dictionary-encoded columns stored UNCOMPRESSED in every row group.
+/// With the compression probe on, the generic codec rule stays quiet below
+/// `MAX_SMALL_COLUMN_BYTES` because its size-based guesses do not hold
+/// there; this rule only speaks when the probe measured a clear win.
+#[async_trait::async_trait]
+impl Rule for UncompressedDictionaryRule {
+    fn name(&self) -> &'static str {
+        "uncompressed-small-dictionary"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL015",
+            description: "Small dictionary-encoded columns stored uncompressed where a measured codec saves space",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[MIN_SAVED_BYTES],
+            directives: &["compression"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let row_groups = ctx.metadata.row_groups();
+        if row_groups.is_empty() || !ctx.options.probe_compression {
+            return diagnostics;
+        }
+        let min_saved_bytes = ctx.threshold(self, &MIN_SAVED_BYTES) as i64;
+
+        let candidates: Vec<usize> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col_idx| {
+                let chunks = row_groups
+                    .iter()
+                    .map(|rg| rg.column(col_idx))
+                    .filter(|chunk| chunk.num_values() > 0);
+                let uncompressed: i64 = chunks.clone().map(|c| c.uncompressed_size()).sum();
+                let mut chunks = chunks.peekable();
+                chunks.peek().is_some()
+                    && uncompressed < MAX_SMALL_COLUMN_BYTES
+                    && chunks.all(|chunk| {
+                        chunk.compression() == Compression::UNCOMPRESSED
+                            && chunk.encodings().any(|e| {
+                                matches!(e, Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY)
+                            })
+                    })
+            })
+            .collect();
+        ctx.columns.load(&candidates).await;
+
+        for col_idx in candidates {
+            let Some(col_ctx) = ctx.columns.get(col_idx).await else {
+                continue;
+            };
+            let trials = &col_ctx.compression_trials;
+            let Some(baseline) = trials
+                .iter()
+                .find(|t| t.codec == Compression::UNCOMPRESSED && t.compressed_size > 0)
+            else {
+                continue;
+            };
+            let Some(best) = trials
+                .iter()
+                .filter(|t| t.codec != Compression::UNCOMPRESSED)
+                .min_by_key(|t| t.compressed_size)
+            else {
+                continue;
+            };
+            let saving = 1.0 - best.compressed_size as f64 / baseline.compressed_size as f64;
+            let saved_bytes = (saving * col_ctx.compressed_size as f64) as i64;
+            if saving < MIN_SAVING || saved_bytes < min_saved_bytes {
+                continue;
+            }
+
+            let Some(codec) = Codec::from_compression(best.codec) else {
+                continue;
+            };
+            let path = row_groups[0].column(col_idx).column_path().clone();
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnCompression(path.clone(), codec));
+            diagnostics.push(Diagnostic {
+                rule_name: self.name(),
+                severity: Severity::Suggestion,
+                category: Category::Performance,
+                location: Location::Column {
+                    column: col_idx,
+                    path,
+                },
+                message: format!(
+                    "dictionary-encoded column stored UNCOMPRESSED; a sampled rewrite with \
+                     {codec} is {:.0}% smaller (about {:.1}KB saved of {:.1}KB)",
+                    saving * 100.0,
+                    saved_bytes as f64 / 1024.0,
+                    col_ctx.compressed_size as f64 / 1024.0,
+                ),
+                prescription,
+                related: Vec::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{RecordBatch, StringArray};
+
+    use crate::options::LintOptions;
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[tokio::test]
+    async fn suggests_measured_codec_for_small_dictionary_column() -> anyhow::Result<()> {
+        let values: StringArray = (0..50_000)
+            .map(|i| Some(format!("warehouse-region-{}", i % 40)))
+            .collect();
+        let batch = RecordBatch::try_from_iter([("region", Arc::new(values) as _)])?;
+        let file =
+            TestFile::new(batch.clone()).settings("set column region compression uncompressed");
+        let rules = ["uncompressed-small-dictionary"];
+
+        assert!(file.lint(&rules).await?.is_empty());
+        let options = LintOptions {
+            probe_compression: true,
+            ..LintOptions::default()
+        };
+        assert_snapshot(
+            &file.lint_with_options(&rules, options.clone()).await?,
+            r#"
+                [suggestion] uncompressed-small-dictionary @ column[0]("region"): dictionary-encoded column stored UNCOMPRESSED; a sampled rewrite with zstd(3) ...
+                  fix: set column region compression zstd(3)
+            "#,
+        );
+
+        let compressed =
+            TestFile::new(batch.clone()).settings("set column region compression snappy");
+        assert!(
+            compressed
+                .lint_with_options(&rules, options.clone())
+                .await?
+                .is_empty()
+        );
+        let plain = TestFile::new(batch).settings(
+            "set column region compression uncompressed
+             set column region dictionary false",
+        );
+        assert!(plain.lint_with_options(&rules, options).await?.is_empty());
+        Ok(())
+    }
+}
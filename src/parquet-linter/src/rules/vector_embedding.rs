@@ -1,6 +1,7 @@
-use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
 use crate::prescription::{Directive, Prescription};
-use crate::rule::{Rule, RuleContext};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
 use parquet::basic::Type as PhysicalType;
 
 pub struct VectorEmbeddingRule;
@@ -8,7 +9,8 @@ pub struct VectorEmbeddingRule;
 // 8KB pages are good for point lookups but often hurt full scans. Use a
 // moderate page size to preserve most benefits without over-fragmenting data.
 const SMALL_PAGE_SIZE: usize = 256 * 1024; // 256 KB
-const MIN_ELEMENTS_PER_ROW: i64 = 64;
+const MIN_ELEMENTS_PER_ROW: Parameter =
+    Parameter::above("min_values_per_row", ParameterKind::Count, 64.0);
 
 #[async_trait::async_trait]
 impl Rule for VectorEmbeddingRule {
@@ -16,6 +18,18 @@ impl Rule for VectorEmbeddingRule {
         "vector-embedding-page-size"
     }
 
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL005",
+            description: "Embedding-like list columns whose pages are too large for random-access lookups",
+            category: Category::Performance,
+            default_severity: Severity::Warning,
+            parameters: &[MIN_ELEMENTS_PER_ROW],
+            directives: &["data_page_size_limit"],
+        }
+    }
+
     async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
         let row_groups = ctx.metadata.row_groups();
@@ -23,8 +37,7 @@ impl Rule for VectorEmbeddingRule {
             return diagnostics;
         }
 
-        let num_columns = row_groups[0].num_columns();
-        for col_idx in 0..num_columns {
+        for col_idx in ctx.column_indices() {
             let col0 = row_groups[0].column(col_idx);
             let descr = col0.column_descr();
             let is_float = matches!(
@@ -52,13 +65,14 @@ impl Rule for VectorEmbeddingRule {
             }
 
             let avg_values = total_values / total_rows;
-            if avg_values >= MIN_ELEMENTS_PER_ROW {
+            if avg_values as f64 >= ctx.threshold(self, &MIN_ELEMENTS_PER_ROW) {
                 let path = col0.column_path().clone();
                 let mut prescription = Prescription::new();
                 prescription.push(Directive::SetFileDataPageSizeLimit(SMALL_PAGE_SIZE));
                 diagnostics.push(Diagnostic {
                     rule_name: self.name(),
                     severity: Severity::Warning,
+                    category: Category::Performance,
                     location: Location::Column {
                         column: col_idx,
                         path: path.clone(),
@@ -68,6 +82,7 @@ impl Rule for VectorEmbeddingRule {
                          consider smaller page size for random-access lookups"
                     ),
                     prescription,
+                    related: Vec::new(),
                 });
             }
         }
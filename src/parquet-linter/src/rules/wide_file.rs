@@ -0,0 +1,158 @@
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::prescription::Prescription;
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::schema::types::ColumnPath;
+
+pub struct WideFileRule;
+
+/// Files with this many leaf columns pay for every column in footer
+/// parsing and planning, however few a query reads.
+const WIDE_FILE_COLUMNS: Parameter =
+    Parameter::above("wide_file_columns", ParameterKind::Count, 1000.0);
+/// With workload hints, warn when queries touch at most this share of columns.
+const NARROW_WORKLOAD_SHARE: f64 = 0.1;
+const SHOWN_COLUMNS: usize = 5;
+
+/// Leaf columns with their share of the file's compressed bytes, largest
+/// first.
+fn byte_shares(metadata: &ParquetMetaData) -> Vec<(ColumnPath, f64)> {
+    let schema = metadata.file_metadata().schema_descr();
+    let mut bytes = vec![0i64; schema.num_columns()];
+    for rg in metadata.row_groups() {
+        for (col_idx, col) in rg.columns().iter().enumerate() {
+            bytes[col_idx] += col.compressed_size();
+        }
+    }
+    let total = bytes.iter().sum::<i64>().max(1) as f64;
+    let mut shares: Vec<(ColumnPath, f64)> = bytes
+        .into_iter()
+        .enumerate()
+        .map(|(col_idx, b)| (schema.column(col_idx).path().clone(), b as f64 / total))
+        .collect();
+    shares.sort_by(|a, b| b.1.total_cmp(&a.1));
+    shares
+}
+
+fn describe<'a>(shares: impl IntoIterator<Item = &'a (ColumnPath, f64)>) -> String {
+    shares
+        .into_iter()
+        .take(SHOWN_COLUMNS)
+        .map(|(path, share)| format!("{} {:.1}%", path.string(), share * 100.0))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[async_trait::async_trait]
+impl Rule for WideFileRule {
+    fn name(&self) -> &'static str {
+        "wide-file"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL028",
+            description: "Files with so many columns that footer parsing dominates narrow reads",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[WIDE_FILE_COLUMNS],
+            directives: &[],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let num_columns = ctx.metadata.file_metadata().schema_descr().num_columns();
+        if num_columns < ctx.threshold(self, &WIDE_FILE_COLUMNS) as usize {
+            return Vec::new();
+        }
+        let shares = byte_shares(&ctx.metadata);
+        let workload = &ctx.options.workload;
+
+        let (severity, message) = if workload.is_empty() {
+            // Without hints, show how concentrated the bytes are.
+            let mut covered = 0.0;
+            let hot = shares
+                .iter()
+                .take_while(|(_, share)| {
+                    let take = covered < 0.9;
+                    covered += share;
+                    take
+                })
+                .count();
+            (
+                Severity::Suggestion,
+                format!(
+                    "file has {num_columns} leaf columns; {hot} of them hold 90% of the bytes \
+                     (largest: {}); if queries read few columns, consider splitting into \
+                     column-family files",
+                    describe(&shares)
+                ),
+            )
+        } else {
+            let touched: Vec<_> = shares
+                .iter()
+                .filter(|(path, _)| workload.touches(path))
+                .collect();
+            let touched_share = touched.len() as f64 / num_columns as f64;
+            if touched_share > NARROW_WORKLOAD_SHARE {
+                return Vec::new();
+            }
+            let touched_bytes: f64 = touched.iter().map(|(_, share)| share).sum();
+            (
+                Severity::Warning,
+                format!(
+                    "file has {num_columns} leaf columns but queries touch {} ({:.1}% of bytes: \
+                     {}); split those columns into their own files (vertical partitioning)",
+                    touched.len(),
+                    touched_bytes * 100.0,
+                    describe(touched.iter().copied())
+                ),
+            )
+        };
+        vec![Diagnostic {
+            rule_name: self.name(),
+            severity,
+            category: Category::Performance,
+            location: Location::File,
+            message,
+            prescription: Prescription::new(),
+            related: Vec::new(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+
+    use super::*;
+    use crate::options::{LintOptions, Workload};
+    use crate::testing::TestFile;
+
+    #[tokio::test]
+    async fn warns_when_workload_touches_few_of_many_columns() -> anyhow::Result<()> {
+        let columns = (0..WIDE_FILE_COLUMNS.default as usize).map(|i| {
+            let values: ArrayRef = Arc::new(Int32Array::from(vec![i as i32; 10]));
+            (format!("c{i}"), values)
+        });
+        let file = TestFile::new(RecordBatch::try_from_iter(columns)?);
+        let diagnostics = file.lint(&["wide-file"]).await?;
+        assert_eq!(diagnostics[0].severity, Severity::Suggestion);
+
+        let options = LintOptions {
+            workload: Workload {
+                projection_columns: vec!["c1".to_string(), "c2".to_string()],
+                ..Workload::default()
+            },
+            ..LintOptions::default()
+        };
+        let diagnostics = file.lint_with_options(&["wide-file"], options).await?;
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.contains("queries touch 2"));
+        Ok(())
+    }
+}
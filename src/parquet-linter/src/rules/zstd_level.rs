@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use crate::cardinality;
+use crate::diagnostic::{Category, Diagnostic, Location, Severity};
+use crate::page_cache::ChunkSpan;
+use crate::prescription::{Codec, Directive, Prescription};
+use crate::rule::{Rule, RuleContext, RuleMetadata};
+use crate::thresholds::{Parameter, ParameterKind};
+use parquet::basic::Compression;
+use parquet::column::page::{Page, PageReader};
+
+pub struct ZstdLevelRule;
+
+/// Levels a sampled page is re-compressed at to find the one it was written
+/// with. Higher levels are rare and slow to try.
+const CANDIDATE_LEVELS: [i32; 7] = [1, 3, 6, 9, 12, 15, 19];
+/// Pages smaller than this compress too little to tell levels apart.
+const MIN_SAMPLE_BYTES: usize = 16 * 1024;
+/// Pages larger than this are too slow to re-compress at every level.
+const MAX_SAMPLE_BYTES: usize = 2 * 1024 * 1024;
+/// Bytes read from the start of a chunk: a sample-sized page plus room for
+/// its header and any compression overhead.
+const FIRST_PAGE_SPAN: ChunkSpan = ChunkSpan::Prefix((MAX_SAMPLE_BYTES + 64 * 1024) as u64);
+/// Levels at least this far above the file's lowest count as a mismatch.
+const MIN_LEVEL_GAP: i32 = 6;
+/// Share of bytes a higher level must save over the lowest level on the
+/// sampled page to justify its slower writes.
+const MIN_SIZE_GAIN: Parameter = Parameter::below("min_size_gain", ParameterKind::Ratio, 0.03);
+/// Compressed zstd bytes in the file below which the write cost does not
+/// matter.
+const MIN_ZSTD_BYTES: Parameter = Parameter::above(
+    "min_zstd_bytes",
+    ParameterKind::Bytes,
+    64.0 * 1024.0 * 1024.0,
+);
+
+/// A column's zstd level, inferred from one re-compressed page.
+struct InferredLevel {
+    column: usize,
+    level: i32,
+    /// Compressed size of the sampled page at each candidate level.
+    sizes: BTreeMap<i32, usize>,
+    /// Compressed bytes of the column over all row groups.
+    compressed_bytes: i64,
+}
+
+/// The size a page header declares for its compressed body. parquet
+/// writers emit the thrift fields in order, so `compressed_page_size` is
+/// the third field, after the page type and the uncompressed size, each a
+/// compact-protocol i32 with a one-byte field header.
+fn header_compressed_size(header: &[u8]) -> Option<usize> {
+    let mut rest = header;
+    let mut value = 0;
+    for _ in 0..3 {
+        let (&field, tail) = rest.split_first()?;
+        if field != 0x15 {
+            return None;
+        }
+        let (decoded, tail) = read_zigzag_varint(tail)?;
+        value = decoded;
+        rest = tail;
+    }
+    usize::try_from(value).ok()
+}
+
+fn read_zigzag_varint(bytes: &[u8]) -> Option<(i64, &[u8])> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().take(5).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let decoded = (value >> 1) as i64 ^ -((value & 1) as i64);
+            return Some((decoded, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+/// Compressed size of `data` at `level`, framed the way the `parquet`
+/// crate's zstd codec frames a page.
+fn zstd_size(data: &[u8], level: i32) -> Option<usize> {
+    let mut encoder = zstd::Encoder::new(Vec::new(), level).ok()?;
+    encoder.write_all(data).ok()?;
+    Some(encoder.finish().ok()?.len())
+}
+
+/// Infer the zstd level of column `col` from the first page of its chunk in
+/// row group `rg`: the candidate that reproduces the page's compressed size
+/// exactly. `None` when no single candidate does, e.g. for another zstd
+/// build or a level outside the candidates.
+async fn infer_level(
+    ctx: &RuleContext,
+    rg: usize,
+    col: usize,
+) -> Option<(i32, BTreeMap<i32, usize>)> {
+    let raw = ctx.pages.bytes(rg, col, FIRST_PAGE_SPAN).await.ok()?;
+    let compressed = header_compressed_size(&raw)?;
+    let mut pages = ctx.pages.page_reader(rg, col, FIRST_PAGE_SPAN).await.ok()?;
+    let (body, uncompressed_prefix) = match pages.get_next_page().ok()?? {
+        Page::DictionaryPage { buf, .. } | Page::DataPage { buf, .. } => (buf, 0),
+        Page::DataPageV2 {
+            buf,
+            def_levels_byte_len,
+            rep_levels_byte_len,
+            is_compressed: true,
+            ..
+        } => (buf, (def_levels_byte_len + rep_levels_byte_len) as usize),
+        Page::DataPageV2 { .. } => return None,
+    };
+    let data = body.get(uncompressed_prefix..)?;
+    if !(MIN_SAMPLE_BYTES..=MAX_SAMPLE_BYTES).contains(&data.len()) {
+        return None;
+    }
+    let sizes: BTreeMap<i32, usize> = CANDIDATE_LEVELS
+        .iter()
+        .map(|&level| Some((level, uncompressed_prefix + zstd_size(data, level)?)))
+        .collect::<Option<_>>()?;
+    let mut matching = sizes.iter().filter(|&(_, &size)| size == compressed);
+    let level = match (matching.next(), matching.next()) {
+        (Some((&level, _)), None) => level,
+        _ => return None,
+    };
+    Some((level, sizes))
+}
+
+/// Files whose columns were compressed with zstd levels far apart, where
+/// the higher level barely shrinks its columns: every write pays for the
+/// slow level with nothing to show for it. Footers do not record levels,
+/// so they are inferred by re-compressing a sampled page; the rule only
+/// runs with `LintOptions::probe_compression`.
+#[async_trait::async_trait]
+impl Rule for ZstdLevelRule {
+    fn name(&self) -> &'static str {
+        "zstd-level-mismatch"
+    }
+
+    fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            name: self.name(),
+            code: "PL033",
+            description: "Columns compressed with much higher zstd levels than the rest of the file for no size gain",
+            category: Category::Performance,
+            default_severity: Severity::Suggestion,
+            parameters: &[MIN_ZSTD_BYTES, MIN_SIZE_GAIN],
+            directives: &["compression"],
+        }
+    }
+
+    async fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let row_groups = ctx.metadata.row_groups();
+        if !ctx.options.probe_compression || ctx.options.metadata_only || row_groups.is_empty() {
+            return Vec::new();
+        }
+        let sample_rg = cardinality::pick_sample_row_group(&ctx.metadata);
+        let zstd_columns: Vec<(usize, i64)> = ctx
+            .column_indices()
+            .into_iter()
+            .filter(|&col| {
+                matches!(
+                    row_groups[sample_rg].column(col).compression(),
+                    Compression::ZSTD(_)
+                )
+            })
+            .map(|col| {
+                let bytes = row_groups
+                    .iter()
+                    .map(|rg| rg.column(col).compressed_size())
+                    .sum();
+                (col, bytes)
+            })
+            .collect();
+        let total_bytes: i64 = zstd_columns.iter().map(|&(_, bytes)| bytes).sum();
+        if zstd_columns.len() < 2 || (total_bytes as f64) < ctx.threshold(self, &MIN_ZSTD_BYTES) {
+            return Vec::new();
+        }
+
+        let chunks: Vec<_> = zstd_columns
+            .iter()
+            .map(|&(col, _)| (sample_rg, col, FIRST_PAGE_SPAN))
+            .collect();
+        let _ = ctx.pages.prefetch(&chunks).await;
+        let mut inferred = Vec::new();
+        for (col, compressed_bytes) in zstd_columns {
+            let Some((level, sizes)) = infer_level(ctx, sample_rg, col).await else {
+                continue;
+            };
+            inferred.push(InferredLevel {
+                column: col,
+                level,
+                sizes,
+                compressed_bytes,
+            });
+        }
+        let Some(lowest) = inferred.iter().map(|c| c.level).min() else {
+            return Vec::new();
+        };
+
+        // Higher-level columns that do not earn their level.
+        let min_size_gain = ctx.threshold(self, &MIN_SIZE_GAIN);
+        let unjustified: Vec<(&InferredLevel, f64)> = inferred
+            .iter()
+            .filter(|c| c.level >= lowest + MIN_LEVEL_GAP)
+            .map(|c| (c, c.sizes[&lowest] as f64 / c.sizes[&c.level] as f64 - 1.0))
+            .filter(|&(_, gain)| gain < min_size_gain)
+            .collect();
+        if unjustified.is_empty() {
+            return Vec::new();
+        }
+
+        let schema = ctx.metadata.file_metadata().schema_descr();
+        let inferred_bytes: i64 = inferred.iter().map(|c| c.compressed_bytes).sum();
+        let mut by_level: BTreeMap<i32, (i64, usize)> = BTreeMap::new();
+        for column in &inferred {
+            let entry = by_level.entry(column.level).or_default();
+            entry.0 += column.compressed_bytes;
+            entry.1 += 1;
+        }
+        let shares: Vec<String> = by_level
+            .iter()
+            .map(|(level, (bytes, columns))| {
+                format!(
+                    "zstd({level}) {:.0}% in {columns} column{}",
+                    *bytes as f64 / inferred_bytes.max(1) as f64 * 100.0,
+                    if *columns == 1 { "" } else { "s" }
+                )
+            })
+            .collect();
+        let mut prescription = Prescription::new();
+        let mut columns = Vec::with_capacity(unjustified.len());
+        for (column, gain) in &unjustified {
+            let path = schema.column(column.column).path().clone();
+            columns.push(format!(
+                "{} (zstd({}) saves {:.1}%)",
+                path.string(),
+                column.level,
+                gain * 100.0
+            ));
+            prescription.push(Directive::SetColumnCompression(path, Codec::Zstd(lowest)));
+        }
+        vec![Diagnostic {
+            rule_name: self.name(),
+            severity: Severity::Suggestion,
+            category: Category::Performance,
+            location: Location::File,
+            message: format!(
+                "zstd levels differ across columns (compressed bytes: {}); higher levels barely \
+                 shrink {} over zstd({lowest}), recommend zstd({lowest}) throughout",
+                shares.join(", "),
+                columns.join(", ")
+            ),
+            prescription,
+            related: Vec::new(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch};
+
+    use super::header_compressed_size;
+    use crate::options::LintOptions;
+    use crate::testing::{TestFile, assert_snapshot};
+
+    #[test]
+    fn reads_compressed_size_from_page_header() {
+        // type 0 (DATA_PAGE), uncompressed 100, compressed 64.
+        assert_eq!(
+            header_compressed_size(&[0x15, 0x00, 0x15, 0xc8, 0x01, 0x15, 0x80, 0x01, 0x2c]),
+            Some(64)
+        );
+        assert_eq!(header_compressed_size(&[0x16, 0x00]), None);
+    }
+
+    #[tokio::test]
+    async fn flags_high_level_column_without_size_gain() -> anyhow::Result<()> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut noisy = |range: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % range) as i64
+        };
+        let fast: Vec<i64> = (0..20_000).map(|_| noisy(1 << 40)).collect();
+        let slow: Vec<i64> = (0..20_000).map(|_| noisy(1 << 40)).collect();
+        let batch = RecordBatch::try_from_iter([
+            ("fast", Arc::new(Int64Array::from(fast)) as _),
+            ("slow", Arc::new(Int64Array::from(slow)) as _),
+        ])?;
+        let file = TestFile::new(batch).settings(
+            "set column fast compression zstd(1)
+             set column slow compression zstd(19)
+             set column fast dictionary false
+             set column slow dictionary false",
+        );
+        let mut options = LintOptions {
+            probe_compression: true,
+            ..LintOptions::default()
+        };
+        options
+            .thresholds
+            .set("zstd-level-mismatch", "min_zstd_bytes", 1.0);
+        assert_snapshot(
+            &file
+                .lint_with_options(&["zstd-level-mismatch"], options)
+                .await?,
+            r#"
+                [suggestion] zstd-level-mismatch @ file: zstd levels differ across columns (compressed bytes: zstd(1) 50% in 1 column, zstd(19) 50% in 1 column); ...
+                  fix: set column slow compression zstd(1)
+            "#,
+        );
+        assert!(file.lint(&["zstd-level-mismatch"]).await?.is_empty());
+        Ok(())
+    }
+}
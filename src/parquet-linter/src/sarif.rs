@@ -0,0 +1,178 @@
+//! SARIF 2.1.0 reports, for GitHub code scanning and other SARIF consumers.
+//!
+//! Each diagnostic becomes a result whose rule id is the rule's code
+//! (`PL001`), located at the linted file. Data files have no lines, so the
+//! region is always line 1; the column path, row group or page goes into
+//! a logical location instead.
+
+use serde_json::{Value, json};
+
+use crate::cache::fnv1a;
+use crate::diagnostic::{Diagnostic, Location, Severity};
+use crate::rule::RuleMetadata;
+use crate::rules;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/XiangpengHao/parquet-linter";
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Suggestion => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn artifact_location(file: &str) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": {"uri": file},
+            "region": {"startLine": 1},
+        },
+    })
+}
+
+fn logical_location(location: &Location) -> Option<Value> {
+    let (fully_qualified_name, kind) = match location {
+        Location::File => return None,
+        Location::RowGroup { .. } => (location.to_string(), "module"),
+        Location::Column { path, .. } | Location::Page { path, .. } => (path.string(), "member"),
+    };
+    Some(json!({
+        "name": location.to_string(),
+        "fullyQualifiedName": fully_qualified_name,
+        "kind": kind,
+    }))
+}
+
+fn result(file: &str, d: &Diagnostic, rules: &[RuleMetadata]) -> Value {
+    let mut text = d.message.clone();
+    for directive in d.prescription.directives() {
+        text.push_str(&format!("\nfix: {directive}"));
+    }
+    let mut location = artifact_location(file);
+    if let Some(logical) = logical_location(&d.location) {
+        location["logicalLocations"] = json!([logical]);
+    }
+    let fingerprint = fnv1a(format!("{}\0{file}\0{}", d.rule_name, d.location).as_bytes());
+    let mut result = json!({
+        "ruleId": d.rule_name,
+        "level": level(d.severity),
+        "message": {"text": text},
+        "locations": [location],
+        "partialFingerprints": {"parquetLinter/v1": format!("{fingerprint:016x}")},
+        "properties": {
+            "rule": d.rule_name,
+            "category": d.category.to_string(),
+            "location": d.location,
+            "directives": d.prescription,
+        },
+    });
+    // Rules registered outside the built-ins have no code to refer to.
+    if let Some(index) = rules.iter().position(|r| r.name == d.rule_name) {
+        result["ruleId"] = json!(rules[index].code);
+        result["ruleIndex"] = json!(index);
+    }
+    result
+}
+
+fn rule_descriptor(metadata: &RuleMetadata) -> Value {
+    json!({
+        "id": metadata.code,
+        "name": metadata.name,
+        "shortDescription": {"text": metadata.description},
+        "defaultConfiguration": {"level": level(metadata.default_severity)},
+        "properties": {"category": metadata.category.to_string()},
+    })
+}
+
+/// One SARIF log with a run over `files` and their diagnostics. `failures`
+/// are files that could not be linted, reported as tool notifications.
+pub fn report(files: &[(&str, Vec<&Diagnostic>)], failures: &[(&str, String)]) -> Value {
+    let rules: Vec<RuleMetadata> = rules::all_rules().iter().map(|r| r.metadata()).collect();
+    let results: Vec<Value> = files
+        .iter()
+        .flat_map(|(file, diagnostics)| diagnostics.iter().map(|d| result(file, d, &rules)))
+        .collect();
+    let notifications: Vec<Value> = failures
+        .iter()
+        .map(|(file, error)| {
+            json!({
+                "level": "error",
+                "message": {"text": error},
+                "locations": [artifact_location(file)],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "parquet-linter",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules.iter().map(rule_descriptor).collect::<Vec<_>>(),
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": failures.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet::schema::types::ColumnPath;
+
+    use super::*;
+    use crate::diagnostic::Category;
+    use crate::prescription::Prescription;
+
+    #[test]
+    fn maps_diagnostics_to_coded_results() {
+        let diagnostic = Diagnostic {
+            rule_name: "sorting-order",
+            severity: Severity::Suggestion,
+            category: Category::Performance,
+            location: Location::Column {
+                column: 2,
+                path: ColumnPath::new(vec!["user".to_string(), "id".to_string()]),
+            },
+            message: "not sorted".to_string(),
+            prescription: Prescription::new(),
+            related: Vec::new(),
+        };
+        let log = report(
+            &[("s3://b/a.parquet", vec![&diagnostic])],
+            &[("s3://b/c.parquet", "bad footer".to_string())],
+        );
+        let run = &log["runs"][0];
+        let result = &run["results"][0];
+        let index = result["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(
+            run["tool"]["driver"]["rules"][index]["name"],
+            "sorting-order"
+        );
+        assert_eq!(
+            result["ruleId"],
+            run["tool"]["driver"]["rules"][index]["id"]
+        );
+        assert_eq!(result["level"], "note");
+        let location = &result["locations"][0];
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "s3://b/a.parquet"
+        );
+        assert_eq!(
+            location["logicalLocations"][0]["fullyQualifiedName"],
+            "user.id"
+        );
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+    }
+}
@@ -0,0 +1,396 @@
+//! Row sorting for rewrites: `set file sorting_columns` sorts each row group
+//! on its own, `set file sort_by` sorts the whole file, spilling sorted runs
+//! to temporary files and merging them when the rows outgrow memory.
+
+use std::cmp::Ordering;
+use std::fs::File;
+
+use anyhow::{Context, Result, bail};
+use arrow_array::RecordBatch;
+use arrow_ord::ord::{DynComparator, make_comparator};
+use arrow_ord::sort::{SortColumn, lexsort_to_indices};
+use arrow_schema::{SchemaRef, SortOptions};
+use arrow_select::concat::concat_batches;
+use arrow_select::interleave::interleave_record_batch;
+use arrow_select::take::take_record_batch;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::{ArrowSchemaConverter, ArrowWriter};
+use parquet::basic::Compression;
+use parquet::file::metadata::SortingColumn;
+use parquet::file::properties::WriterProperties;
+
+use crate::prescription::SortKey;
+
+/// Decoded bytes a whole-file sort holds before spilling a sorted run.
+const SORT_MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+/// Rows per batch read back from spilled runs and produced by merges.
+const MERGE_BATCH_ROWS: usize = 8192;
+
+/// Sort keys resolved against a schema. Nulls sort first in either
+/// direction.
+#[derive(Debug, Clone)]
+pub(crate) struct SortOrder {
+    /// Arrow field index and direction of each key.
+    keys: Vec<(usize, bool)>,
+    /// The same keys as parquet leaf columns, for the footer.
+    pub(crate) sorting_columns: Vec<SortingColumn>,
+}
+
+impl SortOrder {
+    pub(crate) fn new(schema: &SchemaRef, keys: &[SortKey]) -> Result<Self> {
+        let parquet_schema = ArrowSchemaConverter::new().convert(schema)?;
+        let mut fields = Vec::with_capacity(keys.len());
+        let mut sorting_columns = Vec::with_capacity(keys.len());
+        for key in keys {
+            let column = key.column.string();
+            let [name] = key.column.parts() else {
+                bail!("cannot sort by {column}: only top-level columns can be sorted by");
+            };
+            let field = schema
+                .index_of(name)
+                .with_context(|| format!("cannot sort by {column}: no such column"))?;
+            let leaf = (0..parquet_schema.num_columns())
+                .find(|&i| parquet_schema.column(i).path() == &key.column)
+                .with_context(|| {
+                    format!("cannot sort by {column}: it is not a primitive column")
+                })?;
+            fields.push((field, key.descending));
+            sorting_columns.push(SortingColumn {
+                column_idx: leaf as i32,
+                descending: key.descending,
+                nulls_first: true,
+            });
+        }
+        Ok(Self {
+            keys: fields,
+            sorting_columns,
+        })
+    }
+
+    fn options(descending: bool) -> SortOptions {
+        SortOptions {
+            descending,
+            nulls_first: true,
+        }
+    }
+
+    pub(crate) fn sort(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let columns: Vec<SortColumn> = self
+            .keys
+            .iter()
+            .map(|&(field, descending)| SortColumn {
+                values: batch.column(field).clone(),
+                options: Some(Self::options(descending)),
+            })
+            .collect();
+        let indices = lexsort_to_indices(&columns, None)?;
+        Ok(take_record_batch(batch, &indices)?)
+    }
+
+    /// Compares row `i` of `left` with row `j` of `right`.
+    fn comparator(
+        &self,
+        left: &RecordBatch,
+        right: &RecordBatch,
+    ) -> Result<impl Fn(usize, usize) -> Ordering> {
+        let comparators = self
+            .keys
+            .iter()
+            .map(|&(field, descending)| {
+                make_comparator(
+                    left.column(field),
+                    right.column(field),
+                    Self::options(descending),
+                )
+            })
+            .collect::<Result<Vec<DynComparator>, _>>()?;
+        Ok(move |i, j| {
+            comparators
+                .iter()
+                .map(|compare| compare(i, j))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+}
+
+/// Rows buffered until a row group's worth can be sorted and written.
+pub(crate) struct RowGroupSort {
+    pub(crate) order: SortOrder,
+    max_rows: usize,
+    pending: Vec<RecordBatch>,
+    pending_rows: usize,
+}
+
+impl RowGroupSort {
+    pub(crate) fn new(order: SortOrder, max_rows: usize) -> Self {
+        Self {
+            order,
+            max_rows,
+            pending: Vec::new(),
+            pending_rows: 0,
+        }
+    }
+
+    /// Buffer `batch`, returning each row group it completes, sorted.
+    pub(crate) fn push(&mut self, batch: RecordBatch) -> Result<Vec<RecordBatch>> {
+        let schema = batch.schema();
+        self.pending_rows += batch.num_rows();
+        self.pending.push(batch);
+        let mut full = Vec::new();
+        while self.pending_rows >= self.max_rows {
+            let rows = concat_batches(&schema, &self.pending)?;
+            full.push(self.order.sort(&rows.slice(0, self.max_rows))?);
+            let rest = rows.slice(self.max_rows, rows.num_rows() - self.max_rows);
+            self.pending_rows = rest.num_rows();
+            self.pending = vec![rest];
+        }
+        Ok(full)
+    }
+
+    /// The last, partial row group, sorted.
+    pub(crate) fn finish(self) -> Result<Option<RecordBatch>> {
+        if self.pending_rows == 0 {
+            return Ok(None);
+        }
+        let rows = concat_batches(&self.pending[0].schema(), &self.pending)?;
+        Ok(Some(self.order.sort(&rows)?))
+    }
+}
+
+/// Every row of a file, sorted: batches are buffered, and whenever they
+/// outgrow `SORT_MEMORY_LIMIT` sorted and spilled to an anonymous temporary
+/// file as one run. Runs are merged pairwise at the end.
+pub(crate) struct FileSort {
+    order: SortOrder,
+    schema: SchemaRef,
+    pending: Vec<RecordBatch>,
+    pending_bytes: usize,
+    runs: Vec<File>,
+}
+
+impl FileSort {
+    pub(crate) fn new(order: SortOrder, schema: SchemaRef) -> Self {
+        Self {
+            order,
+            schema,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            runs: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, batch: RecordBatch) -> Result<()> {
+        self.pending_bytes += batch.get_array_memory_size();
+        self.pending.push(batch);
+        if self.pending_bytes >= SORT_MEMORY_LIMIT {
+            let run = self.sort_pending()?;
+            self.runs.push(self.spill(|emit| emit(run))?);
+        }
+        Ok(())
+    }
+
+    fn sort_pending(&mut self) -> Result<RecordBatch> {
+        let rows = concat_batches(&self.schema, &self.pending)?;
+        self.pending.clear();
+        self.pending_bytes = 0;
+        self.order.sort(&rows)
+    }
+
+    /// Write what `fill` emits to a new temporary file.
+    fn spill(
+        &self,
+        fill: impl FnOnce(&mut dyn FnMut(RecordBatch) -> Result<()>) -> Result<()>,
+    ) -> Result<File> {
+        let file = tempfile::tempfile().context("failed to create a sort spill file")?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::LZ4_RAW)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.try_clone()?, self.schema.clone(), Some(props))?;
+        fill(&mut |batch| Ok(writer.write(&batch)?))?;
+        writer.close()?;
+        Ok(file)
+    }
+
+    /// Emit every row in order, in batches.
+    pub(crate) fn finish(mut self, emit: &mut dyn FnMut(RecordBatch) -> Result<()>) -> Result<()> {
+        if self.runs.is_empty() {
+            let sorted = self.sort_pending()?;
+            let mut offset = 0;
+            while offset < sorted.num_rows() {
+                let len = MERGE_BATCH_ROWS.min(sorted.num_rows() - offset);
+                emit(sorted.slice(offset, len))?;
+                offset += len;
+            }
+            return Ok(());
+        }
+        if !self.pending.is_empty() {
+            let run = self.sort_pending()?;
+            self.runs.push(self.spill(|emit| emit(run))?);
+        }
+        // Merge pairs into new runs until at most two remain, then merge
+        // those straight into `emit`.
+        let mut runs = std::mem::take(&mut self.runs);
+        while runs.len() > 2 {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(2));
+            let mut pairs = runs.into_iter();
+            while let Some(left) = pairs.next() {
+                merged.push(match pairs.next() {
+                    Some(right) => self.spill(|emit| self.merge(left, Some(right), emit))?,
+                    None => left,
+                });
+            }
+            runs = merged;
+        }
+        let mut runs = runs.into_iter();
+        let left = runs.next().expect("a spilled run");
+        self.merge(left, runs.next(), emit)
+    }
+
+    /// Merge two sorted runs (or stream one), rows of `left` first on ties.
+    fn merge(
+        &self,
+        left: File,
+        right: Option<File>,
+        emit: &mut dyn FnMut(RecordBatch) -> Result<()>,
+    ) -> Result<()> {
+        let mut left = RunReader::new(left)?;
+        let Some(right) = right else {
+            while let Some(batch) = left.next_batch()? {
+                emit(batch)?;
+            }
+            return Ok(());
+        };
+        let mut right = RunReader::new(right)?;
+        loop {
+            let (Some(l), Some(r)) = (left.current()?.cloned(), right.current()?.cloned()) else {
+                break;
+            };
+            let compare = self.order.comparator(&l, &r)?;
+            let mut indices = Vec::with_capacity(MERGE_BATCH_ROWS);
+            let (mut i, mut j) = (left.row, right.row);
+            while i < l.num_rows() && j < r.num_rows() && indices.len() < MERGE_BATCH_ROWS {
+                if compare(i, j).is_le() {
+                    indices.push((0, i));
+                    i += 1;
+                } else {
+                    indices.push((1, j));
+                    j += 1;
+                }
+            }
+            let merged = interleave_record_batch(&[&l, &r], &indices)?;
+            left.row = i;
+            right.row = j;
+            emit(merged)?;
+        }
+        for run in [&mut left, &mut right] {
+            while let Some(batch) = run.next_batch()? {
+                emit(batch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads a spilled run back, batch by batch, tracking the next row.
+struct RunReader {
+    reader: ParquetRecordBatchReader,
+    batch: Option<RecordBatch>,
+    row: usize,
+}
+
+impl RunReader {
+    fn new(file: File) -> Result<Self> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)?
+            .with_batch_size(MERGE_BATCH_ROWS)
+            .build()?;
+        Ok(Self {
+            reader,
+            batch: None,
+            row: 0,
+        })
+    }
+
+    /// The batch holding the next row, `None` once the run is exhausted.
+    fn current(&mut self) -> Result<Option<&RecordBatch>> {
+        while self.batch.as_ref().is_none_or(|b| self.row >= b.num_rows()) {
+            match self.reader.next().transpose()? {
+                Some(batch) => {
+                    self.batch = Some(batch);
+                    self.row = 0;
+                }
+                None => {
+                    self.batch = None;
+                    return Ok(None);
+                }
+            }
+        }
+        Ok(self.batch.as_ref())
+    }
+
+    /// The rest of the current batch, or the next batch.
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let Some(batch) = self.current()?.cloned() else {
+            return Ok(None);
+        };
+        let rest = batch.slice(self.row, batch.num_rows() - self.row);
+        self.row = batch.num_rows();
+        Ok(Some(rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, StringArray};
+    use parquet::schema::types::ColumnPath;
+
+    use super::*;
+
+    #[test]
+    fn spilled_runs_merge_in_order() -> Result<()> {
+        let batch = |values: Vec<i32>| -> Result<RecordBatch> {
+            let names: Vec<String> = values.iter().map(|v| format!("row {v}")).collect();
+            Ok(RecordBatch::try_from_iter([
+                ("k", Arc::new(Int32Array::from(values)) as _),
+                ("name", Arc::new(StringArray::from(names)) as _),
+            ])?)
+        };
+        let first = batch(vec![5, 1, 9])?;
+        let order = SortOrder::new(
+            &first.schema(),
+            &[SortKey {
+                column: ColumnPath::from("k"),
+                descending: true,
+            }],
+        )?;
+        let mut sort = FileSort::new(order, first.schema());
+        // Force a spill per pushed batch, with three runs to merge.
+        for values in [vec![5, 1, 9], vec![3, 7], vec![8, 2, 6, 4]] {
+            sort.push(batch(values)?)?;
+            let run = sort.sort_pending()?;
+            sort.runs.push(sort.spill(|emit| emit(run))?);
+        }
+        let mut keys = Vec::new();
+        sort.finish(&mut |batch| {
+            let k = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            let name = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            for (k, name) in k.values().iter().zip(name.iter()) {
+                assert_eq!(name, Some(format!("row {k}").as_str()));
+                keys.push(*k);
+            }
+            Ok(())
+        })?;
+        assert_eq!(keys, [9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        Ok(())
+    }
+}
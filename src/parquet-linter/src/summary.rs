@@ -0,0 +1,221 @@
+//! File-level summary computed from the footer alone: sizes, codecs,
+//! encodings and dictionary coverage.
+
+use std::fmt;
+
+use parquet::basic::Encoding;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use serde_json::{Value, json};
+
+use crate::cardinality;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSummary {
+    pub num_rows: i64,
+    pub num_row_groups: usize,
+    pub num_columns: usize,
+    pub compressed_size: i64,
+    pub uncompressed_size: i64,
+    /// Compressed bytes per codec, largest first.
+    pub codec_bytes: Vec<(String, i64)>,
+    /// Compressed bytes per data encoding, largest first. A chunk counts
+    /// under `RLE_DICTIONARY` only when no page fell back to another
+    /// encoding, otherwise under its first non-dictionary encoding.
+    pub encoding_bytes: Vec<(String, i64)>,
+    /// Compressed bytes in chunks that are dictionary encoded throughout.
+    pub dictionary_bytes: i64,
+}
+
+impl FileSummary {
+    pub fn from_metadata(metadata: &ParquetMetaData) -> Self {
+        let mut summary = Self {
+            num_rows: metadata.file_metadata().num_rows(),
+            num_row_groups: metadata.num_row_groups(),
+            num_columns: metadata.file_metadata().schema_descr().num_columns(),
+            compressed_size: 0,
+            uncompressed_size: 0,
+            codec_bytes: Vec::new(),
+            encoding_bytes: Vec::new(),
+            dictionary_bytes: 0,
+        };
+        for chunk in metadata.row_groups().iter().flat_map(|rg| rg.columns()) {
+            let bytes = chunk.compressed_size();
+            summary.compressed_size += bytes;
+            summary.uncompressed_size += chunk.uncompressed_size();
+            add(
+                &mut summary.codec_bytes,
+                chunk.compression().to_string(),
+                bytes,
+            );
+            let dictionary = dictionary_only(chunk);
+            if dictionary {
+                summary.dictionary_bytes += bytes;
+            }
+            add(
+                &mut summary.encoding_bytes,
+                data_encoding(chunk, dictionary).to_string(),
+                bytes,
+            );
+        }
+        for breakdown in [&mut summary.codec_bytes, &mut summary.encoding_bytes] {
+            breakdown.sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+        }
+        summary
+    }
+
+    /// Share of compressed bytes in dictionary-encoded chunks, 0 to 1.
+    pub fn dictionary_coverage(&self) -> f64 {
+        share(self.dictionary_bytes, self.compressed_size)
+    }
+
+    /// The `summary` object of the JSON output.
+    pub fn to_json(&self) -> Value {
+        let breakdown = |entries: &[(String, i64)]| -> Value {
+            entries
+                .iter()
+                .map(|(name, bytes)| (name.clone(), json!(bytes)))
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        };
+        json!({
+            "num_rows": self.num_rows,
+            "num_row_groups": self.num_row_groups,
+            "num_columns": self.num_columns,
+            "compressed_size": self.compressed_size,
+            "uncompressed_size": self.uncompressed_size,
+            "codec_bytes": breakdown(&self.codec_bytes),
+            "encoding_bytes": breakdown(&self.encoding_bytes),
+            "dictionary_coverage": self.dictionary_coverage(),
+        })
+    }
+}
+
+impl fmt::Display for FileSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} rows, {} row groups, {} columns, {} compressed ({} uncompressed)",
+            self.num_rows,
+            self.num_row_groups,
+            self.num_columns,
+            mib(self.compressed_size),
+            mib(self.uncompressed_size)
+        )?;
+        let breakdown = |entries: &[(String, i64)]| -> String {
+            entries
+                .iter()
+                .map(|(name, bytes)| {
+                    format!("{name} {:.0}%", share(*bytes, self.compressed_size) * 100.0)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(f, "  codecs:    {}", breakdown(&self.codec_bytes))?;
+        writeln!(f, "  encodings: {}", breakdown(&self.encoding_bytes))?;
+        write!(
+            f,
+            "  dictionary coverage: {:.0}% of bytes",
+            self.dictionary_coverage() * 100.0
+        )
+    }
+}
+
+/// Whether every data page is dictionary encoded. Without page encoding
+/// stats, a chunk listing PLAIN next to a dictionary encoding is assumed to
+/// have fallen back.
+fn dictionary_only(chunk: &ColumnChunkMetaData) -> bool {
+    if chunk.page_encoding_stats_mask().is_some() || chunk.page_encoding_stats().is_some() {
+        return cardinality::fully_dictionary_encoded(chunk);
+    }
+    chunk.encodings().any(is_dictionary) && !chunk.encodings().any(|e| e == Encoding::PLAIN)
+}
+
+/// The encoding most of the chunk's values are stored in. `RLE` and
+/// `BIT_PACKED` only ever carry levels next to a real value encoding.
+fn data_encoding(chunk: &ColumnChunkMetaData, dictionary: bool) -> Encoding {
+    if dictionary {
+        return Encoding::RLE_DICTIONARY;
+    }
+    #[allow(deprecated)]
+    chunk
+        .encodings()
+        .find(|&e| !matches!(e, Encoding::RLE | Encoding::BIT_PACKED) && !is_dictionary(e))
+        .unwrap_or(Encoding::PLAIN)
+}
+
+#[allow(deprecated)]
+fn is_dictionary(encoding: Encoding) -> bool {
+    matches!(
+        encoding,
+        Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY
+    )
+}
+
+fn add(breakdown: &mut Vec<(String, i64)>, name: String, bytes: i64) {
+    match breakdown.iter_mut().find(|(n, _)| *n == name) {
+        Some((_, total)) => *total += bytes,
+        None => breakdown.push((name, bytes)),
+    }
+}
+
+fn share(part: i64, total: i64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn mib(bytes: i64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow_array::{Int64Array, RecordBatch, StringArray};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use parquet::schema::types::ColumnPath;
+
+    #[test]
+    fn breaks_down_codecs_and_encodings() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int64Array::from_iter_values(0..1000)) as _),
+            (
+                "tag",
+                Arc::new(StringArray::from_iter_values(
+                    (0..1000).map(|i| format!("t{}", i % 3)),
+                )) as _,
+            ),
+        ])?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_column_compression(ColumnPath::from("tag"), Compression::UNCOMPRESSED)
+            .set_column_dictionary_enabled(ColumnPath::from("id"), false)
+            .build();
+        let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        let metadata = writer.close()?;
+
+        let summary = FileSummary::from_metadata(&metadata);
+        assert_eq!((summary.num_rows, summary.num_columns), (1000, 2));
+        let codecs: Vec<&str> = summary
+            .codec_bytes
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .collect();
+        assert_eq!(codecs, ["SNAPPY", "UNCOMPRESSED"]);
+        let (_, dictionary) = summary
+            .encoding_bytes
+            .iter()
+            .find(|(n, _)| n == "RLE_DICTIONARY")
+            .unwrap();
+        assert_eq!(*dictionary, summary.dictionary_bytes);
+        assert!(summary.dictionary_coverage() > 0.0 && summary.dictionary_coverage() < 1.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//! Reader compatibility profiles. With `LintOptions::target` set, the
+//! `reader-compatibility` rule flags codecs and encodings the target reader
+//! cannot decode, and fixes from other rules that would introduce them are
+//! dropped.
+
+use std::fmt;
+
+use anyhow::{Result, bail};
+use parquet::basic::{Compression, Encoding};
+
+use crate::prescription::{Codec, DataEncoding, Directive};
+
+/// A reader the file has to stay readable by, with its default settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetReader {
+    /// Readers of the original format: snappy/gzip, plain and dictionary.
+    Legacy,
+    /// Spark 3.3/3.4 (parquet-mr 1.12), vectorized reader.
+    Spark33,
+    /// Spark 3.5 (parquet-mr 1.13), vectorized reader.
+    Spark35,
+    /// Spark 4.0 (parquet-mr 1.15).
+    Spark40,
+}
+
+/// How a target handles a codec or encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    Readable,
+    Unreadable,
+    /// Deprecated `LZ4`: readers disagree on the framing, so some fail or
+    /// fall back to slower trial decoding.
+    Ambiguous,
+}
+
+pub const TARGET_NAMES: &[&str] = &["legacy", "spark-3.3", "spark-3.5", "spark-4.0"];
+
+impl TargetReader {
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "legacy" => TargetReader::Legacy,
+            "spark-3.3" => TargetReader::Spark33,
+            "spark-3.5" => TargetReader::Spark35,
+            "spark-4.0" => TargetReader::Spark40,
+            _ => bail!(
+                "unknown target reader '{name}', expected one of: {}",
+                TARGET_NAMES.join(", ")
+            ),
+        })
+    }
+
+    pub fn codec_support(self, codec: Compression) -> Support {
+        use TargetReader::*;
+        match (self, codec) {
+            (_, Compression::LZ4) => Support::Ambiguous,
+            (_, Compression::UNCOMPRESSED | Compression::SNAPPY | Compression::GZIP(_)) => {
+                Support::Readable
+            }
+            (Legacy, _) => Support::Unreadable,
+            // Spark does not bundle a brotli codec.
+            (_, Compression::BROTLI(_)) => Support::Unreadable,
+            (Spark33, Compression::LZ4_RAW) => Support::Unreadable,
+            _ => Support::Readable,
+        }
+    }
+
+    pub fn encoding_support(self, encoding: Encoding) -> Support {
+        use TargetReader::*;
+        let unreadable = match encoding {
+            Encoding::DELTA_BINARY_PACKED
+            | Encoding::DELTA_LENGTH_BYTE_ARRAY
+            | Encoding::DELTA_BYTE_ARRAY => self == Legacy,
+            // Spark's vectorized reader rejects it before 4.0.
+            Encoding::BYTE_STREAM_SPLIT => self != Spark40,
+            _ => false,
+        };
+        if unreadable {
+            Support::Unreadable
+        } else {
+            Support::Readable
+        }
+    }
+
+    /// Whether the target decrypts modular encryption (parquet-mr 1.12+).
+    /// Readers without it see only the unencrypted columns of files with a
+    /// plaintext footer.
+    pub fn reads_encryption(self) -> bool {
+        self != TargetReader::Legacy
+    }
+
+    /// The codec to switch to from one the target cannot (reliably) read:
+    /// LZ4_RAW for LZ4 where readable, else ZSTD(3), else Snappy.
+    pub fn replacement_codec(self, codec: Compression) -> Codec {
+        let readable = |c: Codec| self.codec_support(c.into()) == Support::Readable;
+        if codec == Compression::LZ4 && readable(Codec::Lz4Raw) {
+            Codec::Lz4Raw
+        } else if readable(Codec::Zstd(3)) {
+            Codec::Zstd(3)
+        } else {
+            Codec::Snappy
+        }
+    }
+
+    /// Whether the target can read what `directive` sets.
+    pub fn reads(self, directive: &Directive) -> bool {
+        let support = match directive {
+            Directive::SetFileCompression(codec) | Directive::SetColumnCompression(_, codec) => {
+                self.codec_support((*codec).into())
+            }
+            Directive::SetColumnEncoding(_, encoding) => self.encoding_support((*encoding).into()),
+            _ => Support::Readable,
+        };
+        support == Support::Readable
+    }
+}
+
+/// Every data encoding can fall back to PLAIN.
+pub const REPLACEMENT_ENCODING: DataEncoding = DataEncoding::Plain;
+
+impl fmt::Display for TargetReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TargetReader::Legacy => "legacy",
+            TargetReader::Spark33 => "spark-3.3",
+            TargetReader::Spark35 => "spark-3.5",
+            TargetReader::Spark40 => "spark-4.0",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::basic::{BrotliLevel, ZstdLevel};
+
+    #[test]
+    fn profiles_narrow_codecs_and_encodings_by_version() {
+        let zstd = Compression::ZSTD(ZstdLevel::default());
+        assert_eq!(
+            TargetReader::Legacy.codec_support(zstd),
+            Support::Unreadable
+        );
+        assert_eq!(TargetReader::Spark33.codec_support(zstd), Support::Readable);
+        assert_eq!(
+            TargetReader::Spark40.codec_support(Compression::BROTLI(BrotliLevel::default())),
+            Support::Unreadable
+        );
+        assert_eq!(
+            TargetReader::Spark35.encoding_support(Encoding::BYTE_STREAM_SPLIT),
+            Support::Unreadable
+        );
+        assert_eq!(
+            TargetReader::Spark33.replacement_codec(Compression::LZ4),
+            Codec::Zstd(3)
+        );
+        assert_eq!(
+            TargetReader::Spark35.replacement_codec(Compression::LZ4),
+            Codec::Lz4Raw
+        );
+        assert_eq!(TargetReader::Legacy.replacement_codec(zstd), Codec::Snappy);
+        for name in TARGET_NAMES {
+            assert_eq!(TargetReader::parse(name).unwrap().to_string(), *name);
+        }
+    }
+}
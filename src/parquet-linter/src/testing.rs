@@ -0,0 +1,206 @@
+//! Support for rule tests: build a small parquet file in memory with exact
+//! writer settings, lint it, and compare the diagnostics with a snapshot.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use arrow_array::{Int64Array, RecordBatch};
+//! # use parquet_linter::testing::{TestFile, assert_snapshot};
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> anyhow::Result<()> {
+//! let batch = RecordBatch::try_from_iter([(
+//!     "id",
+//!     Arc::new(Int64Array::from_iter_values(0..1000)) as _,
+//! )])?;
+//! let file = TestFile::new(batch).settings("set column id statistics none");
+//! let diagnostics = file.lint(&["missing-chunk-statistics"]).await?;
+//! assert_snapshot(&diagnostics, r#"
+//!     [warning] missing-chunk-statistics @ column[0]("id"): no chunk statistics ...
+//!       fix: set column id statistics chunk
+//! "#);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow_array::RecordBatch;
+use bytes::Bytes;
+use object_store::ObjectStore;
+use object_store::memory::InMemory;
+use object_store::path::Path as ObjectPath;
+use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder};
+
+use crate::Linter;
+use crate::diagnostic::Diagnostic;
+use crate::fix::RewriteWriter;
+use crate::loader::LoadedFile;
+use crate::options::LintOptions;
+use crate::prescription::Prescription;
+
+/// A parquet file described by its batches and writer settings, written to
+/// an in-memory store when opened.
+pub struct TestFile {
+    batches: Vec<RecordBatch>,
+    settings: Prescription,
+    properties: Option<PropertiesFn>,
+}
+
+type PropertiesFn = Box<dyn Fn(WriterPropertiesBuilder) -> WriterPropertiesBuilder + Send + Sync>;
+
+impl TestFile {
+    pub fn new(batch: RecordBatch) -> Self {
+        Self {
+            batches: vec![batch],
+            settings: Prescription::new(),
+            properties: None,
+        }
+    }
+
+    /// Append a batch with the same schema; each batch is one `write` call.
+    pub fn batch(mut self, batch: RecordBatch) -> Self {
+        self.batches.push(batch);
+        self
+    }
+
+    /// Writer settings in the prescription DSL, on top of the `parquet`
+    /// crate's defaults, e.g. `set column a compression snappy` or
+    /// `set file max_row_group_size 100`; `set file sorting_columns` sorts
+    /// each row group as rewrites do. Panics if `dsl` does not parse.
+    pub fn settings(mut self, dsl: &str) -> Self {
+        let parsed = Prescription::parse(dsl)
+            .unwrap_or_else(|e| panic!("invalid test file settings {dsl:?}: {e}"));
+        for directive in parsed.directives() {
+            self.settings.push(directive.clone());
+        }
+        self
+    }
+
+    /// Writer properties the DSL cannot express, such as encryption,
+    /// applied on top of `settings`.
+    pub fn properties(
+        mut self,
+        f: impl Fn(WriterPropertiesBuilder) -> WriterPropertiesBuilder + Send + Sync + 'static,
+    ) -> Self {
+        self.properties = Some(Box::new(f));
+        self
+    }
+
+    pub fn writer_properties(&self) -> WriterProperties {
+        let builder = self.settings.apply(WriterPropertiesBuilder::default());
+        match &self.properties {
+            Some(f) => f(builder),
+            None => builder,
+        }
+        .build()
+    }
+
+    pub fn to_bytes(&self) -> Result<Bytes> {
+        let mut buffer = Vec::new();
+        let schema = self.batches[0].schema();
+        let mut writer = RewriteWriter::try_new(
+            &mut buffer,
+            &schema,
+            self.writer_properties(),
+            &self.settings,
+            false,
+        )?;
+        for batch in &self.batches {
+            writer.write(batch)?;
+        }
+        writer.close()?;
+        Ok(buffer.into())
+    }
+
+    /// Write the file to a fresh in-memory store and open it.
+    pub async fn open(&self) -> Result<LoadedFile> {
+        let store = Arc::new(InMemory::new());
+        let path = ObjectPath::from("test.parquet");
+        store.put(&path, self.to_bytes()?.into()).await?;
+        LoadedFile::open(store, path).await
+    }
+
+    /// Lint with the named rules and default options.
+    pub async fn lint(&self, rules: &[&str]) -> Result<Vec<Diagnostic>> {
+        self.lint_with_options(rules, LintOptions::default()).await
+    }
+
+    pub async fn lint_with_options(
+        &self,
+        rules: &[&str],
+        options: LintOptions,
+    ) -> Result<Vec<Diagnostic>> {
+        let file = self.open().await?;
+        Linter::builder()
+            .rules(rules.iter().copied())
+            .options(options)
+            .build()
+            .lint_file(&file)
+            .await
+    }
+}
+
+/// The diagnostics as compared by `assert_snapshot`: one `Display` line per
+/// diagnostic, followed by its `fix:` lines.
+pub fn snapshot(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(Diagnostic::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Assert that `diagnostics` render as `expected`. Lines are compared with
+/// surrounding whitespace trimmed and blank lines dropped, so snapshots can
+/// be indented raw strings; a line ending in `...` only has to match up to
+/// there, which keeps long messages out of snapshots.
+#[track_caller]
+pub fn assert_snapshot(diagnostics: &[Diagnostic], expected: &str) {
+    let actual = snapshot(diagnostics);
+    let lines = |text: &str| -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (actual_lines, expected_lines) = (lines(&actual), lines(expected));
+    let matches = actual_lines.len() == expected_lines.len()
+        && actual_lines
+            .iter()
+            .zip(&expected_lines)
+            .all(|(actual, expected)| match expected.strip_suffix("...") {
+                Some(prefix) => actual.starts_with(prefix),
+                None => actual == expected,
+            });
+    assert!(
+        matches,
+        "diagnostics do not match the snapshot\n--- expected\n{}\n--- actual\n{actual}",
+        expected_lines.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Int64Array;
+
+    #[tokio::test]
+    async fn settings_shape_the_written_file() -> Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..1000)) as _,
+        )])?;
+        let file = TestFile::new(batch)
+            .settings("set file max_row_group_size 300; set column id compression snappy")
+            .open()
+            .await?;
+        let row_groups = file.metadata.row_groups();
+        assert_eq!(row_groups.len(), 4);
+        assert_eq!(
+            row_groups[0].column(0).compression(),
+            parquet::basic::Compression::SNAPPY
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+//! Rule thresholds: the sizes, counts and ratios at which rules start to
+//! speak, set as a whole by a named preset and individually by overrides.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::bail;
+use serde_json::{Value, json};
+
+use crate::prescription::{Directive, Prescription};
+
+/// What a threshold measures. Sizes and counts take values of at least 1;
+/// ratios are fractions between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterKind {
+    Bytes,
+    Count,
+    /// A share such as distinct values per row. Presets leave ratios
+    /// alone: they describe the data, not how big a win must be.
+    Ratio,
+}
+
+impl fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParameterKind::Bytes => "bytes",
+            ParameterKind::Count => "count",
+            ParameterKind::Ratio => "ratio",
+        })
+    }
+}
+
+/// A threshold a rule compares a measured size or count against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub kind: ParameterKind,
+    pub default: f64,
+    /// Whether the rule fires at or above the threshold (a minimum column
+    /// size worth a rewrite) rather than below it (a maximum number of
+    /// distinct values for an enum).
+    pub fires_above: bool,
+}
+
+impl Parameter {
+    /// The rule fires at or above this value.
+    pub const fn above(name: &'static str, kind: ParameterKind, default: f64) -> Self {
+        Self {
+            name,
+            kind,
+            default,
+            fires_above: true,
+        }
+    }
+
+    /// The rule fires below this value.
+    pub const fn below(name: &'static str, kind: ParameterKind, default: f64) -> Self {
+        Self {
+            name,
+            kind,
+            default,
+            fires_above: false,
+        }
+    }
+
+    /// Whether `value` is in range for this parameter's kind.
+    pub fn accepts(&self, value: f64) -> bool {
+        match self.kind {
+            ParameterKind::Bytes | ParameterKind::Count => value.is_finite() && value >= 1.0,
+            ParameterKind::Ratio => (0.0..=1.0).contains(&value),
+        }
+    }
+
+    /// What `accepts` allows, for error messages.
+    pub fn expected(&self) -> &'static str {
+        match self.kind {
+            ParameterKind::Bytes => "a size of at least 1",
+            ParameterKind::Count => "a count of at least 1",
+            ParameterKind::Ratio => "a ratio between 0 and 1",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "name": self.name,
+            "type": self.kind.to_string(),
+            "default": self.default,
+            "minimum": 1,
+            "fires": if self.fires_above { "above" } else { "below" },
+        });
+        if self.kind == ParameterKind::Ratio {
+            value["minimum"] = json!(0);
+            value["maximum"] = json!(1);
+        }
+        value
+    }
+}
+
+/// How eager rules are to suggest changes. Presets scale every size and count
+/// threshold by the same factor, in the direction that makes the rule fire
+/// more or less.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// Only flag clear wins: size thresholds doubled.
+    Conservative,
+    #[default]
+    Default,
+    /// Flag smaller wins too: size thresholds halved.
+    Aggressive,
+}
+
+impl Preset {
+    fn factor(self) -> f64 {
+        match self {
+            Preset::Conservative => 2.0,
+            Preset::Default => 1.0,
+            Preset::Aggressive => 0.5,
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Preset::Conservative => "conservative",
+            Preset::Default => "default",
+            Preset::Aggressive => "aggressive",
+        })
+    }
+}
+
+impl FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "conservative" => Preset::Conservative,
+            "default" => Preset::Default,
+            "aggressive" => Preset::Aggressive,
+            _ => bail!("unknown preset {s:?} (expected conservative, default or aggressive)"),
+        })
+    }
+}
+
+/// The preset plus per-rule overrides, which win over it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Thresholds {
+    pub preset: Preset,
+    /// Values keyed by rule name, then parameter name.
+    overrides: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl Thresholds {
+    pub fn new(preset: Preset) -> Self {
+        Self {
+            preset,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Pin `rule`'s `parameter` to `value`, regardless of the preset.
+    pub fn set(&mut self, rule: &str, parameter: &str, value: f64) {
+        self.overrides
+            .entry(rule.to_string())
+            .or_default()
+            .insert(parameter.to_string(), value);
+    }
+
+    /// Pin every parameter a `configure rule` directive of `prescription`
+    /// names; other directives are ignored. Fails on a rule or parameter
+    /// that does not exist, or a value out of the parameter's range.
+    pub fn configure(&mut self, prescription: &Prescription) -> anyhow::Result<()> {
+        let rules = crate::rules::all_rules();
+        for directive in prescription.directives() {
+            let Directive::ConfigureRule(rule, parameter, value) = directive else {
+                continue;
+            };
+            let Some(metadata) = rules.iter().map(|r| r.metadata()).find(|m| m.name == rule) else {
+                bail!("'{directive}': unknown rule {rule:?}");
+            };
+            let Some(spec) = metadata.parameters.iter().find(|p| p.name == parameter) else {
+                let known: Vec<&str> = metadata.parameters.iter().map(|p| p.name).collect();
+                bail!(
+                    "'{directive}': rule {rule:?} has no parameter {parameter:?} (expected one of: {})",
+                    if known.is_empty() {
+                        "none".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                );
+            };
+            if !spec.accepts(*value) {
+                bail!("'{directive}': {parameter} must be {}", spec.expected());
+            }
+            self.set(rule, parameter, *value);
+        }
+        Ok(())
+    }
+
+    /// The value `rule` should use for `parameter`.
+    pub fn get(&self, rule: &str, parameter: &Parameter) -> f64 {
+        if let Some(value) = self
+            .overrides
+            .get(rule)
+            .and_then(|params| params.get(parameter.name))
+        {
+            return *value;
+        }
+        if parameter.kind == ParameterKind::Ratio {
+            return parameter.default;
+        }
+        let factor = self.preset.factor();
+        if parameter.fires_above {
+            parameter.default * factor
+        } else {
+            parameter.default / factor
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_scale_in_the_firing_direction() {
+        const MIN_BYTES: Parameter =
+            Parameter::above("min_column_bytes", ParameterKind::Bytes, 32.0);
+        const MAX_VALUES: Parameter = Parameter::below("max_values", ParameterKind::Count, 100.0);
+        let aggressive = Thresholds::new(Preset::Aggressive);
+        assert_eq!(aggressive.get("r", &MIN_BYTES), 16.0);
+        assert_eq!(aggressive.get("r", &MAX_VALUES), 200.0);
+        let conservative = Thresholds::new(Preset::Conservative);
+        assert_eq!(conservative.get("r", &MIN_BYTES), 64.0);
+        assert_eq!(conservative.get("r", &MAX_VALUES), 50.0);
+        const SORTED: Parameter = Parameter::above("sorted_fraction", ParameterKind::Ratio, 0.9);
+        assert_eq!(aggressive.get("r", &SORTED), 0.9);
+        assert_eq!(conservative.get("r", &SORTED), 0.9);
+
+        let mut pinned = aggressive;
+        pinned.set("r", "min_column_bytes", 8.0);
+        assert_eq!(pinned.get("r", &MIN_BYTES), 8.0);
+        assert_eq!(pinned.get("other", &MIN_BYTES), 16.0);
+    }
+
+    #[test]
+    fn configure_directives_pin_known_parameters() {
+        const MIN_ROW_GROUPS: Parameter =
+            Parameter::above("min_row_groups", ParameterKind::Count, 3.0);
+        let mut thresholds = Thresholds::default();
+        let policy = Prescription::parse(
+            "configure rule sorting-order min_row_groups 8\n\
+             configure rule sorting-order sorted_fraction 0.75\n\
+             set file compression zstd(3)",
+        )
+        .unwrap();
+        thresholds.configure(&policy).unwrap();
+        assert_eq!(thresholds.get("sorting-order", &MIN_ROW_GROUPS), 8.0);
+        const SORTED: Parameter = Parameter::above("sorted_fraction", ParameterKind::Ratio, 0.9);
+        assert_eq!(thresholds.get("sorting-order", &SORTED), 0.75);
+
+        for text in [
+            "configure rule no-such-rule min_row_groups 8",
+            "configure rule sorting-order min_rows 8",
+            "configure rule sorting-order min_row_groups 0",
+            "configure rule sorting-order sorted_fraction 1.5",
+        ] {
+            let policy = Prescription::parse(text).unwrap();
+            assert!(thresholds.configure(&policy).is_err(), "{text}");
+        }
+    }
+}
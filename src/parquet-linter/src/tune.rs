@@ -0,0 +1,447 @@
+//! Empirical prescription search. Instead of trusting the rules' estimates,
+//! `tune` re-encodes a sample of the file under candidate prescriptions and
+//! keeps the one with the lowest measured scan cost: the cost model's fetch
+//! cost of the written bytes plus the measured decode time.
+//!
+//! Candidates are the file's current settings, the merged rule prescription,
+//! and the rule prescription with the best codec and encoding found by a
+//! per-column search over every large column. For the largest columns that
+//! end up on a leveled codec, the level is then chosen at the knee of the
+//! size over encode-plus-decode-time curve rather than defaulting to zstd(3).
+
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use arrow_array::RecordBatch;
+use arrow_schema::Schema;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::basic::{Compression, Encoding};
+use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
+
+use crate::cardinality;
+use crate::cost::CostModel;
+use crate::fix;
+use crate::loader::LoadedFile;
+use crate::options::LintOptions;
+use crate::prescription::{Codec, DataEncoding, Directive, Prescription};
+use crate::probe;
+
+// `std::time::Instant` panics on wasm32-unknown-unknown.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+const SAMPLE_ROWS: usize = 65_536;
+/// Decode timings are the fastest of this many runs, to damp noise.
+const DECODE_RUNS: usize = 3;
+
+const SEARCH_CODECS: [Codec; 3] = [Codec::Zstd(3), Codec::Lz4Raw, Codec::Snappy];
+/// How many of the largest columns get a compression level search.
+const LEVEL_SEARCH_COLUMNS: usize = 4;
+const LEVEL_CODECS: [Codec; 6] = [
+    Codec::Zstd(1),
+    Codec::Zstd(3),
+    Codec::Zstd(9),
+    Codec::Zstd(19),
+    Codec::Gzip(6),
+    Codec::Brotli(5),
+];
+
+/// Observed result of writing the sample with one set of writer settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub uncompressed_size: i64,
+    pub compressed_size: i64,
+    pub encode_seconds: f64,
+    pub decode_seconds: f64,
+}
+
+impl Measurement {
+    /// Estimated seconds to fetch the written bytes plus the measured
+    /// seconds to decode them.
+    pub fn scan_cost(&self, model: &dyn CostModel) -> f64 {
+        model.fetch_cost(self.compressed_size) + self.decode_seconds
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: &'static str,
+    pub prescription: Prescription,
+    pub measurement: Measurement,
+    pub scan_cost: f64,
+}
+
+/// Compression levels tried for one column and the one at the knee.
+#[derive(Debug, Clone)]
+pub struct LevelSearch {
+    pub column: ColumnPath,
+    pub trials: Vec<(Codec, Measurement)>,
+    pub chosen: Codec,
+}
+
+#[derive(Debug, Clone)]
+pub struct TuneReport {
+    pub sample_rows: usize,
+    pub levels: Vec<LevelSearch>,
+    /// Every evaluated candidate, cheapest first.
+    pub candidates: Vec<Candidate>,
+}
+
+impl TuneReport {
+    pub fn best(&self) -> &Candidate {
+        &self.candidates[0]
+    }
+}
+
+/// Search for the prescription with the lowest measured scan cost on a
+/// sample of `file`.
+pub async fn tune(file: &LoadedFile, options: &LintOptions) -> Result<TuneReport> {
+    let mut from_rules = Prescription::new();
+    for d in crate::lint_file(file, None, options).await? {
+        from_rules.extend(d.prescription);
+    }
+    let batches = read_sample(file).await?;
+    let sample_rows = batches.iter().map(RecordBatch::num_rows).sum();
+    if sample_rows == 0 {
+        bail!("file has no rows to sample");
+    }
+    let base = fix::infer_writer_properties(&file.metadata);
+    let mut searched = search_columns(file, &batches, &base, &from_rules, options)?;
+    let levels = search_levels(file, &batches, &base, &searched, options)?;
+    for level in &levels {
+        searched = replace(
+            &searched,
+            [Directive::SetColumnCompression(
+                level.column.clone(),
+                level.chosen,
+            )],
+        );
+    }
+
+    let model = options.cost_model.as_ref();
+    let mut candidates = Vec::new();
+    for (name, prescription) in [
+        ("current", Prescription::new()),
+        ("rules", from_rules),
+        ("searched", searched),
+    ] {
+        let props = prescription.apply(base.clone().into_builder()).build();
+        let measurement = measure(&batches, props)?;
+        candidates.push(Candidate {
+            name,
+            prescription,
+            measurement,
+            scan_cost: measurement.scan_cost(model),
+        });
+    }
+    candidates.sort_by(|a, b| a.scan_cost.total_cmp(&b.scan_cost));
+    Ok(TuneReport {
+        sample_rows,
+        levels,
+        candidates,
+    })
+}
+
+/// Read up to `SAMPLE_ROWS` rows of every column from the sample row group.
+async fn read_sample(file: &LoadedFile) -> Result<Vec<RecordBatch>> {
+    if file.metadata.num_row_groups() == 0 {
+        return Ok(Vec::new());
+    }
+    let arrow_metadata =
+        ArrowReaderMetadata::try_new(file.metadata.clone(), ArrowReaderOptions::default())?;
+    let batches =
+        ParquetRecordBatchStreamBuilder::new_with_metadata(file.reader.clone(), arrow_metadata)
+            .with_row_groups(vec![cardinality::pick_sample_row_group(&file.metadata)])
+            .with_limit(SAMPLE_ROWS)
+            .build()?
+            .try_collect()
+            .await?;
+    Ok(batches)
+}
+
+/// `rules` with the codec and encoding of every large column replaced by
+/// the cheapest setting found for it, where that beats the rules' choice.
+/// Nested schemas are not searched.
+fn search_columns(
+    file: &LoadedFile,
+    batches: &[RecordBatch],
+    base: &WriterProperties,
+    rules: &Prescription,
+    options: &LintOptions,
+) -> Result<Prescription> {
+    let schema = file.metadata.file_metadata().schema_descr();
+    let flat = batches[0].num_columns() == schema.num_columns();
+    let mut winners = Prescription::new();
+    for (i, column) in schema.columns().iter().enumerate().filter(|_| flat) {
+        let size: i64 = file
+            .metadata
+            .row_groups()
+            .iter()
+            .map(|rg| rg.column(i).uncompressed_size())
+            .sum();
+        if size < probe::MIN_PROBE_COLUMN_BYTES || !options.selects(column.path()) {
+            continue;
+        }
+        let sample = project(batches, i)?;
+        let score = |prescription: &Prescription| -> Result<f64> {
+            let props = prescription.apply(base.clone().into_builder()).build();
+            Ok(measure(&sample, props)?.scan_cost(options.cost_model.as_ref()))
+        };
+        let mut best = (score(rules)?, None);
+        for settings in column_settings(column.path(), column.physical_type()) {
+            let mut trial = rules.clone();
+            trial.extend(settings.clone());
+            // The writer rejects encodings it cannot apply to this type.
+            let Ok(cost) = score(&trial) else { continue };
+            if cost < best.0 {
+                best = (cost, Some(settings));
+            }
+        }
+        if let Some(settings) = best.1 {
+            winners.extend(settings);
+        }
+    }
+    Ok(replace(rules, winners.directives().iter().cloned()))
+}
+
+/// Try every leveled codec on the largest columns whose codec in
+/// `prescription` is leveled, keeping lz4 or snappy where the codec search
+/// chose them for speed.
+fn search_levels(
+    file: &LoadedFile,
+    batches: &[RecordBatch],
+    base: &WriterProperties,
+    prescription: &Prescription,
+    options: &LintOptions,
+) -> Result<Vec<LevelSearch>> {
+    let schema = file.metadata.file_metadata().schema_descr();
+    if batches[0].num_columns() != schema.num_columns() {
+        return Ok(Vec::new());
+    }
+    let mut by_size: Vec<(usize, i64)> = (0..schema.num_columns())
+        .filter(|&i| options.selects(schema.column(i).path()))
+        .map(|i| {
+            let rgs = file.metadata.row_groups().iter();
+            (i, rgs.map(|rg| rg.column(i).uncompressed_size()).sum())
+        })
+        .collect();
+    by_size.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+
+    let props = prescription.apply(base.clone().into_builder()).build();
+    let mut searches = Vec::new();
+    for (i, _) in by_size.into_iter().take(LEVEL_SEARCH_COLUMNS) {
+        let column = schema.column(i).path().clone();
+        if !matches!(
+            props.compression(&column),
+            Compression::ZSTD(_) | Compression::GZIP(_) | Compression::BROTLI(_)
+        ) {
+            continue;
+        }
+        let sample = project(batches, i)?;
+        let mut trials = Vec::new();
+        for codec in LEVEL_CODECS {
+            let trial = replace(
+                prescription,
+                [Directive::SetColumnCompression(column.clone(), codec)],
+            );
+            let props = trial.apply(base.clone().into_builder()).build();
+            trials.push((codec, measure(&sample, props)?));
+        }
+        let chosen = knee(&trials);
+        searches.push(LevelSearch {
+            column,
+            trials,
+            chosen,
+        });
+    }
+    Ok(searches)
+}
+
+/// The codec at the knee of the size over time curve: among the trials on
+/// the Pareto frontier, the one furthest below the straight line from the
+/// fastest to the smallest, with both axes normalized. A flat or straight
+/// curve picks the fastest.
+fn knee(trials: &[(Codec, Measurement)]) -> Codec {
+    let time = |m: &Measurement| m.encode_seconds + m.decode_seconds;
+    let mut frontier: Vec<&(Codec, Measurement)> = trials.iter().collect();
+    frontier.sort_by(|a, b| time(&a.1).total_cmp(&time(&b.1)));
+    let mut smallest = i64::MAX;
+    frontier.retain(|(_, m)| {
+        let keep = m.compressed_size < smallest;
+        smallest = smallest.min(m.compressed_size);
+        keep
+    });
+    let (first, last) = (frontier[0].1, frontier[frontier.len() - 1].1);
+    let time_span = (time(&last) - time(&first)).max(f64::EPSILON);
+    let size_span = (first.compressed_size - last.compressed_size).max(1) as f64;
+    let mut best = (0.0, frontier[0].0);
+    for (codec, m) in &frontier {
+        let x = (time(m) - time(&first)) / time_span;
+        let y = (m.compressed_size - last.compressed_size) as f64 / size_span;
+        // The line runs from (0, 1) to (1, 0); distance below it is 1 - x - y.
+        let below = 1.0 - x - y;
+        if below > best.0 {
+            best = (below, *codec);
+        }
+    }
+    best.1
+}
+
+/// `prescription` with `directives` appended, dropping earlier directives
+/// for the same setting.
+fn replace(
+    prescription: &Prescription,
+    directives: impl IntoIterator<Item = Directive>,
+) -> Prescription {
+    let directives: Vec<Directive> = directives.into_iter().collect();
+    let replaced: Vec<String> = directives.iter().map(Directive::conflict_key).collect();
+    let mut result = Prescription::new();
+    for d in prescription.directives() {
+        if !replaced.contains(&d.conflict_key()) {
+            result.push(d.clone());
+        }
+    }
+    for d in directives {
+        result.push(d);
+    }
+    result
+}
+
+/// Every codec and encoding combination worth trying for one column.
+fn column_settings(path: &ColumnPath, physical_type: parquet::basic::Type) -> Vec<Prescription> {
+    let mut settings = Vec::new();
+    for codec in SEARCH_CODECS {
+        for &encoding in probe::candidate_encodings(physical_type) {
+            let mut prescription = Prescription::new();
+            prescription.push(Directive::SetColumnCompression(path.clone(), codec));
+            match data_encoding(encoding) {
+                Some(encoding) => {
+                    prescription.push(Directive::SetColumnDictionary(path.clone(), false));
+                    prescription.push(Directive::SetColumnEncoding(path.clone(), encoding));
+                }
+                None => prescription.push(Directive::SetColumnDictionary(path.clone(), true)),
+            }
+            settings.push(prescription);
+        }
+    }
+    settings
+}
+
+fn data_encoding(encoding: Encoding) -> Option<DataEncoding> {
+    match encoding {
+        Encoding::PLAIN => Some(DataEncoding::Plain),
+        Encoding::DELTA_BINARY_PACKED => Some(DataEncoding::DeltaBinaryPacked),
+        Encoding::DELTA_LENGTH_BYTE_ARRAY => Some(DataEncoding::DeltaLengthByteArray),
+        Encoding::DELTA_BYTE_ARRAY => Some(DataEncoding::DeltaByteArray),
+        Encoding::BYTE_STREAM_SPLIT => Some(DataEncoding::ByteStreamSplit),
+        _ => None,
+    }
+}
+
+/// Column `i` of the sampled batches on its own.
+fn project(batches: &[RecordBatch], i: usize) -> Result<Vec<RecordBatch>> {
+    let schema = Arc::new(Schema::new(vec![batches[0].schema().field(i).clone()]));
+    batches
+        .iter()
+        .map(|b| {
+            Ok(RecordBatch::try_new(
+                schema.clone(),
+                vec![b.column(i).clone()],
+            )?)
+        })
+        .collect()
+}
+
+/// Write `batches` to memory with `props`, then time reading them back.
+fn measure(batches: &[RecordBatch], props: WriterProperties) -> Result<Measurement> {
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batches[0].schema(), Some(props))?;
+    let start = Instant::now();
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    let metadata = writer.close()?;
+    let encode_seconds = start.elapsed().as_secs_f64();
+    let columns = metadata.row_groups().iter().flat_map(|rg| rg.columns());
+    let (uncompressed_size, compressed_size) = columns.fold((0, 0), |(u, c), column| {
+        (u + column.uncompressed_size(), c + column.compressed_size())
+    });
+    let bytes = Bytes::from(buffer);
+
+    let mut decode_seconds = f64::INFINITY;
+    for _ in 0..DECODE_RUNS {
+        let start = Instant::now();
+        for batch in ParquetRecordBatchReaderBuilder::try_new(bytes.clone())?.build()? {
+            batch?;
+        }
+        decode_seconds = decode_seconds.min(start.elapsed().as_secs_f64());
+    }
+    Ok(Measurement {
+        uncompressed_size,
+        compressed_size,
+        encode_seconds,
+        decode_seconds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::CpuCostModel;
+    use arrow_array::StringArray;
+    use arrow_schema::{DataType, Field};
+
+    #[test]
+    fn search_beats_uncompressed_repetitive_strings() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, false)]));
+        let values: StringArray = (0..16_384)
+            .map(|i| Some(format!("status-{}", i % 5)))
+            .collect();
+        let batches = [RecordBatch::try_new(schema, vec![Arc::new(values)])?];
+        let base = WriterProperties::builder()
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_dictionary_enabled(false)
+            .build();
+
+        let model = CpuCostModel::default();
+        let current = measure(&batches, base.clone())?;
+        let mut best = current;
+        for settings in column_settings(&ColumnPath::from("s"), parquet::basic::Type::BYTE_ARRAY) {
+            let props = settings.apply(base.clone().into_builder()).build();
+            let trial = measure(&batches, props)?;
+            if trial.scan_cost(&model) < best.scan_cost(&model) {
+                best = trial;
+            }
+        }
+        assert!(best.compressed_size * 10 < current.compressed_size);
+        Ok(())
+    }
+
+    #[test]
+    fn knee_skips_levels_that_cost_much_more_time_for_little_size() {
+        let point = |compressed_size, seconds| Measurement {
+            uncompressed_size: 1000,
+            compressed_size,
+            encode_seconds: seconds,
+            decode_seconds: 0.0,
+        };
+        let trials = [
+            (Codec::Zstd(1), point(500, 1.0)),
+            (Codec::Zstd(3), point(300, 1.2)),
+            (Codec::Zstd(9), point(280, 3.0)),
+            (Codec::Zstd(19), point(270, 20.0)),
+            // Dominated: slower and larger than zstd(3).
+            (Codec::Gzip(6), point(320, 4.0)),
+        ];
+        assert_eq!(knee(&trials), Codec::Zstd(3));
+    }
+}
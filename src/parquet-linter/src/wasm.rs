@@ -0,0 +1,42 @@
+//! Browser bindings (enabled by the `wasm` feature): lint a file's footer
+//! client-side, without fetching or decoding any data pages.
+//!
+//! ```js
+//! const tail = new Uint8Array(await file.slice(-footerBytes).arrayBuffer());
+//! const diagnostics = JSON.parse(lintFooter(tail));
+//! ```
+
+use bytes::Bytes;
+use wasm_bindgen::prelude::*;
+
+use crate::diagnostic::Diagnostic;
+use crate::loader::LoadedFile;
+use crate::options::LintOptions;
+
+/// Lint the tail of a Parquet file (the footer and its 8-byte trailer) and
+/// return the diagnostics as a JSON array of `Diagnostic::to_json` objects.
+/// `rules` optionally limits which rules run.
+#[wasm_bindgen(js_name = lintFooter)]
+pub fn lint_footer(tail: &[u8], rules: Option<Vec<String>>) -> Result<String, JsError> {
+    lint_footer_json(Bytes::copy_from_slice(tail), rules.as_deref())
+        .map_err(|e| JsError::new(&format!("{e:#}")))
+}
+
+/// Every rule's name, code, category, severity, parameters and directive
+/// settings as JSON (see `rules::rules_manifest`).
+#[wasm_bindgen(js_name = rulesManifest)]
+pub fn rules_manifest() -> String {
+    crate::rules::rules_manifest().to_string()
+}
+
+fn lint_footer_json(tail: Bytes, rules: Option<&[String]>) -> anyhow::Result<String> {
+    let file = LoadedFile::from_footer(tail)?;
+    let options = LintOptions {
+        metadata_only: true,
+        ..LintOptions::default()
+    };
+    // Metadata-only linting issues no IO, so every future is ready at once.
+    let diagnostics = futures::executor::block_on(crate::lint_file(&file, rules, &options))?;
+    let json = serde_json::Value::Array(diagnostics.iter().map(Diagnostic::to_json).collect());
+    Ok(json.to_string())
+}